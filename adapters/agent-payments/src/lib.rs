@@ -0,0 +1,21 @@
+//! Adapter between x402/AP2-style agent payment protocols and TrustyClaw escrows
+//!
+//! Agent frameworks that pay over these protocols speak in quotes, authorizations,
+//! captures and refunds. An open escrow listing is the quote; authorizing it funds the
+//! escrow, capturing releases it to the provider and refunding returns it to the renter.
+//! The adapter only builds instructions: the caller fetches the escrow account, has the
+//! right party sign and submits, e.g. with `trustyclaw_client::submit`.
+//!
+//! - `quote`: open listings rendered as payment quotes, and quotes checked against the listing
+//! - `request`: incoming protocol requests translated into escrow instructions
+//! - `webhook`: escrow state changes translated into signed payment-status callbacks
+//! - `wire`: base58 and decimal-string encodings used on the JSON wire
+
+pub mod quote;
+pub mod request;
+pub mod webhook;
+pub mod wire;
+
+pub use quote::{PaymentQuote, QuoteError, SCHEME};
+pub use request::{translate, PaymentRequest, RequestError, Translation};
+pub use webhook::{PaymentEvent, PaymentStatus};
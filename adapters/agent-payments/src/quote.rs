@@ -0,0 +1,110 @@
+//! Open listings as payment quotes
+//!
+//! A quote names the escrow to fund, the mint and exact price in base units, and the
+//! listing's `terms_hash`. The payer echoes the quote back when authorizing, and
+//! [`PaymentQuote::check`] rebuilds it from the listing as it stands then, so a quote
+//! for terms the provider has since changed is refused before anything is signed.
+
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowState};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use trustyclaw_client::pda;
+
+use crate::wire;
+
+/// Payment scheme quotes carry, so a payer routes them back to this adapter
+pub const SCHEME: &str = "trustyclaw-escrow";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum QuoteError {
+    #[error("escrow is {0:?}, not an open listing")]
+    NotOpen(EscrowState),
+    #[error("listing stopped accepting funding at {expires_at}")]
+    Expired { expires_at: i64 },
+    #[error("listing cannot be paid through the adapter: {0}")]
+    Unsupported(&'static str),
+    #[error("quote no longer matches the listing")]
+    Stale,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PaymentQuote {
+    /// Always [`SCHEME`]
+    pub scheme: String,
+    /// Escrow account the payment funds
+    #[serde(with = "wire::pubkey")]
+    pub escrow: Pubkey,
+    /// Provider the escrow releases to
+    #[serde(with = "wire::pubkey")]
+    pub pay_to: Pubkey,
+    /// Mint the price is paid in
+    #[serde(with = "wire::pubkey")]
+    pub asset: Pubkey,
+    /// Exact price in the mint's base units
+    #[serde(with = "wire::amount")]
+    pub amount: u64,
+    pub decimals: u8,
+    /// Listing terms the payer acknowledges by funding
+    #[serde(with = "wire::hash")]
+    pub terms_hash: [u8; 32],
+    pub description: String,
+    /// The listing's terms document
+    pub resource: String,
+    /// Last unix time the listing accepts funding (0 = open-ended)
+    pub expires_at: i64,
+}
+
+impl PaymentQuote {
+    /// Quote for funding `listing` at cluster time `now`
+    pub fn for_listing(listing: &EscrowAccount, now: i64) -> Result<Self, QuoteError> {
+        if listing.state != EscrowState::Created {
+            return Err(QuoteError::NotOpen(listing.state.clone()));
+        }
+        let expires_at = funding_expires_at(listing);
+        if !listing.funding_open(now) {
+            return Err(QuoteError::Expired { expires_at });
+        }
+        let terms = &listing.terms;
+        if terms.settle_in_credits {
+            return Err(QuoteError::Unsupported("micro-rentals settle in provider credits"));
+        }
+        if terms.requires_provider_acceptance {
+            return Err(QuoteError::Unsupported("the provider must accept each renter first"));
+        }
+        if terms.funding_rule.is_some() {
+            return Err(QuoteError::Unsupported("the price may be paid in instalments"));
+        }
+        if terms.provider_bond > 0 && !listing.bond_held {
+            return Err(QuoteError::Unsupported("the provider has not deposited the listing's bond"));
+        }
+        Ok(Self {
+            scheme: SCHEME.to_string(),
+            escrow: pda::escrow(&listing.provider, listing.escrow_id),
+            pay_to: listing.provider,
+            asset: listing.token_mint,
+            amount: terms.price_usdc,
+            decimals: terms.price_decimals,
+            terms_hash: listing.terms_hash,
+            description: terms.skill_name.as_str().to_string(),
+            resource: terms.metadata_uri.as_str().to_string(),
+            expires_at,
+        })
+    }
+
+    /// Check a quote the payer sent back against `listing` as it stands at `now`
+    pub fn check(&self, listing: &EscrowAccount, now: i64) -> Result<(), QuoteError> {
+        if *self != Self::for_listing(listing, now)? {
+            return Err(QuoteError::Stale);
+        }
+        Ok(())
+    }
+}
+
+/// Earlier of the funding deadline and the last second before sunset (0 = neither set)
+fn funding_expires_at(listing: &EscrowAccount) -> i64 {
+    let sunset = (listing.sunset_at > 0).then(|| listing.sunset_at - 1);
+    let deadline = (listing.terms.funding_deadline > 0).then_some(listing.terms.funding_deadline);
+    sunset.into_iter().chain(deadline).min().unwrap_or(0)
+}
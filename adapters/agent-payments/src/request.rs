@@ -0,0 +1,176 @@
+//! Protocol requests translated into escrow instructions
+//!
+//! | Request     | Escrow instruction                                            |
+//! |-------------|---------------------------------------------------------------|
+//! | `quote`     | none; the open listing is rendered                            |
+//! | `authorize` | `accept_escrow`, or `fund_with_sol` for wrapped-SOL listings  |
+//! | `capture`   | `complete_task`, releasing a delivered rental to the provider |
+//! | `refund`    | `cancel_escrow`, refunding the renter                         |
+//!
+//! The program decides who may sign each instruction; the adapter only refuses requests
+//! the escrow's state already rules out.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::token::spl_token::native_mint;
+use escrow::{EscrowAccount, EscrowState, FundOverrides};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use trustyclaw_client::amount::{AmountError, TokenAmount};
+use trustyclaw_client::{instructions, pda};
+
+use crate::quote::{PaymentQuote, QuoteError};
+use crate::wire;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RequestError {
+    #[error(transparent)]
+    Quote(#[from] QuoteError),
+    #[error(transparent)]
+    Amount(#[from] AmountError),
+    #[error("escrow account passed is not the one the request names")]
+    EscrowMismatch,
+    #[error("escrow is {0:?}, not funded")]
+    NotFunded(EscrowState),
+    #[error("provider has not submitted a deliverable to capture against")]
+    NotDelivered,
+    #[error("only the escrow's provider can refund it")]
+    NotProvider,
+    #[error("only the escrow's renter or its watchtower can capture it")]
+    NotRenter,
+}
+
+/// An incoming agent-payment request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum PaymentRequest {
+    /// Price the listing at `escrow`
+    Quote {
+        #[serde(with = "wire::pubkey")]
+        escrow: Pubkey,
+    },
+    /// Fund the quoted escrow from `payer`'s token account
+    Authorize {
+        quote: PaymentQuote,
+        #[serde(with = "wire::pubkey")]
+        payer: Pubkey,
+    },
+    /// Release a funded escrow to the provider; `authority` must be the renter or its watchtower
+    Capture {
+        #[serde(with = "wire::pubkey")]
+        escrow: Pubkey,
+        #[serde(with = "wire::pubkey")]
+        authority: Pubkey,
+    },
+    /// Refund a funded escrow to the renter; `authority` must be the provider
+    Refund {
+        #[serde(with = "wire::pubkey")]
+        escrow: Pubkey,
+        #[serde(with = "wire::pubkey")]
+        authority: Pubkey,
+    },
+}
+
+impl PaymentRequest {
+    /// Escrow account the caller must fetch before calling [`translate`]
+    pub fn escrow(&self) -> Pubkey {
+        match self {
+            Self::Quote { escrow } | Self::Capture { escrow, .. } | Self::Refund { escrow, .. } => *escrow,
+            Self::Authorize { quote, .. } => quote.escrow,
+        }
+    }
+}
+
+/// What answers a request
+#[derive(Debug, Clone, PartialEq)]
+pub enum Translation {
+    Quote(PaymentQuote),
+    /// To be signed by the request's payer or authority and submitted in order
+    Instructions(Vec<Instruction>),
+}
+
+/// Translate `request` against the `escrow` account it names, read at cluster time `now`.
+/// `token_program` owns the escrow's mint; `fee_treasury` is `Config::fee_treasury`,
+/// needed to capture whenever the protocol fee is non-zero.
+pub fn translate(
+    request: &PaymentRequest,
+    escrow: &EscrowAccount,
+    token_program: &Pubkey,
+    fee_treasury: Option<&Pubkey>,
+    now: i64,
+) -> Result<Translation, RequestError> {
+    if pda::escrow(&escrow.provider, escrow.escrow_id) != request.escrow() {
+        return Err(RequestError::EscrowMismatch);
+    }
+    let instruction = match request {
+        PaymentRequest::Quote { .. } => return Ok(Translation::Quote(PaymentQuote::for_listing(escrow, now)?)),
+        PaymentRequest::Authorize { quote, payer } => {
+            quote.check(escrow, now)?;
+            let amount = TokenAmount::from_base_units(quote.amount, quote.decimals)?;
+            if escrow.token_mint == native_mint::ID {
+                // The payer needs no wrapped-SOL account of its own
+                let instruction = instructions::fund_with_sol(
+                    payer,
+                    &escrow.provider,
+                    escrow.escrow_id,
+                    amount,
+                    FundOverrides::default(),
+                    quote.terms_hash,
+                    false,
+                )?;
+                return Ok(Translation::Instructions(vec![instruction]));
+            }
+            instructions::accept_escrow(
+                payer,
+                &escrow.provider,
+                escrow.escrow_id,
+                &escrow.token_mint,
+                token_program,
+                quote.decimals,
+                amount,
+                FundOverrides::default(),
+                quote.terms_hash,
+                false,
+            )?
+        }
+        PaymentRequest::Capture { authority, .. } => {
+            require_funded(escrow)?;
+            if escrow.first_delivered_at == 0 {
+                return Err(RequestError::NotDelivered);
+            }
+            if !escrow.is_renter_or_watchtower(authority) {
+                return Err(RequestError::NotRenter);
+            }
+            instructions::complete_task(
+                authority,
+                &escrow.provider,
+                escrow.escrow_id,
+                &escrow.renter,
+                &escrow.token_mint,
+                token_program,
+                fee_treasury,
+            )
+        }
+        PaymentRequest::Refund { authority, .. } => {
+            require_funded(escrow)?;
+            if *authority != escrow.provider {
+                return Err(RequestError::NotProvider);
+            }
+            instructions::cancel_escrow(
+                &escrow.provider,
+                escrow.escrow_id,
+                &escrow.renter,
+                &escrow.token_mint,
+                token_program,
+            )
+        }
+    };
+    Ok(Translation::Instructions(vec![instruction]))
+}
+
+fn require_funded(escrow: &EscrowAccount) -> Result<(), RequestError> {
+    match escrow.state {
+        EscrowState::Funded => Ok(()),
+        ref state => Err(RequestError::NotFunded(state.clone())),
+    }
+}
@@ -0,0 +1,79 @@
+//! Escrow state changes as payment-status callbacks
+//!
+//! Agent frameworks expect to hear when a payment is authorized, captured or refunded.
+//! [`PaymentEvent::transition`] turns an escrow's move between states into that status,
+//! and [`PaymentEvent::signed`] signs the callback with the listing's webhook key, so
+//! receivers verify it with `trustyclaw_client::webhook::verify` like any other
+//! TrustyClaw callback.
+
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowState};
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Keypair;
+use trustyclaw_client::{pda, webhook};
+
+use crate::wire;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    Authorized,
+    Captured,
+    Refunded,
+    Disputed,
+    /// The listing closed or expired before anyone paid
+    Voided,
+}
+
+impl PaymentStatus {
+    /// Status of a payment in an escrow that is `state`, or `None` while it is only quoted
+    pub fn of(state: &EscrowState) -> Option<Self> {
+        match state {
+            EscrowState::Created => None,
+            EscrowState::Funded | EscrowState::Subscribed => Some(Self::Authorized),
+            EscrowState::Completed | EscrowState::Vesting => Some(Self::Captured),
+            EscrowState::Cancelled => Some(Self::Refunded),
+            EscrowState::Disputed => Some(Self::Disputed),
+            EscrowState::Closed | EscrowState::Expired => Some(Self::Voided),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PaymentEvent {
+    pub status: PaymentStatus,
+    #[serde(with = "wire::pubkey")]
+    pub escrow: Pubkey,
+    #[serde(with = "wire::pubkey")]
+    pub asset: Pubkey,
+    /// Held in the escrow when the event was observed, in base units
+    #[serde(with = "wire::amount")]
+    pub amount: u64,
+    pub occurred_at: i64,
+}
+
+impl PaymentEvent {
+    /// Event for `escrow` having moved out of `previous`, observed at `now`; `None` if
+    /// its payment status did not change
+    pub fn transition(previous: &EscrowState, escrow: &EscrowAccount, now: i64) -> Option<Self> {
+        let status = PaymentStatus::of(&escrow.state)?;
+        if PaymentStatus::of(previous) == Some(status) {
+            return None;
+        }
+        Some(Self {
+            status,
+            escrow: pda::escrow(&escrow.provider, escrow.escrow_id),
+            asset: escrow.token_mint,
+            amount: escrow.amount,
+            occurred_at: now,
+        })
+    }
+
+    /// JSON body and webhook headers signed by `signer`, the listing's `webhook_signer`
+    pub fn signed(&self, signer: &Keypair) -> (Vec<u8>, Vec<(&'static str, String)>) {
+        let body = serde_json::to_vec(self).expect("payment events always serialize");
+        let headers = webhook::sign(signer, &self.escrow, self.occurred_at, &body);
+        (body, headers)
+    }
+}
@@ -0,0 +1,46 @@
+//! Serde encodings for the JSON wire
+//!
+//! Payment protocols carry addresses and hashes as base58 strings and amounts as
+//! decimal strings, so JSON readers that parse numbers as doubles keep every base unit.
+
+/// `Pubkey` as a base58 string
+pub mod pubkey {
+    use anchor_lang::prelude::Pubkey;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(key)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
+/// 32-byte hash as a base58 string
+pub mod hash {
+    use anchor_lang::solana_program::hash::Hash;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&Hash::new_from_array(*bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+        let hash: Hash = String::deserialize(deserializer)?.parse().map_err(D::Error::custom)?;
+        Ok(hash.to_bytes())
+    }
+}
+
+/// Base-unit amount as a decimal string
+pub mod amount {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(amount: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(amount)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
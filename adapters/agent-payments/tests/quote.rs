@@ -0,0 +1,78 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowState, EscrowTerms, FundingRule};
+use trustyclaw_agent_payments::{PaymentQuote, QuoteError, SCHEME};
+use trustyclaw_client::{pda, text};
+
+fn listing() -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        escrow_id: 7,
+        token_mint: Pubkey::new_unique(),
+        terms: EscrowTerms {
+            skill_name: text::skill_name("code-review").unwrap(),
+            metadata_uri: text::metadata_uri("https://example.com/terms.json").unwrap(),
+            price_usdc: 25_000_000,
+            price_decimals: 6,
+            funding_deadline: 2_000,
+            ..Default::default()
+        },
+        terms_hash: [9; 32],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn open_listings_quote_their_exact_price_and_terms() {
+    let listing = listing();
+    let quote = PaymentQuote::for_listing(&listing, 1_000).unwrap();
+    assert_eq!(quote.scheme, SCHEME);
+    assert_eq!(quote.escrow, pda::escrow(&listing.provider, 7));
+    assert_eq!(quote.pay_to, listing.provider);
+    assert_eq!(quote.asset, listing.token_mint);
+    assert_eq!((quote.amount, quote.decimals), (25_000_000, 6));
+    assert_eq!(quote.terms_hash, [9; 32]);
+    assert_eq!(quote.description, "code-review");
+    assert_eq!(quote.expires_at, 2_000);
+
+    let wire = serde_json::to_value(&quote).unwrap();
+    assert_eq!(wire["amount"], "25000000");
+    assert_eq!(wire["payTo"], listing.provider.to_string());
+    let echoed: PaymentQuote = serde_json::from_value(wire).unwrap();
+    assert_eq!(echoed, quote);
+}
+
+#[test]
+fn closed_expired_and_unsupported_listings_are_not_quoted() {
+    let mut funded = listing();
+    funded.state = EscrowState::Funded;
+    assert_eq!(
+        PaymentQuote::for_listing(&funded, 1_000),
+        Err(QuoteError::NotOpen(EscrowState::Funded))
+    );
+
+    let mut sunset = listing();
+    sunset.sunset_at = 1_500;
+    assert_eq!(PaymentQuote::for_listing(&sunset, 1_000).unwrap().expires_at, 1_499);
+    assert_eq!(
+        PaymentQuote::for_listing(&sunset, 1_500),
+        Err(QuoteError::Expired { expires_at: 1_499 })
+    );
+
+    let mut instalments = listing();
+    instalments.terms.funding_rule = Some(FundingRule::default());
+    assert!(matches!(
+        PaymentQuote::for_listing(&instalments, 1_000),
+        Err(QuoteError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn quotes_go_stale_when_the_terms_change() {
+    let mut listing = listing();
+    let quote = PaymentQuote::for_listing(&listing, 1_000).unwrap();
+    assert_eq!(quote.check(&listing, 1_000), Ok(()));
+
+    listing.terms.price_usdc = 30_000_000;
+    listing.terms_hash = [10; 32];
+    assert_eq!(quote.check(&listing, 1_000), Err(QuoteError::Stale));
+}
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use escrow::{EscrowAccount, EscrowState, EscrowTerms};
+use trustyclaw_agent_payments::{translate, PaymentQuote, PaymentRequest, QuoteError, RequestError, Translation};
+use trustyclaw_client::pda;
+
+fn listing() -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        escrow_id: 3,
+        token_mint: Pubkey::new_unique(),
+        terms: EscrowTerms {
+            price_usdc: 5_000_000,
+            price_decimals: 6,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn instruction_name(translation: Translation) -> &'static str {
+    let Translation::Instructions(instructions) = translation else {
+        panic!("expected instructions");
+    };
+    assert_eq!(instructions.len(), 1);
+    let data = &instructions[0].data[..8];
+    [
+        (escrow::instruction::AcceptEscrow::DISCRIMINATOR, "accept_escrow"),
+        (escrow::instruction::CompleteTask::DISCRIMINATOR, "complete_task"),
+        (escrow::instruction::CancelEscrow::DISCRIMINATOR, "cancel_escrow"),
+    ]
+    .into_iter()
+    .find(|(discriminator, _)| discriminator[..] == *data)
+    .map(|(_, name)| name)
+    .expect("known instruction")
+}
+
+#[test]
+fn requests_round_trip_as_tagged_json() {
+    let escrow = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let json = format!(r#"{{"type":"capture","escrow":"{escrow}","authority":"{authority}"}}"#);
+    let request: PaymentRequest = serde_json::from_str(&json).unwrap();
+    assert_eq!(request, PaymentRequest::Capture { escrow, authority });
+    assert_eq!(serde_json::to_string(&request).unwrap(), json);
+}
+
+#[test]
+fn quote_then_authorize_funds_the_listing() {
+    let token_program = Pubkey::new_unique();
+    let listing = listing();
+    let escrow = pda::escrow(&listing.provider, 3);
+
+    let Translation::Quote(quote) = translate(&PaymentRequest::Quote { escrow }, &listing, &token_program, None, 0).unwrap()
+    else {
+        panic!("expected a quote");
+    };
+    let authorize = PaymentRequest::Authorize {
+        quote: quote.clone(),
+        payer: Pubkey::new_unique(),
+    };
+    assert_eq!(
+        instruction_name(translate(&authorize, &listing, &token_program, None, 0).unwrap()),
+        "accept_escrow"
+    );
+
+    let tampered = PaymentRequest::Authorize {
+        quote: PaymentQuote { amount: 1, ..quote },
+        payer: Pubkey::new_unique(),
+    };
+    assert_eq!(
+        translate(&tampered, &listing, &token_program, None, 0),
+        Err(RequestError::Quote(QuoteError::Stale))
+    );
+}
+
+#[test]
+fn capture_and_refund_need_a_funded_escrow_and_capture_a_delivery() {
+    let token_program = Pubkey::new_unique();
+    let mut listing = listing();
+    let escrow = pda::escrow(&listing.provider, 3);
+    let authority = Pubkey::new_unique();
+    let capture = PaymentRequest::Capture { escrow, authority };
+    let refund = PaymentRequest::Refund { escrow, authority };
+
+    assert_eq!(
+        translate(&capture, &listing, &token_program, None, 0),
+        Err(RequestError::NotFunded(EscrowState::Created))
+    );
+
+    listing.state = EscrowState::Funded;
+    listing.renter = Pubkey::new_unique();
+    assert_eq!(
+        translate(&refund, &listing, &token_program, None, 0),
+        Err(RequestError::NotProvider)
+    );
+    let refund = PaymentRequest::Refund {
+        escrow,
+        authority: listing.provider,
+    };
+    assert_eq!(instruction_name(translate(&refund, &listing, &token_program, None, 0).unwrap()), "cancel_escrow");
+    assert_eq!(
+        translate(&capture, &listing, &token_program, None, 0),
+        Err(RequestError::NotDelivered)
+    );
+
+    listing.first_delivered_at = 100;
+    assert_eq!(
+        translate(&capture, &listing, &token_program, None, 0),
+        Err(RequestError::NotRenter)
+    );
+    let capture = PaymentRequest::Capture {
+        escrow,
+        authority: listing.renter,
+    };
+    assert_eq!(instruction_name(translate(&capture, &listing, &token_program, None, 0).unwrap()), "complete_task");
+}
+
+#[test]
+fn requests_must_name_the_escrow_passed() {
+    let other = PaymentRequest::Quote {
+        escrow: Pubkey::new_unique(),
+    };
+    assert_eq!(
+        translate(&other, &listing(), &Pubkey::new_unique(), None, 0),
+        Err(RequestError::EscrowMismatch)
+    );
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowState};
+use solana_sdk::signature::{Keypair, Signer};
+use trustyclaw_agent_payments::{PaymentEvent, PaymentStatus};
+use trustyclaw_client::pda;
+use trustyclaw_client::webhook::{verify, SIGNATURE_HEADER};
+
+fn escrow(state: EscrowState) -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        escrow_id: 11,
+        token_mint: Pubkey::new_unique(),
+        state,
+        amount: 5_000_000,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn escrow_states_map_to_payment_statuses() {
+    assert_eq!(PaymentStatus::of(&EscrowState::Created), None);
+    assert_eq!(PaymentStatus::of(&EscrowState::Funded), Some(PaymentStatus::Authorized));
+    assert_eq!(PaymentStatus::of(&EscrowState::Vesting), Some(PaymentStatus::Captured));
+    assert_eq!(PaymentStatus::of(&EscrowState::Cancelled), Some(PaymentStatus::Refunded));
+    assert_eq!(PaymentStatus::of(&EscrowState::Expired), Some(PaymentStatus::Voided));
+}
+
+#[test]
+fn only_status_changes_produce_events() {
+    let funded = escrow(EscrowState::Funded);
+    let event = PaymentEvent::transition(&EscrowState::Created, &funded, 1_000).unwrap();
+    assert_eq!(event.status, PaymentStatus::Authorized);
+    assert_eq!(event.escrow, pda::escrow(&funded.provider, 11));
+    assert_eq!(event.amount, 5_000_000);
+
+    // Completed and vesting are both a capture
+    let vesting = escrow(EscrowState::Vesting);
+    assert_eq!(PaymentEvent::transition(&EscrowState::Completed, &vesting, 1_000), None);
+    assert_eq!(PaymentEvent::transition(&EscrowState::Funded, &escrow(EscrowState::Created), 1_000), None);
+}
+
+#[test]
+fn events_are_signed_as_trustyclaw_webhooks() {
+    let signer = Keypair::new();
+    let event = PaymentEvent::transition(&EscrowState::Funded, &escrow(EscrowState::Completed), 1_000).unwrap();
+    let (body, headers) = event.signed(&signer);
+
+    let signature = headers.iter().find(|(name, _)| *name == SIGNATURE_HEADER).unwrap().1.parse().unwrap();
+    assert_eq!(verify(&signer.pubkey(), &event.escrow, 1_000, &body, &signature, 1_010), Ok(()));
+    let wire: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(wire["status"], "captured");
+    assert_eq!(wire["amount"], "5000000");
+}
@@ -0,0 +1,84 @@
+//! Off-chain acceptance of an escrow's terms, recorded on-chain
+//!
+//! The renter's wallet signs [`Acceptance::message`], a short text naming the escrow,
+//! its terms hash, both parties and an expiry, through the wallet-standard
+//! `signMessage` feature or [`Acceptance::sign`] with a local keypair. Anyone can then
+//! submit the signature with [`record`], e.g. the delegate that funds on the renter's
+//! behalf; the program keeps it in a `TermsAcceptance` account for disputes.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use escrow::{EscrowAccount, TermsAcceptance};
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+use crate::channel::ed25519_verify;
+use crate::instructions;
+
+/// What the renter agrees to: `escrow`'s terms as of `terms_hash`, until `expires_at`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Acceptance {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub terms_hash: [u8; 32],
+    pub expires_at: i64,
+}
+
+impl Acceptance {
+    /// `renter` accepting the current terms of `escrow_account`
+    pub fn new(escrow: Pubkey, escrow_account: &EscrowAccount, renter: Pubkey, expires_at: i64) -> Self {
+        Self {
+            escrow,
+            provider: escrow_account.provider,
+            renter,
+            terms_hash: escrow_account.terms_hash,
+            expires_at,
+        }
+    }
+
+    /// Bytes to hand the wallet for signing
+    pub fn message(&self) -> Vec<u8> {
+        TermsAcceptance::message(&self.escrow, &self.provider, &self.renter, &self.terms_hash, self.expires_at)
+    }
+
+    pub fn sign(self, renter: &Keypair) -> SignedAcceptance {
+        let signature = renter.sign_message(&self.message());
+        self.with_signature(signature)
+    }
+
+    /// Attach the signature a wallet returned for [`Self::message`]
+    pub fn with_signature(self, signature: Signature) -> SignedAcceptance {
+        SignedAcceptance {
+            acceptance: self,
+            signature,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedAcceptance {
+    pub acceptance: Acceptance,
+    pub signature: Signature,
+}
+
+impl SignedAcceptance {
+    pub fn verify(&self) -> bool {
+        self.signature.verify(self.acceptance.renter.as_ref(), &self.acceptance.message())
+    }
+}
+
+/// The two instructions that record `signed`; they must be sent in this order in one
+/// transaction, paid by `payer`
+pub fn record(payer: &Pubkey, signed: &SignedAcceptance) -> [Instruction; 2] {
+    let acceptance = &signed.acceptance;
+    [
+        ed25519_verify(&acceptance.renter, &signed.signature, &acceptance.message()),
+        instructions::record_acceptance(
+            payer,
+            &acceptance.escrow,
+            &acceptance.renter,
+            acceptance.expires_at,
+            signed.signature.into(),
+        ),
+    ]
+}
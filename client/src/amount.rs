@@ -0,0 +1,205 @@
+//! Decimal-safe token amounts
+//!
+//! On-chain instructions take raw base units (`u64`). Passing a display amount
+//! ("25" meaning 25 USDC) where base units are expected funds 1_000_000x less, and the
+//! reverse funds 1_000_000x more. These types always carry their decimals and only
+//! convert to or from `u64` through explicitly named constructors.
+
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// Decimals of Circle's USDC mint on Solana
+pub const USDC_DECIMALS: u8 = 6;
+
+/// Largest supported precision; 10^19 overflows `u64`
+pub const MAX_DECIMALS: u8 = 18;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AmountError {
+    #[error("amount has {got} decimals but the mint uses {expected}")]
+    DecimalsMismatch { expected: u8, got: u8 },
+    #[error("{0} decimals is not supported (max 18)")]
+    UnsupportedDecimals(u8),
+    #[error("'{0}' is not a valid decimal amount")]
+    Invalid(String),
+    #[error("'{input}' has more than {decimals} fractional digits")]
+    TooPrecise { input: String, decimals: u8 },
+    #[error("amount overflows u64 base units")]
+    Overflow,
+}
+
+/// An amount of any SPL token, in base units, tagged with the mint's decimals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenAmount {
+    base_units: u64,
+    decimals: u8,
+}
+
+impl TokenAmount {
+    pub fn from_base_units(base_units: u64, decimals: u8) -> Result<Self, AmountError> {
+        if decimals > MAX_DECIMALS {
+            return Err(AmountError::UnsupportedDecimals(decimals));
+        }
+        Ok(Self { base_units, decimals })
+    }
+
+    /// Whole display units, e.g. `from_whole(25, 6)` is 25.000000
+    pub fn from_whole(whole: u64, decimals: u8) -> Result<Self, AmountError> {
+        let scale = scale(decimals)?;
+        let base_units = whole.checked_mul(scale).ok_or(AmountError::Overflow)?;
+        Ok(Self { base_units, decimals })
+    }
+
+    /// Parse a display amount such as "12.5"; rejects more fractional digits than `decimals`
+    pub fn parse(input: &str, decimals: u8) -> Result<Self, AmountError> {
+        let scale = scale(decimals)?;
+        let invalid = || AmountError::Invalid(input.to_string());
+        let (whole, frac) = match input.split_once('.') {
+            Some((whole, frac)) => (whole, frac),
+            None => (input, ""),
+        };
+        if whole.is_empty() && frac.is_empty() {
+            return Err(invalid());
+        }
+        if !whole.chars().all(|c| c.is_ascii_digit()) || !frac.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        if frac.len() > decimals as usize {
+            return Err(AmountError::TooPrecise {
+                input: input.to_string(),
+                decimals,
+            });
+        }
+
+        let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| AmountError::Overflow)? };
+        let frac_units: u64 = if frac.is_empty() {
+            0
+        } else {
+            let padded = format!("{:0<width$}", frac, width = decimals as usize);
+            padded.parse().map_err(|_| invalid())?
+        };
+        let base_units = whole
+            .checked_mul(scale)
+            .and_then(|w| w.checked_add(frac_units))
+            .ok_or(AmountError::Overflow)?;
+        Ok(Self { base_units, decimals })
+    }
+
+    pub fn base_units(&self) -> u64 {
+        self.base_units
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Base units for an instruction against a mint with `mint_decimals`
+    pub fn base_units_for(&self, mint_decimals: u8) -> Result<u64, AmountError> {
+        if self.decimals != mint_decimals {
+            return Err(AmountError::DecimalsMismatch {
+                expected: mint_decimals,
+                got: self.decimals,
+            });
+        }
+        Ok(self.base_units)
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, AmountError> {
+        self.same_decimals(&other)?;
+        let base_units = self.base_units.checked_add(other.base_units).ok_or(AmountError::Overflow)?;
+        Ok(Self { base_units, ..self })
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, AmountError> {
+        self.same_decimals(&other)?;
+        let base_units = self.base_units.checked_sub(other.base_units).ok_or(AmountError::Overflow)?;
+        Ok(Self { base_units, ..self })
+    }
+
+    fn same_decimals(&self, other: &Self) -> Result<(), AmountError> {
+        if self.decimals != other.decimals {
+            return Err(AmountError::DecimalsMismatch {
+                expected: self.decimals,
+                got: other.decimals,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.decimals == 0 {
+            return write!(f, "{}", self.base_units);
+        }
+        let scale = 10u64.pow(self.decimals as u32);
+        write!(
+            f,
+            "{}.{:0width$}",
+            self.base_units / scale,
+            self.base_units % scale,
+            width = self.decimals as usize
+        )
+    }
+}
+
+/// A USDC amount (6 decimals)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UsdcAmount(u64);
+
+impl UsdcAmount {
+    pub const ZERO: Self = Self(0);
+
+    pub const fn from_base_units(base_units: u64) -> Self {
+        Self(base_units)
+    }
+
+    pub fn from_dollars(dollars: u64) -> Result<Self, AmountError> {
+        TokenAmount::from_whole(dollars, USDC_DECIMALS).map(Self::from)
+    }
+
+    pub const fn base_units(&self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for UsdcAmount {
+    type Err = AmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TokenAmount::parse(s, USDC_DECIMALS).map(Self::from)
+    }
+}
+
+impl From<TokenAmount> for UsdcAmount {
+    /// Rescales are never implicit: only call with a 6-decimal amount.
+    /// Prefer `UsdcAmount::try_from` when the decimals are not known statically.
+    fn from(amount: TokenAmount) -> Self {
+        debug_assert_eq!(amount.decimals, USDC_DECIMALS);
+        Self(amount.base_units)
+    }
+}
+
+impl From<UsdcAmount> for TokenAmount {
+    fn from(amount: UsdcAmount) -> Self {
+        TokenAmount {
+            base_units: amount.0,
+            decimals: USDC_DECIMALS,
+        }
+    }
+}
+
+impl fmt::Display for UsdcAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        TokenAmount::from(*self).fmt(f)
+    }
+}
+
+fn scale(decimals: u8) -> Result<u64, AmountError> {
+    if decimals > MAX_DECIMALS {
+        return Err(AmountError::UnsupportedDecimals(decimals));
+    }
+    Ok(10u64.pow(decimals as u32))
+}
@@ -0,0 +1,101 @@
+//! Prometheus exporter for escrow protocol health
+//!
+//! Serves the gauges of `trustyclaw_client::metrics` on every HTTP request, scanning
+//! all escrow accounts at scrape time. Set the scrape interval accordingly: each scrape
+//! costs one `getProgramAccounts` and a `getMultipleAccounts` per hundred escrows.
+//!
+//! ```text
+//! metrics-exporter --rpc http://127.0.0.1:8899 [--listen 127.0.0.1:9464]
+//! ```
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::ExitCode;
+
+use trustyclaw_client::metrics::{all_escrows, EscrowMetrics};
+use trustyclaw_client::rpc::{RpcClient, RpcError};
+
+struct Args {
+    rpc: String,
+    listen: String,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut rpc = None;
+    let mut listen = "127.0.0.1:9464".to_string();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("{flag} needs a value"));
+        match flag.as_str() {
+            "--rpc" => rpc = Some(value()?),
+            "--listen" => listen = value()?,
+            other => return Err(format!("unknown argument '{other}'")),
+        }
+    }
+
+    Ok(Args {
+        rpc: rpc.ok_or("--rpc is required")?,
+        listen,
+    })
+}
+
+fn scrape(rpc: &RpcClient) -> Result<String, RpcError> {
+    let now = rpc.clock()?.unix_timestamp;
+    let escrows = all_escrows(rpc)?;
+    Ok(EscrowMetrics::collect(&escrows, now).render())
+}
+
+fn respond(mut stream: TcpStream, rpc: &RpcClient) -> std::io::Result<()> {
+    // The request itself does not matter; drain its head before answering
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let (status, body) = match scrape(rpc) {
+        Ok(body) => ("200 OK", body),
+        Err(e) => {
+            eprintln!("metrics-exporter: scrape failed: {e}");
+            ("503 Service Unavailable", format!("scrape failed: {e}\n"))
+        }
+    };
+    write!(
+        stream,
+        "HTTP/1.0 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("metrics-exporter: {e}");
+            return ExitCode::from(2);
+        }
+    };
+    let rpc = match RpcClient::new(&args.rpc) {
+        Ok(rpc) => rpc,
+        Err(e) => {
+            eprintln!("metrics-exporter: {e}");
+            return ExitCode::from(2);
+        }
+    };
+    let listener = match TcpListener::bind(&args.listen) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("metrics-exporter: cannot listen on {}: {e}", args.listen);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| respond(stream, &rpc));
+        if let Err(e) = result {
+            eprintln!("metrics-exporter: {e}");
+        }
+    }
+    ExitCode::SUCCESS
+}
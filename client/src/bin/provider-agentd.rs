@@ -0,0 +1,216 @@
+//! Provider auto-responder
+//!
+//! Watches one of the provider's escrows for `EscrowFunded` events, runs the configured workload
+//! command or webhook, and submits the returned artifact hash via `submit_deliverable`.
+//! With `--webhook-signer`, callbacks are signed with that keypair, which must match the
+//! listing's on-chain `webhook_signer`. With `--nonce-account`, deliverables are signed
+//! against that durable nonce (whose authority must be the provider), so a submission
+//! interrupted by an outage cannot expire before it is resent.
+//!
+//! ```text
+//! provider-agentd --rpc http://127.0.0.1:8899 --keypair provider.json
+//!                 (--command '<shell command>' | --webhook http://127.0.0.1:8080/run
+//!                  [--webhook-signer notifier.json])
+//!                 [--escrow-id <id>] [--nonce-account <PUBKEY>] [--interval <secs>]
+//! ```
+
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use trustyclaw_client::decode::decode_escrow;
+use trustyclaw_client::pda;
+use trustyclaw_client::provider_agent::{deliverable_instruction, funded_events, AgentError, Responder};
+use trustyclaw_client::rpc::{HttpEndpoint, RpcClient};
+use trustyclaw_client::submit::{Lifetime, Outcome, Submitter};
+use trustyclaw_client::PreflightRpc;
+
+struct Args {
+    rpc: String,
+    keypair: String,
+    responder: Responder,
+    webhook_signer: Option<String>,
+    escrow_id: u64,
+    nonce_account: Option<Pubkey>,
+    interval: Duration,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut rpc = None;
+    let mut keypair = None;
+    let mut responder = None;
+    let mut webhook_signer = None;
+    let mut escrow_id = 0;
+    let mut nonce_account = None;
+    let mut interval = Duration::from_secs(5);
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("{flag} needs a value"));
+        match flag.as_str() {
+            "--rpc" => rpc = Some(value()?),
+            "--keypair" => keypair = Some(value()?),
+            "--command" => responder = Some(Responder::Command(value()?)),
+            "--webhook" => {
+                let endpoint = HttpEndpoint::new(&value()?).map_err(|e| e.to_string())?;
+                responder = Some(Responder::Webhook(endpoint.with_timeout(Duration::from_secs(600))));
+            }
+            "--webhook-signer" => webhook_signer = Some(value()?),
+            "--escrow-id" => escrow_id = value()?.parse().map_err(|_| "invalid --escrow-id")?,
+            "--nonce-account" => {
+                nonce_account = Some(value()?.parse().map_err(|_| "invalid --nonce-account pubkey")?)
+            }
+            "--interval" => {
+                interval = Duration::from_secs(value()?.parse().map_err(|_| "invalid --interval")?)
+            }
+            other => return Err(format!("unknown argument '{other}'")),
+        }
+    }
+
+    Ok(Args {
+        rpc: rpc.ok_or("--rpc is required")?,
+        keypair: keypair.ok_or("--keypair is required")?,
+        responder: responder.ok_or("one of --command or --webhook is required")?,
+        webhook_signer,
+        escrow_id,
+        nonce_account,
+        interval,
+    })
+}
+
+/// Handle every transaction since `cursor`, returning the new cursor
+fn poll(
+    rpc: &RpcClient,
+    provider: &Keypair,
+    escrow_id: u64,
+    lifetime: Lifetime,
+    responder: &Responder,
+    cursor: Option<Signature>,
+) -> Result<Option<Signature>, AgentError> {
+    let escrow = pda::escrow(&provider.pubkey(), escrow_id);
+    let signatures = rpc.signatures_for_address(&escrow, cursor.as_ref())?;
+    let Some(newest) = signatures.first().copied() else {
+        return Ok(cursor);
+    };
+
+    for signature in signatures.iter().rev() {
+        for event in funded_events(&rpc.transaction_logs(signature)?, &provider.pubkey()) {
+            println!("{}: funded by {} for {}", event.escrow, event.renter, event.amount);
+            let artifact = match responder.respond(&event) {
+                Ok(artifact) => artifact,
+                Err(e) => {
+                    eprintln!("{}: workload failed: {e}", event.escrow);
+                    continue;
+                }
+            };
+
+            let ix = deliverable_instruction(&provider.pubkey(), event.escrow_id, &artifact);
+            match Submitter::new(rpc).submit(&provider.pubkey(), &[provider], &[ix], lifetime) {
+                Ok(Outcome::Confirmed(signature)) => println!("{}: submitted deliverable ({signature})", event.escrow),
+                Ok(Outcome::Failed { signature, error }) => {
+                    eprintln!("{}: deliverable failed ({signature}): {error}", event.escrow)
+                }
+                Ok(Outcome::Expired) => eprintln!("{}: deliverable never landed", event.escrow),
+                Err(e) => eprintln!("{}: deliverable submission abandoned: {e}", event.escrow),
+            }
+        }
+    }
+    Ok(Some(newest))
+}
+
+/// Sign webhook callbacks with `signer` once it is confirmed as the listing's registered key
+fn signed_responder(
+    rpc: &RpcClient,
+    provider: &Pubkey,
+    escrow_id: u64,
+    responder: Responder,
+    signer: Keypair,
+) -> Result<Responder, String> {
+    let Responder::Webhook(endpoint) = responder else {
+        return Err("--webhook-signer needs --webhook".to_string());
+    };
+    let data = rpc
+        .account_data(&pda::escrow(provider, escrow_id))
+        .map_err(|e| e.to_string())?
+        .ok_or("provider has no listing")?;
+    let registered = decode_escrow(&data).map_err(|e| e.to_string())?.escrow.webhook_signer;
+    if registered != signer.pubkey() {
+        return Err(format!(
+            "listing registers webhook signer {registered}, not {}; run set_webhook_signer first",
+            signer.pubkey()
+        ));
+    }
+    Ok(Responder::SignedWebhook(endpoint, Arc::new(signer)))
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("provider-agentd: {e}");
+            return ExitCode::from(2);
+        }
+    };
+    let provider = match read_keypair_file(&args.keypair) {
+        Ok(keypair) => keypair,
+        Err(e) => {
+            eprintln!("provider-agentd: cannot read keypair {}: {e}", args.keypair);
+            return ExitCode::from(2);
+        }
+    };
+    let rpc = match RpcClient::new(&args.rpc) {
+        Ok(rpc) => rpc,
+        Err(e) => {
+            eprintln!("provider-agentd: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let responder = match args.webhook_signer {
+        None => args.responder,
+        Some(path) => {
+            let signer = match read_keypair_file(&path) {
+                Ok(keypair) => keypair,
+                Err(e) => {
+                    eprintln!("provider-agentd: cannot read keypair {path}: {e}");
+                    return ExitCode::from(2);
+                }
+            };
+            match signed_responder(&rpc, &provider.pubkey(), args.escrow_id, args.responder, signer) {
+                Ok(responder) => responder,
+                Err(e) => {
+                    eprintln!("provider-agentd: {e}");
+                    return ExitCode::from(2);
+                }
+            }
+        }
+    };
+
+    // Start from the current tip; earlier fundings are not replayed
+    let escrow = pda::escrow(&provider.pubkey(), args.escrow_id);
+    let mut cursor = match rpc.signatures_for_address(&escrow, None) {
+        Ok(signatures) => signatures.first().copied(),
+        Err(e) => {
+            eprintln!("provider-agentd: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let lifetime = match args.nonce_account {
+        Some(account) => Lifetime::DurableNonce {
+            account,
+            authority: provider.pubkey(),
+        },
+        None => Lifetime::Blockhash,
+    };
+    loop {
+        match poll(&rpc, &provider, args.escrow_id, lifetime, &responder, cursor) {
+            Ok(next) => cursor = next,
+            Err(e) => eprintln!("provider-agentd: poll failed: {e}"),
+        }
+        thread::sleep(args.interval);
+    }
+}
@@ -0,0 +1,146 @@
+//! Renter watchtower
+//!
+//! Polls a renter's funded escrows and, signing with a delegate key appointed through
+//! `set_watchtower` (or the renter's own key), disputes lapsed rentals or cranks their
+//! timeout refunds.
+//!
+//! ```text
+//! watchtower --rpc http://127.0.0.1:8899 --keypair delegate.json --renter <PUBKEY>
+//!            [--on-expiry refund|dispute] [--interval <secs>] [--once]
+//! ```
+
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use trustyclaw_client::preflight::{preflight, SimulationFailure};
+use trustyclaw_client::rpc::{RpcClient, RpcError};
+use trustyclaw_client::submit::{Lifetime, Outcome, SubmitError, Submitter};
+use trustyclaw_client::watchtower::{build_action, next_action, renter_escrows, OnExpiry};
+
+struct Args {
+    rpc: String,
+    keypair: String,
+    renter: Pubkey,
+    on_expiry: OnExpiry,
+    interval: Duration,
+    once: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut rpc = None;
+    let mut keypair = None;
+    let mut renter = None;
+    let mut on_expiry = OnExpiry::default();
+    let mut interval = Duration::from_secs(60);
+    let mut once = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("{flag} needs a value"));
+        match flag.as_str() {
+            "--rpc" => rpc = Some(value()?),
+            "--keypair" => keypair = Some(value()?),
+            "--renter" => renter = Some(value()?.parse().map_err(|_| "invalid --renter pubkey")?),
+            "--on-expiry" => {
+                on_expiry = match value()?.as_str() {
+                    "refund" => OnExpiry::Refund,
+                    "dispute" => OnExpiry::Dispute,
+                    other => return Err(format!("unknown --on-expiry policy '{other}'")),
+                }
+            }
+            "--interval" => {
+                interval = Duration::from_secs(value()?.parse().map_err(|_| "invalid --interval")?)
+            }
+            "--once" => once = true,
+            other => return Err(format!("unknown argument '{other}'")),
+        }
+    }
+
+    Ok(Args {
+        rpc: rpc.ok_or("--rpc is required")?,
+        keypair: keypair.ok_or("--keypair is required")?,
+        renter: renter.ok_or("--renter is required")?,
+        on_expiry,
+        interval,
+        once,
+    })
+}
+
+fn sweep(rpc: &RpcClient, signer: &Keypair, args: &Args) -> Result<(), RpcError> {
+    let now = rpc.clock()?.unix_timestamp;
+    for (address, escrow) in renter_escrows(rpc, &args.renter)? {
+        let Some(action) = next_action(&escrow, now, args.on_expiry) else {
+            continue;
+        };
+        let Some(token_program) = rpc.account_owner(&escrow.token_mint)? else {
+            eprintln!("{address}: skipping {action:?}, mint {} not found", escrow.token_mint);
+            continue;
+        };
+        let ix = build_action(&signer.pubkey(), &escrow, &token_program, action);
+
+        let report = preflight(rpc, &signer.pubkey(), std::slice::from_ref(&ix), &[])?;
+        match &report.failure {
+            None => {}
+            Some(SimulationFailure::Program { error, .. }) => {
+                eprintln!("{address}: skipping {action:?}, simulation failed with {error}");
+                continue;
+            }
+            Some(other) => {
+                eprintln!("{address}: skipping {action:?}, simulation failed with {other:?}");
+                continue;
+            }
+        }
+
+        match Submitter::new(rpc).submit(&signer.pubkey(), &[signer], &[ix], Lifetime::Blockhash) {
+            Ok(Outcome::Confirmed(signature)) => println!("{address}: submitted {action:?} ({signature})"),
+            Ok(Outcome::Failed { signature, error }) => {
+                eprintln!("{address}: {action:?} failed ({signature}): {error}")
+            }
+            // The next sweep retries anything that did not land
+            Ok(Outcome::Expired) => eprintln!("{address}: {action:?} never landed"),
+            Err(SubmitError::Unreachable { source, .. }) => return Err(source),
+            Err(e) => eprintln!("{address}: {action:?} not submitted: {e}"),
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("watchtower: {e}");
+            return ExitCode::from(2);
+        }
+    };
+    let signer = match read_keypair_file(&args.keypair) {
+        Ok(keypair) => keypair,
+        Err(e) => {
+            eprintln!("watchtower: cannot read keypair {}: {e}", args.keypair);
+            return ExitCode::from(2);
+        }
+    };
+    let rpc = match RpcClient::new(&args.rpc) {
+        Ok(rpc) => rpc,
+        Err(e) => {
+            eprintln!("watchtower: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    loop {
+        if let Err(e) = sweep(&rpc, &signer, &args) {
+            eprintln!("watchtower: sweep failed: {e}");
+            if args.once {
+                return ExitCode::FAILURE;
+            }
+        }
+        if args.once {
+            return ExitCode::SUCCESS;
+        }
+        thread::sleep(args.interval);
+    }
+}
@@ -0,0 +1,93 @@
+//! Off-chain side of settlement channels
+//!
+//! The payer signs a cumulative balance after each rental; the payee keeps only the
+//! latest update and submits it with [`settle`] whenever it wants to be paid.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+use escrow::SettlementChannel;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+use crate::instructions;
+use crate::pda;
+
+// num_signatures (1) + padding (1) + seven u16 offsets (14)
+const DATA_START: usize = 16;
+
+/// A payer-signed cumulative balance for one channel
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelUpdate {
+    pub channel: Pubkey,
+    pub cumulative: u64,
+    pub nonce: u64,
+    pub signature: Signature,
+}
+
+impl ChannelUpdate {
+    /// Sign `cumulative` as the total owed on the channel from `payer` to `payee`
+    pub fn sign(payer: &Keypair, payee: &Pubkey, mint: &Pubkey, cumulative: u64, nonce: u64) -> Self {
+        let channel = pda::settlement_channel(&payer.pubkey(), payee, mint);
+        let signature = payer.sign_message(&SettlementChannel::message(&channel, cumulative, nonce));
+        Self {
+            channel,
+            cumulative,
+            nonce,
+            signature,
+        }
+    }
+
+    pub fn message(&self) -> Vec<u8> {
+        SettlementChannel::message(&self.channel, self.cumulative, self.nonce)
+    }
+
+    pub fn verify(&self, payer: &Pubkey) -> bool {
+        self.signature.verify(payer.as_ref(), &self.message())
+    }
+}
+
+/// Ed25519 program instruction checking `signature` by `signer` over `message`,
+/// with the key, signature and message all carried inline
+pub fn ed25519_verify(signer: &Pubkey, signature: &Signature, message: &[u8]) -> Instruction {
+    let key_offset = DATA_START;
+    let sig_offset = key_offset + 32;
+    let msg_offset = sig_offset + 64;
+    let inline = u16::MAX;
+
+    let mut data = Vec::with_capacity(msg_offset + message.len());
+    data.extend_from_slice(&[1, 0]);
+    for field in [
+        sig_offset as u16,
+        inline,
+        key_offset as u16,
+        inline,
+        msg_offset as u16,
+        message.len() as u16,
+        inline,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// The two instructions that settle `update`; they must be sent in this order in one transaction
+pub fn settle(
+    payer: &Pubkey,
+    payee: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    update: &ChannelUpdate,
+) -> [Instruction; 2] {
+    [
+        ed25519_verify(payer, &update.signature, &update.message()),
+        instructions::settle_channel(payer, payee, mint, token_program, update.cumulative, update.nonce),
+    ]
+}
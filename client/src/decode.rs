@@ -0,0 +1,158 @@
+//! Version-tolerant account decoding for indexers
+//!
+//! Accounts keep the `LEN` of the layout that wrote them until they are migrated, so
+//! after the discriminator the data length identifies that layout. Every known layout
+//! is decoded into the current account struct; fields the layout did not have yet are
+//! left at their defaults, and [`EscrowView::layout`] says which ones are real. Escrow
+//! layouts are defined by the program in [`escrow::layout`], whose `migrate_escrow`
+//! reads them too.
+//!
+//! Since [`EscrowLayout::V13`] and [`AgentLayout::V3`] accounts end in a zeroed
+//! reserve, and later fields are carved out of it without changing the length. Such
+//! fields read as zero on accounts written before them, which is the same default the
+//! older layouts decode to, so they need no new layout variant.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use escrow::layout::read_escrow;
+pub use escrow::layout::EscrowLayout;
+use escrow::EscrowAccount;
+use reputation::Agent;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error("account is shorter than its discriminator")]
+    TooShort,
+    #[error("discriminator does not belong to a decodable account")]
+    UnknownDiscriminator,
+    #[error("no known layout is {0} bytes long")]
+    UnknownLayout(usize),
+    #[error("account data does not match its layout: {0}")]
+    Malformed(#[from] std::io::Error),
+}
+
+/// Historical reputation `Agent` layouts, oldest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AgentLayout {
+    /// Original deployment, before external reputation imports
+    V0,
+    /// `external_score_sum` and `external_sources`
+    V1,
+    /// Time-weighted average reputation
+    V2,
+    /// `ACCOUNT_RESERVE` bytes reserved for future fields
+    V3,
+}
+
+impl AgentLayout {
+    pub const ALL: [AgentLayout; 4] = [Self::V0, Self::V1, Self::V2, Self::V3];
+    pub const CURRENT: AgentLayout = Self::V3;
+
+    pub fn account_len(self) -> usize {
+        const V2: usize = Agent::LEN - reputation::ACCOUNT_RESERVE;
+        const V1: usize = V2 - 8 - 8;
+        match self {
+            Self::V0 => V1 - 8 - 2,
+            Self::V1 => V1,
+            Self::V2 => V2,
+            Self::V3 => Agent::LEN,
+        }
+    }
+
+    pub fn from_len(len: usize) -> Option<Self> {
+        Self::ALL.into_iter().find(|layout| layout.account_len() == len)
+    }
+}
+
+#[derive(Clone)]
+pub struct EscrowView {
+    pub layout: EscrowLayout,
+    pub escrow: EscrowAccount,
+}
+
+impl EscrowView {
+    /// Whether the program rejects the escrow with `LayoutVersionMismatch` until
+    /// `migrate_escrow` rewrites it
+    pub fn needs_migration(&self) -> bool {
+        self.escrow.version != escrow::ESCROW_VERSION
+    }
+}
+
+#[derive(Clone)]
+pub struct AgentView {
+    pub layout: AgentLayout,
+    pub agent: Agent,
+}
+
+#[derive(Clone)]
+pub enum AccountView {
+    Escrow(Box<EscrowView>),
+    Agent(Box<AgentView>),
+}
+
+/// Decode an escrow or reputation agent account written by any known layout
+pub fn decode(data: &[u8]) -> Result<AccountView, DecodeError> {
+    let discriminator = data.get(..8).ok_or(DecodeError::TooShort)?;
+    if discriminator == EscrowAccount::DISCRIMINATOR {
+        decode_escrow(data).map(|view| AccountView::Escrow(Box::new(view)))
+    } else if discriminator == Agent::DISCRIMINATOR {
+        decode_agent(data).map(|view| AccountView::Agent(Box::new(view)))
+    } else {
+        Err(DecodeError::UnknownDiscriminator)
+    }
+}
+
+pub fn decode_escrow(data: &[u8]) -> Result<EscrowView, DecodeError> {
+    let body = body_of(data, &EscrowAccount::DISCRIMINATOR)?;
+    let layout = EscrowLayout::from_len(data.len()).ok_or(DecodeError::UnknownLayout(data.len()))?;
+    let mut escrow = EscrowAccount::default();
+    read_escrow(layout, body, &mut escrow)?;
+    Ok(EscrowView { layout, escrow })
+}
+
+pub fn decode_agent(data: &[u8]) -> Result<AgentView, DecodeError> {
+    let body = body_of(data, &Agent::DISCRIMINATOR)?;
+    let layout = AgentLayout::from_len(data.len()).ok_or(DecodeError::UnknownLayout(data.len()))?;
+    let buf = &mut &body[..];
+
+    let mut agent = Agent {
+        authority: read(buf)?,
+        state: read(buf)?,
+        name: read(buf)?,
+        bio: read(buf)?,
+        reputation_score: read(buf)?,
+        total_ratings: read(buf)?,
+        rating_sum: read(buf)?,
+        created_at: read(buf)?,
+        updated_at: read(buf)?,
+        is_active: read(buf)?,
+        external_score_sum: 0,
+        external_sources: 0,
+        twar_score: 0,
+        twar_updated_at: 0,
+        _reserved: [0; reputation::ACCOUNT_RESERVE],
+    };
+    if layout >= AgentLayout::V1 {
+        agent.external_score_sum = read(buf)?;
+        agent.external_sources = read(buf)?;
+    }
+    if layout >= AgentLayout::V2 {
+        agent.twar_score = read(buf)?;
+        agent.twar_updated_at = read(buf)?;
+    }
+    if layout >= AgentLayout::V3 {
+        agent._reserved = read(buf)?;
+    }
+    Ok(AgentView { layout, agent })
+}
+
+fn body_of<'a>(data: &'a [u8], discriminator: &[u8]) -> Result<&'a [u8], DecodeError> {
+    let (head, body) = data.split_at_checked(8).ok_or(DecodeError::TooShort)?;
+    if head != discriminator {
+        return Err(DecodeError::UnknownDiscriminator);
+    }
+    Ok(body)
+}
+
+fn read<T: AnchorDeserialize>(buf: &mut &[u8]) -> Result<T, DecodeError> {
+    Ok(T::deserialize(buf)?)
+}
@@ -0,0 +1,106 @@
+//! Devnet sandbox on an escrow program built with `devnet-demo`
+//!
+//! [`bootstrap`] funds a provider and a renter from the demo faucet, registers both as
+//! reputation agents and leaves one open sample listing and one funded sample rental,
+//! so an integrator can walk the rest of the lifecycle from there. The demo mint must
+//! exist (`instructions::create_demo_mint`) and the reputation program be initialized.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::InstructionData;
+use anchor_spl::token;
+use escrow::{EscrowAccount, EscrowTerms, FundOverrides, OverridePolicy};
+
+use crate::amount::{AmountError, TokenAmount};
+use crate::{instructions, pda, text};
+
+/// Price of the sample escrows, in whole demo tokens
+pub const DEMO_PRICE: u64 = 5;
+/// Demo tokens airdropped to the renter; a few rentals' worth
+pub const DEMO_RENTER_AIRDROP: u64 = 50;
+
+/// Terms of the sample escrows, priced in the demo mint
+pub fn sample_terms(skill: &str) -> EscrowTerms {
+    let dispute_window_seconds = 24 * 60 * 60;
+    let review_window_seconds = 60 * 60;
+    EscrowTerms {
+        skill_name: text::truncated(skill),
+        category: "demo".to_string(),
+        duration_seconds: 60 * 60,
+        price_usdc: DEMO_PRICE * 10u64.pow(escrow::DEMO_MINT_DECIMALS as u32),
+        dispute_window_seconds,
+        review_window_seconds,
+        override_policy: OverridePolicy {
+            min_dispute_window_seconds: dispute_window_seconds,
+            max_dispute_window_seconds: dispute_window_seconds,
+            min_review_window_seconds: review_window_seconds,
+            max_review_window_seconds: review_window_seconds,
+            allow_arbiter_override: false,
+        },
+        price_decimals: escrow::DEMO_MINT_DECIMALS,
+        ..Default::default()
+    }
+}
+
+/// Transactions, in order, setting up the sandbox: `provider`'s open listing at
+/// `first_escrow_id` and the rental `renter` funds at `first_escrow_id + 1`. Each group
+/// is signed by the accounts it names and fits in one transaction.
+pub fn bootstrap(
+    provider: &Pubkey,
+    renter: &Pubkey,
+    first_escrow_id: u64,
+) -> std::result::Result<Vec<Vec<Instruction>>, AmountError> {
+    let mint = pda::demo_mint();
+    let rental_id = first_escrow_id + 1;
+    let rental_terms = sample_terms("demo-summarizer");
+    let terms_hash = EscrowAccount::compute_terms_hash(&rental_terms, &mint).expect("sample terms serialize");
+
+    Ok(vec![
+        vec![
+            // Creates the provider's token account, which listings are paid into
+            instructions::airdrop_demo_tokens(provider, TokenAmount::from_whole(1, escrow::DEMO_MINT_DECIMALS)?)?,
+            instructions::airdrop_demo_tokens(renter, TokenAmount::from_whole(DEMO_RENTER_AIRDROP, escrow::DEMO_MINT_DECIMALS)?)?,
+            register_agent(provider, "demo-provider"),
+            register_agent(renter, "demo-renter"),
+        ],
+        vec![instructions::initialize_escrow(provider, first_escrow_id, &mint, &token::ID, sample_terms("demo-code-review"))],
+        vec![instructions::initialize_escrow(provider, rental_id, &mint, &token::ID, rental_terms)],
+        vec![instructions::accept_escrow(
+            renter,
+            provider,
+            rental_id,
+            &mint,
+            &token::ID,
+            escrow::DEMO_MINT_DECIMALS,
+            TokenAmount::from_whole(DEMO_PRICE, escrow::DEMO_MINT_DECIMALS)?,
+            FundOverrides::default(),
+            terms_hash,
+            false,
+        )?],
+    ])
+}
+
+/// Register `authority` as a reputation agent
+fn register_agent(authority: &Pubkey, name: &str) -> Instruction {
+    let accounts = reputation::accounts::RegisterAgent {
+        state: pda::reputation_state(),
+        agent: pda::reputation_agent(authority),
+        authority: *authority,
+        system_program: system_program::ID,
+        activity: reputation::accounts::ActivityAccounts {
+            activity_log_program: None,
+            activity_feed: None,
+            activity_authority: None,
+        },
+    };
+    Instruction {
+        program_id: reputation::ID,
+        accounts: accounts.to_account_metas(None),
+        data: reputation::instruction::RegisterAgent {
+            name: name.to_string(),
+            bio: "Sample agent from the devnet demo".to_string(),
+        }
+        .data(),
+    }
+}
@@ -0,0 +1,214 @@
+//! Registry of custom error codes for the TrustyClaw programs
+//!
+//! Anchor reports failures as a bare `Custom(code)`; this maps `(program, code)` back to
+//! the variant name and message defined in each program's error enum.
+
+use anchor_lang::prelude::Pubkey;
+use escrow::EscrowError;
+use reputation::ErrorCode as ReputationError;
+
+/// A program error resolved from its numeric code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredError {
+    pub program: &'static str,
+    pub code: u32,
+    pub name: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for RegisteredError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::{} ({}): {}", self.program, self.name, self.code, self.message)
+    }
+}
+
+const ESCROW_ERRORS: &[EscrowError] = &[
+    EscrowError::InvalidState,
+    EscrowError::TimeoutNotElapsed,
+    EscrowError::Unauthorized,
+    EscrowError::InsufficientFunds,
+    EscrowError::ProviderAtCapacity,
+    EscrowError::InvalidSettlementMode,
+    EscrowError::DecryptionKeyRequired,
+    EscrowError::DecryptionKeyMismatch,
+    EscrowError::KeyCiphertextTooLong,
+    EscrowError::InvalidOverridePolicy,
+    EscrowError::OverrideOutOfRange,
+    EscrowError::DisputeWindowClosed,
+    EscrowError::InvalidRemainingAccounts,
+    EscrowError::InvalidOracle,
+    EscrowError::ReentrancyDetected,
+    EscrowError::TermsHashMismatch,
+    EscrowError::InvalidActivityFeed,
+    EscrowError::InvalidEmissionSchedule,
+    EscrowError::RedemptionDisabled,
+    EscrowError::InsufficientPoints,
+    EscrowError::InvalidSlaTerms,
+    EscrowError::SlaNotTracked,
+    EscrowError::SlaNotBreached,
+    EscrowError::RenterTokenAccountRequired,
+    EscrowError::RandomnessPhaseClosed,
+    EscrowError::InvalidCommitment,
+    EscrowError::AlreadyCommitted,
+    EscrowError::InvalidScreeningConfig,
+    EscrowError::ScreeningPending,
+    EscrowError::ListingRateLimited,
+    EscrowError::CategoryTooLong,
+    EscrowError::InvalidFundingRule,
+    EscrowError::InvalidReputationAccount,
+    EscrowError::DeferredBalanceOutstanding,
+    EscrowError::ChannelClosing,
+    EscrowError::StaleChannelUpdate,
+    EscrowError::InvalidChannelSignature,
+    EscrowError::InvalidAccessCommitment,
+    EscrowError::RenterUnassigned,
+    EscrowError::InvalidSplit,
+    EscrowError::FeatureDisabled,
+    EscrowError::ProviderAcceptanceRequired,
+    EscrowError::ProviderAcceptanceNotRequired,
+    EscrowError::AcceptanceWindowClosed,
+    EscrowError::RevisionNotRequested,
+    EscrowError::TooManyRevisions,
+    EscrowError::InvalidTrialTerms,
+    EscrowError::ListingExpired,
+    EscrowError::InvalidVestingTerms,
+    EscrowError::NothingVested,
+    EscrowError::InvalidTaxRate,
+    EscrowError::InvalidSunset,
+    EscrowError::InvalidMilestone,
+    EscrowError::TooManyMilestones,
+    EscrowError::MilestonesExceedEscrow,
+    EscrowError::MilestoneNotApproved,
+    EscrowError::InvalidLanguageTag,
+    EscrowError::InvalidLocalizedUri,
+    EscrowError::TooManyLocalizations,
+    EscrowError::InvalidFee,
+    EscrowError::FeeTreasuryRequired,
+    EscrowError::InvalidFeeTreasury,
+    EscrowError::InvalidTermsText,
+    EscrowError::InvalidArbiter,
+    EscrowError::AmountMismatch,
+    EscrowError::RentalRequestsPending,
+    EscrowError::ReservedEscrowId,
+    EscrowError::DemoAirdropTooLarge,
+    EscrowError::NoCancelProposal,
+    EscrowError::CancelProposalExpired,
+    EscrowError::EvidenceUriTooLong,
+    EscrowError::TooManyEvidenceEntries,
+    EscrowError::InvalidArbitrationWindow,
+    EscrowError::NoArbitrationDeadline,
+    EscrowError::ArbiterConflict,
+    EscrowError::ConflictNotDeclared,
+    EscrowError::NoConflict,
+    EscrowError::InvalidCouncil,
+    EscrowError::NotCouncilMember,
+    EscrowError::ProposalMismatch,
+    EscrowError::ThresholdNotMet,
+    EscrowError::InvalidRuling,
+    EscrowError::MintDecimalsMismatch,
+    EscrowError::RecoveryMismatch,
+    EscrowError::InvalidRecovery,
+    EscrowError::InvalidMint,
+    EscrowError::TooManyAllowedMints,
+    EscrowError::NotWrappedSol,
+    EscrowError::AutoReleaseNotAgreed,
+    EscrowError::DeliverableUriTooLong,
+    EscrowError::NotDelivered,
+    EscrowError::EpochNotOver,
+    EscrowError::BondNotFunded,
+    EscrowError::BondAlreadyFunded,
+    EscrowError::InvalidExtension,
+    EscrowError::SubscriptionNotOffered,
+    EscrowError::InvalidSubscription,
+    EscrowError::NoPeriodDue,
+    EscrowError::InvalidRiskWeights,
+    EscrowError::RiskScoringDisabled,
+    EscrowError::NothingStreamed,
+    EscrowError::LayoutVersionMismatch,
+    EscrowError::UnknownEscrowLayout,
+    EscrowError::AlreadyMigrated,
+    EscrowError::AcceptanceExpired,
+    EscrowError::InvalidAcceptanceSignature,
+    EscrowError::EscrowIdTaken,
+    EscrowError::CreditPriceTooHigh,
+    EscrowError::ProposalPending,
+];
+
+const REPUTATION_ERRORS: &[ReputationError] = &[
+    ReputationError::NotInitialized,
+    ReputationError::NameTooLong,
+    ReputationError::BioTooLong,
+    ReputationError::InvalidRating,
+    ReputationError::CommentTooLong,
+    ReputationError::CategoryTooLong,
+    ReputationError::AgentNotActive,
+    ReputationError::AgentAlreadyInactive,
+    ReputationError::InvalidScore,
+    ReputationError::PlatformIdTooLong,
+    ReputationError::AttestorNotActive,
+    ReputationError::PlatformMismatch,
+    ReputationError::Unauthorized,
+    ReputationError::UriTooLong,
+    ReputationError::CommentHashMismatch,
+    ReputationError::InvalidDelegate,
+    ReputationError::TooManyDelegates,
+    ReputationError::InvalidExpiry,
+    ReputationError::InvalidBounty,
+    ReputationError::CategoryMismatch,
+    ReputationError::BountyExhausted,
+    ReputationError::EscrowMismatch,
+    ReputationError::InvalidActivityFeed,
+    ReputationError::InvalidReviewShard,
+];
+
+/// Anchor framework errors (codes below 6000) that preflight commonly hits
+const FRAMEWORK_ERRORS: &[anchor_lang::error::ErrorCode] = &[
+    anchor_lang::error::ErrorCode::InstructionDidNotDeserialize,
+    anchor_lang::error::ErrorCode::ConstraintMut,
+    anchor_lang::error::ErrorCode::ConstraintHasOne,
+    anchor_lang::error::ErrorCode::ConstraintSigner,
+    anchor_lang::error::ErrorCode::ConstraintRaw,
+    anchor_lang::error::ErrorCode::ConstraintSeeds,
+    anchor_lang::error::ErrorCode::ConstraintTokenMint,
+    anchor_lang::error::ErrorCode::ConstraintTokenOwner,
+    anchor_lang::error::ErrorCode::ConstraintAssociated,
+    anchor_lang::error::ErrorCode::RequireKeysEqViolated,
+    anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch,
+    anchor_lang::error::ErrorCode::AccountDidNotDeserialize,
+    anchor_lang::error::ErrorCode::AccountNotEnoughKeys,
+    anchor_lang::error::ErrorCode::AccountNotMutable,
+    anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram,
+    anchor_lang::error::ErrorCode::InvalidProgramId,
+    anchor_lang::error::ErrorCode::AccountNotInitialized,
+];
+
+/// Resolve a custom error code returned by `program_id`
+pub fn lookup(program_id: &Pubkey, code: u32) -> Option<RegisteredError> {
+    if code < anchor_lang::error::ERROR_CODE_OFFSET {
+        return find("anchor", FRAMEWORK_ERRORS, code, |e| (e.name(), e.to_string()));
+    }
+    if *program_id == escrow::ID {
+        find("escrow", ESCROW_ERRORS, code, |e| (e.name(), e.to_string()))
+    } else if *program_id == reputation::ID {
+        find("reputation", REPUTATION_ERRORS, code, |e| (e.name(), e.to_string()))
+    } else {
+        None
+    }
+}
+
+fn find<E: Copy + Into<u32>>(
+    program: &'static str,
+    table: &[E],
+    code: u32,
+    describe: impl Fn(&E) -> (String, String),
+) -> Option<RegisteredError> {
+    table.iter().find(|e| (**e).into() == code).map(|e| {
+        let (name, message) = describe(e);
+        RegisteredError {
+            program,
+            code,
+            name,
+            message,
+        }
+    })
+}
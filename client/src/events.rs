@@ -0,0 +1,386 @@
+//! Typed escrow program events and subscriptions to them
+//!
+//! [`TrustyClawEvent`] decodes any event the escrow program emits, whether it arrives
+//! as a `Program data:` log line or as the data of an event-CPI inner instruction, and
+//! knows the escrow, wallets and [`EventCategory`] it concerns. [`EventSubscription`]
+//! follows an address the way a `logsSubscribe` would, and [`EventStreamExt`] narrows
+//! any stream of events by escrow, wallet or category:
+//!
+//! ```ignore
+//! let rpc = RpcClient::new("http://127.0.0.1:8899")?;
+//! for event in EventSubscription::new(&rpc, escrow::ID).for_wallet(agent).in_category(EventCategory::Dispute) {
+//!     println!("{}", event?.event.name());
+//! }
+//! ```
+//!
+//! The RPC transport speaks plain HTTP only, so subscriptions poll
+//! `getSignaturesForAddress` rather than holding a websocket open.
+
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use solana_sdk::signature::Signature;
+
+use crate::rpc::{RpcClient, RpcError};
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Default time a subscription waits between polls that found nothing new
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventCategory {
+    /// Publishing, repricing and retiring listings
+    Listing,
+    /// Rental requests and funding
+    Funding,
+    /// Deliverables, revisions, milestones and service levels
+    Delivery,
+    /// Payouts, refunds, fees and invoices
+    Settlement,
+    /// Disputes and arbitration
+    Dispute,
+    /// Admin recovery of stuck escrows
+    Recovery,
+    /// Protocol-wide statistics and account migrations
+    Protocol,
+}
+
+/// Pubkeys, or lists of them, an event names as parties
+trait Parties {
+    fn push_into(&self, out: &mut Vec<Pubkey>);
+}
+
+impl Parties for Pubkey {
+    fn push_into(&self, out: &mut Vec<Pubkey>) {
+        out.push(*self);
+    }
+}
+
+impl Parties for Vec<Pubkey> {
+    fn push_into(&self, out: &mut Vec<Pubkey>) {
+        out.extend_from_slice(self);
+    }
+}
+
+/// Declares [`TrustyClawEvent`] with, per event, its category, the field naming the
+/// escrow (or listing) it concerns, and the fields naming wallets party to it
+macro_rules! events {
+    ($($name:ident($category:ident) { escrow: [$($($escrow:ident).+)?], wallets: [$($($wallet:ident).+),*] },)*) => {
+        /// Any event the escrow program emits
+        pub enum TrustyClawEvent {
+            $($name(escrow::$name),)*
+        }
+
+        impl TrustyClawEvent {
+            /// Decode an event from its discriminator and borsh body
+            pub fn decode(bytes: &[u8]) -> Option<Self> {
+                let (discriminator, mut body) = bytes.split_at_checked(8)?;
+                $(
+                    if discriminator == escrow::$name::DISCRIMINATOR {
+                        return escrow::$name::deserialize(&mut body).ok().map(Self::$name);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$name(_) => stringify!($name),)*
+                }
+            }
+
+            pub fn category(&self) -> EventCategory {
+                match self {
+                    $(Self::$name(_) => EventCategory::$category,)*
+                }
+            }
+
+            /// Escrow or listing account the event concerns, if any
+            pub fn escrow(&self) -> Option<Pubkey> {
+                match self {
+                    $(Self::$name(_event) => {
+                        let escrow: &[Pubkey] = &[$(_event.$($escrow).+)?];
+                        escrow.first().copied()
+                    })*
+                }
+            }
+
+            /// Wallets named as parties: providers, renters, arbiters, signers and the like
+            pub fn wallets(&self) -> Vec<Pubkey> {
+                let mut wallets = Vec::new();
+                match self {
+                    $(Self::$name(_event) => {
+                        $(Parties::push_into(&_event.$($wallet).+, &mut wallets);)*
+                    })*
+                }
+                wallets
+            }
+        }
+    };
+}
+
+events! {
+    EscrowInitialized(Listing) { escrow: [escrow], wallets: [provider] },
+    SkillListed(Listing) { escrow: [listing], wallets: [provider] },
+    ListingClosed(Listing) { escrow: [listing], wallets: [provider] },
+    ListingExpired(Listing) { escrow: [escrow], wallets: [provider] },
+    ListingDeprecated(Listing) { escrow: [escrow], wallets: [provider] },
+    BondFunded(Listing) { escrow: [escrow], wallets: [provider] },
+    PriceUpdated(Listing) { escrow: [escrow], wallets: [] },
+    TermsLocalized(Listing) { escrow: [escrow], wallets: [] },
+    ProviderWoundDown(Listing) { escrow: [], wallets: [provider] },
+    RentalRequested(Funding) { escrow: [escrow], wallets: [provider, renter] },
+    RentalRequestWithdrawn(Funding) { escrow: [escrow], wallets: [renter, by] },
+    RentalOpened(Funding) { escrow: [escrow], wallets: [renter] },
+    TermsAccepted(Funding) { escrow: [escrow], wallets: [provider, renter] },
+    EscrowFunded(Funding) { escrow: [escrow], wallets: [provider, renter] },
+    SubscriptionFunded(Funding) { escrow: [escrow], wallets: [provider, renter] },
+    EscrowExpedited(Funding) { escrow: [escrow], wallets: [provider, renter] },
+    DeferredPaymentDefaulted(Funding) { escrow: [escrow], wallets: [renter] },
+    ReleaseScreened(Funding) { escrow: [escrow], wallets: [screener] },
+    AccessCredentialCommitted(Delivery) { escrow: [escrow], wallets: [provider, renter] },
+    DeliverableSubmitted(Delivery) { escrow: [escrow], wallets: [provider] },
+    DeliverableAccepted(Delivery) { escrow: [escrow], wallets: [renter] },
+    RevisionRequested(Delivery) { escrow: [escrow], wallets: [renter] },
+    DurationExtended(Delivery) { escrow: [escrow], wallets: [provider, renter] },
+    DecryptionKeyReleased(Delivery) { escrow: [escrow], wallets: [renter] },
+    SlaBreachReported(Delivery) { escrow: [escrow], wallets: [verifier] },
+    MilestoneAdded(Delivery) { escrow: [escrow], wallets: [] },
+    MilestoneApproved(Delivery) { escrow: [escrow], wallets: [renter] },
+    EscrowSettled(Settlement) { escrow: [escrow], wallets: [provider, renter] },
+    EscrowCancelled(Settlement) { escrow: [escrow], wallets: [provider] },
+    CancelProposed(Settlement) { escrow: [escrow], wallets: [provider, renter] },
+    MilestoneReleased(Settlement) { escrow: [escrow], wallets: [provider] },
+    StreamClaimed(Settlement) { escrow: [escrow], wallets: [provider] },
+    PeriodClaimed(Settlement) { escrow: [escrow], wallets: [provider] },
+    SubscriptionCancelled(Settlement) { escrow: [escrow], wallets: [renter] },
+    VestingStarted(Settlement) { escrow: [escrow], wallets: [provider] },
+    VestedClaimed(Settlement) { escrow: [escrow], wallets: [provider] },
+    BondReleased(Settlement) { escrow: [escrow], wallets: [provider, renter] },
+    ProtocolFeeCharged(Settlement) { escrow: [escrow], wallets: [] },
+    InvoiceIssued(Settlement) { escrow: [escrow], wallets: [provider, renter] },
+    EscrowSnapshotted(Settlement) { escrow: [snapshot.escrow], wallets: [snapshot.provider, snapshot.renter] },
+    ChannelSettled(Settlement) { escrow: [], wallets: [payer, payee] },
+    PointsRedeemed(Settlement) { escrow: [], wallets: [owner] },
+    EscrowDisputed(Dispute) { escrow: [escrow], wallets: [provider, renter, arbiter, raised_by] },
+    EvidenceSubmitted(Dispute) { escrow: [escrow], wallets: [submitted_by] },
+    ArbiterChanged(Dispute) { escrow: [escrow], wallets: [previous, arbiter] },
+    ConflictDeclared(Dispute) { escrow: [escrow], wallets: [arbiter] },
+    ConflictReported(Dispute) { escrow: [escrow], wallets: [arbiter, reported_by] },
+    DisputeRandomnessFinalized(Dispute) { escrow: [escrow], wallets: [] },
+    CouncilCreated(Dispute) { escrow: [], wallets: [members] },
+    ResolutionApproved(Dispute) { escrow: [escrow], wallets: [member] },
+    DisputeResolved(Dispute) { escrow: [escrow], wallets: [provider, renter, arbiter] },
+    DefaultJudgment(Dispute) { escrow: [escrow], wallets: [arbiter, claimed_by] },
+    RecoveryQueued(Recovery) { escrow: [escrow], wallets: [queued_by] },
+    RecoveryCancelled(Recovery) { escrow: [escrow], wallets: [cancelled_by] },
+    RecoveryExecuted(Recovery) { escrow: [escrow], wallets: [executed_by] },
+    EpochRolledOver(Protocol) { escrow: [], wallets: [rolled_over_by] },
+    EscrowMigrated(Protocol) { escrow: [escrow], wallets: [provider] },
+}
+
+impl TrustyClawEvent {
+    /// Decode the data of an event-CPI (`emit_cpi!`) inner instruction
+    pub fn decode_cpi(data: &[u8]) -> Option<Self> {
+        Self::decode(data.strip_prefix(&EVENT_IX_TAG_LE[..])?)
+    }
+
+    /// Every event emitted as a `Program data:` line in a transaction's logs, in order.
+    /// Lines that are not escrow events are skipped.
+    pub fn from_logs(logs: &[String]) -> Vec<Self> {
+        program_data(logs).filter_map(|bytes| Self::decode(&bytes)).collect()
+    }
+
+    /// Whether `wallet` is named as a party
+    pub fn involves(&self, wallet: &Pubkey) -> bool {
+        self.wallets().contains(wallet)
+    }
+}
+
+/// Decoded payloads of the `Program data:` lines in a transaction's logs
+pub(crate) fn program_data(logs: &[String]) -> impl Iterator<Item = Vec<u8>> + '_ {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix(PROGRAM_DATA_PREFIX))
+        .filter_map(|data| BASE64.decode(data).ok())
+}
+
+/// An event and the transaction that emitted it
+pub struct Notification {
+    pub signature: Signature,
+    pub event: TrustyClawEvent,
+}
+
+/// What a subscription needs from an RPC node
+pub trait EventRpc {
+    type Error: std::error::Error;
+
+    /// Successful transactions touching `address`, newest first, stopping before `until`
+    fn signatures_for_address(&self, address: &Pubkey, until: Option<&Signature>) -> Result<Vec<Signature>, Self::Error>;
+
+    /// Log messages of a confirmed transaction
+    fn transaction_logs(&self, signature: &Signature) -> Result<Vec<String>, Self::Error>;
+}
+
+impl EventRpc for RpcClient {
+    type Error = RpcError;
+
+    fn signatures_for_address(&self, address: &Pubkey, until: Option<&Signature>) -> Result<Vec<Signature>, RpcError> {
+        RpcClient::signatures_for_address(self, address, until)
+    }
+
+    fn transaction_logs(&self, signature: &Signature) -> Result<Vec<String>, RpcError> {
+        RpcClient::transaction_logs(self, signature)
+    }
+}
+
+enum Cursor {
+    /// Not polled yet; the first poll only marks where the subscription starts
+    Latest,
+    After(Option<Signature>),
+}
+
+/// Events from transactions touching an address (an escrow, a listing, or `escrow::ID`
+/// for the whole program), oldest first. Iterating blocks between polls and never ends;
+/// a failed poll is yielded as an error and retried on the next call.
+pub struct EventSubscription<'a, R: EventRpc> {
+    rpc: &'a R,
+    address: Pubkey,
+    cursor: Cursor,
+    pending: std::collections::VecDeque<Notification>,
+    poll_interval: Duration,
+}
+
+impl<'a, R: EventRpc> EventSubscription<'a, R> {
+    /// Subscribe to events from transactions confirmed from now on
+    pub fn new(rpc: &'a R, address: Pubkey) -> Self {
+        Self::with_cursor(rpc, address, Cursor::Latest)
+    }
+
+    /// Resume after `cursor`, the last signature a previous subscription yielded; `None`
+    /// replays the address's recent history first
+    pub fn resume(rpc: &'a R, address: Pubkey, cursor: Option<Signature>) -> Self {
+        Self::with_cursor(rpc, address, Cursor::After(cursor))
+    }
+
+    fn with_cursor(rpc: &'a R, address: Pubkey, cursor: Cursor) -> Self {
+        Self {
+            rpc,
+            address,
+            cursor,
+            pending: Default::default(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Fetch transactions confirmed since the last poll, returning how many events they
+    /// queued. The cursor only moves past a transaction once its logs are fetched.
+    pub fn poll(&mut self) -> Result<usize, R::Error> {
+        let until = match &self.cursor {
+            Cursor::Latest => None,
+            Cursor::After(cursor) => cursor.as_ref(),
+        };
+        let signatures = self.rpc.signatures_for_address(&self.address, until)?;
+        if let Cursor::Latest = self.cursor {
+            self.cursor = Cursor::After(signatures.first().copied());
+            return Ok(0);
+        }
+
+        let before = self.pending.len();
+        for signature in signatures.into_iter().rev() {
+            let logs = self.rpc.transaction_logs(&signature)?;
+            self.pending
+                .extend(TrustyClawEvent::from_logs(&logs).into_iter().map(|event| Notification { signature, event }));
+            self.cursor = Cursor::After(Some(signature));
+        }
+        Ok(self.pending.len() - before)
+    }
+}
+
+impl<R: EventRpc> Iterator for EventSubscription<'_, R> {
+    type Item = Result<Notification, R::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(notification) = self.pending.pop_front() {
+                return Some(Ok(notification));
+            }
+            let first_poll = matches!(self.cursor, Cursor::Latest);
+            match self.poll() {
+                Err(e) => return Some(Err(e)),
+                Ok(0) if !first_poll => thread::sleep(self.poll_interval),
+                Ok(_) => {}
+            }
+        }
+    }
+}
+
+/// Which events a [`Filtered`] stream lets through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFilter {
+    Escrow(Pubkey),
+    Wallet(Pubkey),
+    Category(EventCategory),
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &TrustyClawEvent) -> bool {
+        match self {
+            Self::Escrow(escrow) => event.escrow() == Some(*escrow),
+            Self::Wallet(wallet) => event.involves(wallet),
+            Self::Category(category) => event.category() == *category,
+        }
+    }
+}
+
+/// A stream passing on the events that match its filter, and every error
+pub struct Filtered<I> {
+    inner: I,
+    filter: EventFilter,
+}
+
+impl<I, E> Iterator for Filtered<I>
+where
+    I: Iterator<Item = Result<Notification, E>>,
+{
+    type Item = Result<Notification, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .by_ref()
+            .find(|item| item.as_ref().map_or(true, |notification| self.filter.matches(&notification.event)))
+    }
+}
+
+/// Combinators narrowing a stream of notifications, such as an [`EventSubscription`]
+pub trait EventStreamExt<E>: Iterator<Item = Result<Notification, E>> + Sized {
+    fn matching(self, filter: EventFilter) -> Filtered<Self> {
+        Filtered { inner: self, filter }
+    }
+
+    /// Events concerning the escrow or listing at `escrow`
+    fn for_escrow(self, escrow: Pubkey) -> Filtered<Self> {
+        self.matching(EventFilter::Escrow(escrow))
+    }
+
+    /// Events naming `wallet` as a party
+    fn for_wallet(self, wallet: Pubkey) -> Filtered<Self> {
+        self.matching(EventFilter::Wallet(wallet))
+    }
+
+    fn in_category(self, category: EventCategory) -> Filtered<Self> {
+        self.matching(EventFilter::Category(category))
+    }
+}
+
+impl<I, E> EventStreamExt<E> for I where I: Iterator<Item = Result<Notification, E>> {}
@@ -0,0 +1,1844 @@
+//! Instruction builders for the escrow program
+//!
+//! Amounts are taken as [`TokenAmount`] and checked against the mint's decimals before
+//! being encoded, so a display amount can never be sent as base units. Builders that
+//! touch token accounts take the mint's `token_program`, classic SPL Token or
+//! Token-2022, which also decides the associated token account addresses.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::{AnchorDeserialize, Discriminator, InstructionData};
+use anchor_spl::associated_token::{self, get_associated_token_address, get_associated_token_address_with_program_id};
+use anchor_spl::token;
+use anchor_spl::token::spl_token::native_mint;
+use escrow::{EscrowTerms, FundOverrides, ResolutionOutcome, SlaMetric};
+
+use crate::amount::{AmountError, TokenAmount};
+use crate::pda;
+
+/// Optional activity-log accounts, omitted
+fn no_activity() -> escrow::accounts::ActivityAccounts {
+    escrow::accounts::ActivityAccounts {
+        activity_log_program: None,
+        activity_feed: None,
+        activity_authority: None,
+    }
+}
+
+pub fn initialize_escrow(
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    terms: EscrowTerms,
+) -> Instruction {
+    let accounts = escrow::accounts::InitializeEscrow {
+        provider: *provider,
+        escrow_account: pda::escrow(provider, escrow_id),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        token_mint: *mint,
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::InitializeEscrow { terms, escrow_id }.data(),
+    }
+}
+
+/// Create the faucet mint of a `devnet-demo` deployment
+pub fn create_demo_mint(payer: &Pubkey) -> Instruction {
+    let accounts = escrow::accounts::CreateDemoMint {
+        payer: *payer,
+        demo_mint: pda::demo_mint(),
+        system_program: system_program::ID,
+        token_program: token::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::CreateDemoMint {}.data(),
+    }
+}
+
+/// Mint demo tokens to `recipient` on a `devnet-demo` deployment
+pub fn airdrop_demo_tokens(
+    recipient: &Pubkey,
+    amount: impl Into<TokenAmount>,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(escrow::DEMO_MINT_DECIMALS)?;
+    let demo_mint = pda::demo_mint();
+    let accounts = escrow::accounts::AirdropDemoTokens {
+        recipient: *recipient,
+        demo_mint,
+        recipient_token_account: get_associated_token_address(recipient, &demo_mint),
+        system_program: system_program::ID,
+        token_program: token::ID,
+        associated_token_program: associated_token::ID,
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::AirdropDemoTokens { amount }.data(),
+    })
+}
+
+/// Reprice `provider`'s unfunded listing. Fails if `price` does not carry the mint's decimals.
+pub fn update_price(
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint_decimals: u8,
+    price: impl Into<TokenAmount>,
+) -> std::result::Result<Instruction, AmountError> {
+    let price_usdc = price.into().base_units_for(mint_decimals)?;
+    let accounts = escrow::accounts::UpdateTerms {
+        provider: *provider,
+        escrow_account: pda::escrow(provider, escrow_id),
+        config: pda::config(),
+        activity: no_activity(),
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::UpdatePrice { price_usdc }.data(),
+    })
+}
+
+/// List a skill once for any number of renters; see [`open_rental`]
+pub fn create_listing(
+    provider: &Pubkey,
+    listing_id: u16,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    terms: EscrowTerms,
+) -> Instruction {
+    let accounts = escrow::accounts::CreateListing {
+        provider: *provider,
+        skill_listing: pda::skill_listing(provider, listing_id),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        config: pda::config(),
+        token_mint: *mint,
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        token_program: *token_program,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::CreateListing { listing_id, terms }.data(),
+    }
+}
+
+/// Open and fund the next rental of `provider`'s listing. `rentals_opened` is the
+/// listing's current count, which fixes the new escrow's id; if another renter opens
+/// one first the transaction fails and must be rebuilt. Fails if `amount` does not
+/// carry the mint's decimals.
+#[allow(clippy::too_many_arguments)]
+pub fn open_rental(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    listing_id: u16,
+    rentals_opened: u32,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    mint_decimals: u8,
+    amount: impl Into<TokenAmount>,
+    overrides: FundOverrides,
+    terms_hash: [u8; 32],
+    with_reputation: bool,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(mint_decimals)?;
+    let escrow_account = pda::listing_rental(provider, listing_id, rentals_opened);
+    let accounts = escrow::accounts::OpenRental {
+        renter: *renter,
+        skill_listing: pda::skill_listing(provider, listing_id),
+        escrow_account,
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        engagement: pda::engagement(provider, renter),
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        renter_reputation: with_reputation.then(|| pda::reputation_agent(renter)),
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        activity: no_activity(),
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::OpenRental {
+            amount,
+            overrides,
+            terms_hash,
+        }
+        .data(),
+    })
+}
+
+pub fn close_listing(provider: &Pubkey, listing_id: u16) -> Instruction {
+    let accounts = escrow::accounts::CloseListing {
+        provider: *provider,
+        skill_listing: pda::skill_listing(provider, listing_id),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::CloseListing {}.data(),
+    }
+}
+
+/// Fund an escrow for exactly the listed price, or with a funding rule at least the
+/// upfront share of it. Fails if `amount` does not carry the mint's decimals.
+///
+/// Pass `with_reputation` when the listing has a funding rule and the renter has a
+/// reputation account, so the program can grant reduced upfront funding.
+#[allow(clippy::too_many_arguments)]
+pub fn accept_escrow(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    mint_decimals: u8,
+    amount: impl Into<TokenAmount>,
+    overrides: FundOverrides,
+    terms_hash: [u8; 32],
+    with_reputation: bool,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(mint_decimals)?;
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::AcceptEscrow {
+        renter: *renter,
+        escrow_account,
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        engagement: pda::engagement(provider, renter),
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        renter_reputation: with_reputation.then(|| pda::reputation_agent(renter)),
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        activity: no_activity(),
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::AcceptEscrow {
+            amount,
+            overrides,
+            terms_hash,
+        }
+        .data(),
+    })
+}
+
+/// Fund a listing priced in (SPL Token) wrapped SOL straight from the renter's lamports;
+/// the renter needs no token account. Fails if `amount` does not carry SOL's 9 decimals.
+pub fn fund_with_sol(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    amount: impl Into<TokenAmount>,
+    overrides: FundOverrides,
+    terms_hash: [u8; 32],
+    with_reputation: bool,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(native_mint::DECIMALS)?;
+    let mint = native_mint::ID;
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::FundWithSol {
+        renter: *renter,
+        escrow_account,
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        engagement: pda::engagement(provider, renter),
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        provider_token_account: get_associated_token_address(provider, &mint),
+        token_mint: mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, &mint, &token::ID),
+        renter_reputation: with_reputation.then(|| pda::reputation_agent(renter)),
+        system_program: system_program::ID,
+        token_program: token::ID,
+        associated_token_program: associated_token::ID,
+        activity: no_activity(),
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::FundWithSol {
+            amount,
+            overrides,
+            terms_hash,
+        }
+        .data(),
+    })
+}
+
+pub fn pay_deferred(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    mint_decimals: u8,
+    amount: impl Into<TokenAmount>,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(mint_decimals)?;
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::PayDeferred {
+        renter: *renter,
+        escrow_account,
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        token_program: *token_program,
+        activity: no_activity(),
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::PayDeferred { amount }.data(),
+    })
+}
+
+/// Pay `extra_fee` on top of a funded rental for priority handling. Fails if
+/// `extra_fee` does not carry the mint's decimals.
+pub fn expedite(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    mint_decimals: u8,
+    extra_fee: impl Into<TokenAmount>,
+) -> std::result::Result<Instruction, AmountError> {
+    let extra_fee = extra_fee.into().base_units_for(mint_decimals)?;
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::PayDeferred {
+        renter: *renter,
+        escrow_account,
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        token_program: *token_program,
+        activity: no_activity(),
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::Expedite { extra_fee }.data(),
+    })
+}
+
+/// Deposit on a listing that requires provider acceptance; see [`accept_renter`]
+#[allow(clippy::too_many_arguments)]
+pub fn request_rental(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    mint_decimals: u8,
+    amount: impl Into<TokenAmount>,
+    overrides: FundOverrides,
+    terms_hash: [u8; 32],
+    with_reputation: bool,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(mint_decimals)?;
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let rental_request = pda::rental_request(&escrow_account, renter);
+    let accounts = escrow::accounts::RequestRental {
+        renter: *renter,
+        escrow_account,
+        rental_request,
+        token_mint: *mint,
+        request_vault: get_associated_token_address_with_program_id(&rental_request, mint, token_program),
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        renter_reputation: with_reputation.then(|| pda::reputation_agent(renter)),
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::RequestRental {
+            amount,
+            overrides,
+            terms_hash,
+        }
+        .data(),
+    })
+}
+
+pub fn accept_renter(
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let rental_request = pda::rental_request(&escrow_account, renter);
+    let accounts = escrow::accounts::AcceptRenter {
+        provider: *provider,
+        escrow_account,
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        engagement: pda::engagement(provider, renter),
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        rental_request,
+        renter: *renter,
+        token_mint: *mint,
+        request_vault: get_associated_token_address_with_program_id(&rental_request, mint, token_program),
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::AcceptRenter {}.data(),
+    }
+}
+
+/// `authority` is the renter, the provider declining, or anyone after the window closes
+pub fn withdraw_rental_request(
+    authority: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let rental_request = pda::rental_request(&escrow_account, renter);
+    let accounts = escrow::accounts::WithdrawRentalRequest {
+        authority: *authority,
+        escrow_account,
+        rental_request,
+        renter: *renter,
+        token_mint: *mint,
+        request_vault: get_associated_token_address_with_program_id(&rental_request, mint, token_program),
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        token_program: *token_program,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::WithdrawRentalRequest {}.data(),
+    }
+}
+
+/// Decode the funded amount back out of an `accept_escrow` instruction
+pub fn decode_accept_amount(ix: &Instruction, mint_decimals: u8) -> Option<TokenAmount> {
+    let (discriminator, mut args) = ix.data.split_at_checked(8)?;
+    if discriminator != escrow::instruction::AcceptEscrow::DISCRIMINATOR {
+        return None;
+    }
+    let decoded = escrow::instruction::AcceptEscrow::deserialize(&mut args).ok()?;
+    TokenAmount::from_base_units(decoded.amount, mint_decimals).ok()
+}
+
+pub fn submit_deliverable(
+    provider: &Pubkey,
+    escrow_id: u64,
+    deliverable_hash: [u8; 32],
+    deliverable_uri: &str,
+    encrypted_key_hash: [u8; 32],
+) -> Instruction {
+    let accounts = escrow::accounts::SubmitDeliverable {
+        provider: *provider,
+        escrow_account: pda::escrow(provider, escrow_id),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::SubmitDeliverable {
+            deliverable_hash,
+            deliverable_uri: deliverable_uri.to_string(),
+            encrypted_key_hash,
+        }
+        .data(),
+    }
+}
+
+/// Associated token account of `Config::fee_treasury` for `mint`, when fees are charged
+fn fee_token_account(fee_treasury: Option<&Pubkey>, mint: &Pubkey, token_program: &Pubkey) -> Option<Pubkey> {
+    fee_treasury.map(|treasury| get_associated_token_address_with_program_id(treasury, mint, token_program))
+}
+
+fn release_accounts(
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    fee_treasury: Option<&Pubkey>,
+) -> escrow::accounts::ReleaseAccounts {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    escrow::accounts::ReleaseAccounts {
+        escrow_account,
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        renter_token_account: Some(get_associated_token_address_with_program_id(renter, mint, token_program)),
+        token_mint: *mint,
+        token_program: *token_program,
+        price_oracle: None,
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        provider_points: None,
+        fee_token_account: fee_token_account(fee_treasury, mint, token_program),
+        provider: escrow::is_wrapped_sol(mint).then_some(*provider),
+        activity: no_activity(),
+    }
+}
+
+/// `authority` must be the renter or its watchtower. The renter's ATA is always passed so
+/// any SLA penalty can be refunded. `fee_treasury` is `Config::fee_treasury`, needed
+/// whenever the protocol fee is non-zero. A wrapped-SOL payout is unwrapped into the
+/// provider's wallet.
+pub fn complete_task(
+    authority: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    fee_treasury: Option<&Pubkey>,
+) -> Instruction {
+    Instruction {
+        program_id: escrow::ID,
+        accounts: escrow::accounts::CompleteTask {
+            authority: *authority,
+            release: release_accounts(provider, escrow_id, renter, mint, token_program, fee_treasury),
+        }
+        .to_account_metas(None),
+        data: escrow::instruction::CompleteTask {}.data(),
+    }
+}
+
+/// Release a delivered rental once auto-release is agreed and due or the renter's review
+/// window has lapsed; `keeper` can be anyone. Accounts as for [`complete_task`].
+pub fn auto_release(
+    keeper: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    fee_treasury: Option<&Pubkey>,
+) -> Instruction {
+    Instruction {
+        program_id: escrow::ID,
+        accounts: escrow::accounts::AutoRelease {
+            keeper: *keeper,
+            release: release_accounts(provider, escrow_id, renter, mint, token_program, fee_treasury),
+        }
+        .to_account_metas(None),
+        data: escrow::instruction::AutoRelease {}.data(),
+    }
+}
+
+fn refund_accounts(
+    authority: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Vec<AccountMeta> {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let unwrap = escrow::is_wrapped_sol(mint);
+    escrow::accounts::CancelEscrow {
+        authority: *authority,
+        escrow_account,
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        renter_token_account: (!unwrap).then(|| get_associated_token_address_with_program_id(renter, mint, token_program)),
+        token_mint: *mint,
+        token_program: *token_program,
+        price_oracle: None,
+        renter: unwrap.then_some(*renter),
+        activity: no_activity(),
+    }
+    .to_account_metas(None)
+}
+
+/// Provider cancels its funded rental, refunding the renter; only `provider` may sign
+pub fn cancel_escrow(
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: escrow::ID,
+        accounts: refund_accounts(provider, provider, escrow_id, renter, mint, token_program),
+        data: escrow::instruction::CancelEscrow {}.data(),
+    }
+}
+
+/// Provider or renter proposes a mutual cancellation of a funded rental
+pub fn propose_cancel(authority: &Pubkey, provider: &Pubkey, escrow_id: u64) -> Instruction {
+    let accounts = escrow::accounts::ProposeCancel {
+        authority: *authority,
+        escrow_account: pda::escrow(provider, escrow_id),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ProposeCancel {}.data(),
+    }
+}
+
+/// The other party co-signs a proposed cancellation, refunding the renter
+pub fn accept_cancel(
+    authority: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: escrow::ID,
+        accounts: refund_accounts(authority, provider, escrow_id, renter, mint, token_program),
+        data: escrow::instruction::AcceptCancel {}.data(),
+    }
+}
+
+/// Refund an undelivered rental whose period is over; `cranker` can be anyone
+pub fn refund_after_timeout(
+    cranker: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: escrow::ID,
+        accounts: refund_accounts(cranker, provider, escrow_id, renter, mint, token_program),
+        data: escrow::instruction::RefundAfterTimeout {}.data(),
+    }
+}
+
+/// Issue the invoice for `provider`'s settled rental; `funded_at` comes from the escrow
+pub fn issue_invoice(payer: &Pubkey, provider: &Pubkey, escrow_id: u64, funded_at: i64) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::IssueInvoice {
+        payer: *payer,
+        escrow_account,
+        config: pda::config(),
+        invoice: pda::invoice(&escrow_account, funded_at),
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::IssueInvoice {}.data(),
+    }
+}
+
+/// Provider collects the unlocked part of a vesting payout
+pub fn claim_vested(provider: &Pubkey, escrow_id: u64, mint: &Pubkey, token_program: &Pubkey) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::ClaimVested {
+        provider: *provider,
+        escrow_account,
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        token_program: *token_program,
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ClaimVested {}.data(),
+    }
+}
+
+/// Add a milestone paying `amount` to `provider`'s escrow plan
+pub fn add_milestone(
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint_decimals: u8,
+    description_hash: [u8; 32],
+    amount: impl Into<TokenAmount>,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(mint_decimals)?;
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::AddMilestone {
+        provider: *provider,
+        escrow_account,
+        milestone_plan: pda::milestone_plan(&escrow_account),
+        system_program: system_program::ID,
+        activity: no_activity(),
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::AddMilestone { description_hash, amount }.data(),
+    })
+}
+
+pub fn approve_milestone(renter: &Pubkey, provider: &Pubkey, escrow_id: u64, index: u8) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::ApproveMilestone {
+        renter: *renter,
+        escrow_account,
+        milestone_plan: pda::milestone_plan(&escrow_account),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ApproveMilestone { index }.data(),
+    }
+}
+
+pub fn release_milestone(
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    index: u8,
+    fee_treasury: Option<&Pubkey>,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::ReleaseMilestone {
+        provider: *provider,
+        escrow_account,
+        milestone_plan: pda::milestone_plan(&escrow_account),
+        provider_stats: pda::provider_stats(provider),
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        token_program: *token_program,
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        fee_token_account: fee_token_account(fee_treasury, mint, token_program),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ReleaseMilestone { index }.data(),
+    }
+}
+
+/// Collect what has streamed of a `stream_payment` rental since the last claim
+pub fn claim_streamed(
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    fee_treasury: Option<&Pubkey>,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::ClaimStreamed {
+        provider: *provider,
+        escrow_account,
+        provider_stats: pda::provider_stats(provider),
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        token_program: *token_program,
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        fee_token_account: fee_token_account(fee_treasury, mint, token_program),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ClaimStreamed {}.data(),
+    }
+}
+
+/// Needs both the provider's and the renter's signature
+pub fn set_arbiter(provider: &Pubkey, escrow_id: u64, renter: &Pubkey, arbiter: &Pubkey) -> Instruction {
+    let accounts = escrow::accounts::SetArbiter {
+        provider: *provider,
+        renter: *renter,
+        escrow_account: pda::escrow(provider, escrow_id),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::SetArbiter { arbiter: *arbiter }.data(),
+    }
+}
+
+/// Lengthen a funded rental; needs both the provider's and the renter's signature
+pub fn extend_duration(provider: &Pubkey, escrow_id: u64, renter: &Pubkey, extra_seconds: i64) -> Instruction {
+    let accounts = escrow::accounts::ExtendDuration {
+        provider: *provider,
+        renter: *renter,
+        escrow_account: pda::escrow(provider, escrow_id),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ExtendDuration { extra_seconds }.data(),
+    }
+}
+
+pub fn approve_release(screener: &Pubkey, provider: &Pubkey, escrow_id: u64) -> Instruction {
+    let accounts = escrow::accounts::ApproveRelease {
+        screener: *screener,
+        config: pda::config(),
+        escrow_account: pda::escrow(provider, escrow_id),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ApproveRelease {}.data(),
+    }
+}
+
+pub fn set_watchtower(renter: &Pubkey, provider: &Pubkey, escrow_id: u64, watchtower: &Pubkey) -> Instruction {
+    let accounts = escrow::accounts::SetWatchtower {
+        renter: *renter,
+        escrow_account: pda::escrow(provider, escrow_id),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::SetWatchtower { watchtower: *watchtower }.data(),
+    }
+}
+
+/// Register the key that signs `provider`'s webhook callbacks; `Pubkey::default()` clears it
+pub fn set_webhook_signer(provider: &Pubkey, escrow_id: u64, signer: &Pubkey) -> Instruction {
+    let accounts = escrow::accounts::SetWebhookSigner {
+        provider: *provider,
+        escrow_account: pda::escrow(provider, escrow_id),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::SetWebhookSigner { signer: *signer }.data(),
+    }
+}
+
+/// Offer, or withdraw, automatic release on the listing for renters who opt in when funding
+pub fn offer_auto_release(provider: &Pubkey, escrow_id: u64, offered: bool) -> Instruction {
+    let accounts = escrow::accounts::OfferAutoRelease {
+        provider: *provider,
+        escrow_account: pda::escrow(provider, escrow_id),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::OfferAutoRelease { offered }.data(),
+    }
+}
+
+pub fn offer_subscription(provider: &Pubkey, escrow_id: u64, offered: bool) -> Instruction {
+    let accounts = escrow::accounts::OfferAutoRelease {
+        provider: *provider,
+        escrow_account: pda::escrow(provider, escrow_id),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::OfferSubscription { offered }.data(),
+    }
+}
+
+/// Prepay `periods` periods of the listing at `amount_per_period` (its price) each
+#[allow(clippy::too_many_arguments)]
+pub fn fund_subscription(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    periods: u32,
+    amount_per_period: u64,
+    terms_hash: [u8; 32],
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::FundSubscription {
+        renter: *renter,
+        escrow_account,
+        subscription_plan: pda::subscription_plan(&escrow_account),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::FundSubscription {
+            periods,
+            amount_per_period,
+            terms_hash,
+        }
+        .data(),
+    }
+}
+
+/// Claim every elapsed subscription period not yet paid
+pub fn claim_period(
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    fee_treasury: Option<&Pubkey>,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::ClaimPeriod {
+        provider: *provider,
+        escrow_account,
+        subscription_plan: pda::subscription_plan(&escrow_account),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        token_program: *token_program,
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        fee_token_account: fee_token_account(fee_treasury, mint, token_program),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ClaimPeriod {}.data(),
+    }
+}
+
+/// Refund the renter the subscription periods that have not started
+pub fn cancel_subscription(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::CancelSubscription {
+        renter: *renter,
+        escrow_account,
+        subscription_plan: pda::subscription_plan(&escrow_account),
+        token_mint: *mint,
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        token_program: *token_program,
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::CancelSubscription {}.data(),
+    }
+}
+
+/// Freeze the counters of `epoch`, the one `EpochStats` is counting, into its snapshot;
+/// anyone can crank this once the cluster has moved past it, paying for the snapshot
+pub fn rollover_epoch(payer: &Pubkey, epoch: u64) -> Instruction {
+    let accounts = escrow::accounts::RolloverEpoch {
+        payer: *payer,
+        epoch_stats: pda::epoch_stats(),
+        snapshot: pda::epoch_snapshot(epoch),
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::RolloverEpoch {}.data(),
+    }
+}
+
+/// Publish, or with `None` withdraw, the listing's terms in `language`; `terms_hash`
+/// must be the escrow's current canonical hash
+pub fn set_terms_localization(
+    provider: &Pubkey,
+    escrow_id: u64,
+    terms_hash: [u8; 32],
+    language: &str,
+    metadata_uri: Option<String>,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::SetTermsLocalization {
+        provider: *provider,
+        escrow_account,
+        terms_localization: pda::terms_localization(&escrow_account),
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::SetTermsLocalization {
+            terms_hash,
+            language: language.to_string(),
+            metadata_uri,
+        }
+        .data(),
+    }
+}
+
+/// Stop new fundings of `provider`'s listing from `sunset_at` on
+pub fn deprecate_listing(provider: &Pubkey, escrow_id: u64, sunset_at: i64) -> Instruction {
+    let accounts = escrow::accounts::DeprecateListing {
+        provider: *provider,
+        escrow_account: pda::escrow(provider, escrow_id),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::DeprecateListing { sunset_at }.data(),
+    }
+}
+
+/// Withdraw `provider`'s unfunded listing and reclaim its rent. Pass `with_milestones` and
+/// `with_localizations` when the listing has a milestone plan or localized terms, so they
+/// are closed along with it.
+pub fn cancel_unfunded(
+    provider: &Pubkey,
+    escrow_id: u64,
+    with_milestones: bool,
+    with_localizations: bool,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::CancelUnfunded {
+        provider: *provider,
+        escrow_account,
+        milestone_plan: with_milestones.then(|| pda::milestone_plan(&escrow_account)),
+        terms_localization: with_localizations.then(|| pda::terms_localization(&escrow_account)),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::CancelUnfunded {}.data(),
+    }
+}
+
+/// Permissionless expiry of `provider`'s listing once its funding deadline or sunset has passed
+pub fn expire_listing(provider: &Pubkey, escrow_id: u64) -> Instruction {
+    let accounts = escrow::accounts::ExpireListing {
+        escrow_account: pda::escrow(provider, escrow_id),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ExpireListing {}.data(),
+    }
+}
+
+/// Provider locks the listing's `terms.provider_bond` in its bond vault
+pub fn fund_bond(provider: &Pubkey, escrow_id: u64, mint: &Pubkey, token_program: &Pubkey) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::FundBond {
+        provider: *provider,
+        escrow_account,
+        bond_vault: pda::bond_vault(&escrow_account),
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        token_mint: *mint,
+        system_program: system_program::ID,
+        token_program: *token_program,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::FundBond {}.data(),
+    }
+}
+
+/// Pay out the provider bond of a settled escrow, or of an unfunded listing when
+/// `authority` is the provider. Pass `renter` when a dispute ruling owes them part of it.
+pub fn release_bond(
+    authority: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: Option<&Pubkey>,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::ReleaseBond {
+        authority: *authority,
+        escrow_account,
+        provider: *provider,
+        bond_vault: pda::bond_vault(&escrow_account),
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        renter_token_account: renter
+            .map(|renter| get_associated_token_address_with_program_id(renter, mint, token_program)),
+        token_mint: *mint,
+        token_program: *token_program,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ReleaseBond {}.data(),
+    }
+}
+
+/// Ask the provider to revise the delivered work; `notes_hash` commits to the feedback
+pub fn request_revision(renter: &Pubkey, provider: &Pubkey, escrow_id: u64, notes_hash: [u8; 32]) -> Instruction {
+    let accounts = escrow::accounts::RequestRevision {
+        renter: *renter,
+        escrow_account: pda::escrow(provider, escrow_id),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::RequestRevision { notes_hash }.data(),
+    }
+}
+
+/// Commit the hash of the credential issued to the renter; repeat on every rotation
+pub fn commit_access_credential(provider: &Pubkey, escrow_id: u64, commitment: [u8; 32]) -> Instruction {
+    let accounts = escrow::accounts::CommitAccessCredential {
+        provider: *provider,
+        escrow_account: pda::escrow(provider, escrow_id),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::CommitAccessCredential { commitment }.data(),
+    }
+}
+
+pub fn report_sla_breach(
+    verifier: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    metric: SlaMetric,
+    observed: u64,
+    evidence_hash: [u8; 32],
+) -> Instruction {
+    let accounts = escrow::accounts::ReportSlaBreach {
+        verifier: *verifier,
+        escrow_account: pda::escrow(provider, escrow_id),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ReportSlaBreach {
+            metric,
+            observed,
+            evidence_hash,
+        }
+        .data(),
+    }
+}
+
+pub fn resolve_dispute_release(
+    arbiter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    ruling_hash: [u8; 32],
+    fee_treasury: Option<&Pubkey>,
+) -> Instruction {
+    let accounts = escrow::accounts::ResolveDisputeRelease {
+        arbiter: *arbiter,
+        escrow_account: pda::escrow(provider, escrow_id),
+        declaration: pda::arbiter_declaration(&pda::escrow(provider, escrow_id), arbiter),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        arbiter_stats: pda::arbiter_stats(arbiter),
+        escrow_token_account: pda::escrow_vault(&pda::escrow(provider, escrow_id), mint, token_program),
+        provider: *provider,
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        token_mint: *mint,
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        price_oracle: None,
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        provider_points: None,
+        fee_token_account: fee_token_account(fee_treasury, mint, token_program),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ResolveDisputeRelease { ruling_hash }.data(),
+    }
+}
+
+pub fn resolve_dispute_refund(
+    arbiter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    ruling_hash: [u8; 32],
+) -> Instruction {
+    let accounts = escrow::accounts::ResolveDisputeRefund {
+        arbiter: *arbiter,
+        escrow_account: pda::escrow(provider, escrow_id),
+        declaration: pda::arbiter_declaration(&pda::escrow(provider, escrow_id), arbiter),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        arbiter_stats: pda::arbiter_stats(arbiter),
+        escrow_token_account: pda::escrow_vault(&pda::escrow(provider, escrow_id), mint, token_program),
+        renter: *renter,
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        token_mint: *mint,
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        price_oracle: None,
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ResolveDisputeRefund { ruling_hash }.data(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_dispute_split(
+    arbiter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    provider_bps: u16,
+    ruling_hash: [u8; 32],
+    fee_treasury: Option<&Pubkey>,
+) -> Instruction {
+    let accounts = escrow::accounts::ResolveDisputeSplit {
+        arbiter: *arbiter,
+        escrow_account: pda::escrow(provider, escrow_id),
+        declaration: pda::arbiter_declaration(&pda::escrow(provider, escrow_id), arbiter),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        arbiter_stats: pda::arbiter_stats(arbiter),
+        escrow_token_account: pda::escrow_vault(&pda::escrow(provider, escrow_id), mint, token_program),
+        provider: *provider,
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        renter: *renter,
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        token_mint: *mint,
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        price_oracle: None,
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        fee_token_account: fee_token_account(fee_treasury, mint, token_program),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ResolveDisputeSplit {
+            provider_bps,
+            ruling_hash,
+        }
+        .data(),
+    }
+}
+
+/// Read `arbiter`'s resolution record; simulate and decode the return data as `ArbiterStatsData`
+pub fn get_arbiter_stats(arbiter: &Pubkey) -> Instruction {
+    let accounts = escrow::accounts::GetArbiterStats {
+        arbiter_stats: pda::arbiter_stats(arbiter),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::GetArbiterStats {}.data(),
+    }
+}
+
+/// Preview `renter` funding `provider`'s listing with `overrides`; simulate and decode
+/// the return data as `EngagementPreview`, or use [`crate::preview::engagement`]. Pass
+/// the reputation flags for parties with an agent account, and `arbiter` when the
+/// rental's arbiter has an `ArbiterStats` record.
+pub fn preview_engagement(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    overrides: FundOverrides,
+    renter_reputation: bool,
+    provider_reputation: bool,
+    arbiter: Option<&Pubkey>,
+) -> Instruction {
+    let accounts = escrow::accounts::PreviewEngagement {
+        renter: *renter,
+        escrow_account: pda::escrow(provider, escrow_id),
+        config: pda::config(),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        renter_reputation: renter_reputation.then(|| pda::reputation_agent(renter)),
+        provider_reputation: provider_reputation.then(|| pda::reputation_agent(provider)),
+        arbiter_stats: arbiter.map(pda::arbiter_stats),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::PreviewEngagement { overrides }.data(),
+    }
+}
+
+/// Score `provider`'s counterparty risk on the listing; simulate and decode the return
+/// data as `RiskAssessment`, or use [`crate::preview::risk`]. Pass `provider_reputation`
+/// when the provider has an agent account, or they are scored as brand new.
+pub fn compute_risk(provider: &Pubkey, escrow_id: u64, provider_reputation: bool) -> Instruction {
+    let accounts = escrow::accounts::ComputeRisk {
+        escrow_account: pda::escrow(provider, escrow_id),
+        config: pda::config(),
+        provider_stats: pda::provider_stats(provider),
+        provider_reputation: provider_reputation.then(|| pda::reputation_agent(provider)),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ComputeRisk {}.data(),
+    }
+}
+
+/// Dry run of [`initialize_escrow`]; simulate with [`crate::preview::validate`]. Pass
+/// `provider_stats` once the provider has listed before, so the per-epoch listing cap is
+/// checked too.
+pub fn validate_initialize(
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    terms: EscrowTerms,
+    provider_stats: bool,
+) -> Instruction {
+    let accounts = escrow::accounts::ValidateInitialize {
+        provider: *provider,
+        escrow_account: pda::escrow(provider, escrow_id),
+        provider_stats: provider_stats.then(|| pda::provider_stats(provider)),
+        config: pda::config(),
+        token_mint: *mint,
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        token_program: *token_program,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ValidateInitialize { terms, escrow_id }.data(),
+    }
+}
+
+/// Dry run of [`accept_escrow`]; simulate with [`crate::preview::validate`]. Pass
+/// `renter_token_account` only when the renter's associated token account exists, or
+/// the simulation fails before any check runs; `with_reputation` as for `accept_escrow`.
+#[allow(clippy::too_many_arguments)]
+pub fn validate_fund(
+    renter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    mint_decimals: u8,
+    amount: impl Into<TokenAmount>,
+    overrides: FundOverrides,
+    terms_hash: [u8; 32],
+    renter_token_account: bool,
+    with_reputation: bool,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(mint_decimals)?;
+    let accounts = escrow::accounts::ValidateFund {
+        renter: *renter,
+        escrow_account: pda::escrow(provider, escrow_id),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        config: pda::config(),
+        token_mint: *mint,
+        renter_token_account: renter_token_account
+            .then(|| get_associated_token_address_with_program_id(renter, mint, token_program)),
+        renter_reputation: with_reputation.then(|| pda::reputation_agent(renter)),
+        token_program: *token_program,
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ValidateFund {
+            amount,
+            overrides,
+            terms_hash,
+        }
+        .data(),
+    })
+}
+
+/// Dry run of [`complete_task`], or of the program's `release_with_key` when
+/// `key_ciphertext` is given; simulate with [`crate::preview::validate`]. `fee_treasury`
+/// as for `complete_task`.
+pub fn validate_release(
+    provider: &Pubkey,
+    escrow_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    fee_treasury: Option<&Pubkey>,
+    key_ciphertext: Option<Vec<u8>>,
+) -> Instruction {
+    let accounts = escrow::accounts::ValidateRelease {
+        escrow_account: pda::escrow(provider, escrow_id),
+        config: pda::config(),
+        fee_token_account: fee_token_account(fee_treasury, mint, token_program),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ValidateRelease { key_ciphertext }.data(),
+    }
+}
+
+/// Commit to the final state of `provider`'s settled escrow; see `EscrowSnapshotted`
+pub fn snapshot_escrow(provider: &Pubkey, escrow_id: u64) -> Instruction {
+    let accounts = escrow::accounts::SnapshotEscrow {
+        escrow_account: pda::escrow(provider, escrow_id),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::SnapshotEscrow {}.data(),
+    }
+}
+
+/// Record `renter`'s signed acceptance of `escrow_account`'s terms; must directly follow
+/// the Ed25519 verification of `signature`. See `acceptance::record`.
+pub fn record_acceptance(
+    payer: &Pubkey,
+    escrow_account: &Pubkey,
+    renter: &Pubkey,
+    expires_at: i64,
+    signature: [u8; 64],
+) -> Instruction {
+    let accounts = escrow::accounts::RecordAcceptance {
+        payer: *payer,
+        escrow_account: *escrow_account,
+        renter: *renter,
+        acceptance: pda::terms_acceptance(escrow_account, renter),
+        instructions: anchor_lang::solana_program::sysvar::instructions::ID,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::RecordAcceptance { expires_at, signature }.data(),
+    }
+}
+
+/// Rewrite an escrow left by an older release in the current layout; `payer` can be
+/// anyone and covers any extra rent. See `EscrowView::needs_migration`.
+pub fn migrate_escrow(payer: &Pubkey, escrow_account: &Pubkey) -> Instruction {
+    let accounts = escrow::accounts::MigrateEscrow {
+        payer: *payer,
+        escrow_account: *escrow_account,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::MigrateEscrow {}.data(),
+    }
+}
+
+pub fn open_channel(
+    payer: &Pubkey,
+    payee: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    mint_decimals: u8,
+    amount: impl Into<TokenAmount>,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(mint_decimals)?;
+    let channel = pda::settlement_channel(payer, payee, mint);
+    let accounts = escrow::accounts::OpenChannel {
+        payer: *payer,
+        payee: *payee,
+        token_mint: *mint,
+        channel,
+        channel_vault: get_associated_token_address_with_program_id(&channel, mint, token_program),
+        payer_token_account: get_associated_token_address_with_program_id(payer, mint, token_program),
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::OpenChannel { amount }.data(),
+    })
+}
+
+pub fn fund_channel(
+    payer: &Pubkey,
+    payee: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    mint_decimals: u8,
+    amount: impl Into<TokenAmount>,
+) -> std::result::Result<Instruction, AmountError> {
+    let amount = amount.into().base_units_for(mint_decimals)?;
+    let channel = pda::settlement_channel(payer, payee, mint);
+    let accounts = escrow::accounts::FundChannel {
+        payer: *payer,
+        channel,
+        token_mint: *mint,
+        channel_vault: get_associated_token_address_with_program_id(&channel, mint, token_program),
+        payer_token_account: get_associated_token_address_with_program_id(payer, mint, token_program),
+        token_program: *token_program,
+    };
+    Ok(Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::FundChannel { amount }.data(),
+    })
+}
+
+/// Settle a channel to `cumulative`; must directly follow the payer's Ed25519
+/// verification, see [`crate::channel::settle`]
+pub fn settle_channel(
+    payer: &Pubkey,
+    payee: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    cumulative: u64,
+    nonce: u64,
+) -> Instruction {
+    let channel = pda::settlement_channel(payer, payee, mint);
+    let accounts = escrow::accounts::SettleChannel {
+        channel,
+        token_mint: *mint,
+        channel_vault: get_associated_token_address_with_program_id(&channel, mint, token_program),
+        payee_token_account: get_associated_token_address_with_program_id(payee, mint, token_program),
+        token_program: *token_program,
+        instructions: anchor_lang::solana_program::sysvar::instructions::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::SettleChannel { cumulative, nonce }.data(),
+    }
+}
+
+pub fn request_channel_close(payer: &Pubkey, payee: &Pubkey, mint: &Pubkey) -> Instruction {
+    let accounts = escrow::accounts::RequestChannelClose {
+        payer: *payer,
+        channel: pda::settlement_channel(payer, payee, mint),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::RequestChannelClose {}.data(),
+    }
+}
+
+pub fn close_channel(payer: &Pubkey, payee: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Instruction {
+    let channel = pda::settlement_channel(payer, payee, mint);
+    let accounts = escrow::accounts::CloseChannel {
+        payer: *payer,
+        channel,
+        token_mint: *mint,
+        channel_vault: get_associated_token_address_with_program_id(&channel, mint, token_program),
+        payer_token_account: get_associated_token_address_with_program_id(payer, mint, token_program),
+        token_program: *token_program,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::CloseChannel {}.data(),
+    }
+}
+
+pub fn dispute(authority: &Pubkey, provider: &Pubkey, escrow_id: u64) -> Instruction {
+    let accounts = escrow::accounts::Dispute {
+        authority: *authority,
+        escrow_account: pda::escrow(provider, escrow_id),
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        provider_stats: pda::provider_stats(provider),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::Dispute {}.data(),
+    }
+}
+
+/// Provider or renter attaches a pointer to off-chain proof to the open dispute
+pub fn submit_evidence(
+    authority: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    evidence_uri: String,
+    evidence_hash: [u8; 32],
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::SubmitEvidence {
+        authority: *authority,
+        escrow_account,
+        evidence: pda::dispute_evidence(&escrow_account),
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::SubmitEvidence {
+            evidence_uri,
+            evidence_hash,
+        }
+        .data(),
+    }
+}
+
+/// Arbiter declares no prior rental with `provider` or `renter`; needed before ruling.
+/// A member of the escrow's arbiter council passes the `council` address.
+pub fn declare_no_conflict(
+    arbiter: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    council: Option<&Pubkey>,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::DeclareNoConflict {
+        arbiter: *arbiter,
+        escrow_account,
+        council: council.copied(),
+        declaration: pda::arbiter_declaration(&escrow_account, arbiter),
+        arbiter_stats: pda::arbiter_stats(arbiter),
+        arbiter_to_renter: pda::engagement(arbiter, renter),
+        renter_to_arbiter: pda::engagement(renter, arbiter),
+        arbiter_to_provider: pda::engagement(arbiter, provider),
+        provider_to_arbiter: pda::engagement(provider, arbiter),
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::DeclareNoConflict {}.data(),
+    }
+}
+
+/// Void `arbiter`'s declaration on `escrow` with the rental record of
+/// `engagement_provider` and `engagement_renter`; `reporter` can be anyone
+pub fn report_conflict(
+    reporter: &Pubkey,
+    escrow: &Pubkey,
+    arbiter: &Pubkey,
+    engagement_provider: &Pubkey,
+    engagement_renter: &Pubkey,
+) -> Instruction {
+    let accounts = escrow::accounts::ReportConflict {
+        reporter: *reporter,
+        declaration: pda::arbiter_declaration(escrow, arbiter),
+        engagement: pda::engagement(engagement_provider, engagement_renter),
+        arbiter_stats: pda::arbiter_stats(arbiter),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ReportConflict {}.data(),
+    }
+}
+
+pub fn create_council(creator: &Pubkey, council_id: u32, members: Vec<Pubkey>, threshold: u8) -> Instruction {
+    let accounts = escrow::accounts::CreateCouncil {
+        creator: *creator,
+        council: pda::arbiter_council(creator, council_id),
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::CreateCouncil {
+            council_id,
+            members,
+            threshold,
+        }
+        .data(),
+    }
+}
+
+fn council_vote(member: &Pubkey, provider: &Pubkey, escrow_id: u64, council: &Pubkey) -> Vec<AccountMeta> {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    escrow::accounts::ProposeResolution {
+        member: *member,
+        escrow_account,
+        council: *council,
+        proposal: pda::resolution_proposal(&escrow_account),
+        declaration: pda::arbiter_declaration(&escrow_account, member),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None)
+}
+
+/// Council `member` proposes, and approves, a ruling on the escrow's open dispute
+pub fn propose_resolution(
+    member: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    council: &Pubkey,
+    outcome: ResolutionOutcome,
+    ruling_hash: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: escrow::ID,
+        accounts: council_vote(member, provider, escrow_id, council),
+        data: escrow::instruction::ProposeResolution { outcome, ruling_hash }.data(),
+    }
+}
+
+/// Council `member` approves the pending ruling, restated as `outcome` and `ruling_hash`
+pub fn approve_resolution(
+    member: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    council: &Pubkey,
+    outcome: ResolutionOutcome,
+    ruling_hash: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: escrow::ID,
+        accounts: council_vote(member, provider, escrow_id, council),
+        data: escrow::instruction::ApproveResolution { outcome, ruling_hash }.data(),
+    }
+}
+
+/// Settle the dispute as the council's approved proposal rules; `executor` can be
+/// anyone. `approvers` are the members whose approvals count toward the threshold.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_resolution(
+    executor: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    council: &Pubkey,
+    approvers: &[Pubkey],
+    fee_treasury: Option<&Pubkey>,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::ExecuteResolution {
+        executor: *executor,
+        escrow_account,
+        council: *council,
+        proposal: pda::resolution_proposal(&escrow_account),
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        arbiter_stats: pda::arbiter_stats(council),
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        provider: *provider,
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        renter: *renter,
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        token_mint: *mint,
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        price_oracle: None,
+        fee_token_account: fee_token_account(fee_treasury, mint, token_program),
+        provider_points: None,
+        activity: no_activity(),
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(
+        approvers
+            .iter()
+            .map(|member| AccountMeta::new_readonly(pda::arbiter_declaration(&escrow_account, member), false)),
+    );
+    Instruction {
+        program_id: escrow::ID,
+        accounts: metas,
+        data: escrow::instruction::ExecuteResolution {}.data(),
+    }
+}
+
+/// Settle a dispute left unresolved past its arbitration deadline; `cranker` can be
+/// anyone. `fee_treasury` is needed when default judgment releases with a fee.
+pub fn claim_default_resolution(
+    cranker: &Pubkey,
+    provider: &Pubkey,
+    escrow_id: u64,
+    renter: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    fee_treasury: Option<&Pubkey>,
+) -> Instruction {
+    let escrow_account = pda::escrow(provider, escrow_id);
+    let accounts = escrow::accounts::ClaimDefaultResolution {
+        cranker: *cranker,
+        escrow_account,
+        provider_index: pda::provider_index(provider),
+        provider_stats: pda::provider_stats(provider),
+        config: pda::config(),
+        epoch_stats: pda::epoch_stats(),
+        escrow_token_account: pda::escrow_vault(&escrow_account, mint, token_program),
+        provider: *provider,
+        provider_token_account: get_associated_token_address_with_program_id(provider, mint, token_program),
+        renter: *renter,
+        renter_token_account: get_associated_token_address_with_program_id(renter, mint, token_program),
+        token_mint: *mint,
+        system_program: system_program::ID,
+        token_program: *token_program,
+        associated_token_program: associated_token::ID,
+        price_oracle: None,
+        fee_token_account: fee_token_account(fee_treasury, mint, token_program),
+        activity: no_activity(),
+    };
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: escrow::instruction::ClaimDefaultResolution {}.data(),
+    }
+}
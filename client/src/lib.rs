@@ -0,0 +1,49 @@
+//! Rust client for the TrustyClaw escrow and reputation programs
+//!
+//! - `amount`: decimal-carrying token amounts, so base units and display units can't be mixed up
+//! - `pda`: program-derived address helpers
+//! - `instructions`: instruction builders for the escrow program
+//! - `channel`: signing and submitting settlement-channel balance updates
+//! - `acceptance`: renter-signed acceptance of an escrow's terms, recorded on-chain
+//! - `demo`: sample escrows and reputation agents for a `devnet-demo` deployment
+//! - `decode`: decodes escrow and agent accounts written by any historical layout
+//! - `events`: typed escrow events, polled subscriptions and filters over them
+//! - `errors`: registry mapping custom error codes to names and messages
+//! - `preflight`: simulate and check balances before sending
+//! - `preview`: a listing's terms, its provider's risk score and dry runs of
+//!   initializing, funding and releasing, read by simulation
+//! - `rpc`: minimal blocking JSON-RPC transport
+//! - `submit`: resend-until-definitive transaction submission, with durable nonce support
+//! - `metrics` (feature `metrics`): Prometheus gauges of protocol health
+//! - `scan`: filtered, paginated account listings (escrows by party or skill, open disputes, reviews)
+//! - `provider_agent`: funding-event handling used by the `provider-agentd` binary
+//! - `watchtower`: renter-side timeout policy used by the `watchtower` binary
+//! - `terms_document`: versioned schema and hashes of the off-chain terms document at `metadata_uri`
+//! - `text`: building the fixed-size skill name and metadata URI fields of escrow terms
+//! - `webhook`: signing and verifying webhook callbacks against the listing's registered key
+
+pub mod acceptance;
+pub mod amount;
+pub mod channel;
+pub mod decode;
+pub mod demo;
+pub mod errors;
+pub mod events;
+pub mod instructions;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod pda;
+pub mod preflight;
+pub mod preview;
+pub mod provider_agent;
+pub mod rpc;
+pub mod scan;
+pub mod submit;
+pub mod terms_document;
+pub mod text;
+pub mod watchtower;
+pub mod webhook;
+
+pub use amount::{AmountError, TokenAmount, UsdcAmount, USDC_DECIMALS};
+pub use preflight::{preflight, PreflightReport, PreflightRpc};
+pub use submit::{Lifetime, Outcome, Submitter, SubmitRpc};
@@ -0,0 +1,184 @@
+//! Protocol health metrics in the Prometheus text format
+//!
+//! Computed from a full scan of escrow accounts, so every number reflects what is on
+//! chain at scrape time and nothing is accumulated between scrapes. That makes every
+//! metric a gauge: closed accounts drop out of the totals. The `metrics-exporter`
+//! binary serves [`EscrowMetrics::render`] over HTTP.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowState};
+
+use crate::decode::decode_escrow;
+use crate::scan::{AccountFilter, ScanRpc};
+
+/// Prometheus names of every `EscrowState`, in declaration order
+const STATES: [(EscrowState, &str); 9] = [
+    (EscrowState::Created, "created"),
+    (EscrowState::Funded, "funded"),
+    (EscrowState::Completed, "completed"),
+    (EscrowState::Cancelled, "cancelled"),
+    (EscrowState::Disputed, "disputed"),
+    (EscrowState::Closed, "closed"),
+    (EscrowState::Expired, "expired"),
+    (EscrowState::Vesting, "vesting"),
+    (EscrowState::Subscribed, "subscribed"),
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EscrowMetrics {
+    /// Escrow accounts per state
+    pub escrows: BTreeMap<&'static str, u64>,
+    /// Rentals ever funded, and how many of them were disputed
+    pub funded_rentals: u64,
+    pub disputed_rentals: u64,
+    /// `amount` of every funded rental per mint, in base units
+    pub funded_volume: BTreeMap<Pubkey, u64>,
+    /// Funded amounts per mint still held in escrow vaults, in base units
+    pub locked: BTreeMap<Pubkey, u64>,
+    /// Seconds from funding to release, summed over every released rental
+    pub release_seconds_total: u64,
+    pub releases: u64,
+    /// Listings `expire_listing` could end now
+    pub expirable_listings: u64,
+    /// Undelivered rentals `refund_after_timeout` could refund now
+    pub refundable_rentals: u64,
+}
+
+impl EscrowMetrics {
+    /// Tally `escrows` as of cluster time `now`
+    pub fn collect<'a>(escrows: impl IntoIterator<Item = &'a EscrowAccount>, now: i64) -> Self {
+        let mut metrics = Self::default();
+        for (_, name) in STATES {
+            metrics.escrows.insert(name, 0);
+        }
+        for escrow in escrows {
+            let state = STATES.iter().find(|(state, _)| *state == escrow.state).map(|(_, name)| *name);
+            if let Some(name) = state {
+                *metrics.escrows.entry(name).or_default() += 1;
+            }
+            if escrow.state == EscrowState::Created && !escrow.funding_open(now) {
+                metrics.expirable_listings += 1;
+            }
+            if escrow.funded_at == 0 {
+                continue;
+            }
+
+            metrics.funded_rentals += 1;
+            let volume = metrics.funded_volume.entry(escrow.token_mint).or_default();
+            *volume = volume.saturating_add(escrow.amount);
+            if escrow.disputed_at > 0 {
+                metrics.disputed_rentals += 1;
+            }
+            match escrow.state {
+                EscrowState::Funded | EscrowState::Disputed => {
+                    let locked = metrics.locked.entry(escrow.token_mint).or_default();
+                    *locked = locked.saturating_add(escrow.amount);
+                    if escrow.check_timeout_refund(now).is_ok() {
+                        metrics.refundable_rentals += 1;
+                    }
+                }
+                EscrowState::Completed | EscrowState::Vesting if escrow.completed_at >= escrow.funded_at => {
+                    metrics.releases += 1;
+                    metrics.release_seconds_total = metrics
+                        .release_seconds_total
+                        .saturating_add((escrow.completed_at - escrow.funded_at) as u64);
+                }
+                _ => {}
+            }
+        }
+        metrics
+    }
+
+    /// Share of funded rentals that were disputed, 0 before any funding
+    pub fn dispute_rate(&self) -> f64 {
+        match self.funded_rentals {
+            0 => 0.0,
+            funded => self.disputed_rentals as f64 / funded as f64,
+        }
+    }
+
+    /// Mean seconds from funding to release, 0 before any release
+    pub fn mean_time_to_release_seconds(&self) -> f64 {
+        match self.releases {
+            0 => 0.0,
+            releases => self.release_seconds_total as f64 / releases as f64,
+        }
+    }
+
+    /// Escrows on which a timed action is overdue
+    pub fn crank_backlog(&self) -> u64 {
+        self.expirable_listings + self.refundable_rentals
+    }
+
+    /// Prometheus text exposition of every metric
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let open = ["created", "funded", "disputed", "vesting", "subscribed"]
+            .iter()
+            .map(|state| self.escrows.get(state).copied().unwrap_or_default())
+            .sum::<u64>();
+
+        header(&mut out, "trustyclaw_escrows", "gauge", "Escrow accounts by state");
+        for (state, count) in &self.escrows {
+            writeln!(out, "trustyclaw_escrows{{state=\"{state}\"}} {count}").unwrap();
+        }
+        header(&mut out, "trustyclaw_open_escrows", "gauge", "Escrows not yet settled or withdrawn");
+        writeln!(out, "trustyclaw_open_escrows {open}").unwrap();
+        header(&mut out, "trustyclaw_funded_rentals", "gauge", "Rentals ever funded");
+        writeln!(out, "trustyclaw_funded_rentals {}", self.funded_rentals).unwrap();
+        header(&mut out, "trustyclaw_disputed_rentals", "gauge", "Funded rentals that were disputed");
+        writeln!(out, "trustyclaw_disputed_rentals {}", self.disputed_rentals).unwrap();
+        header(&mut out, "trustyclaw_dispute_rate", "gauge", "Share of funded rentals that were disputed");
+        writeln!(out, "trustyclaw_dispute_rate {}", self.dispute_rate()).unwrap();
+        header(&mut out, "trustyclaw_funded_volume_base_units", "gauge", "Funded amounts by mint");
+        for (mint, amount) in &self.funded_volume {
+            writeln!(out, "trustyclaw_funded_volume_base_units{{mint=\"{mint}\"}} {amount}").unwrap();
+        }
+        header(&mut out, "trustyclaw_locked_base_units", "gauge", "Amounts held in escrow vaults by mint");
+        for (mint, amount) in &self.locked {
+            writeln!(out, "trustyclaw_locked_base_units{{mint=\"{mint}\"}} {amount}").unwrap();
+        }
+        header(
+            &mut out,
+            "trustyclaw_mean_time_to_release_seconds",
+            "gauge",
+            "Mean seconds from funding to release",
+        );
+        writeln!(out, "trustyclaw_mean_time_to_release_seconds {}", self.mean_time_to_release_seconds()).unwrap();
+        header(&mut out, "trustyclaw_crank_backlog", "gauge", "Escrows on which a timed action is overdue");
+        writeln!(out, "trustyclaw_crank_backlog{{crank=\"expire_listing\"}} {}", self.expirable_listings).unwrap();
+        writeln!(
+            out,
+            "trustyclaw_crank_backlog{{crank=\"refund_after_timeout\"}} {}",
+            self.refundable_rentals
+        )
+        .unwrap();
+        out
+    }
+}
+
+/// Every escrow account, decoded from whichever layout wrote it
+pub fn all_escrows<R: ScanRpc>(rpc: &R) -> Result<Vec<EscrowAccount>, R::Error> {
+    let mut addresses = rpc.program_account_keys(&escrow::ID, &[AccountFilter::discriminator::<EscrowAccount>()])?;
+    addresses.sort_unstable();
+    addresses.dedup();
+    let mut escrows = Vec::with_capacity(addresses.len());
+    for chunk in addresses.chunks(crate::scan::MAX_MULTIPLE_ACCOUNTS) {
+        escrows.extend(
+            rpc.multiple_accounts(chunk)?
+                .into_iter()
+                .flatten()
+                .filter_map(|data| decode_escrow(&data).ok())
+                .map(|view| view.escrow),
+        );
+    }
+    Ok(escrows)
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {name} {help}").unwrap();
+    writeln!(out, "# TYPE {name} {kind}").unwrap();
+}
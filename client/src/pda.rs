@@ -0,0 +1,167 @@
+//! Program-derived addresses used by the escrow program
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+pub fn escrow(provider: &Pubkey, escrow_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[escrow::ESCROW_SEED, provider.as_ref(), &escrow::escrow_id_seed(escrow_id)],
+        &escrow::ID,
+    )
+    .0
+}
+
+pub fn provider_index(provider: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::PROVIDER_INDEX_SEED, provider.as_ref()], &escrow::ID).0
+}
+
+pub fn provider_stats(provider: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::PROVIDER_STATS_SEED, provider.as_ref()], &escrow::ID).0
+}
+
+/// Resolution record of `arbiter`, created at their first ruling
+pub fn arbiter_stats(arbiter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::ARBITER_STATS_SEED, arbiter.as_ref()], &escrow::ID).0
+}
+
+pub fn credit_balance(provider: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[escrow::CREDIT_SEED, provider.as_ref(), mint.as_ref()],
+        &escrow::ID,
+    )
+    .0
+}
+
+pub fn config() -> Pubkey {
+    Pubkey::find_program_address(&[escrow::CONFIG_SEED], &escrow::ID).0
+}
+
+/// The protocol's running `EpochStats`
+pub fn epoch_stats() -> Pubkey {
+    Pubkey::find_program_address(&[escrow::EPOCH_STATS_SEED], &escrow::ID).0
+}
+
+/// `EpochSnapshot` of the counting period that started in `epoch`
+pub fn epoch_snapshot(epoch: u64) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::EPOCH_SNAPSHOT_SEED, &epoch.to_le_bytes()], &escrow::ID).0
+}
+
+/// Token account holding `escrow`'s provider bond, owned by the escrow itself
+pub fn bond_vault(escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::BOND_VAULT_SEED, escrow.as_ref()], &escrow::ID).0
+}
+
+pub fn points(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::POINTS_SEED, owner.as_ref()], &escrow::ID).0
+}
+
+/// Settlement channel from `payer` to `payee` in `mint`
+pub fn settlement_channel(payer: &Pubkey, payee: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[escrow::CHANNEL_SEED, payer.as_ref(), payee.as_ref(), mint.as_ref()],
+        &escrow::ID,
+    )
+    .0
+}
+
+/// Pending request by `renter` on a listing that requires provider acceptance
+pub fn rental_request(escrow: &Pubkey, renter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::RENTAL_REQUEST_SEED, escrow.as_ref(), renter.as_ref()], &escrow::ID).0
+}
+
+/// Invoice of the rental funded at `funded_at` on `escrow`
+pub fn invoice(escrow: &Pubkey, funded_at: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[escrow::INVOICE_SEED, escrow.as_ref(), &funded_at.to_le_bytes()],
+        &escrow::ID,
+    )
+    .0
+}
+
+/// Milestone payout plan of `escrow`, created by its first `add_milestone`
+pub fn milestone_plan(escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::MILESTONES_SEED, escrow.as_ref()], &escrow::ID).0
+}
+
+pub fn subscription_plan(escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::SUBSCRIPTION_SEED, escrow.as_ref()], &escrow::ID).0
+}
+
+/// `renter`'s recorded acceptance of `escrow`'s terms
+pub fn terms_acceptance(escrow: &Pubkey, renter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::ACCEPTANCE_SEED, escrow.as_ref(), renter.as_ref()], &escrow::ID).0
+}
+
+/// Evidence submitted in `escrow`'s disputes, created by its first `submit_evidence`
+pub fn dispute_evidence(escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::EVIDENCE_SEED, escrow.as_ref()], &escrow::ID).0
+}
+
+/// Rental history of `provider` with `renter`, created when their first rental is funded
+pub fn engagement(provider: &Pubkey, renter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::ENGAGEMENT_SEED, provider.as_ref(), renter.as_ref()], &escrow::ID).0
+}
+
+/// `arbiter`'s no-conflict declaration for `escrow`'s dispute
+pub fn arbiter_declaration(escrow: &Pubkey, arbiter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::DECLARATION_SEED, escrow.as_ref(), arbiter.as_ref()], &escrow::ID).0
+}
+
+/// Arbiter council `council_id` formed by `creator`
+pub fn arbiter_council(creator: &Pubkey, council_id: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[escrow::COUNCIL_SEED, creator.as_ref(), &council_id.to_le_bytes()],
+        &escrow::ID,
+    )
+    .0
+}
+
+/// Pending council ruling on `escrow`'s dispute, created by its first `propose_resolution`
+pub fn resolution_proposal(escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::PROPOSAL_SEED, escrow.as_ref()], &escrow::ID).0
+}
+
+/// Translated terms of `escrow`, created by its first `set_terms_localization`
+pub fn terms_localization(escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[escrow::TERMS_LOCALIZATION_SEED, escrow.as_ref()], &escrow::ID).0
+}
+
+/// `provider`'s skill listing `listing_id`
+pub fn skill_listing(provider: &Pubkey, listing_id: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[escrow::SKILL_LISTING_SEED, provider.as_ref(), &listing_id.to_le_bytes()],
+        &escrow::ID,
+    )
+    .0
+}
+
+/// Escrow of the `rental`-th rental opened against `provider`'s listing `listing_id`
+pub fn listing_rental(provider: &Pubkey, listing_id: u16, rental: u32) -> Pubkey {
+    escrow(provider, escrow::rental_escrow_id(listing_id, rental))
+}
+
+/// Global `ReputationState` of the reputation program
+pub fn reputation_state() -> Pubkey {
+    Pubkey::find_program_address(&[b"reputation_state"], &reputation::ID).0
+}
+
+/// Reputation `Agent` account of `owner`
+pub fn reputation_agent(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"agent", owner.as_ref()], &reputation::ID).0
+}
+
+/// `ReviewShard` of `agent` that `reviewer`'s sharded reviews go to
+pub fn review_shard(agent: &Pubkey, reviewer: &Pubkey) -> Pubkey {
+    let shard = reputation::review_shard_index(reviewer);
+    Pubkey::find_program_address(&[reputation::REVIEW_SHARD_SEED, agent.as_ref(), &[shard]], &reputation::ID).0
+}
+
+/// Vault holding a (non-credit) escrow's funds; `token_program` is the mint's owner
+pub fn escrow_vault(escrow: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(escrow, mint, token_program)
+}
+
+/// Faucet mint of a `devnet-demo` deployment
+pub fn demo_mint() -> Pubkey {
+    Pubkey::find_program_address(&[escrow::DEMO_MINT_SEED], &escrow::ID).0
+}
@@ -0,0 +1,150 @@
+//! Transaction preflight
+//!
+//! Simulates a transaction and checks the token accounts it depends on before anything
+//! is signed and sent, so deterministic failures (wrong state, missing ATA, short
+//! balance) are caught without paying fees. The RPC is abstracted behind
+//! [`PreflightRpc`] so this crate does not pin a Solana client version.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::state::{Account as SplAccount, Mint as SplMint};
+
+use crate::amount::TokenAmount;
+use crate::errors::{self, RegisteredError};
+
+/// What the client needs from an RPC node
+pub trait PreflightRpc {
+    type Error: std::error::Error;
+
+    /// Simulate `instructions` with `payer` as fee payer, without signature verification
+    fn simulate(&self, payer: &Pubkey, instructions: &[Instruction]) -> Result<Simulation, Self::Error>;
+
+    /// Raw account data, or `None` if the account does not exist
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, Self::Error>;
+}
+
+/// Result of a simulation as reported by the RPC
+#[derive(Debug, Clone, Default)]
+pub struct Simulation {
+    /// `(instruction_index, code)` when the failure was `InstructionError::Custom`
+    pub custom_error: Option<(u8, u32)>,
+    /// Any other failure, stringified
+    pub other_error: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// Data the transaction's last `set_return_data` left, with the program that set it
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+}
+
+/// A token account the transaction will draw from
+#[derive(Debug, Clone, Copy)]
+pub struct TokenRequirement {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: TokenAmount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenCheck {
+    Ok { balance: TokenAmount },
+    MissingAta { ata: Pubkey },
+    MissingMint,
+    DecimalsMismatch { mint_decimals: u8, requested: u8 },
+    InsufficientBalance { balance: TokenAmount, required: TokenAmount },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationFailure {
+    /// A custom program error resolved through the error registry
+    Program { instruction: u8, program_id: Pubkey, error: RegisteredError },
+    /// A custom code the registry does not know
+    UnknownCustom { instruction: u8, program_id: Option<Pubkey>, code: u32 },
+    Other(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct PreflightReport {
+    pub failure: Option<SimulationFailure>,
+    pub token_checks: Vec<(TokenRequirement, TokenCheck)>,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+}
+
+impl PreflightReport {
+    /// True when sending the transaction is expected to succeed
+    pub fn is_ok(&self) -> bool {
+        self.failure.is_none() && self.token_checks.iter().all(|(_, c)| matches!(c, TokenCheck::Ok { .. }))
+    }
+}
+
+/// Simulate `instructions` and check every `requirement`
+pub fn preflight<R: PreflightRpc>(
+    rpc: &R,
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    requirements: &[TokenRequirement],
+) -> Result<PreflightReport, R::Error> {
+    let simulation = rpc.simulate(payer, instructions)?;
+    let failure = decode_failure(&simulation, instructions);
+
+    let mut token_checks = Vec::with_capacity(requirements.len());
+    for requirement in requirements {
+        token_checks.push((*requirement, check_token(rpc, requirement)?));
+    }
+
+    Ok(PreflightReport {
+        failure,
+        token_checks,
+        units_consumed: simulation.units_consumed,
+        logs: simulation.logs,
+    })
+}
+
+pub(crate) fn decode_failure(simulation: &Simulation, instructions: &[Instruction]) -> Option<SimulationFailure> {
+    if let Some((instruction, code)) = simulation.custom_error {
+        let program_id = instructions.get(instruction as usize).map(|ix| ix.program_id);
+        return Some(
+            match program_id.and_then(|id| errors::lookup(&id, code).map(|e| (id, e))) {
+                Some((program_id, error)) => SimulationFailure::Program {
+                    instruction,
+                    program_id,
+                    error,
+                },
+                None => SimulationFailure::UnknownCustom {
+                    instruction,
+                    program_id,
+                    code,
+                },
+            },
+        );
+    }
+    simulation.other_error.clone().map(SimulationFailure::Other)
+}
+
+fn check_token<R: PreflightRpc>(rpc: &R, requirement: &TokenRequirement) -> Result<TokenCheck, R::Error> {
+    let Some(mint) = rpc.account_data(&requirement.mint)?.and_then(|d| SplMint::unpack(&d).ok()) else {
+        return Ok(TokenCheck::MissingMint);
+    };
+    if mint.decimals != requirement.amount.decimals() {
+        return Ok(TokenCheck::DecimalsMismatch {
+            mint_decimals: mint.decimals,
+            requested: requirement.amount.decimals(),
+        });
+    }
+
+    let ata = get_associated_token_address(&requirement.owner, &requirement.mint);
+    let Some(account) = rpc.account_data(&ata)?.and_then(|d| SplAccount::unpack(&d).ok()) else {
+        return Ok(TokenCheck::MissingAta { ata });
+    };
+    // decimals were just validated against the mint, so this cannot fail
+    let balance = TokenAmount::from_base_units(account.amount, mint.decimals).expect("valid decimals");
+    if account.amount < requirement.amount.base_units() {
+        return Ok(TokenCheck::InsufficientBalance {
+            balance,
+            required: requirement.amount,
+        });
+    }
+    Ok(TokenCheck::Ok { balance })
+}
@@ -0,0 +1,99 @@
+//! "What you're agreeing to" before funding
+//!
+//! [`engagement`] simulates the escrow's `preview_engagement` view instruction for a
+//! listing and a prospective renter and decodes the [`EngagementPreview`] it returns:
+//! both parties' reputation, the arbiter's record, whatever would block the funding,
+//! the amount due now and later, the protocol fee and every deadline the rental would
+//! run on. [`risk`] does the same for `compute_risk`, scoring the provider as a
+//! counterparty, and [`validate`] for the `validate_*` dry runs of initializing, funding
+//! and releasing, whose [`Validation`] names the first check that would fail. Nothing is
+//! signed or sent.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::AnchorDeserialize;
+use escrow::{EngagementPreview, EscrowAccount, FundOverrides, RiskAssessment, Validation};
+use thiserror::Error;
+
+use crate::preflight::{decode_failure, PreflightRpc, SimulationFailure};
+use crate::{instructions, pda};
+
+#[derive(Debug, Error)]
+pub enum PreviewError<E: std::error::Error> {
+    #[error(transparent)]
+    Rpc(E),
+    /// The listing could not be previewed, e.g. an override outside its policy
+    #[error("preview failed: {0:?}")]
+    Failed(SimulationFailure),
+    #[error("preview returned no data")]
+    NoReturnData,
+    #[error("preview return data is malformed: {0}")]
+    Malformed(std::io::Error),
+}
+
+/// Preview `renter` funding `listing` with `overrides`, simulated with `renter` as fee
+/// payer. Reputation and arbiter accounts are passed whenever they exist.
+pub fn engagement<R: PreflightRpc>(
+    rpc: &R,
+    renter: &Pubkey,
+    listing: &EscrowAccount,
+    overrides: FundOverrides,
+) -> Result<EngagementPreview, PreviewError<R::Error>> {
+    let exists = |address: Pubkey| rpc.account_data(&address).map(|data| data.is_some()).map_err(PreviewError::Rpc);
+    let arbiter = overrides.arbiter.unwrap_or(listing.terms.arbiter);
+    let instruction = instructions::preview_engagement(
+        renter,
+        &listing.provider,
+        listing.escrow_id,
+        overrides,
+        exists(pda::reputation_agent(renter))?,
+        exists(pda::reputation_agent(&listing.provider))?,
+        exists(pda::arbiter_stats(&arbiter))?.then_some(&arbiter),
+    );
+
+    simulate_view(rpc, renter, instruction)
+}
+
+/// Score `listing`'s provider as a counterparty, simulated with `payer` as fee payer.
+/// Fails with `RiskScoringDisabled` until the admin sets the config's risk weights.
+pub fn risk<R: PreflightRpc>(
+    rpc: &R,
+    payer: &Pubkey,
+    listing: &EscrowAccount,
+) -> Result<RiskAssessment, PreviewError<R::Error>> {
+    let reputation = rpc
+        .account_data(&pda::reputation_agent(&listing.provider))
+        .map_err(PreviewError::Rpc)?
+        .is_some();
+    let instruction = instructions::compute_risk(&listing.provider, listing.escrow_id, reputation);
+    simulate_view(rpc, payer, instruction)
+}
+
+/// Simulate a `validate_initialize`, `validate_fund` or `validate_release` instruction,
+/// with `payer` as fee payer. A failed check is an `Ok` [`Validation`] carrying its error;
+/// `Failed` means the accounts passed could not be loaded, e.g. a listing that is gone.
+pub fn validate<R: PreflightRpc>(
+    rpc: &R,
+    payer: &Pubkey,
+    instruction: Instruction,
+) -> Result<Validation, PreviewError<R::Error>> {
+    simulate_view(rpc, payer, instruction)
+}
+
+fn simulate_view<R: PreflightRpc, T: AnchorDeserialize>(
+    rpc: &R,
+    payer: &Pubkey,
+    instruction: Instruction,
+) -> Result<T, PreviewError<R::Error>> {
+    let instructions = [instruction];
+    let simulation = rpc.simulate(payer, &instructions).map_err(PreviewError::Rpc)?;
+    if let Some(failure) = decode_failure(&simulation, &instructions) {
+        return Err(PreviewError::Failed(failure));
+    }
+    match simulation.return_data {
+        Some((program_id, data)) if program_id == escrow::ID => {
+            T::try_from_slice(&data).map_err(PreviewError::Malformed)
+        }
+        _ => Err(PreviewError::NoReturnData),
+    }
+}
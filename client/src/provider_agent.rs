@@ -0,0 +1,183 @@
+//! Provider-side automation
+//!
+//! Decodes `EscrowFunded` events from transaction logs, hands each one to a local
+//! command or webhook that runs the skill workload, and turns the artifact hash it
+//! returns into a `submit_deliverable` instruction. Used by the `provider-agentd` binary.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use escrow::{EscrowDisputed, EscrowExpedited, EscrowFunded};
+use serde_json::{json, Value};
+use solana_sdk::signature::Keypair;
+use thiserror::Error;
+
+use crate::events::program_data;
+use crate::instructions;
+use crate::rpc::{HttpEndpoint, RpcError};
+use crate::webhook;
+
+#[derive(Debug, Error)]
+pub enum AgentError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    #[error("workload command exited with {0}")]
+    CommandFailed(std::process::ExitStatus),
+    #[error("invalid artifact response: {0}")]
+    InvalidArtifact(String),
+}
+
+/// Proof returned by the workload, committed on-chain via `submit_deliverable`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    pub deliverable_hash: [u8; 32],
+    /// Where the renter fetches the deliverable; empty if handed over off-chain
+    pub deliverable_uri: String,
+    /// Zero when the deliverable is not key-escrowed
+    pub encrypted_key_hash: [u8; 32],
+}
+
+impl Artifact {
+    /// Accepts either `{"deliverable_hash": "<hex>", "deliverable_uri": "...",
+    /// "encrypted_key_hash": "<hex>"}` or whitespace-separated hex hashes. The key hash is
+    /// optional in both forms; only the JSON form carries a URI.
+    pub fn parse(output: &str) -> Result<Self, AgentError> {
+        let output = output.trim();
+        let (deliverable, uri, key) = if output.starts_with('{') {
+            let value: Value =
+                serde_json::from_str(output).map_err(|e| AgentError::InvalidArtifact(e.to_string()))?;
+            (
+                value["deliverable_hash"].as_str().map(str::to_string),
+                value["deliverable_uri"].as_str().map(str::to_string),
+                value["encrypted_key_hash"].as_str().map(str::to_string),
+            )
+        } else {
+            let mut parts = output.split_whitespace().map(str::to_string);
+            (parts.next(), None, parts.next())
+        };
+
+        let deliverable = deliverable.ok_or_else(|| AgentError::InvalidArtifact("missing deliverable hash".into()))?;
+        Ok(Self {
+            deliverable_hash: parse_hash(&deliverable)?,
+            deliverable_uri: uri.unwrap_or_default(),
+            encrypted_key_hash: key.as_deref().map(parse_hash).transpose()?.unwrap_or_default(),
+        })
+    }
+}
+
+/// Where funded escrows are handed off to
+#[derive(Debug, Clone)]
+pub enum Responder {
+    /// Run through `sh -c`, with the event as JSON on stdin and as `TRUSTYCLAW_*` env vars
+    Command(String),
+    /// POST the event as JSON; the response body is the artifact
+    Webhook(HttpEndpoint),
+    /// [`Responder::Webhook`], signed with the listing's registered webhook signer
+    SignedWebhook(HttpEndpoint, Arc<Keypair>),
+}
+
+impl Responder {
+    pub fn respond(&self, event: &EscrowFunded) -> Result<Artifact, AgentError> {
+        let payload = event_json(event).to_string();
+        let output = match self {
+            Responder::Command(command) => {
+                let mut child = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .env("TRUSTYCLAW_ESCROW", event.escrow.to_string())
+                    .env("TRUSTYCLAW_RENTER", event.renter.to_string())
+                    .env("TRUSTYCLAW_MINT", event.token_mint.to_string())
+                    .env("TRUSTYCLAW_AMOUNT", event.amount.to_string())
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .spawn()?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(payload.as_bytes())?;
+                }
+                let output = child.wait_with_output()?;
+                if !output.status.success() {
+                    return Err(AgentError::CommandFailed(output.status));
+                }
+                String::from_utf8_lossy(&output.stdout).into_owned()
+            }
+            Responder::Webhook(endpoint) => endpoint.post_json(&payload)?,
+            Responder::SignedWebhook(endpoint, signer) => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+                let headers = webhook::sign(signer, &event.escrow, now, payload.as_bytes());
+                endpoint.post_json_with_headers(&payload, &headers)?
+            }
+        };
+        Artifact::parse(&output)
+    }
+}
+
+/// `EscrowFunded` events for `provider` found in a transaction's logs
+pub fn funded_events(logs: &[String], provider: &Pubkey) -> Vec<EscrowFunded> {
+    events::<EscrowFunded>(logs).filter(|event| event.provider == *provider).collect()
+}
+
+/// `EscrowExpedited` events for `provider` found in a transaction's logs; a work queue
+/// can move the rental up by its `expedite_fee`
+pub fn expedited_events(logs: &[String], provider: &Pubkey) -> Vec<EscrowExpedited> {
+    events::<EscrowExpedited>(logs).filter(|event| event.provider == *provider).collect()
+}
+
+/// `EscrowDisputed` events on `provider`'s rentals found in a transaction's logs; an
+/// agent should stop work on the rental until the arbiter rules
+pub fn disputed_events(logs: &[String], provider: &Pubkey) -> Vec<EscrowDisputed> {
+    events::<EscrowDisputed>(logs).filter(|event| event.provider == *provider).collect()
+}
+
+fn events<E: AnchorDeserialize + Discriminator>(logs: &[String]) -> impl Iterator<Item = E> + '_ {
+    program_data(logs).filter_map(|bytes| {
+        let (discriminator, mut body) = bytes.split_at_checked(8)?;
+        if discriminator != E::DISCRIMINATOR {
+            return None;
+        }
+        E::deserialize(&mut body).ok()
+    })
+}
+
+pub fn deliverable_instruction(provider: &Pubkey, escrow_id: u64, artifact: &Artifact) -> Instruction {
+    instructions::submit_deliverable(
+        provider,
+        escrow_id,
+        artifact.deliverable_hash,
+        &artifact.deliverable_uri,
+        artifact.encrypted_key_hash,
+    )
+}
+
+fn event_json(event: &EscrowFunded) -> Value {
+    json!({
+        "escrow": event.escrow.to_string(),
+        "provider": event.provider.to_string(),
+        "escrow_id": event.escrow_id,
+        "renter": event.renter.to_string(),
+        "token_mint": event.token_mint.to_string(),
+        "amount": event.amount,
+        "funded_at": event.funded_at,
+        "settle_in_credits": event.settle_in_credits,
+    })
+}
+
+fn parse_hash(hex: &str) -> Result<[u8; 32], AgentError> {
+    let invalid = || AgentError::InvalidArtifact(format!("'{hex}' is not a 32-byte hex hash"));
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() != 64 {
+        return Err(invalid());
+    }
+    let mut hash = [0u8; 32];
+    for (byte, pair) in hash.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+        let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+    }
+    Ok(hash)
+}
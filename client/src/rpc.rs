@@ -0,0 +1,365 @@
+//! Minimal blocking JSON-RPC client for the daemons in `src/bin`
+//!
+//! Speaks plain HTTP/1.0 only; point it at a local validator or an HTTP-to-HTTPS proxy
+//! in front of a hosted RPC.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+use thiserror::Error;
+
+use crate::preflight::{PreflightRpc, Simulation};
+use crate::scan::{AccountFilter, ScanRpc};
+use crate::submit::{SignatureStatus, SubmitRpc};
+
+#[derive(Debug, Error)]
+pub enum RpcError {
+    #[error("unsupported RPC url '{0}' (expected http://host[:port][/path])")]
+    Url(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("HTTP error: {0}")]
+    Http(String),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("RPC error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("unexpected RPC response: {0}")]
+    Decode(String),
+}
+
+/// A plain-HTTP endpoint, also used for provider webhooks
+#[derive(Debug, Clone)]
+pub struct HttpEndpoint {
+    host: String,
+    port: u16,
+    path: String,
+    timeout: Duration,
+}
+
+impl HttpEndpoint {
+    pub fn new(url: &str) -> Result<Self, RpcError> {
+        let rest = url.strip_prefix("http://").ok_or_else(|| RpcError::Url(url.to_string()))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| RpcError::Url(url.to_string()))?),
+            None => (authority, 80),
+        };
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// POST a JSON body and return the response body of a 200 reply
+    pub fn post_json(&self, body: &str) -> Result<String, RpcError> {
+        self.post_json_with_headers(body, &[])
+    }
+
+    /// [`Self::post_json`] with extra request headers
+    pub fn post_json_with_headers(&self, body: &str, headers: &[(&str, String)]) -> Result<String, RpcError> {
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        let extra: String = headers.iter().map(|(name, value)| format!("{name}: {value}\r\n")).collect();
+        write!(
+            stream,
+            "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\n\r\n{}",
+            self.path,
+            self.host,
+            extra,
+            body.len(),
+            body
+        )?;
+
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw)?;
+        let raw = String::from_utf8_lossy(&raw);
+        let (head, payload) = raw
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| RpcError::Http("malformed response".to_string()))?;
+        let status = head.lines().next().unwrap_or_default();
+        if !status.contains(" 200") {
+            return Err(RpcError::Http(status.to_string()));
+        }
+        Ok(payload.to_string())
+    }
+}
+
+pub struct RpcClient {
+    endpoint: HttpEndpoint,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Result<Self, RpcError> {
+        Ok(Self {
+            endpoint: HttpEndpoint::new(url)?,
+        })
+    }
+
+    pub fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string();
+        let mut response: Value = serde_json::from_str(&self.endpoint.post_json(&body)?)?;
+        if let Some(error) = response.get("error") {
+            return Err(RpcError::Rpc {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        Ok(response["result"].take())
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash, RpcError> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        result["value"]["blockhash"]
+            .as_str()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| RpcError::Decode(result.to_string()))
+    }
+
+    /// Current cluster clock, read from the sysvar rather than local time
+    pub fn clock(&self) -> Result<Clock, RpcError> {
+        let data = self
+            .account_data(&sysvar::clock::ID)?
+            .ok_or_else(|| RpcError::Decode("clock sysvar missing".to_string()))?;
+        bincode::deserialize(&data).map_err(|e| RpcError::Decode(e.to_string()))
+    }
+
+    /// Program owning `address`; for a mint, the token program its accounts live under
+    pub fn account_owner(&self, address: &Pubkey) -> Result<Option<Pubkey>, RpcError> {
+        let result = self.call("getAccountInfo", json!([address.to_string(), { "encoding": "base64" }]))?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            account => account["owner"]
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .map(Some)
+                .ok_or_else(|| RpcError::Decode(account.to_string())),
+        }
+    }
+
+    /// Accounts owned by `program_id` passing every filter
+    pub fn program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[AccountFilter],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, RpcError> {
+        let filters: Vec<Value> = filters.iter().map(filter_json).collect();
+        let result = self.call(
+            "getProgramAccounts",
+            json!([program_id.to_string(), { "encoding": "base64", "filters": filters }]),
+        )?;
+        let entries = result.as_array().ok_or_else(|| RpcError::Decode(result.to_string()))?;
+        entries
+            .iter()
+            .map(|entry| {
+                let pubkey = entry["pubkey"]
+                    .as_str()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or_else(|| RpcError::Decode(entry.to_string()))?;
+                Ok((pubkey, decode_data(&entry["account"]["data"])?))
+            })
+            .collect()
+    }
+
+    /// Signatures touching `address`, newest first, stopping before `until`
+    pub fn signatures_for_address(
+        &self,
+        address: &Pubkey,
+        until: Option<&Signature>,
+    ) -> Result<Vec<Signature>, RpcError> {
+        let mut config = json!({ "commitment": "confirmed" });
+        if let Some(until) = until {
+            config["until"] = json!(until.to_string());
+        }
+        let result = self.call("getSignaturesForAddress", json!([address.to_string(), config]))?;
+        let entries = result.as_array().ok_or_else(|| RpcError::Decode(result.to_string()))?;
+        Ok(entries
+            .iter()
+            .filter(|entry| entry["err"].is_null())
+            .filter_map(|entry| entry["signature"].as_str().and_then(|s| s.parse().ok()))
+            .collect())
+    }
+
+    /// Log messages of a confirmed transaction
+    pub fn transaction_logs(&self, signature: &Signature) -> Result<Vec<String>, RpcError> {
+        let result = self.call(
+            "getTransaction",
+            json!([signature.to_string(), { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 }]),
+        )?;
+        Ok(result["meta"]["logMessages"]
+            .as_array()
+            .map(|logs| logs.iter().filter_map(|l| l.as_str().map(str::to_string)).collect())
+            .unwrap_or_default())
+    }
+
+    pub fn send_transaction(&self, transaction: &Transaction) -> Result<Signature, RpcError> {
+        let encoded = encode_transaction(transaction)?;
+        let result = self.call("sendTransaction", json!([encoded, { "encoding": "base64" }]))?;
+        result
+            .as_str()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| RpcError::Decode(result.to_string()))
+    }
+}
+
+impl PreflightRpc for RpcClient {
+    type Error = RpcError;
+
+    fn simulate(&self, payer: &Pubkey, instructions: &[Instruction]) -> Result<Simulation, RpcError> {
+        let transaction = Transaction::new_with_payer(instructions, Some(payer));
+        let encoded = encode_transaction(&transaction)?;
+        let result = self.call(
+            "simulateTransaction",
+            json!([encoded, { "encoding": "base64", "sigVerify": false, "replaceRecentBlockhash": true }]),
+        )?;
+        let value = &result["value"];
+        let logs = value["logs"]
+            .as_array()
+            .map(|logs| logs.iter().filter_map(|l| l.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+
+        let err = &value["err"];
+        let custom = &err["InstructionError"];
+        let custom_error = match (custom[0].as_u64(), custom[1]["Custom"].as_u64()) {
+            (Some(index), Some(code)) => Some((index as u8, code as u32)),
+            _ => None,
+        };
+        let return_data = match &value["returnData"] {
+            Value::Null => None,
+            data => {
+                let program_id = data["programId"]
+                    .as_str()
+                    .and_then(|id| id.parse().ok())
+                    .ok_or_else(|| RpcError::Decode(data.to_string()))?;
+                Some((program_id, decode_data(&data["data"])?))
+            }
+        };
+        Ok(Simulation {
+            other_error: (custom_error.is_none() && !err.is_null()).then(|| err.to_string()),
+            custom_error,
+            logs,
+            units_consumed: value["unitsConsumed"].as_u64(),
+            return_data,
+        })
+    }
+
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, RpcError> {
+        let result = self.call("getAccountInfo", json!([address.to_string(), { "encoding": "base64" }]))?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            account => decode_data(&account["data"]).map(Some),
+        }
+    }
+}
+
+impl SubmitRpc for RpcClient {
+    fn latest_blockhash(&self) -> Result<Hash, RpcError> {
+        RpcClient::latest_blockhash(self)
+    }
+
+    fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool, RpcError> {
+        let result = self.call("isBlockhashValid", json!([blockhash.to_string(), { "commitment": "confirmed" }]))?;
+        result["value"].as_bool().ok_or_else(|| RpcError::Decode(result.to_string()))
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature, RpcError> {
+        RpcClient::send_transaction(self, transaction)
+    }
+
+    fn signature_status(&self, signature: &Signature) -> Result<SignatureStatus, RpcError> {
+        let result = self.call(
+            "getSignatureStatuses",
+            json!([[signature.to_string()], { "searchTransactionHistory": true }]),
+        )?;
+        let status = &result["value"][0];
+        // A merely processed transaction can still be dropped with its fork
+        let confirmed = matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized"));
+        if status.is_null() || !confirmed {
+            return Ok(None);
+        }
+        Ok(Some(match &status["err"] {
+            Value::Null => Ok(()),
+            err => Err(err.to_string()),
+        }))
+    }
+}
+
+impl ScanRpc for RpcClient {
+    type Error = RpcError;
+
+    fn program_account_keys(&self, program_id: &Pubkey, filters: &[AccountFilter]) -> Result<Vec<Pubkey>, RpcError> {
+        let filters: Vec<Value> = filters.iter().map(filter_json).collect();
+        let result = self.call(
+            "getProgramAccounts",
+            json!([program_id.to_string(), {
+                "encoding": "base64",
+                "dataSlice": { "offset": 0, "length": 0 },
+                "filters": filters,
+            }]),
+        )?;
+        let entries = result.as_array().ok_or_else(|| RpcError::Decode(result.to_string()))?;
+        entries
+            .iter()
+            .map(|entry| {
+                entry["pubkey"]
+                    .as_str()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or_else(|| RpcError::Decode(entry.to_string()))
+            })
+            .collect()
+    }
+
+    fn multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>, RpcError> {
+        let addresses: Vec<String> = addresses.iter().map(Pubkey::to_string).collect();
+        let result = self.call("getMultipleAccounts", json!([addresses, { "encoding": "base64" }]))?;
+        let values = result["value"].as_array().ok_or_else(|| RpcError::Decode(result.to_string()))?;
+        values
+            .iter()
+            .map(|account| match account {
+                Value::Null => Ok(None),
+                account => decode_data(&account["data"]).map(Some),
+            })
+            .collect()
+    }
+}
+
+fn filter_json(filter: &AccountFilter) -> Value {
+    match filter {
+        AccountFilter::Memcmp { offset, bytes } => {
+            json!({ "memcmp": { "offset": offset, "bytes": BASE64.encode(bytes), "encoding": "base64" } })
+        }
+        AccountFilter::DataSize(len) => json!({ "dataSize": len }),
+    }
+}
+
+fn encode_transaction(transaction: &Transaction) -> Result<String, RpcError> {
+    let bytes = bincode::serialize(transaction).map_err(|e| RpcError::Decode(e.to_string()))?;
+    Ok(BASE64.encode(bytes))
+}
+
+/// Decode a `["<base64>", "base64"]` account data field
+fn decode_data(data: &Value) -> Result<Vec<u8>, RpcError> {
+    let encoded = data[0].as_str().ok_or_else(|| RpcError::Decode(data.to_string()))?;
+    BASE64.decode(encoded).map_err(|e| RpcError::Decode(e.to_string()))
+}
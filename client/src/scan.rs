@@ -0,0 +1,224 @@
+//! Filtered, paginated program account scans
+//!
+//! `getProgramAccounts` has no paging of its own, so a scan first asks for the matching
+//! addresses only (an empty data slice), sorts them, and then loads one page of accounts
+//! at a time through `getMultipleAccounts`. Pass a page's [`Page::next`] back in as
+//! [`PageRequest::after`] to continue.
+//!
+//! Filters can only match fixed offsets. `EscrowAccount` starts with `provider` and
+//! `renter` in every layout, and since [`EscrowLayout::V14`] the fixed-size
+//! `terms.skill_name` follows at a fixed offset too. Everything after the variable-length
+//! category still moves, so state filters such as [`list_open_disputes`] are applied
+//! after decoding.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator};
+use escrow::{EscrowAccount, EscrowState};
+use reputation::Review;
+
+use crate::decode::{decode_escrow, EscrowLayout, EscrowView};
+use crate::text::SkillName;
+
+/// Byte offset of `EscrowAccount::provider`, after the discriminator
+pub const ESCROW_PROVIDER_OFFSET: usize = 8;
+/// Byte offset of `EscrowAccount::renter`
+pub const ESCROW_RENTER_OFFSET: usize = 8 + 32;
+/// Byte offset of `EscrowAccount::terms.skill_name`, after the five leading keys
+pub const ESCROW_SKILL_NAME_OFFSET: usize = 8 + 32 * 5;
+/// Byte offset of `Review::agent`
+pub const REVIEW_AGENT_OFFSET: usize = 8;
+
+/// Most addresses `getMultipleAccounts` accepts per call
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// A `getProgramAccounts` filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountFilter {
+    /// Account data at `offset` starts with `bytes`
+    Memcmp { offset: usize, bytes: Vec<u8> },
+    /// Account data is exactly this many bytes long
+    DataSize(usize),
+}
+
+impl AccountFilter {
+    /// Accounts of Anchor type `T`
+    pub fn discriminator<T: Discriminator>() -> Self {
+        Self::Memcmp {
+            offset: 0,
+            bytes: T::DISCRIMINATOR.to_vec(),
+        }
+    }
+
+    /// Accounts holding `key` at `offset`
+    pub fn key(offset: usize, key: &Pubkey) -> Self {
+        Self::Memcmp {
+            offset,
+            bytes: key.to_bytes().to_vec(),
+        }
+    }
+
+    /// Whether `data` passes this filter, as the RPC node would evaluate it
+    pub fn matches(&self, data: &[u8]) -> bool {
+        match self {
+            Self::Memcmp { offset, bytes } => data.get(*offset..offset + bytes.len()) == Some(bytes.as_slice()),
+            Self::DataSize(len) => data.len() == *len,
+        }
+    }
+}
+
+/// What a scan needs from an RPC node
+pub trait ScanRpc {
+    type Error: std::error::Error;
+
+    /// Addresses of accounts owned by `program_id` that pass every filter, in any order
+    fn program_account_keys(&self, program_id: &Pubkey, filters: &[AccountFilter]) -> Result<Vec<Pubkey>, Self::Error>;
+
+    /// Data of each of at most [`MAX_MULTIPLE_ACCOUNTS`] addresses, `None` where missing
+    fn multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>, Self::Error>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    /// Resume after this address (the previous page's `next`)
+    pub after: Option<Pubkey>,
+    /// Matching addresses to examine; at least 1
+    pub limit: usize,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self {
+            after: None,
+            limit: MAX_MULTIPLE_ACCOUNTS,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<(Pubkey, T)>,
+    /// Cursor for the following page; `None` once the scan is exhausted
+    pub next: Option<Pubkey>,
+}
+
+/// One page of `program_id` accounts passing `filters`, decoded by `decode`
+///
+/// Accounts closed since the address scan, or that `decode` rejects, are skipped, so a
+/// page may hold fewer than `page.limit` items while `next` is still set.
+pub fn scan<R: ScanRpc, T>(
+    rpc: &R,
+    program_id: &Pubkey,
+    filters: &[AccountFilter],
+    page: &PageRequest,
+    decode: impl Fn(&[u8]) -> Option<T>,
+) -> Result<Page<T>, R::Error> {
+    let mut addresses = rpc.program_account_keys(program_id, filters)?;
+    addresses.sort_unstable();
+    addresses.dedup();
+    let start = match page.after {
+        Some(after) => addresses.partition_point(|address| *address <= after),
+        None => 0,
+    };
+    let end = addresses.len().min(start + page.limit.max(1));
+    let more = end < addresses.len();
+    let addresses = &addresses[start..end];
+
+    let mut items = Vec::with_capacity(addresses.len());
+    for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts = rpc.multiple_accounts(chunk)?;
+        items.extend(
+            chunk
+                .iter()
+                .zip(accounts)
+                .filter_map(|(address, data)| decode(&data?).map(|item| (*address, item))),
+        );
+    }
+    Ok(Page {
+        items,
+        next: more.then(|| addresses[addresses.len() - 1]),
+    })
+}
+
+/// Escrow accounts listed by `provider`
+pub fn escrows_by_provider_filters(provider: &Pubkey) -> Vec<AccountFilter> {
+    vec![
+        AccountFilter::discriminator::<EscrowAccount>(),
+        AccountFilter::key(ESCROW_PROVIDER_OFFSET, provider),
+    ]
+}
+
+/// Escrow accounts funded by `renter`
+pub fn escrows_by_renter_filters(renter: &Pubkey) -> Vec<AccountFilter> {
+    vec![
+        AccountFilter::discriminator::<EscrowAccount>(),
+        AccountFilter::key(ESCROW_RENTER_OFFSET, renter),
+    ]
+}
+
+/// Escrow accounts whose skill name is exactly `skill_name`; see [`crate::text::skill_name`].
+/// Only [`EscrowLayout::V14`] and later store it at a fixed offset, so older listings
+/// are not matched.
+pub fn escrows_by_skill_filters(skill_name: &SkillName) -> Vec<AccountFilter> {
+    vec![
+        AccountFilter::discriminator::<EscrowAccount>(),
+        AccountFilter::DataSize(EscrowLayout::CURRENT.account_len()),
+        AccountFilter::Memcmp {
+            offset: ESCROW_SKILL_NAME_OFFSET,
+            bytes: skill_name.try_to_vec().expect("fixed-size serialization"),
+        },
+    ]
+}
+
+/// Reviews left for the reputation `agent` account
+pub fn reviews_for_agent_filters(agent: &Pubkey) -> Vec<AccountFilter> {
+    vec![
+        AccountFilter::discriminator::<Review>(),
+        AccountFilter::DataSize(Review::LEN),
+        AccountFilter::key(REVIEW_AGENT_OFFSET, agent),
+    ]
+}
+
+/// Escrows of `provider`, decoded from whichever layout wrote them
+pub fn list_escrows_by_provider<R: ScanRpc>(
+    rpc: &R,
+    provider: &Pubkey,
+    page: &PageRequest,
+) -> Result<Page<EscrowView>, R::Error> {
+    scan(rpc, &escrow::ID, &escrows_by_provider_filters(provider), page, |data| decode_escrow(data).ok())
+}
+
+/// Escrows funded by `renter`, decoded from whichever layout wrote them
+pub fn list_escrows_by_renter<R: ScanRpc>(
+    rpc: &R,
+    renter: &Pubkey,
+    page: &PageRequest,
+) -> Result<Page<EscrowView>, R::Error> {
+    scan(rpc, &escrow::ID, &escrows_by_renter_filters(renter), page, |data| decode_escrow(data).ok())
+}
+
+/// Escrows offering `skill_name`
+pub fn list_escrows_by_skill<R: ScanRpc>(
+    rpc: &R,
+    skill_name: &SkillName,
+    page: &PageRequest,
+) -> Result<Page<EscrowView>, R::Error> {
+    scan(rpc, &escrow::ID, &escrows_by_skill_filters(skill_name), page, |data| decode_escrow(data).ok())
+}
+
+/// Escrows awaiting an arbiter's ruling
+///
+/// The state byte has no fixed offset, so this walks every escrow and pages may come
+/// back short; keep following `next` until it is `None`.
+pub fn list_open_disputes<R: ScanRpc>(rpc: &R, page: &PageRequest) -> Result<Page<EscrowView>, R::Error> {
+    let filters = [AccountFilter::discriminator::<EscrowAccount>()];
+    scan(rpc, &escrow::ID, &filters, page, |data| {
+        decode_escrow(data).ok().filter(|view| view.escrow.state == EscrowState::Disputed)
+    })
+}
+
+/// Reviews of the reputation `agent` account
+pub fn list_reviews_for_agent<R: ScanRpc>(rpc: &R, agent: &Pubkey, page: &PageRequest) -> Result<Page<Review>, R::Error> {
+    scan(rpc, &reputation::ID, &reviews_for_agent_filters(agent), page, |data| {
+        Review::try_deserialize(&mut &data[..]).ok()
+    })
+}
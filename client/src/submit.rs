@@ -0,0 +1,248 @@
+//! Transaction submission that survives dropped connections
+//!
+//! A transaction is signed once and resent unchanged until its fate is known, so no
+//! number of retries can execute it twice: its signature is the idempotency key. A
+//! blockhash transaction is only re-signed, with a fresh blockhash, once the old one
+//! has expired without the transaction landing. A durable-nonce transaction never
+//! expires that way; it is dead only once its nonce has been advanced by some other
+//! transaction, so an agent can hold it across an outage and [`Submitter::resume`] it.
+
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_instruction::{self, SystemInstruction};
+use anchor_lang::solana_program::system_program;
+use solana_sdk::nonce::state::{State as NonceState, Versions as NonceVersions};
+use solana_sdk::signature::{Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use thiserror::Error;
+
+use crate::preflight::PreflightRpc;
+
+/// Result of a confirmed transaction, or `None` while the cluster has no confirmed record of it
+pub type SignatureStatus = Option<Result<(), String>>;
+
+/// What submission needs from an RPC node, on top of account reads
+pub trait SubmitRpc: PreflightRpc {
+    fn latest_blockhash(&self) -> Result<Hash, Self::Error>;
+
+    /// Whether transactions using `blockhash` can still land
+    fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool, Self::Error>;
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature, Self::Error>;
+
+    fn signature_status(&self, signature: &Signature) -> Result<SignatureStatus, Self::Error>;
+}
+
+/// How a transaction is kept from landing after it has been given up on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifetime {
+    /// A recent blockhash, replaced after it expires
+    Blockhash,
+    /// The current value of a durable nonce account, advanced by the transaction itself
+    DurableNonce { account: Pubkey, authority: Pubkey },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Time between resends, and between status checks
+    pub poll_interval: Duration,
+    /// Consecutive RPC failures tolerated before giving up with [`SubmitError::Unreachable`]
+    pub max_rpc_failures: u32,
+    /// Fresh blockhashes tried before reporting [`Outcome::Expired`]
+    pub max_resigns: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(2),
+            max_rpc_failures: 30,
+            max_resigns: 3,
+        }
+    }
+}
+
+/// Definitive result of a submission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Confirmed(Signature),
+    /// Landed but failed: fees were paid and nothing else changed
+    Failed { signature: Signature, error: String },
+    /// Never landed and never will: every blockhash expired, or the nonce was consumed
+    Expired,
+}
+
+#[derive(Debug, Error)]
+pub enum SubmitError<E: std::error::Error> {
+    /// The RPC stayed unreachable. A signed `transaction` may still land; resume it once
+    /// the connection is back, or wait out its lifetime before building a new one.
+    #[error("RPC unreachable: {source}")]
+    Unreachable { transaction: Option<Box<Transaction>>, source: E },
+    #[error("{0} is not an initialized nonce account")]
+    InvalidNonceAccount(Pubkey),
+    #[error("signing failed: {0}")]
+    Signing(String),
+}
+
+pub struct Submitter<'a, R: SubmitRpc> {
+    rpc: &'a R,
+    policy: RetryPolicy,
+}
+
+impl<'a, R: SubmitRpc> Submitter<'a, R> {
+    pub fn new(rpc: &'a R) -> Self {
+        Self {
+            rpc,
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sign `instructions` and send them until the outcome is definitive. A durable
+    /// nonce's authority must be among `signers`.
+    pub fn submit(
+        &self,
+        payer: &Pubkey,
+        signers: &[&dyn Signer],
+        instructions: &[Instruction],
+        lifetime: Lifetime,
+    ) -> Result<Outcome, SubmitError<R::Error>> {
+        match lifetime {
+            Lifetime::Blockhash => {
+                for _ in 0..=self.policy.max_resigns {
+                    let blockhash = self.retrying(None, || self.rpc.latest_blockhash())?;
+                    let transaction = sign(payer, signers, instructions, blockhash)?;
+                    match self.resume(&transaction)? {
+                        Outcome::Expired => continue,
+                        outcome => return Ok(outcome),
+                    }
+                }
+                Ok(Outcome::Expired)
+            }
+            Lifetime::DurableNonce { account, authority } => {
+                let nonce = self.retrying(None, || nonce_value(self.rpc, &account))?;
+                let nonce = nonce.ok_or(SubmitError::InvalidNonceAccount(account))?;
+                let mut with_advance = vec![system_instruction::advance_nonce_account(&account, &authority)];
+                with_advance.extend_from_slice(instructions);
+                let transaction = sign(payer, signers, &with_advance, nonce)?;
+                self.resume(&transaction)
+            }
+        }
+    }
+
+    /// Keep sending an already signed transaction until it lands or can no longer land
+    pub fn resume(&self, transaction: &Transaction) -> Result<Outcome, SubmitError<R::Error>> {
+        let signature = transaction.signatures[0];
+        let lifetime = transaction.message.recent_blockhash;
+        let nonce_account = nonce_account(transaction);
+        loop {
+            // A failed send is not a failed transaction; the status check decides
+            let _ = self.rpc.send_transaction(transaction);
+            thread::sleep(self.policy.poll_interval);
+
+            let status = self.retrying(Some(transaction), || self.rpc.signature_status(&signature))?;
+            if let Some(outcome) = outcome(signature, status) {
+                return Ok(outcome);
+            }
+            let alive = match nonce_account {
+                Some(account) => {
+                    self.retrying(Some(transaction), || nonce_value(self.rpc, &account))? == Some(lifetime)
+                }
+                None => self.retrying(Some(transaction), || self.rpc.is_blockhash_valid(&lifetime))?,
+            };
+            if !alive {
+                // It may have landed between the status check and the expiry check
+                let status = self.retrying(Some(transaction), || self.rpc.signature_status(&signature))?;
+                return Ok(outcome(signature, status).unwrap_or(Outcome::Expired));
+            }
+        }
+    }
+
+    /// Run `call` until it succeeds or fails `max_rpc_failures` times in a row
+    fn retrying<T>(
+        &self,
+        transaction: Option<&Transaction>,
+        mut call: impl FnMut() -> Result<T, R::Error>,
+    ) -> Result<T, SubmitError<R::Error>> {
+        let mut failures = 0;
+        loop {
+            match call() {
+                Ok(value) => return Ok(value),
+                Err(source) => {
+                    failures += 1;
+                    if failures >= self.policy.max_rpc_failures.max(1) {
+                        return Err(SubmitError::Unreachable {
+                            transaction: transaction.cloned().map(Box::new),
+                            source,
+                        });
+                    }
+                    thread::sleep(self.policy.poll_interval);
+                }
+            }
+        }
+    }
+}
+
+/// Instructions creating `nonce` as a durable nonce account controlled by `authority`;
+/// `lamports` must cover its rent exemption
+pub fn create_nonce_account(payer: &Pubkey, nonce: &Pubkey, authority: &Pubkey, lamports: u64) -> Vec<Instruction> {
+    system_instruction::create_nonce_account(payer, nonce, authority, lamports)
+}
+
+/// Current value of a durable nonce account, or `None` if it is missing or uninitialized
+pub fn nonce_value<R: PreflightRpc>(rpc: &R, account: &Pubkey) -> Result<Option<Hash>, R::Error> {
+    let Some(data) = rpc.account_data(account)? else {
+        return Ok(None);
+    };
+    Ok(match bincode::deserialize::<NonceVersions>(&data) {
+        Ok(versions) => match versions.state() {
+            NonceState::Initialized(data) => Some(data.blockhash()),
+            NonceState::Uninitialized => None,
+        },
+        Err(_) => None,
+    })
+}
+
+/// The nonce account a transaction advances, if it uses a durable nonce
+pub fn nonce_account(transaction: &Transaction) -> Option<Pubkey> {
+    let message = &transaction.message;
+    let first = message.instructions.first()?;
+    let keys = &message.account_keys;
+    let advances = *keys.get(first.program_id_index as usize)? == system_program::ID
+        && matches!(
+            bincode::deserialize::<SystemInstruction>(&first.data),
+            Ok(SystemInstruction::AdvanceNonceAccount)
+        );
+    if !advances {
+        return None;
+    }
+    keys.get(*first.accounts.first()? as usize).copied()
+}
+
+fn sign<E: std::error::Error>(
+    payer: &Pubkey,
+    signers: &[&dyn Signer],
+    instructions: &[Instruction],
+    lifetime: Hash,
+) -> Result<Transaction, SubmitError<E>> {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(payer));
+    transaction
+        .try_sign(signers, lifetime)
+        .map_err(|e| SubmitError::Signing(e.to_string()))?;
+    Ok(transaction)
+}
+
+fn outcome(signature: Signature, status: SignatureStatus) -> Option<Outcome> {
+    match status? {
+        Ok(()) => Some(Outcome::Confirmed(signature)),
+        Err(error) => Some(Outcome::Failed { signature, error }),
+    }
+}
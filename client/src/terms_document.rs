@@ -0,0 +1,136 @@
+//! Versioned schema of the off-chain terms document a listing's `metadata_uri` points to
+//!
+//! The document carries what does not fit on chain: the scope of the work, how it will
+//! be accepted, how service levels are measured and how revisions are handled. It is
+//! JSON tagged with its schema version, and unknown fields are rejected so every
+//! integrator reads and writes the same shape. The document has no maps, so serializing
+//! one always yields the same bytes; [`TermsDocument::document_hash`] is the SHA-256 of
+//! those bytes, and a fetched copy is checked against it with [`TermsDocument::from_fetched`].
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use escrow::{EscrowAccount, EscrowTerms, MAX_DELIVERABLE_REVISIONS};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Schema tag of [`TermsDocumentV1`]
+pub const TERMS_SCHEMA_V1: &str = "trustyclaw.terms/v1";
+
+#[derive(Debug, Error)]
+pub enum TermsDocumentError {
+    #[error("document hash does not match the published hash")]
+    HashMismatch,
+    #[error("malformed terms document: {0}")]
+    Malformed(#[from] serde_json::Error),
+    #[error("invalid terms document: {0}")]
+    Invalid(&'static str),
+}
+
+/// A terms document of any supported schema version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "schema")]
+pub enum TermsDocument {
+    #[serde(rename = "trustyclaw.terms/v1")]
+    V1(TermsDocumentV1),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TermsDocumentV1 {
+    /// What the provider will do, in plain language
+    pub scope: String,
+    /// What the renter receives
+    pub deliverables: Vec<String>,
+    /// Checks a deliverable must pass to be accepted
+    pub acceptance_criteria: Vec<AcceptanceCriterion>,
+    /// How the listing's on-chain `SlaTerms` are measured, if it has any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sla: Option<SlaMeasurement>,
+    pub revision_policy: RevisionPolicy,
+    /// BCP 47 tag of the language the document is written in
+    pub language: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AcceptanceCriterion {
+    pub description: String,
+    /// How the renter or a verifier checks it, e.g. a test command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SlaMeasurement {
+    /// How latency is sampled, e.g. "p95 over 5-minute windows"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<String>,
+    /// How uptime is probed, e.g. "HTTPS health check every 60 s"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uptime: Option<String>,
+    /// Where the verifier publishes its measurements
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_uri: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RevisionPolicy {
+    /// Resubmissions the renter may ask for, at most `MAX_DELIVERABLE_REVISIONS`
+    pub max_revisions: u16,
+    /// What a revision request may and may not ask for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl TermsDocument {
+    /// Canonical JSON encoding; publish exactly these bytes at the `metadata_uri`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("terms documents always serialize")
+    }
+
+    /// SHA-256 of [`Self::to_bytes`]
+    pub fn document_hash(&self) -> [u8; 32] {
+        hash(&self.to_bytes()).to_bytes()
+    }
+
+    /// Parse a document fetched from a `metadata_uri`, after checking its bytes against
+    /// the hash the provider published
+    pub fn from_fetched(bytes: &[u8], document_hash: &[u8; 32]) -> Result<Self, TermsDocumentError> {
+        if hash(bytes).to_bytes() != *document_hash {
+            return Err(TermsDocumentError::HashMismatch);
+        }
+        let document: Self = serde_json::from_slice(bytes)?;
+        document.validate()?;
+        Ok(document)
+    }
+
+    /// Constraints the schema itself cannot express
+    pub fn validate(&self) -> Result<(), TermsDocumentError> {
+        match self {
+            TermsDocument::V1(document) => {
+                if document.scope.trim().is_empty() {
+                    return Err(TermsDocumentError::Invalid("scope is empty"));
+                }
+                if document.acceptance_criteria.is_empty() {
+                    return Err(TermsDocumentError::Invalid("no acceptance criteria"));
+                }
+                if document.revision_policy.max_revisions > MAX_DELIVERABLE_REVISIONS {
+                    return Err(TermsDocumentError::Invalid("more revisions than the program allows"));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// `EscrowAccount::terms_hash` of `terms` listed in `mint`, as the program computes it
+pub fn terms_hash(terms: &EscrowTerms, mint: &Pubkey) -> [u8; 32] {
+    EscrowAccount::compute_terms_hash(terms, mint).expect("escrow terms always serialize")
+}
+
+/// `EscrowInitialized::metadata_uri_hash` of the listing's `metadata_uri`
+pub fn metadata_uri_hash(metadata_uri: &str) -> [u8; 32] {
+    hash(metadata_uri.as_bytes()).to_bytes()
+}
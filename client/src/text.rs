@@ -0,0 +1,41 @@
+//! Conversions for the fixed-size text fields of escrow terms
+//!
+//! `EscrowTerms::skill_name` and `metadata_uri` are [`FixedString`]s: a length and a
+//! zero-padded buffer, so they serialize to the same size whatever they hold. The
+//! program rejects buffers that are not zero-padded UTF-8; these helpers only build
+//! valid ones.
+
+use escrow::{FixedString, MAX_METADATA_URI_LEN, MAX_SKILL_NAME_LEN};
+use thiserror::Error;
+
+pub type SkillName = FixedString<{ MAX_SKILL_NAME_LEN }>;
+pub type MetadataUri = FixedString<{ MAX_METADATA_URI_LEN }>;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("text is {len} bytes but the field holds at most {capacity}")]
+pub struct TextTooLong {
+    pub len: usize,
+    pub capacity: usize,
+}
+
+/// `text` in an `N`-byte field
+pub fn fixed<const N: usize>(text: &str) -> Result<FixedString<N>, TextTooLong> {
+    FixedString::new(text).ok_or(TextTooLong {
+        len: text.len(),
+        capacity: N,
+    })
+}
+
+pub fn skill_name(name: &str) -> Result<SkillName, TextTooLong> {
+    fixed(name)
+}
+
+pub fn metadata_uri(uri: &str) -> Result<MetadataUri, TextTooLong> {
+    fixed(uri)
+}
+
+/// `text` cut at the last character boundary that fits, for strings written before the
+/// fields had a capacity
+pub fn truncated<const N: usize>(text: &str) -> FixedString<N> {
+    FixedString::truncated(text)
+}
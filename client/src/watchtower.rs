@@ -0,0 +1,79 @@
+//! Renter-side timeout watching
+//!
+//! Decides, per escrow, whether a renter's watchtower should dispute a lapsed rental or
+//! crank its timeout refund. The `watchtower` binary polls with this and submits the
+//! resulting instructions.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::AccountDeserialize;
+use escrow::EscrowAccount;
+
+use crate::instructions;
+use crate::rpc::{RpcClient, RpcError};
+use crate::scan::escrows_by_renter_filters;
+
+/// What to do when a rental period lapses with nothing delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnExpiry {
+    /// Open a dispute immediately so the arbiter decides
+    Dispute,
+    /// Crank `refund_after_timeout` as soon as the rental period ends
+    #[default]
+    Refund,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchAction {
+    Dispute,
+    RefundAfterTimeout,
+}
+
+/// Next action for `escrow` at cluster time `now`, if any
+pub fn next_action(escrow: &EscrowAccount, now: i64, on_expiry: OnExpiry) -> Option<WatchAction> {
+    if escrow.terms.settle_in_credits || escrow.check_timeout_refund(now).is_err() {
+        return None;
+    }
+    // A dispute can still be opened until the dispute window closes; after that, refund
+    if on_expiry == OnExpiry::Dispute && now <= escrow.dispute_deadline() {
+        Some(WatchAction::Dispute)
+    } else {
+        Some(WatchAction::RefundAfterTimeout)
+    }
+}
+
+/// Instruction carrying out `action`, signed by `authority` (the renter or its watchtower).
+/// `token_program` owns the escrow's mint.
+pub fn build_action(
+    authority: &Pubkey,
+    escrow: &EscrowAccount,
+    token_program: &Pubkey,
+    action: WatchAction,
+) -> Instruction {
+    match action {
+        WatchAction::Dispute => instructions::dispute(authority, &escrow.provider, escrow.escrow_id),
+        WatchAction::RefundAfterTimeout => {
+            instructions::refund_after_timeout(
+                authority,
+                &escrow.provider,
+                escrow.escrow_id,
+                &escrow.renter,
+                &escrow.token_mint,
+                token_program,
+            )
+        }
+    }
+}
+
+/// Every escrow account funded by `renter`
+pub fn renter_escrows(rpc: &RpcClient, renter: &Pubkey) -> Result<Vec<(Pubkey, EscrowAccount)>, RpcError> {
+    let accounts = rpc.program_accounts(&escrow::ID, &escrows_by_renter_filters(renter))?;
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(address, data)| {
+            EscrowAccount::try_deserialize(&mut data.as_slice())
+                .ok()
+                .map(|escrow| (address, escrow))
+        })
+        .collect())
+}
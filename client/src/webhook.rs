@@ -0,0 +1,103 @@
+//! Signed webhook callbacks
+//!
+//! A provider registers its notifier's public key on the listing with
+//! `set_webhook_signer`. Every callback then carries an ed25519 signature over the
+//! escrow address, a unix timestamp and the SHA-256 of the body, so a receiver can check
+//! it against `EscrowAccount::webhook_signer` without sharing a secret. Receivers reject
+//! timestamps outside [`MAX_WEBHOOK_AGE_SECONDS`] and remember signatures seen within
+//! that window with a [`ReplayGuard`], which together make a captured callback useless.
+
+use std::collections::HashMap;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hashv;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use thiserror::Error;
+
+/// Header naming the escrow the callback is about
+pub const ESCROW_HEADER: &str = "X-TrustyClaw-Escrow";
+/// Header carrying the signing unix timestamp
+pub const TIMESTAMP_HEADER: &str = "X-TrustyClaw-Timestamp";
+/// Header carrying the base58 ed25519 signature
+pub const SIGNATURE_HEADER: &str = "X-TrustyClaw-Signature";
+
+/// How far a callback's timestamp may be from the receiver's clock
+pub const MAX_WEBHOOK_AGE_SECONDS: i64 = 300;
+
+const DOMAIN: &[u8] = b"trustyclaw-webhook-v1";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WebhookError {
+    #[error("listing has no registered webhook signer")]
+    NoSigner,
+    #[error("callback timestamp {timestamp} is outside the accepted window at {now}")]
+    Stale { timestamp: i64, now: i64 },
+    #[error("signature does not match the registered webhook signer")]
+    BadSignature,
+    #[error("callback was already delivered")]
+    Replayed,
+}
+
+/// Bytes a callback signature covers
+pub fn signing_message(escrow: &Pubkey, timestamp: i64, body: &[u8]) -> Vec<u8> {
+    let body_hash = hashv(&[body]);
+    [DOMAIN, escrow.as_ref(), &timestamp.to_le_bytes(), body_hash.as_ref()].concat()
+}
+
+/// Headers to send with `body`, signed by `signer` at `timestamp`
+pub fn sign(signer: &Keypair, escrow: &Pubkey, timestamp: i64, body: &[u8]) -> Vec<(&'static str, String)> {
+    let signature = signer.sign_message(&signing_message(escrow, timestamp, body));
+    vec![
+        (ESCROW_HEADER, escrow.to_string()),
+        (TIMESTAMP_HEADER, timestamp.to_string()),
+        (SIGNATURE_HEADER, signature.to_string()),
+    ]
+}
+
+/// Check a callback against the listing's registered `webhook_signer` at receiver time `now`
+pub fn verify(
+    webhook_signer: &Pubkey,
+    escrow: &Pubkey,
+    timestamp: i64,
+    body: &[u8],
+    signature: &Signature,
+    now: i64,
+) -> Result<(), WebhookError> {
+    if *webhook_signer == Pubkey::default() {
+        return Err(WebhookError::NoSigner);
+    }
+    if timestamp.abs_diff(now) > MAX_WEBHOOK_AGE_SECONDS as u64 {
+        return Err(WebhookError::Stale { timestamp, now });
+    }
+    if !signature.verify(webhook_signer.as_ref(), &signing_message(escrow, timestamp, body)) {
+        return Err(WebhookError::BadSignature);
+    }
+    Ok(())
+}
+
+/// Signatures accepted within the last [`MAX_WEBHOOK_AGE_SECONDS`]
+#[derive(Debug, Default)]
+pub struct ReplayGuard {
+    seen: HashMap<Signature, i64>,
+}
+
+impl ReplayGuard {
+    /// Verify a callback and record it, rejecting one already accepted
+    pub fn accept(
+        &mut self,
+        webhook_signer: &Pubkey,
+        escrow: &Pubkey,
+        timestamp: i64,
+        body: &[u8],
+        signature: &Signature,
+        now: i64,
+    ) -> Result<(), WebhookError> {
+        verify(webhook_signer, escrow, timestamp, body, signature, now)?;
+        // Anything older than the window would fail `verify` as stale anyway
+        self.seen.retain(|_, at| at.abs_diff(now) <= MAX_WEBHOOK_AGE_SECONDS as u64);
+        if self.seen.insert(*signature, timestamp).is_some() {
+            return Err(WebhookError::Replayed);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{verified_ed25519_signature, EscrowAccount, ACCEPTANCE_MESSAGE_PREFIX};
+use solana_sdk::ed25519_instruction;
+use solana_sdk::feature_set::FeatureSet;
+use solana_sdk::signature::{Keypair, Signer};
+use trustyclaw_client::acceptance::{self, Acceptance};
+use trustyclaw_client::channel;
+
+fn listing() -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        terms_hash: [7; 32],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn signed_acceptance_passes_precompile_and_program_checks() {
+    let renter = Keypair::new();
+    let escrow = Pubkey::new_unique();
+    let signed = Acceptance::new(escrow, &listing(), renter.pubkey(), 1_700_086_400).sign(&renter);
+    assert!(signed.verify());
+
+    let message = signed.acceptance.message();
+    assert!(message.starts_with(ACCEPTANCE_MESSAGE_PREFIX));
+    let text = std::str::from_utf8(&message).unwrap();
+    assert!(text.contains(&escrow.to_string()) && text.contains(&"07".repeat(32)));
+
+    let [verify_ix, record_ix] = acceptance::record(&Pubkey::new_unique(), &signed);
+    assert_eq!(record_ix.program_id, escrow::ID);
+    ed25519_instruction::verify(&verify_ix.data, &[&verify_ix.data], &FeatureSet::all_enabled())
+        .expect("precompile rejects the verify instruction");
+    assert_eq!(
+        verified_ed25519_signature(&verify_ix, &renter.pubkey(), &message),
+        Some(<[u8; 64]>::from(signed.signature))
+    );
+}
+
+#[test]
+fn program_rejects_other_renters_terms_and_expiries() {
+    let renter = Keypair::new();
+    let signed = Acceptance::new(Pubkey::new_unique(), &listing(), renter.pubkey(), 1_700_086_400).sign(&renter);
+    let verify_ix = channel::ed25519_verify(&renter.pubkey(), &signed.signature, &signed.acceptance.message());
+
+    let other = Keypair::new();
+    assert_eq!(verified_ed25519_signature(&verify_ix, &other.pubkey(), &signed.acceptance.message()), None);
+    let extended = Acceptance {
+        expires_at: 1_800_000_000,
+        ..signed.acceptance.clone()
+    };
+    assert_eq!(verified_ed25519_signature(&verify_ix, &renter.pubkey(), &extended.message()), None);
+    let amended = Acceptance {
+        terms_hash: [8; 32],
+        ..signed.acceptance.clone()
+    };
+    assert!(!amended.with_signature(signed.signature).verify());
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token;
+use escrow::FundOverrides;
+use trustyclaw_client::instructions::{accept_escrow, decode_accept_amount, fund_with_sol};
+use trustyclaw_client::{AmountError, TokenAmount, UsdcAmount, USDC_DECIMALS};
+
+#[test]
+fn parses_display_amounts_into_base_units() {
+    let amount: UsdcAmount = "12.5".parse().unwrap();
+    assert_eq!(amount.base_units(), 12_500_000);
+    assert_eq!(amount.to_string(), "12.500000");
+
+    assert_eq!(UsdcAmount::from_dollars(1).unwrap().base_units(), 1_000_000);
+    assert_eq!(TokenAmount::parse("0.000001", 6).unwrap().base_units(), 1);
+    assert_eq!(TokenAmount::parse("7", 0).unwrap().to_string(), "7");
+}
+
+#[test]
+fn rejects_malformed_and_overly_precise_input() {
+    assert!(matches!(
+        "1.0000001".parse::<UsdcAmount>(),
+        Err(AmountError::TooPrecise { decimals: 6, .. })
+    ));
+    assert!(matches!("-1".parse::<UsdcAmount>(), Err(AmountError::Invalid(_))));
+    assert!(matches!(".".parse::<UsdcAmount>(), Err(AmountError::Invalid(_))));
+    assert_eq!(
+        TokenAmount::from_whole(u64::MAX, 6).unwrap_err(),
+        AmountError::Overflow
+    );
+}
+
+#[test]
+fn arithmetic_requires_matching_decimals() {
+    let usdc = TokenAmount::from(UsdcAmount::from_dollars(2).unwrap());
+    let nine = TokenAmount::from_whole(2, 9).unwrap();
+    assert_eq!(
+        usdc.checked_add(nine).unwrap_err(),
+        AmountError::DecimalsMismatch { expected: 6, got: 9 }
+    );
+    assert_eq!(usdc.checked_sub(usdc).unwrap().base_units(), 0);
+}
+
+#[test]
+fn amount_round_trips_through_accept_escrow() {
+    let (renter, provider, mint) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let amount = UsdcAmount::from_dollars(25).unwrap();
+
+    let ix = accept_escrow(&renter, &provider, 0, &mint, &token::ID, USDC_DECIMALS, amount, FundOverrides::default(), [0; 32], false)
+        .unwrap();
+    let decoded = decode_accept_amount(&ix, USDC_DECIMALS).unwrap();
+    assert_eq!(decoded, TokenAmount::from(amount));
+    assert_eq!(decoded.base_units(), 25_000_000);
+}
+
+#[test]
+fn accept_escrow_rejects_decimal_mismatch() {
+    let (renter, provider, mint) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let nine_decimals = TokenAmount::from_whole(25, 9).unwrap();
+
+    let err = accept_escrow(&renter, &provider, 0, &mint, &token::ID, USDC_DECIMALS, nine_decimals, FundOverrides::default(), [0; 32], false)
+        .unwrap_err();
+    assert_eq!(err, AmountError::DecimalsMismatch { expected: 6, got: 9 });
+}
+
+#[test]
+fn fund_with_sol_takes_lamports() {
+    let (renter, provider) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let half_sol = TokenAmount::parse("0.5", 9).unwrap();
+
+    let ix = fund_with_sol(&renter, &provider, 0, half_sol, FundOverrides::default(), [0; 32], false).unwrap();
+    assert_eq!(ix.data[8..16], 500_000_000u64.to_le_bytes());
+    let err = fund_with_sol(&renter, &provider, 0, UsdcAmount::from_dollars(25).unwrap(), FundOverrides::default(), [0; 32], false)
+        .unwrap_err();
+    assert_eq!(err, AmountError::DecimalsMismatch { expected: 9, got: 6 });
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token;
+use escrow::verifies_ed25519;
+use solana_sdk::ed25519_instruction;
+use solana_sdk::feature_set::FeatureSet;
+use solana_sdk::signature::{Keypair, Signer};
+use trustyclaw_client::channel::{self, ChannelUpdate};
+
+#[test]
+fn signed_update_passes_precompile_and_program_checks() {
+    let payer = Keypair::new();
+    let (payee, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let update = ChannelUpdate::sign(&payer, &payee, &mint, 7_500_000, 3);
+    assert!(update.verify(&payer.pubkey()));
+
+    let [verify_ix, settle_ix] = channel::settle(&payer.pubkey(), &payee, &mint, &token::ID, &update);
+    assert_eq!(settle_ix.program_id, escrow::ID);
+    ed25519_instruction::verify(&verify_ix.data, &[&verify_ix.data], &FeatureSet::all_enabled())
+        .expect("precompile rejects the verify instruction");
+    assert!(verifies_ed25519(&verify_ix, &payer.pubkey(), &update.message()));
+}
+
+#[test]
+fn program_rejects_other_signers_and_messages() {
+    let payer = Keypair::new();
+    let (payee, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let update = ChannelUpdate::sign(&payer, &payee, &mint, 1_000, 1);
+    let verify_ix = channel::ed25519_verify(&payer.pubkey(), &update.signature, &update.message());
+
+    assert!(!verifies_ed25519(&verify_ix, &payee, &update.message()));
+    let replayed = ChannelUpdate { nonce: 2, ..update.clone() };
+    assert!(!verifies_ed25519(&verify_ix, &payer.pubkey(), &replayed.message()));
+    assert!(!replayed.verify(&payer.pubkey()));
+}
+
+#[test]
+fn program_rejects_data_borrowed_from_other_instructions() {
+    let payer = Keypair::new();
+    let (payee, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let update = ChannelUpdate::sign(&payer, &payee, &mint, 1_000, 1);
+    let mut verify_ix = channel::ed25519_verify(&payer.pubkey(), &update.signature, &update.message());
+    // message_instruction_index pointing at instruction 0 instead of inline data
+    verify_ix.data[14..16].copy_from_slice(&0u16.to_le_bytes());
+    assert!(!verifies_ed25519(&verify_ix, &payer.pubkey(), &update.message()));
+
+    let mut wrong_program = channel::ed25519_verify(&payer.pubkey(), &update.signature, &update.message());
+    wrong_program.program_id = escrow::ID;
+    assert!(!verifies_ed25519(&wrong_program, &payer.pubkey(), &update.message()));
+}
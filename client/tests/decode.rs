@@ -0,0 +1,167 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountSerialize, AnchorSerialize, Discriminator};
+use escrow::{CancelProposer, EscrowAccount, EscrowState, ResolutionOutcome, ESCROW_VERSION, MAX_SKILL_NAME_LEN};
+use reputation::Agent;
+use trustyclaw_client::decode::{decode, decode_escrow, AccountView, AgentLayout, DecodeError, EscrowLayout};
+
+/// `EscrowAccount` golden vector of each layout since V1, as committed when it was current
+const FIXTURES: &str = include_str!("fixtures/escrow_layouts.txt");
+
+fn padded(mut data: Vec<u8>, len: usize) -> Vec<u8> {
+    assert!(data.len() <= len);
+    data.resize(len, 0);
+    data
+}
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+#[test]
+fn every_published_escrow_layout_decodes() {
+    for line in FIXTURES.lines() {
+        let (name, bytes) = line.split_once(" = ").unwrap();
+        let layout = EscrowLayout::ALL.into_iter().find(|l| format!("{l:?}") == name).unwrap();
+        let view = decode_escrow(&padded(hex(bytes), layout.account_len())).unwrap();
+
+        assert_eq!(view.layout, layout);
+        let escrow = &view.escrow;
+        assert_eq!(escrow.provider, Pubkey::new_from_array([1; 32]));
+        assert_eq!(escrow.terms.skill_name.as_str(), "code-review");
+        assert_eq!(escrow.terms.price_usdc, 25_000_000);
+        assert_eq!(escrow.arbiter, Pubkey::new_from_array([9; 32]));
+        assert_eq!(escrow.terms.category.is_empty(), layout < EscrowLayout::V2);
+        assert_eq!(escrow.terms.funding_rule.is_some(), layout >= EscrowLayout::V3);
+        assert_eq!(escrow.access_rotations == 2, layout >= EscrowLayout::V4);
+        assert_eq!(escrow.resolution == Some(ResolutionOutcome::Split(6_000)), layout >= EscrowLayout::V5);
+        assert_eq!(escrow.terms.requires_provider_acceptance, layout >= EscrowLayout::V6);
+        assert_eq!(escrow.revisions == 1, layout >= EscrowLayout::V7);
+        assert_eq!(escrow.terms.funding_deadline > 0, layout >= EscrowLayout::V9);
+        assert_eq!(escrow.vesting_total > 0, layout >= EscrowLayout::V10);
+        assert_eq!(escrow.provider_payout > 0, layout >= EscrowLayout::V11);
+        assert_eq!(escrow.webhook_signer != Pubkey::default(), layout >= EscrowLayout::V12);
+        assert_eq!(escrow.terms.price_decimals == 6, layout >= EscrowLayout::V15);
+        assert_eq!(escrow.terms.provider_bond > 0, layout >= EscrowLayout::V16);
+        assert_eq!(escrow.terms.stream_payment, layout >= EscrowLayout::V17);
+        assert_eq!(escrow.claimed_amount > 0, layout >= EscrowLayout::V17);
+        assert!(view.needs_migration());
+    }
+}
+
+#[test]
+fn original_escrow_layout_decodes() {
+    let mut data = EscrowAccount::DISCRIMINATOR.to_vec();
+    for key in 1..=5u8 {
+        data.extend_from_slice(&[key; 32]);
+    }
+    ("translate".to_string(), 3_600i64, 5_000_000u64, "ipfs://v0".to_string()).serialize(&mut data).unwrap();
+    (EscrowState::Funded, 5_000_000u64, 100i64, 0i64, 0i64).serialize(&mut data).unwrap();
+
+    let view = decode_escrow(&padded(data, EscrowLayout::V0.account_len())).unwrap();
+    assert_eq!(view.layout, EscrowLayout::V0);
+    assert_eq!(view.escrow.renter, Pubkey::new_from_array([2; 32]));
+    assert_eq!(view.escrow.terms.metadata_uri.as_str(), "ipfs://v0");
+    assert_eq!(view.escrow.state, EscrowState::Funded);
+    assert_eq!(view.escrow.created_at, 100);
+}
+
+#[test]
+fn current_escrow_round_trips() {
+    let escrow = EscrowAccount {
+        renter: Pubkey::new_unique(),
+        deferred_balance: 7,
+        sunset_at: 1_700_050_000,
+        pending_requests: 2,
+        cancel_proposed_by: CancelProposer::Renter,
+        cancel_expires_at: 1_700_086_400,
+        version: ESCROW_VERSION,
+        ..Default::default()
+    };
+    let mut data = Vec::new();
+    escrow.try_serialize(&mut data).unwrap();
+
+    let AccountView::Escrow(view) = decode(&padded(data, EscrowAccount::LEN)).unwrap() else {
+        panic!("not decoded as an escrow");
+    };
+    assert_eq!(view.layout, EscrowLayout::CURRENT);
+    assert_eq!(view.escrow.renter, escrow.renter);
+    assert_eq!(view.escrow.deferred_balance, 7);
+    assert_eq!(view.escrow.sunset_at, 1_700_050_000);
+    assert_eq!(view.escrow.pending_requests, 2);
+    assert_eq!(view.escrow.cancel_proposed_by, CancelProposer::Renter);
+    assert_eq!(view.escrow.cancel_expires_at, 1_700_086_400);
+    assert!(!view.needs_migration());
+}
+
+#[test]
+fn legacy_text_longer_than_the_field_is_truncated() {
+    let mut data = EscrowAccount::DISCRIMINATOR.to_vec();
+    for key in 1..=5u8 {
+        data.extend_from_slice(&[key; 32]);
+    }
+    let name = "n".repeat(MAX_SKILL_NAME_LEN + 8);
+    (name, 3_600i64, 5_000_000u64, "ipfs://v0".to_string()).serialize(&mut data).unwrap();
+    (EscrowState::Funded, 5_000_000u64, 100i64, 0i64, 0i64).serialize(&mut data).unwrap();
+
+    let view = decode_escrow(&padded(data, EscrowLayout::V0.account_len())).unwrap();
+    assert_eq!(view.escrow.terms.skill_name.as_str(), "n".repeat(MAX_SKILL_NAME_LEN));
+    assert_eq!(view.escrow.terms.metadata_uri.as_str(), "ipfs://v0");
+}
+
+#[test]
+fn layout_lengths_are_distinct() {
+    let mut lens: Vec<usize> = EscrowLayout::ALL.iter().map(|layout| layout.account_len()).collect();
+    lens.dedup();
+    assert_eq!(lens.len(), EscrowLayout::ALL.len());
+}
+
+#[test]
+fn agent_layouts_decode() {
+    let mut v0 = Agent::DISCRIMINATOR.to_vec();
+    v0.extend_from_slice(&[4; 32]);
+    v0.extend_from_slice(&[5; 32]);
+    ("agent".to_string(), "bio".to_string(), 87i64, 3u64, 13u64, 1i64, 2i64, true).serialize(&mut v0).unwrap();
+    let mut v1 = v0.clone();
+    (500u64, 2u16).serialize(&mut v1).unwrap();
+    let mut v2 = v1.clone();
+    (855_000u64, 1_700_000_000i64).serialize(&mut v2).unwrap();
+
+    let AccountView::Agent(old) = decode(&padded(v0, AgentLayout::V0.account_len())).unwrap() else {
+        panic!("not decoded as an agent");
+    };
+    assert_eq!(old.layout, AgentLayout::V0);
+    assert_eq!(old.agent.reputation_score, 87);
+    assert_eq!(old.agent.external_sources, 0);
+
+    let AccountView::Agent(new) = decode(&padded(v1, AgentLayout::V1.account_len())).unwrap() else {
+        panic!("not decoded as an agent");
+    };
+    assert_eq!(new.layout, AgentLayout::V1);
+    assert_eq!(new.agent.external_score_sum, 500);
+    assert_eq!(new.agent.external_sources, 2);
+    assert_eq!(new.agent.twar_score, 0);
+
+    let AccountView::Agent(twar) = decode(&padded(v2.clone(), AgentLayout::V2.account_len())).unwrap() else {
+        panic!("not decoded as an agent");
+    };
+    assert_eq!(twar.layout, AgentLayout::V2);
+    assert_eq!(twar.agent.external_sources, 2);
+    assert_eq!(twar.agent.twar_score, 855_000);
+    assert_eq!(twar.agent.twar_updated_at, 1_700_000_000);
+
+    let AccountView::Agent(current) = decode(&padded(v2, Agent::LEN)).unwrap() else {
+        panic!("not decoded as an agent");
+    };
+    assert_eq!(current.layout, AgentLayout::CURRENT);
+    assert_eq!(current.agent.twar_score, 855_000);
+    assert_eq!(current.agent._reserved, [0; reputation::ACCOUNT_RESERVE]);
+}
+
+#[test]
+fn unknown_accounts_are_rejected() {
+    assert!(matches!(decode(&[0; 4]), Err(DecodeError::TooShort)));
+    assert!(matches!(decode(&[0; 64]), Err(DecodeError::UnknownDiscriminator)));
+    let mut data = EscrowAccount::DISCRIMINATOR.to_vec();
+    data.resize(100, 0);
+    assert!(matches!(decode(&data), Err(DecodeError::UnknownLayout(100))));
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AnchorDeserialize;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::transaction::Transaction;
+use trustyclaw_client::{demo, pda};
+
+#[test]
+fn every_bootstrap_group_fits_in_a_transaction() {
+    let (provider, renter) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let groups = demo::bootstrap(&provider, &renter, 7).unwrap();
+    assert_eq!(groups.len(), 4);
+    for group in &groups {
+        let transaction = Transaction::new_with_payer(group, Some(&provider));
+        let size = bincode::serialize(&transaction).unwrap().len();
+        assert!(size <= PACKET_DATA_SIZE, "{size} bytes");
+    }
+}
+
+#[test]
+fn sample_rental_is_funded_at_the_listed_price() {
+    let (provider, renter) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let groups = demo::bootstrap(&provider, &renter, 7).unwrap();
+
+    let listing = &groups[1][0];
+    assert!(listing.accounts.iter().any(|meta| meta.pubkey == pda::escrow(&provider, 7)));
+
+    let accept = &groups[3][0];
+    assert!(accept.accounts.iter().any(|meta| meta.pubkey == pda::escrow(&provider, 8)));
+    assert!(accept.accounts.iter().any(|meta| meta.pubkey == pda::demo_mint()));
+    let args = escrow::instruction::AcceptEscrow::deserialize(&mut &accept.data[8..]).unwrap();
+    assert_eq!(args.amount, demo::sample_terms("demo-summarizer").price_usdc);
+}
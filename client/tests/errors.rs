@@ -0,0 +1,23 @@
+use trustyclaw_client::errors::lookup;
+
+/// Every error in the committed IDL must resolve to the same name and message
+fn assert_registry_matches_idl(program_id: &anchor_lang::prelude::Pubkey, idl: &str) {
+    let idl: serde_json::Value = serde_json::from_str(idl).unwrap();
+    for error in idl["errors"].as_array().unwrap() {
+        let code = error["code"].as_u64().unwrap() as u32;
+        let name = error["name"].as_str().unwrap();
+        let resolved = lookup(program_id, code).unwrap_or_else(|| panic!("{name} ({code}) is not registered"));
+        assert_eq!(resolved.name, name);
+        assert_eq!(resolved.message, error["msg"].as_str().unwrap());
+    }
+}
+
+#[test]
+fn escrow_registry_covers_idl() {
+    assert_registry_matches_idl(&escrow::ID, include_str!("../../idl/escrow.json"));
+}
+
+#[test]
+fn reputation_registry_covers_idl() {
+    assert_registry_matches_idl(&reputation::ID, include_str!("../../idl/reputation.json"));
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::io;
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use escrow::{CouncilCreated, EscrowDisputed, EscrowFunded};
+use solana_sdk::signature::Signature;
+use trustyclaw_client::events::{
+    EventCategory, EventRpc, EventStreamExt, EventSubscription, Notification, TrustyClawEvent,
+};
+
+fn funded(escrow: Pubkey, renter: Pubkey) -> EscrowFunded {
+    EscrowFunded {
+        escrow,
+        provider: Pubkey::new_unique(),
+        escrow_id: 3,
+        renter,
+        token_mint: Pubkey::new_unique(),
+        amount: 25_000_000,
+        funded_at: 1_000,
+        settle_in_credits: false,
+    }
+}
+
+fn disputed(escrow: Pubkey, renter: Pubkey) -> EscrowDisputed {
+    EscrowDisputed {
+        escrow,
+        provider: Pubkey::new_unique(),
+        escrow_id: 3,
+        renter,
+        arbiter: Pubkey::new_unique(),
+        raised_by: renter,
+        amount: 25_000_000,
+        post_release: false,
+        disputed_at: 2_000,
+    }
+}
+
+fn log_line(event: &impl Event) -> String {
+    format!("Program data: {}", BASE64.encode(event.data()))
+}
+
+#[test]
+fn decodes_log_lines_and_event_cpi_data() {
+    let escrow = Pubkey::new_unique();
+    let logs = vec![
+        "Program log: Instruction: AcceptEscrow".to_string(),
+        log_line(&funded(escrow, Pubkey::new_unique())),
+        "Program data: bm90IGFuIGV2ZW50".to_string(),
+    ];
+    let events = TrustyClawEvent::from_logs(&logs);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].name(), "EscrowFunded");
+    assert_eq!(events[0].category(), EventCategory::Funding);
+    assert_eq!(events[0].escrow(), Some(escrow));
+
+    let data = [&EVENT_IX_TAG_LE[..], &disputed(escrow, Pubkey::new_unique()).data()].concat();
+    let event = TrustyClawEvent::decode_cpi(&data).unwrap();
+    assert!(matches!(event, TrustyClawEvent::EscrowDisputed(_)));
+    assert!(TrustyClawEvent::decode_cpi(&disputed(escrow, Pubkey::new_unique()).data()).is_none());
+}
+
+#[test]
+fn events_name_their_parties() {
+    let renter = Pubkey::new_unique();
+    let event = TrustyClawEvent::decode(&disputed(Pubkey::new_unique(), renter).data()).unwrap();
+    let TrustyClawEvent::EscrowDisputed(ref inner) = event else {
+        panic!("expected a dispute");
+    };
+    assert_eq!(event.wallets(), vec![inner.provider, renter, inner.arbiter, renter]);
+    assert!(event.involves(&renter));
+    assert!(!event.involves(&Pubkey::new_unique()));
+
+    let members = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let council = CouncilCreated {
+        council: Pubkey::new_unique(),
+        members: members.clone(),
+        threshold: 2,
+    };
+    let event = TrustyClawEvent::decode(&council.data()).unwrap();
+    assert_eq!(event.escrow(), None);
+    assert_eq!(event.wallets(), members);
+}
+
+/// Serves a fixed, oldest-first transaction history, failing once a poll finds nothing
+/// new so streams under test end
+#[derive(Default)]
+struct MockRpc {
+    history: Vec<Signature>,
+    logs: HashMap<Signature, Vec<String>>,
+}
+
+impl MockRpc {
+    fn confirm(&mut self, logs: Vec<String>) -> Signature {
+        let signature = Signature::new_unique();
+        self.history.push(signature);
+        self.logs.insert(signature, logs);
+        signature
+    }
+}
+
+impl EventRpc for MockRpc {
+    type Error = io::Error;
+
+    fn signatures_for_address(&self, _: &Pubkey, until: Option<&Signature>) -> Result<Vec<Signature>, io::Error> {
+        let start = until.map_or(0, |until| self.history.iter().position(|s| s == until).unwrap() + 1);
+        if until.is_some() && start == self.history.len() {
+            return Err(io::Error::other("nothing new"));
+        }
+        Ok(self.history[start..].iter().rev().copied().collect())
+    }
+
+    fn transaction_logs(&self, signature: &Signature) -> Result<Vec<String>, io::Error> {
+        Ok(self.logs[signature].clone())
+    }
+}
+
+fn names<E>(stream: impl Iterator<Item = Result<Notification, E>>) -> Vec<(&'static str, Option<Pubkey>)> {
+    stream
+        .map_while(Result::ok)
+        .map(|notification| (notification.event.name(), notification.event.escrow()))
+        .collect()
+}
+
+#[test]
+fn subscription_skips_history_and_resumes_after_its_cursor() {
+    let escrow = Pubkey::new_unique();
+    let mut rpc = MockRpc::default();
+    let old = rpc.confirm(vec![log_line(&funded(escrow, Pubkey::new_unique()))]);
+    assert_eq!(EventSubscription::new(&rpc, escrow::ID).poll().unwrap(), 0);
+
+    let first = rpc.confirm(vec![log_line(&funded(escrow, Pubkey::new_unique()))]);
+    let second = rpc.confirm(vec![log_line(&disputed(escrow, Pubkey::new_unique()))]);
+    let yielded: Vec<_> = EventSubscription::resume(&rpc, escrow::ID, Some(old))
+        .map_while(Result::ok)
+        .map(|notification| (notification.signature, notification.event.name()))
+        .collect();
+    assert_eq!(yielded, vec![(first, "EscrowFunded"), (second, "EscrowDisputed")]);
+}
+
+#[test]
+fn combinators_narrow_by_escrow_wallet_and_category() {
+    let (mine, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let renter = Pubkey::new_unique();
+    let mut rpc = MockRpc::default();
+    rpc.confirm(vec![
+        log_line(&funded(mine, renter)),
+        log_line(&funded(other, renter)),
+        log_line(&disputed(mine, Pubkey::new_unique())),
+    ]);
+    rpc.confirm(vec![log_line(&disputed(other, renter))]);
+    let subscribe = || EventSubscription::resume(&rpc, escrow::ID, None);
+
+    assert_eq!(
+        names(subscribe().for_escrow(mine)),
+        vec![("EscrowFunded", Some(mine)), ("EscrowDisputed", Some(mine))]
+    );
+    assert_eq!(
+        names(subscribe().for_wallet(renter).in_category(EventCategory::Dispute)),
+        vec![("EscrowDisputed", Some(other))]
+    );
+
+    // Errors pass through every filter
+    let mut filtered = subscribe().in_category(EventCategory::Recovery);
+    assert!(filtered.next().unwrap().is_err());
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "metrics")]
+
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowState, EscrowTerms};
+use trustyclaw_client::metrics::EscrowMetrics;
+
+fn escrow(state: EscrowState, mint: Pubkey, amount: u64) -> EscrowAccount {
+    EscrowAccount {
+        state,
+        token_mint: mint,
+        amount,
+        funded_at: 1_000,
+        dispute_window_seconds: 500,
+        terms: EscrowTerms {
+            duration_seconds: 100,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn tallies_states_volume_and_disputes() {
+    let usdc = Pubkey::new_unique();
+    let listing = EscrowAccount {
+        funded_at: 0,
+        ..escrow(EscrowState::Created, usdc, 0)
+    };
+    let disputed = EscrowAccount {
+        disputed_at: 1_200,
+        ..escrow(EscrowState::Disputed, usdc, 300)
+    };
+    let escrows = [
+        listing,
+        escrow(EscrowState::Funded, usdc, 100),
+        disputed,
+        EscrowAccount {
+            completed_at: 1_400,
+            ..escrow(EscrowState::Completed, usdc, 200)
+        },
+        EscrowAccount {
+            completed_at: 1_200,
+            ..escrow(EscrowState::Vesting, usdc, 400)
+        },
+    ];
+    let metrics = EscrowMetrics::collect(&escrows, 1_050);
+
+    assert_eq!(metrics.escrows["created"], 1);
+    assert_eq!(metrics.escrows["cancelled"], 0);
+    assert_eq!(metrics.funded_rentals, 4);
+    assert_eq!(metrics.disputed_rentals, 1);
+    assert_eq!(metrics.dispute_rate(), 0.25);
+    assert_eq!(metrics.funded_volume[&usdc], 1_000);
+    assert_eq!(metrics.locked[&usdc], 400);
+    assert_eq!(metrics.mean_time_to_release_seconds(), 300.0);
+    assert_eq!(metrics.crank_backlog(), 0);
+}
+
+#[test]
+fn counts_overdue_cranks() {
+    let usdc = Pubkey::new_unique();
+    let lapsed_listing = EscrowAccount {
+        funded_at: 0,
+        terms: EscrowTerms {
+            funding_deadline: 1_500,
+            ..Default::default()
+        },
+        ..escrow(EscrowState::Created, usdc, 0)
+    };
+    let delivered = EscrowAccount {
+        first_delivered_at: 1_050,
+        ..escrow(EscrowState::Funded, usdc, 100)
+    };
+    let escrows = [lapsed_listing, escrow(EscrowState::Funded, usdc, 100), delivered];
+
+    assert_eq!(EscrowMetrics::collect(&escrows, 1_099).crank_backlog(), 0);
+    let metrics = EscrowMetrics::collect(&escrows, 1_100);
+    assert_eq!(metrics.expirable_listings, 0);
+    assert_eq!(metrics.refundable_rentals, 1);
+    assert_eq!(EscrowMetrics::collect(&escrows, 1_501).crank_backlog(), 2);
+}
+
+#[test]
+fn renders_prometheus_text() {
+    let usdc = Pubkey::new_unique();
+    let rendered = EscrowMetrics::collect(&[escrow(EscrowState::Funded, usdc, 100)], 1_050).render();
+
+    assert!(rendered.contains("# TYPE trustyclaw_escrows gauge\n"));
+    assert!(rendered.contains("trustyclaw_escrows{state=\"funded\"} 1\n"));
+    assert!(rendered.contains("trustyclaw_open_escrows 1\n"));
+    assert!(rendered.contains(&format!("trustyclaw_locked_base_units{{mint=\"{usdc}\"}} 100\n")));
+    assert!(rendered.contains("trustyclaw_crank_backlog{crank=\"expire_listing\"} 0\n"));
+    assert!(rendered.lines().all(|line| line.starts_with('#') || line.split(' ').count() == 2));
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::state::{Account, AccountState, Mint};
+use trustyclaw_client::preflight::{Simulation, SimulationFailure, TokenCheck, TokenRequirement};
+use trustyclaw_client::{instructions, preflight, PreflightRpc, TokenAmount, UsdcAmount};
+
+#[derive(Default)]
+struct MockRpc {
+    simulation: Simulation,
+    accounts: HashMap<Pubkey, Vec<u8>>,
+}
+
+impl MockRpc {
+    fn with_mint(mut self, mint: Pubkey, decimals: u8) -> Self {
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(
+            Mint {
+                mint_authority: COption::None,
+                supply: u64::MAX,
+                decimals,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            &mut data,
+        )
+        .unwrap();
+        self.accounts.insert(mint, data);
+        self
+    }
+
+    fn with_balance(mut self, owner: Pubkey, mint: Pubkey, amount: u64) -> Self {
+        let mut data = vec![0; Account::LEN];
+        Account::pack(
+            Account {
+                mint,
+                owner,
+                amount,
+                state: AccountState::Initialized,
+                ..Default::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+        self.accounts.insert(get_associated_token_address(&owner, &mint), data);
+        self
+    }
+}
+
+impl PreflightRpc for MockRpc {
+    type Error = std::io::Error;
+
+    fn simulate(&self, _payer: &Pubkey, _instructions: &[Instruction]) -> Result<Simulation, Self::Error> {
+        Ok(self.simulation.clone())
+    }
+
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.accounts.get(address).cloned())
+    }
+}
+
+fn usdc(dollars: u64) -> TokenAmount {
+    UsdcAmount::from_dollars(dollars).unwrap().into()
+}
+
+#[test]
+fn decodes_escrow_error_from_simulation() {
+    let provider = Pubkey::new_unique();
+    let ix = instructions::dispute(&provider, &provider, 0);
+    let rpc = MockRpc {
+        simulation: Simulation {
+            custom_error: Some((0, 6000)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let report = preflight(&rpc, &provider, &[ix], &[]).unwrap();
+    assert!(!report.is_ok());
+    match report.failure {
+        Some(SimulationFailure::Program { instruction, program_id, error }) => {
+            assert_eq!(instruction, 0);
+            assert_eq!(program_id, escrow::ID);
+            assert_eq!(error.name, "InvalidState");
+        }
+        other => panic!("unexpected failure: {other:?}"),
+    }
+}
+
+#[test]
+fn reports_missing_ata_and_short_balance() {
+    let (renter, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let other = Pubkey::new_unique();
+    let rpc = MockRpc::default().with_mint(mint, 6).with_balance(renter, mint, 5_000_000);
+
+    let report = preflight(
+        &rpc,
+        &renter,
+        &[],
+        &[
+            TokenRequirement { owner: renter, mint, amount: usdc(10) },
+            TokenRequirement { owner: other, mint, amount: usdc(1) },
+        ],
+    )
+    .unwrap();
+
+    assert!(matches!(report.token_checks[0].1, TokenCheck::InsufficientBalance { balance, .. } if balance == usdc(5)));
+    assert!(matches!(report.token_checks[1].1, TokenCheck::MissingAta { .. }));
+    assert!(!report.is_ok());
+}
+
+#[test]
+fn passes_when_simulation_and_balances_are_fine() {
+    let (renter, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let rpc = MockRpc::default().with_mint(mint, 6).with_balance(renter, mint, 25_000_000);
+
+    let report = preflight(&rpc, &renter, &[], &[TokenRequirement { owner: renter, mint, amount: usdc(25) }]).unwrap();
+    assert!(report.is_ok());
+
+    let nine = TokenAmount::from_whole(25, 9).unwrap();
+    let report = preflight(&rpc, &renter, &[], &[TokenRequirement { owner: renter, mint, amount: nine }]).unwrap();
+    assert!(matches!(report.token_checks[0].1, TokenCheck::DecimalsMismatch { mint_decimals: 6, requested: 9 }));
+}
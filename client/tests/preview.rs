@@ -0,0 +1,156 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::AnchorSerialize;
+use escrow::{
+    EngagementPreview, EscrowAccount, EscrowError, FundOverrides, FundingBlock, RiskAssessment, Validation,
+};
+use trustyclaw_client::preflight::{Simulation, SimulationFailure};
+use trustyclaw_client::preview::{self, PreviewError};
+use trustyclaw_client::{errors, instructions, pda, PreflightRpc, TokenAmount};
+
+#[derive(Default)]
+struct MockRpc {
+    simulation: Simulation,
+    accounts: HashMap<Pubkey, Vec<u8>>,
+    simulated: RefCell<Vec<Instruction>>,
+}
+
+impl PreflightRpc for MockRpc {
+    type Error = std::io::Error;
+
+    fn simulate(&self, _payer: &Pubkey, instructions: &[Instruction]) -> Result<Simulation, Self::Error> {
+        self.simulated.borrow_mut().extend_from_slice(instructions);
+        Ok(self.simulation.clone())
+    }
+
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.accounts.get(address).cloned())
+    }
+}
+
+fn listing() -> EscrowAccount {
+    let mut listing = EscrowAccount {
+        provider: Pubkey::new_unique(),
+        escrow_id: 4,
+        ..Default::default()
+    };
+    listing.terms.arbiter = Pubkey::new_unique();
+    listing
+}
+
+fn returning(view: &impl AnchorSerialize) -> Simulation {
+    Simulation {
+        return_data: Some((escrow::ID, view.try_to_vec().unwrap())),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn decodes_the_preview_and_passes_the_accounts_that_exist() {
+    let (renter, listing) = (Pubkey::new_unique(), listing());
+    let expected = EngagementPreview {
+        escrow: pda::escrow(&listing.provider, listing.escrow_id),
+        renter,
+        renter_score: Some(72),
+        blocked_by: Some(FundingBlock::BondNotFunded),
+        price: 10_000_000,
+        ..Default::default()
+    };
+    let mut rpc = MockRpc {
+        simulation: returning(&expected),
+        ..Default::default()
+    };
+    rpc.accounts.insert(pda::reputation_agent(&renter), vec![1]);
+    rpc.accounts.insert(pda::arbiter_stats(&listing.terms.arbiter), vec![1]);
+
+    let preview = preview::engagement(&rpc, &renter, &listing, FundOverrides::default()).unwrap();
+    assert_eq!(preview, expected);
+
+    // Absent optional accounts are passed as the program id
+    let simulated = rpc.simulated.borrow();
+    let keys: Vec<Pubkey> = simulated[0].accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        keys[5..],
+        [pda::reputation_agent(&renter), escrow::ID, pda::arbiter_stats(&listing.terms.arbiter)]
+    );
+}
+
+#[test]
+fn reports_a_failed_simulation_and_missing_return_data() {
+    let (renter, listing) = (Pubkey::new_unique(), listing());
+    let rpc = MockRpc {
+        simulation: Simulation {
+            custom_error: Some((0, 6010)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    match preview::engagement(&rpc, &renter, &listing, FundOverrides::default()) {
+        Err(PreviewError::Failed(SimulationFailure::Program { error, .. })) => assert_eq!(error.name, "OverrideOutOfRange"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    let rpc = MockRpc::default();
+    assert!(matches!(
+        preview::engagement(&rpc, &renter, &listing, FundOverrides::default()),
+        Err(PreviewError::NoReturnData)
+    ));
+}
+
+#[test]
+fn scores_a_provider_without_a_reputation_account() {
+    let listing = listing();
+    let expected = RiskAssessment {
+        escrow: pda::escrow(&listing.provider, listing.escrow_id),
+        provider: listing.provider,
+        score: 61,
+        age_risk_bps: 10_000,
+        ..Default::default()
+    };
+    let rpc = MockRpc {
+        simulation: returning(&expected),
+        ..Default::default()
+    };
+    assert_eq!(preview::risk(&rpc, &Pubkey::new_unique(), &listing).unwrap(), expected);
+
+    let simulated = rpc.simulated.borrow();
+    assert_eq!(simulated[0].accounts.last().unwrap().pubkey, escrow::ID);
+}
+
+#[test]
+fn decodes_the_first_failed_check_of_a_dry_run() {
+    let (renter, listing) = (Pubkey::new_unique(), listing());
+    let expected = Validation::of(Err(EscrowError::InsufficientFunds.into()));
+    let rpc = MockRpc {
+        simulation: returning(&expected),
+        ..Default::default()
+    };
+    let instruction = instructions::validate_fund(
+        &renter,
+        &listing.provider,
+        listing.escrow_id,
+        &listing.token_mint,
+        &anchor_spl::token::ID,
+        6,
+        TokenAmount::from_whole(10, 6).unwrap(),
+        FundOverrides::default(),
+        listing.terms_hash,
+        false,
+        false,
+    )
+    .unwrap();
+
+    let validation = preview::validate(&rpc, &renter, instruction).unwrap();
+    assert!(!validation.is_valid());
+    let failure = validation.failure.unwrap();
+    let registered = errors::lookup(&escrow::ID, failure.code as u32).unwrap();
+    assert_eq!(registered.name, "InsufficientFunds");
+    assert_eq!((failure.name, failure.message), (registered.name, registered.message));
+
+    // Without a token account the renter's slot is passed as the program id
+    let simulated = rpc.simulated.borrow();
+    assert_eq!(simulated[0].accounts[6].pubkey, escrow::ID);
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{Discriminator, Event};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use escrow::{EscrowDisputed, EscrowExpedited, EscrowFunded};
+use trustyclaw_client::provider_agent::{disputed_events, expedited_events, funded_events, Artifact, Responder};
+
+fn funded(provider: Pubkey) -> EscrowFunded {
+    EscrowFunded {
+        escrow: Pubkey::new_unique(),
+        provider,
+        escrow_id: 3,
+        renter: Pubkey::new_unique(),
+        token_mint: Pubkey::new_unique(),
+        amount: 25_000_000,
+        funded_at: 1_000,
+        settle_in_credits: false,
+    }
+}
+
+fn log_line(event: &impl Event) -> String {
+    format!("Program data: {}", BASE64.encode(event.data()))
+}
+
+#[test]
+fn decodes_funded_events_for_the_provider_only() {
+    let provider = Pubkey::new_unique();
+    let mine = funded(provider);
+    let other = funded(Pubkey::new_unique());
+    let logs = vec![
+        "Program 8uBMA8S33eGFMRA677Y1gPvmnBGUjFtdwxf2A8JufpA3 invoke [1]".to_string(),
+        log_line(&mine),
+        log_line(&other),
+        format!("Program data: {}", BASE64.encode([&EscrowFunded::DISCRIMINATOR[..], &[0u8; 4]].concat())),
+    ];
+
+    let events = funded_events(&logs, &provider);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].escrow, mine.escrow);
+    assert_eq!(events[0].escrow_id, 3);
+    assert_eq!(events[0].amount, 25_000_000);
+}
+
+#[test]
+fn decodes_expedite_fees_alongside_fundings() {
+    let provider = Pubkey::new_unique();
+    let funding = funded(provider);
+    let expedited = EscrowExpedited {
+        escrow: funding.escrow,
+        provider,
+        escrow_id: 3,
+        renter: funding.renter,
+        extra_fee: 2_000_000,
+        expedite_fee: 3_000_000,
+    };
+    let logs = vec![log_line(&funding), log_line(&expedited)];
+
+    assert_eq!(funded_events(&logs, &provider).len(), 1);
+    let events = expedited_events(&logs, &provider);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].escrow, funding.escrow);
+    assert_eq!(events[0].expedite_fee, 3_000_000);
+    assert!(expedited_events(&logs, &Pubkey::new_unique()).is_empty());
+}
+
+#[test]
+fn decodes_disputes_on_the_providers_rentals() {
+    let provider = Pubkey::new_unique();
+    let funding = funded(provider);
+    let dispute = EscrowDisputed {
+        escrow: funding.escrow,
+        provider,
+        escrow_id: 3,
+        renter: funding.renter,
+        arbiter: Pubkey::new_unique(),
+        raised_by: funding.renter,
+        amount: 25_000_000,
+        post_release: false,
+        disputed_at: 2_000,
+    };
+    let logs = vec![log_line(&funding), log_line(&dispute)];
+
+    let events = disputed_events(&logs, &provider);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].raised_by, funding.renter);
+    assert_eq!(events[0].amount, 25_000_000);
+    assert!(disputed_events(&logs, &Pubkey::new_unique()).is_empty());
+}
+
+#[test]
+fn parses_artifacts_in_both_formats() {
+    let hash = "ab".repeat(32);
+    let plain = Artifact::parse(&format!("{hash}\n")).unwrap();
+    assert_eq!(plain.deliverable_hash, [0xab; 32]);
+    assert_eq!(plain.encrypted_key_hash, [0; 32]);
+    assert!(plain.deliverable_uri.is_empty());
+
+    let key = "01".repeat(32);
+    let json = Artifact::parse(&format!(
+        r#"{{"deliverable_hash":"0x{hash}","deliverable_uri":"ipfs://out","encrypted_key_hash":"{key}"}}"#
+    ))
+    .unwrap();
+    assert_eq!(json.deliverable_hash, [0xab; 32]);
+    assert_eq!(json.deliverable_uri, "ipfs://out");
+    assert_eq!(json.encrypted_key_hash, [0x01; 32]);
+
+    assert!(Artifact::parse("not-a-hash").is_err());
+    assert!(Artifact::parse("").is_err());
+}
+
+#[test]
+fn command_responder_receives_event_and_returns_artifact() {
+    let provider = Pubkey::new_unique();
+    let event = funded(provider);
+    let responder = Responder::Command(format!(
+        r#"test "$TRUSTYCLAW_AMOUNT" = 25000000 && grep -q '"escrow":"{}"' && echo {}"#,
+        event.escrow,
+        "cd".repeat(32)
+    ));
+
+    let artifact = responder.respond(&event).unwrap();
+    assert_eq!(artifact.deliverable_hash, [0xcd; 32]);
+}
@@ -0,0 +1,192 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountSerialize;
+use escrow::{EscrowAccount, EscrowState};
+use reputation::Review;
+use trustyclaw_client::decode::EscrowLayout;
+use trustyclaw_client::text::skill_name;
+use trustyclaw_client::scan::{
+    escrows_by_provider_filters, escrows_by_renter_filters, list_escrows_by_provider, list_escrows_by_skill, list_open_disputes,
+    list_reviews_for_agent, AccountFilter, PageRequest, ScanRpc, MAX_MULTIPLE_ACCOUNTS,
+};
+
+/// Evaluates filters the way an RPC node would
+#[derive(Default)]
+struct MockRpc {
+    accounts: HashMap<Pubkey, (Pubkey, Vec<u8>)>,
+    batches: Cell<usize>,
+}
+
+impl MockRpc {
+    fn insert(&mut self, program_id: Pubkey, data: Vec<u8>) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.accounts.insert(address, (program_id, data));
+        address
+    }
+
+    fn escrow(&mut self, escrow: EscrowAccount) -> Pubkey {
+        self.insert(escrow::ID, serialized(&escrow, EscrowLayout::CURRENT.account_len()))
+    }
+}
+
+impl ScanRpc for MockRpc {
+    type Error = std::io::Error;
+
+    fn program_account_keys(&self, program_id: &Pubkey, filters: &[AccountFilter]) -> Result<Vec<Pubkey>, Self::Error> {
+        Ok(self
+            .accounts
+            .iter()
+            .filter(|(_, (owner, data))| owner == program_id && filters.iter().all(|f| f.matches(data)))
+            .map(|(address, _)| *address)
+            .collect())
+    }
+
+    fn multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
+        assert!(addresses.len() <= MAX_MULTIPLE_ACCOUNTS);
+        self.batches.set(self.batches.get() + 1);
+        Ok(addresses.iter().map(|a| self.accounts.get(a).map(|(_, d)| d.clone())).collect())
+    }
+}
+
+fn serialized<T: AccountSerialize>(account: &T, len: usize) -> Vec<u8> {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    data.resize(len, 0);
+    data
+}
+
+fn review(agent: Pubkey) -> Review {
+    Review {
+        agent,
+        reviewer: Pubkey::new_unique(),
+        rating: 5,
+        comment: "solid".to_string(),
+        skill_category: "code-review".to_string(),
+        created_at: 1_700_000_000,
+        comment_hash: [0; 32],
+        translations_uri: None,
+        delegate: None,
+    }
+}
+
+#[test]
+fn filters_match_the_fixed_offsets() {
+    let provider = Pubkey::new_unique();
+    let renter = Pubkey::new_unique();
+    let escrow = EscrowAccount {
+        provider,
+        renter,
+        ..Default::default()
+    };
+    let data = serialized(&escrow, EscrowAccount::LEN);
+
+    let by_provider = escrows_by_provider_filters(&provider);
+    assert!(by_provider.iter().all(|f| f.matches(&data)));
+    let by_renter = escrows_by_renter_filters(&renter);
+    assert!(by_renter.iter().all(|f| f.matches(&data)));
+    let swapped = escrows_by_provider_filters(&renter);
+    assert!(!swapped.iter().all(|f| f.matches(&data)));
+}
+
+#[test]
+fn skill_listing_matches_the_exact_name() {
+    let mut rpc = MockRpc::default();
+    let mut listing = |name: &str| {
+        let mut escrow = EscrowAccount::default();
+        escrow.terms.skill_name = skill_name(name).unwrap();
+        rpc.escrow(escrow)
+    };
+    let review = listing("code-review");
+    listing("code-review-pro");
+    listing("translate");
+
+    let page = list_escrows_by_skill(&rpc, &skill_name("code-review").unwrap(), &PageRequest::default()).unwrap();
+    let found: Vec<Pubkey> = page.items.iter().map(|(address, _)| *address).collect();
+    assert_eq!(found, vec![review]);
+}
+
+#[test]
+fn provider_listing_pages_through_every_escrow_once() {
+    let provider = Pubkey::new_unique();
+    let mut rpc = MockRpc::default();
+    let mut expected: Vec<Pubkey> = (0..5)
+        .map(|_| {
+            rpc.escrow(EscrowAccount {
+                provider,
+                ..Default::default()
+            })
+        })
+        .collect();
+    rpc.escrow(EscrowAccount {
+        provider: Pubkey::new_unique(),
+        ..Default::default()
+    });
+    expected.sort();
+
+    let mut seen = Vec::new();
+    let mut page = PageRequest { after: None, limit: 2 };
+    loop {
+        let result = list_escrows_by_provider(&rpc, &provider, &page).unwrap();
+        assert!(result.items.len() <= 2);
+        seen.extend(result.items.iter().map(|(address, view)| {
+            assert_eq!(view.escrow.provider, provider);
+            *address
+        }));
+        match result.next {
+            Some(next) => page.after = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(seen, expected);
+}
+
+#[test]
+fn large_pages_are_loaded_in_batches() {
+    let provider = Pubkey::new_unique();
+    let mut rpc = MockRpc::default();
+    for _ in 0..MAX_MULTIPLE_ACCOUNTS + 1 {
+        rpc.escrow(EscrowAccount {
+            provider,
+            ..Default::default()
+        });
+    }
+    let page = PageRequest { after: None, limit: 500 };
+    let result = list_escrows_by_provider(&rpc, &provider, &page).unwrap();
+    assert_eq!(result.items.len(), MAX_MULTIPLE_ACCOUNTS + 1);
+    assert_eq!(result.next, None);
+    assert_eq!(rpc.batches.get(), 2);
+}
+
+#[test]
+fn open_disputes_are_filtered_after_decoding() {
+    let mut rpc = MockRpc::default();
+    let disputed = rpc.escrow(EscrowAccount {
+        state: EscrowState::Disputed,
+        ..Default::default()
+    });
+    rpc.escrow(EscrowAccount {
+        state: EscrowState::Funded,
+        ..Default::default()
+    });
+
+    let result = list_open_disputes(&rpc, &PageRequest::default()).unwrap();
+    let addresses: Vec<Pubkey> = result.items.iter().map(|(address, _)| *address).collect();
+    assert_eq!(addresses, vec![disputed]);
+}
+
+#[test]
+fn reviews_are_listed_per_agent() {
+    let agent = Pubkey::new_unique();
+    let mut rpc = MockRpc::default();
+    let mine = rpc.insert(reputation::ID, serialized(&review(agent), Review::LEN));
+    rpc.insert(reputation::ID, serialized(&review(Pubkey::new_unique()), Review::LEN));
+    // Same bytes owned by another program are not reviews
+    rpc.insert(escrow::ID, serialized(&review(agent), Review::LEN));
+
+    let result = list_reviews_for_agent(&rpc, &agent, &PageRequest::default()).unwrap();
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].0, mine);
+    assert_eq!(result.items[0].1.comment, "solid");
+}
@@ -0,0 +1,222 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use solana_sdk::nonce::state::{DurableNonce, State, Versions};
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use trustyclaw_client::preflight::Simulation;
+use trustyclaw_client::submit::{nonce_account as advanced_nonce, RetryPolicy, SignatureStatus, SubmitError};
+use trustyclaw_client::{instructions, Lifetime, Outcome, PreflightRpc, SubmitRpc, Submitter};
+
+/// Scripted RPC: each call pops the next scripted reply, and every send is recorded
+#[derive(Default)]
+struct MockRpc {
+    blockhashes: RefCell<VecDeque<Hash>>,
+    /// Replies to `signature_status`; `Err(())` is a dropped connection
+    statuses: RefCell<VecDeque<Result<SignatureStatus, ()>>>,
+    blockhash_valid: RefCell<VecDeque<bool>>,
+    accounts: RefCell<HashMap<Pubkey, Vec<u8>>>,
+    sent: RefCell<Vec<Transaction>>,
+}
+
+fn unreachable() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::ConnectionReset, "connection reset")
+}
+
+impl PreflightRpc for MockRpc {
+    type Error = std::io::Error;
+
+    fn simulate(&self, _payer: &Pubkey, _instructions: &[Instruction]) -> Result<Simulation, Self::Error> {
+        Ok(Simulation::default())
+    }
+
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.accounts.borrow().get(address).cloned())
+    }
+}
+
+impl SubmitRpc for MockRpc {
+    fn latest_blockhash(&self) -> Result<Hash, Self::Error> {
+        self.blockhashes.borrow_mut().pop_front().ok_or_else(unreachable)
+    }
+
+    fn is_blockhash_valid(&self, _blockhash: &Hash) -> Result<bool, Self::Error> {
+        Ok(self.blockhash_valid.borrow_mut().pop_front().unwrap_or(true))
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<Signature, Self::Error> {
+        self.sent.borrow_mut().push(transaction.clone());
+        // Half the sends fail, as on a flaky connection
+        match self.sent.borrow().len() % 2 {
+            0 => Err(unreachable()),
+            _ => Ok(transaction.signatures[0]),
+        }
+    }
+
+    fn signature_status(&self, _signature: &Signature) -> Result<SignatureStatus, Self::Error> {
+        match self.statuses.borrow_mut().pop_front() {
+            Some(Ok(status)) => Ok(status),
+            Some(Err(())) => Err(unreachable()),
+            None => Ok(None),
+        }
+    }
+}
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        poll_interval: Duration::ZERO,
+        max_rpc_failures: 3,
+        max_resigns: 1,
+    }
+}
+
+fn nonce_data(authority: &Pubkey, seed: u8) -> Vec<u8> {
+    let durable = DurableNonce::from_blockhash(&Hash::new_from_array([seed; 32]));
+    let state = State::new_initialized(authority, durable, 5_000);
+    bincode::serialize(&Versions::new(state)).unwrap()
+}
+
+/// Any instruction signed by `renter` alone
+fn instruction(renter: &Pubkey) -> Instruction {
+    instructions::set_watchtower(renter, &Pubkey::new_unique(), 0, &Pubkey::new_unique())
+}
+
+#[test]
+fn resends_the_same_transaction_until_it_confirms() {
+    let payer = Keypair::new();
+    let rpc = MockRpc::default();
+    rpc.blockhashes.borrow_mut().push_back(Hash::new_unique());
+    rpc.statuses.borrow_mut().extend([Ok(None), Err(()), Ok(None), Ok(Some(Ok(())))]);
+
+    let outcome = Submitter::new(&rpc)
+        .with_policy(policy())
+        .submit(&payer.pubkey(), &[&payer], &[instruction(&payer.pubkey())], Lifetime::Blockhash)
+        .unwrap();
+
+    let sent = rpc.sent.borrow();
+    assert_eq!(sent.len(), 3);
+    assert!(sent.iter().all(|tx| tx.signatures == sent[0].signatures));
+    assert_eq!(outcome, Outcome::Confirmed(sent[0].signatures[0]));
+}
+
+#[test]
+fn re_signs_only_after_the_blockhash_expires_unlanded() {
+    let payer = Keypair::new();
+    let rpc = MockRpc::default();
+    rpc.blockhashes.borrow_mut().extend([Hash::new_unique(), Hash::new_unique()]);
+    rpc.blockhash_valid.borrow_mut().extend([true, false]);
+    rpc.statuses
+        .borrow_mut()
+        .extend([Ok(None), Ok(None), Ok(None), Ok(Some(Err("custom program error: 0x1770".to_string())))]);
+
+    let outcome = Submitter::new(&rpc)
+        .with_policy(policy())
+        .submit(&payer.pubkey(), &[&payer], &[instruction(&payer.pubkey())], Lifetime::Blockhash)
+        .unwrap();
+
+    let sent = rpc.sent.borrow();
+    assert_eq!(sent.len(), 3);
+    assert_eq!(sent[0].signatures, sent[1].signatures);
+    assert_ne!(sent[1].message.recent_blockhash, sent[2].message.recent_blockhash);
+    assert_eq!(
+        outcome,
+        Outcome::Failed {
+            signature: sent[2].signatures[0],
+            error: "custom program error: 0x1770".to_string(),
+        }
+    );
+}
+
+#[test]
+fn gives_up_once_every_blockhash_expired() {
+    let payer = Keypair::new();
+    let rpc = MockRpc::default();
+    rpc.blockhashes.borrow_mut().extend([Hash::new_unique(), Hash::new_unique()]);
+    rpc.blockhash_valid.borrow_mut().extend([false, false]);
+
+    let outcome = Submitter::new(&rpc)
+        .with_policy(policy())
+        .submit(&payer.pubkey(), &[&payer], &[instruction(&payer.pubkey())], Lifetime::Blockhash)
+        .unwrap();
+    assert_eq!(outcome, Outcome::Expired);
+    assert_eq!(rpc.sent.borrow().len(), 2);
+}
+
+#[test]
+fn durable_nonce_transactions_advance_the_nonce_first() {
+    let payer = Keypair::new();
+    let account = Pubkey::new_unique();
+    let rpc = MockRpc::default();
+    rpc.accounts.borrow_mut().insert(account, nonce_data(&payer.pubkey(), 1));
+    rpc.statuses.borrow_mut().extend([Ok(None), Ok(Some(Ok(())))]);
+
+    let lifetime = Lifetime::DurableNonce {
+        account,
+        authority: payer.pubkey(),
+    };
+    let outcome = Submitter::new(&rpc)
+        .with_policy(policy())
+        .submit(&payer.pubkey(), &[&payer], &[instruction(&payer.pubkey())], lifetime)
+        .unwrap();
+
+    let sent = rpc.sent.borrow();
+    let expected = DurableNonce::from_blockhash(&Hash::new_from_array([1; 32]));
+    assert_eq!(sent[0].message.recent_blockhash, *expected.as_hash());
+    assert_eq!(sent[0].message.instructions.len(), 2);
+    assert_eq!(advanced_nonce(&sent[0]), Some(account));
+    assert_eq!(outcome, Outcome::Confirmed(sent[0].signatures[0]));
+}
+
+#[test]
+fn consumed_nonce_means_the_transaction_can_never_land() {
+    let payer = Keypair::new();
+    let account = Pubkey::new_unique();
+    let rpc = MockRpc::default();
+    rpc.accounts.borrow_mut().insert(account, nonce_data(&payer.pubkey(), 1));
+    let lifetime = Lifetime::DurableNonce {
+        account,
+        authority: payer.pubkey(),
+    };
+    let submitter = Submitter::new(&rpc).with_policy(RetryPolicy {
+        max_rpc_failures: 1,
+        ..policy()
+    });
+
+    // Connectivity drops: the caller keeps the signed transaction
+    rpc.statuses.borrow_mut().push_back(Err(()));
+    let Err(SubmitError::Unreachable {
+        transaction: Some(transaction),
+        ..
+    }) = submitter.submit(&payer.pubkey(), &[&payer], &[instruction(&payer.pubkey())], lifetime)
+    else {
+        panic!("expected the signed transaction back");
+    };
+
+    // Meanwhile another transaction used the nonce
+    rpc.accounts.borrow_mut().insert(account, nonce_data(&payer.pubkey(), 2));
+    assert_eq!(submitter.resume(&transaction).unwrap(), Outcome::Expired);
+    assert!(rpc.sent.borrow().iter().all(|tx| tx.signatures == transaction.signatures));
+}
+
+#[test]
+fn uninitialized_nonce_accounts_are_rejected() {
+    let payer = Keypair::new();
+    let account = Pubkey::new_unique();
+    let rpc = MockRpc::default();
+    let uninitialized = bincode::serialize(&Versions::new(State::Uninitialized)).unwrap();
+    rpc.accounts.borrow_mut().insert(account, uninitialized);
+
+    let lifetime = Lifetime::DurableNonce {
+        account,
+        authority: payer.pubkey(),
+    };
+    let result = Submitter::new(&rpc)
+        .with_policy(policy())
+        .submit(&payer.pubkey(), &[&payer], &[instruction(&payer.pubkey())], lifetime);
+    assert!(matches!(result, Err(SubmitError::InvalidNonceAccount(a)) if a == account));
+    assert!(rpc.sent.borrow().is_empty());
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use escrow::EscrowAccount;
+use trustyclaw_client::demo::sample_terms;
+use trustyclaw_client::terms_document::{
+    metadata_uri_hash, terms_hash, AcceptanceCriterion, RevisionPolicy, TermsDocument, TermsDocumentError,
+    TermsDocumentV1, TERMS_SCHEMA_V1,
+};
+
+fn document() -> TermsDocument {
+    TermsDocument::V1(TermsDocumentV1 {
+        scope: "Review one pull request of at most 500 changed lines".to_string(),
+        deliverables: vec!["Inline review comments".to_string()],
+        acceptance_criteria: vec![AcceptanceCriterion {
+            description: "Every changed file is commented on or marked clean".to_string(),
+            verification: None,
+        }],
+        sla: None,
+        revision_policy: RevisionPolicy {
+            max_revisions: 1,
+            notes: None,
+        },
+        language: "en".to_string(),
+    })
+}
+
+#[test]
+fn documents_round_trip_with_their_schema_tag() {
+    let document = document();
+    let bytes = document.to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["schema"], TERMS_SCHEMA_V1);
+
+    let fetched = TermsDocument::from_fetched(&bytes, &document.document_hash()).unwrap();
+    assert_eq!(fetched, document);
+    assert_eq!(fetched.to_bytes(), bytes);
+}
+
+#[test]
+fn unknown_schemas_and_fields_are_rejected() {
+    let mut json: serde_json::Value = serde_json::from_slice(&document().to_bytes()).unwrap();
+    json["schema"] = "trustyclaw.terms/v0".into();
+    let bytes = serde_json::to_vec(&json).unwrap();
+    let result = TermsDocument::from_fetched(&bytes, &hash(&bytes).to_bytes());
+    assert!(matches!(result, Err(TermsDocumentError::Malformed(_))));
+
+    json["schema"] = TERMS_SCHEMA_V1.into();
+    json["price"] = 10.into();
+    let bytes = serde_json::to_vec(&json).unwrap();
+    let result = TermsDocument::from_fetched(&bytes, &hash(&bytes).to_bytes());
+    assert!(matches!(result, Err(TermsDocumentError::Malformed(_))));
+}
+
+#[test]
+fn tampered_documents_fail_the_hash_check() {
+    let document = document();
+    let mut bytes = document.to_bytes();
+    let at = bytes.iter().position(|b| *b == b'5').unwrap();
+    bytes[at] = b'9';
+    let result = TermsDocument::from_fetched(&bytes, &document.document_hash());
+    assert!(matches!(result, Err(TermsDocumentError::HashMismatch)));
+}
+
+#[test]
+fn revision_policy_is_bounded_by_the_program() {
+    let TermsDocument::V1(mut v1) = document();
+    v1.revision_policy.max_revisions = escrow::MAX_DELIVERABLE_REVISIONS + 1;
+    let document = TermsDocument::V1(v1);
+    assert!(matches!(document.validate(), Err(TermsDocumentError::Invalid(_))));
+}
+
+#[test]
+fn hashes_match_what_the_program_records() {
+    let terms = sample_terms("code-review");
+    let mint = Pubkey::new_unique();
+    assert_eq!(terms_hash(&terms, &mint), EscrowAccount::compute_terms_hash(&terms, &mint).unwrap());
+
+    let uri = "https://example.com/terms/code-review.json";
+    assert_eq!(metadata_uri_hash(uri), hash(uri.as_bytes()).to_bytes());
+}
@@ -0,0 +1,31 @@
+use escrow::{MAX_METADATA_URI_LEN, MAX_SKILL_NAME_LEN};
+use trustyclaw_client::text::{fixed, metadata_uri, skill_name, truncated, TextTooLong};
+
+#[test]
+fn text_round_trips_through_fixed_fields() {
+    let name = skill_name("code-review").unwrap();
+    assert_eq!(name.as_str(), "code-review");
+    assert!(name.is_valid());
+    assert_eq!(metadata_uri("ipfs://terms").unwrap().as_str(), "ipfs://terms");
+}
+
+#[test]
+fn text_over_capacity_is_rejected() {
+    let long = "x".repeat(MAX_SKILL_NAME_LEN + 1);
+    assert_eq!(
+        skill_name(&long).unwrap_err(),
+        TextTooLong {
+            len: MAX_SKILL_NAME_LEN + 1,
+            capacity: MAX_SKILL_NAME_LEN,
+        }
+    );
+    assert!(metadata_uri(&"x".repeat(MAX_METADATA_URI_LEN)).is_ok());
+}
+
+#[test]
+fn truncation_keeps_whole_characters() {
+    // "é" is two bytes; a 4-byte field cannot end halfway through the second one
+    assert_eq!(truncated::<4>("aéé").as_str(), "aé");
+    assert_eq!(truncated::<4>("ab").as_str(), "ab");
+    assert_eq!(fixed::<4>("aéé").unwrap_err().len, 5);
+}
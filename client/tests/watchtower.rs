@@ -0,0 +1,50 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowState, EscrowTerms};
+use trustyclaw_client::watchtower::{next_action, OnExpiry, WatchAction};
+
+fn funded_escrow() -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        renter: Pubkey::new_unique(),
+        state: EscrowState::Funded,
+        funded_at: 1_000,
+        dispute_window_seconds: 500,
+        terms: EscrowTerms {
+            duration_seconds: 100,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn waits_until_the_rental_period_ends() {
+    let escrow = funded_escrow();
+    assert_eq!(next_action(&escrow, 1_050, OnExpiry::Dispute), None);
+    assert_eq!(next_action(&escrow, 1_050, OnExpiry::Refund), None);
+}
+
+#[test]
+fn refunds_as_soon_as_the_rental_period_ends() {
+    let escrow = funded_escrow();
+    assert_eq!(next_action(&escrow, 1_100, OnExpiry::Refund), Some(WatchAction::RefundAfterTimeout));
+}
+
+#[test]
+fn disputes_until_the_dispute_window_closes_then_refunds() {
+    let escrow = funded_escrow();
+    assert_eq!(next_action(&escrow, 1_100, OnExpiry::Dispute), Some(WatchAction::Dispute));
+    assert_eq!(next_action(&escrow, 1_600, OnExpiry::Dispute), Some(WatchAction::Dispute));
+    assert_eq!(next_action(&escrow, 1_601, OnExpiry::Dispute), Some(WatchAction::RefundAfterTimeout));
+}
+
+#[test]
+fn ignores_delivered_and_settled_escrows() {
+    let mut delivered = funded_escrow();
+    delivered.first_delivered_at = 1_050;
+    assert_eq!(next_action(&delivered, 10_000, OnExpiry::Refund), None);
+
+    let mut disputed = funded_escrow();
+    disputed.state = EscrowState::Disputed;
+    assert_eq!(next_action(&disputed, 10_000, OnExpiry::Refund), None);
+}
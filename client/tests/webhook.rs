@@ -0,0 +1,66 @@
+use anchor_lang::prelude::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use trustyclaw_client::webhook::{
+    sign, verify, ReplayGuard, WebhookError, MAX_WEBHOOK_AGE_SECONDS, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+
+const BODY: &[u8] = br#"{"escrow":"x","amount":25000000}"#;
+
+fn signed(signer: &Keypair, escrow: &Pubkey, timestamp: i64) -> Signature {
+    let headers = sign(signer, escrow, timestamp, BODY);
+    let header = |name| headers.iter().find(|(n, _)| *n == name).unwrap().1.clone();
+    assert_eq!(header(TIMESTAMP_HEADER), timestamp.to_string());
+    header(SIGNATURE_HEADER).parse().unwrap()
+}
+
+#[test]
+fn callbacks_verify_against_the_registered_signer() {
+    let signer = Keypair::new();
+    let escrow = Pubkey::new_unique();
+    let signature = signed(&signer, &escrow, 1_000);
+
+    assert_eq!(verify(&signer.pubkey(), &escrow, 1_000, BODY, &signature, 1_010), Ok(()));
+    assert_eq!(
+        verify(&Keypair::new().pubkey(), &escrow, 1_000, BODY, &signature, 1_010),
+        Err(WebhookError::BadSignature)
+    );
+    assert_eq!(
+        verify(&Pubkey::default(), &escrow, 1_000, BODY, &signature, 1_010),
+        Err(WebhookError::NoSigner)
+    );
+}
+
+#[test]
+fn signature_binds_escrow_timestamp_and_body() {
+    let signer = Keypair::new();
+    let escrow = Pubkey::new_unique();
+    let signature = signed(&signer, &escrow, 1_000);
+    let key = signer.pubkey();
+
+    assert_eq!(
+        verify(&key, &Pubkey::new_unique(), 1_000, BODY, &signature, 1_000),
+        Err(WebhookError::BadSignature)
+    );
+    assert_eq!(verify(&key, &escrow, 1_001, BODY, &signature, 1_000), Err(WebhookError::BadSignature));
+    assert_eq!(verify(&key, &escrow, 1_000, b"{}", &signature, 1_000), Err(WebhookError::BadSignature));
+}
+
+#[test]
+fn stale_and_replayed_callbacks_are_rejected() {
+    let signer = Keypair::new();
+    let escrow = Pubkey::new_unique();
+    let signature = signed(&signer, &escrow, 1_000);
+    let late = 1_000 + MAX_WEBHOOK_AGE_SECONDS + 1;
+
+    assert_eq!(
+        verify(&signer.pubkey(), &escrow, 1_000, BODY, &signature, late),
+        Err(WebhookError::Stale { timestamp: 1_000, now: late })
+    );
+
+    let mut guard = ReplayGuard::default();
+    assert_eq!(guard.accept(&signer.pubkey(), &escrow, 1_000, BODY, &signature, 1_005), Ok(()));
+    assert_eq!(
+        guard.accept(&signer.pubkey(), &escrow, 1_000, BODY, &signature, 1_006),
+        Err(WebhookError::Replayed)
+    );
+}
@@ -0,0 +1,201 @@
+//! Central activity log for TrustyClaw programs
+//!
+//! - Each agent has a `ActivityFeed` PDA holding its most recent records in a ring buffer
+//! - Escrow and reputation programs CPI `log_activity` on every state change, signing
+//!   with their `activity_authority` PDA
+//! - Every record is also emitted as an `ActivityLogged` event, so the full history
+//!   stays available to indexers after the ring wraps
+
+use anchor_lang::prelude::*;
+
+declare_id!("8XWNocACz8HucLAfh29DomEhnpgM1VPpHfg2rZZJckxs");
+
+pub const FEED_SEED: &[u8] = b"activity_feed";
+/// Seed of the signer PDA each source program uses when calling `log_activity`
+pub const ACTIVITY_AUTHORITY_SEED: &[u8] = b"activity_authority";
+
+/// Records kept on-chain per feed before the oldest is overwritten
+pub const FEED_CAPACITY: usize = 32;
+
+pub const ESCROW_PROGRAM_ID: Pubkey = pubkey!("8uBMA8S33eGFMRA677Y1gPvmnBGUjFtdwxf2A8JufpA3");
+pub const REPUTATION_PROGRAM_ID: Pubkey = pubkey!("J9X4dDqyFL2pG3MZJn4WEEK3Mcku9nG8XJcEo8zB9z2");
+
+#[program]
+pub mod activity_log {
+    use super::*;
+
+    /// Create the activity feed for an agent (permissionless; payer covers rent)
+    pub fn init_feed(ctx: Context<InitFeed>, agent: Pubkey) -> Result<()> {
+        let feed = &mut ctx.accounts.feed;
+        feed.agent = agent;
+        feed.next_seq = 0;
+        feed.records = Vec::new();
+        feed.bump = ctx.bumps.feed;
+        Ok(())
+    }
+
+    /// Append a record to an agent's feed. Only callable by a TrustyClaw program
+    /// through its `activity_authority` PDA.
+    pub fn log_activity(
+        ctx: Context<LogActivity>,
+        kind: ActivityKind,
+        subject: Pubkey,
+        counterparty: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let source = ActivitySource::from_authority(&ctx.accounts.authority.key())
+            .ok_or(ActivityLogError::UnknownSource)?;
+        let clock = Clock::get()?;
+        let feed = &mut ctx.accounts.feed;
+
+        let record = ActivityRecord {
+            seq: feed.next_seq,
+            timestamp: clock.unix_timestamp,
+            slot: clock.slot,
+            source,
+            kind,
+            subject,
+            counterparty,
+            amount,
+        };
+        if feed.records.len() < FEED_CAPACITY {
+            feed.records.push(record.clone());
+        } else {
+            let slot = (feed.next_seq % FEED_CAPACITY as u64) as usize;
+            feed.records[slot] = record.clone();
+        }
+        feed.next_seq += 1;
+
+        emit!(ActivityLogged {
+            agent: feed.agent,
+            record,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(agent: Pubkey)]
+pub struct InitFeed<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = ActivityFeed::LEN,
+        seeds = [FEED_SEED, agent.as_ref()],
+        bump
+    )]
+    pub feed: Account<'info, ActivityFeed>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LogActivity<'info> {
+    #[account(
+        mut,
+        seeds = [FEED_SEED, feed.agent.as_ref()],
+        bump = feed.bump
+    )]
+    pub feed: Account<'info, ActivityFeed>,
+    pub authority: Signer<'info>,
+}
+
+#[account]
+pub struct ActivityFeed {
+    pub agent: Pubkey,
+    /// Sequence number of the next record; records are ordered by `seq`
+    pub next_seq: u64,
+    pub records: Vec<ActivityRecord>,
+    pub bump: u8,
+}
+
+impl ActivityFeed {
+    /// 8 + 32 + 8 + (4 + 32 * 100) + 1
+    pub const LEN: usize = 8 + 32 + 8 + 4 + FEED_CAPACITY * ActivityRecord::LEN + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ActivityRecord {
+    pub seq: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+    pub source: ActivitySource,
+    pub kind: ActivityKind,
+    /// Escrow, review or agent account the record is about
+    pub subject: Pubkey,
+    pub counterparty: Pubkey,
+    pub amount: u64,
+}
+
+impl ActivityRecord {
+    /// 8 + 8 + 8 + 1 + 1 + 32 + 32 + 8 (+2 spare for enum growth)
+    pub const LEN: usize = 8 + 8 + 8 + 1 + 1 + 32 + 32 + 8 + 2;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ActivitySource {
+    Escrow,
+    Reputation,
+}
+
+impl ActivitySource {
+    /// Map a signer to the program whose `activity_authority` PDA it is
+    pub fn from_authority(authority: &Pubkey) -> Option<Self> {
+        let is_authority_of = |program_id: &Pubkey| {
+            Pubkey::find_program_address(&[ACTIVITY_AUTHORITY_SEED], program_id).0 == *authority
+        };
+        if is_authority_of(&ESCROW_PROGRAM_ID) {
+            Some(Self::Escrow)
+        } else if is_authority_of(&REPUTATION_PROGRAM_ID) {
+            Some(Self::Reputation)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ActivityKind {
+    EscrowInitialized,
+    EscrowTermsUpdated,
+    EscrowFunded,
+    DeliverableSubmitted,
+    EscrowReleased,
+    EscrowRefunded,
+    EscrowDisputed,
+    DisputeResolved,
+    ProviderWoundDown,
+    CreditsWithdrawn,
+    AgentRegistered,
+    AgentDeactivated,
+    ReviewAdded,
+    ReputationUpdated,
+    ExternalReputationImported,
+    SlaBreachReported,
+    AccessCredentialCommitted,
+    RevisionRequested,
+    ListingExpired,
+    VestedPayoutClaimed,
+    ListingDeprecated,
+    MilestoneAdded,
+    MilestoneApproved,
+    MilestoneReleased,
+    ListingCancelled,
+    EscrowExpedited,
+    CancelProposed,
+    StreamedPayoutClaimed,
+}
+
+#[event]
+pub struct ActivityLogged {
+    pub agent: Pubkey,
+    pub record: ActivityRecord,
+}
+
+#[error_code]
+pub enum ActivityLogError {
+    #[msg("Signer is not the activity authority of a TrustyClaw program")]
+    UnknownSource,
+}
@@ -4,275 +4,10452 @@
 //! - Renter accepts and funds (USDC locked)
 //! - Task completes → funds released to provider
 //! - Cancel → funds refunded to renter
+//! - Dispute → arbiter releases to provider or refunds renter, with commit-reveal randomness for panel selection
+//! - Optional SLA terms; verifier-attested breaches refund part of the payment at release
+//! - Providers commit a hash of each issued access credential, so access disputes can be checked on-chain
+//! - Nothing delivered by the end of the dispute window → renter (or their watchtower) claims a refund
+//! - Every state change can be mirrored into the provider's activity-log feed
+//! - Listings can relax upfront funding for renters with a high reputation score
+//! - Optional compliance screening: large releases wait for a screener or a delay
+//! - Settlement channels net many rentals between two agents into periodic transfers
+//! - Cargo features (`disputes`, `oracle`, ...) select instruction sets per deployment; see `Config::features`
+//! - Micro-rentals can settle into a per-provider credit ledger, withdrawn in batches
+//! - Devnet builds can include a demo-token faucet (`devnet-demo`) for integrators
 
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use activity_log::program::ActivityLog;
+use activity_log::{ActivityKind, ACTIVITY_AUTHORITY_SEED};
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_interface::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 declare_id!("8uBMA8S33eGFMRA677Y1gPvmnBGUjFtdwxf2A8JufpA3");
 
-const ESCROW_SEED: &[u8] = b"escrow";
+pub const ESCROW_SEED: &[u8] = b"escrow";
+pub const PROVIDER_INDEX_SEED: &[u8] = b"provider_index";
+pub const CREDIT_SEED: &[u8] = b"credit";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const POINTS_SEED: &[u8] = b"points";
+pub const RANDOMNESS_SEED: &[u8] = b"dispute_randomness";
+pub const PROVIDER_STATS_SEED: &[u8] = b"provider_stats";
+pub const CHANNEL_SEED: &[u8] = b"channel";
+pub const RENTAL_REQUEST_SEED: &[u8] = b"rental_request";
+pub const INVOICE_SEED: &[u8] = b"invoice";
+pub const ARBITER_STATS_SEED: &[u8] = b"arbiter_stats";
+pub const MILESTONES_SEED: &[u8] = b"milestones";
+pub const TERMS_LOCALIZATION_SEED: &[u8] = b"terms_localization";
+pub const SKILL_LISTING_SEED: &[u8] = b"skill_listing";
+pub const DEMO_MINT_SEED: &[u8] = b"demo_mint";
+pub const EVIDENCE_SEED: &[u8] = b"dispute_evidence";
+pub const ENGAGEMENT_SEED: &[u8] = b"engagement";
+pub const DECLARATION_SEED: &[u8] = b"arbiter_declaration";
+pub const COUNCIL_SEED: &[u8] = b"arbiter_council";
+pub const PROPOSAL_SEED: &[u8] = b"resolution_proposal";
+pub const RECOVERY_SEED: &[u8] = b"recovery";
+pub const EPOCH_STATS_SEED: &[u8] = b"epoch_stats";
+pub const EPOCH_SNAPSHOT_SEED: &[u8] = b"epoch_snapshot";
+pub const BOND_VAULT_SEED: &[u8] = b"bond_vault";
+pub const SUBSCRIPTION_SEED: &[u8] = b"subscription";
+pub const ACCEPTANCE_SEED: &[u8] = b"terms_acceptance";
+/// Optional instruction sets, selected per deployment with cargo features of the same
+/// name and recorded in `Config::features`. `streaming` gates `claim_streamed`.
+pub const FEATURE_FEES: u8 = 1 << 0;
+pub const FEATURE_DISPUTES: u8 = 1 << 1;
+pub const FEATURE_STREAMING: u8 = 1 << 2;
+pub const FEATURE_ORACLE: u8 = 1 << 3;
+pub const FEATURE_DEVNET_DEMO: u8 = 1 << 4;
+pub const COMPILED_FEATURES: u8 = (if cfg!(feature = "fees") { FEATURE_FEES } else { 0 })
+    | (if cfg!(feature = "disputes") { FEATURE_DISPUTES } else { 0 })
+    | (if cfg!(feature = "streaming") { FEATURE_STREAMING } else { 0 })
+    | (if cfg!(feature = "oracle") { FEATURE_ORACLE } else { 0 })
+    | (if cfg!(feature = "devnet-demo") { FEATURE_DEVNET_DEMO } else { 0 });
+
+/// Decimals of the `devnet-demo` mint, matching USDC
+pub const DEMO_MINT_DECIMALS: u8 = 6;
+/// Most demo tokens one `airdrop_demo_tokens` call mints, in base units
+pub const DEMO_AIRDROP_MAX: u64 = 10_000 * 10u64.pow(DEMO_MINT_DECIMALS as u32);
+
+/// Escrow ids with this bit set belong to rentals opened against a `SkillListing`; see
+/// `rental_escrow_id`. Providers cannot initialize escrows in that range themselves.
+pub const RENTAL_ESCROW_ID_FLAG: u64 = 1 << 63;
+/// `EscrowAccount::renter` of a listing nobody has funded yet
+pub const UNASSIGNED_RENTER: Pubkey = Pubkey::new_from_array([0u8; 32]);
+/// Domain separator for signed channel balance updates
+pub const CHANNEL_MESSAGE_PREFIX: &[u8] = b"trustyclaw-channel-v1";
+/// Domain separator for escrow snapshot commitments consumed on other chains
+pub const SNAPSHOT_MESSAGE_PREFIX: &[u8] = b"trustyclaw-escrow-snapshot-v1";
+/// First line of the text a renter's wallet signs to accept an escrow's terms
+pub const ACCEPTANCE_MESSAGE_PREFIX: &[u8] = b"trustyclaw-acceptance-v1";
+/// Time the payee has to submit its latest update after the payer asks to close
+pub const CHANNEL_CLOSE_DELAY_SECONDS: i64 = 24 * 60 * 60;
+/// Time a provider has to accept a renter's request before anyone may return the deposit
+pub const PROVIDER_ACCEPTANCE_WINDOW_SECONDS: i64 = 24 * 60 * 60;
+/// Time the counterparty has to `accept_cancel` a proposed mutual cancellation
+pub const CANCEL_PROPOSAL_SECONDS: i64 = 24 * 60 * 60;
+/// Delay between the admin queueing a recovery action and being able to execute it,
+/// giving the parties time to see it coming and settle on their own
+pub const RECOVERY_TIMELOCK_SECONDS: i64 = 3 * 24 * 60 * 60;
+/// After declaring no conflict, an arbiter who starts renting with either party within
+/// this long can be reported
+pub const CONFLICT_COOLING_OFF_SECONDS: i64 = 90 * 24 * 60 * 60;
+/// Resubmissions allowed after the first deliverable, each answering a `request_revision`
+pub const MAX_DELIVERABLE_REVISIONS: u16 = 5;
+/// Commit phase length, measured from `disputed_at`
+pub const RANDOMNESS_COMMIT_SECONDS: i64 = 24 * 60 * 60;
+/// Reveal phase length, following the commit phase
+pub const RANDOMNESS_REVEAL_SECONDS: i64 = 24 * 60 * 60;
+/// Milestones one `MilestonePlan` can hold; its account is allocated for all of them
+pub const MAX_MILESTONES: usize = 8;
+/// Evidence entries one `DisputeEvidence` can hold per dispute; allocated for all of them
+pub const MAX_EVIDENCE_ENTRIES: usize = 8;
+/// Capacity of `EvidenceEntry::uri`, in bytes
+pub const MAX_EVIDENCE_URI_LEN: usize = 200;
+/// Members one `ArbiterCouncil` can hold; `ResolutionProposal::approvals` has a bit for each
+pub const MAX_COUNCIL_MEMBERS: usize = 9;
+/// How long an approved council proposal stands before a different ruling may replace it
+pub const RESOLUTION_PROPOSAL_SECONDS: i64 = 24 * 60 * 60;
+/// Languages one `TermsLocalization` can list
+pub const MAX_LOCALIZATIONS: usize = 8;
+/// Longest accepted BCP 47 language tag, e.g. `zh-Hant-TW`
+pub const MAX_LANGUAGE_TAG_LEN: usize = 16;
+pub const MAX_LOCALIZED_URI_LEN: usize = 200;
+/// Longest `deliverable_uri` a provider may publish with a submission
+pub const MAX_DELIVERABLE_URI_LEN: usize = 200;
+/// Highest price of a credit-settled listing, in whole tokens of its mint. Credit
+/// settlement skips the protocol fee, so it is kept to micro-rentals.
+pub const MAX_CREDIT_PRICE_TOKENS: u64 = 1;
+/// Zeroed bytes at the end of every account layout, from `EscrowAccount` on.
+///
+/// A new field is carved out of the front of the reserve and the reserve shrinks by
+/// the same amount, keeping `LEN` unchanged. Carved fields must read zero as "unset",
+/// since existing accounts hold zeros there. Only once a release needs more than is
+/// left does the layout grow again, starting a fresh reserve; live escrows then grow
+/// into it through `migrate_escrow`.
+pub const ACCOUNT_RESERVE: usize = 64;
+/// What is left of the reserve on `EscrowAccount`, after the fields carved out of it
+/// from `sunset_at` on
+pub const ESCROW_RESERVE: usize = ACCOUNT_RESERVE - 8 - 8 - 8 - 2 - 8 - 1 - 8 - 8 - 8 - 1 - 1 - 1 - 1 - 1;
+/// `EscrowAccount::version` of accounts written by this release
+pub const ESCROW_VERSION: u8 = layout::EscrowLayout::CURRENT as u8;
+/// What is left of the reserve on `ArbiterStats` after `conflicts`
+pub const ARBITER_STATS_RESERVE: usize = ACCOUNT_RESERVE - 2;
+const MAX_EMISSION_TIERS: usize = 4;
+/// Capacity of `Config::allowed_mints`
+pub const MAX_ALLOWED_MINTS: usize = 8;
+const MAX_KEY_CIPHERTEXT_LEN: usize = 512;
+const BPS_DENOMINATOR: u16 = 10_000;
+/// Highest protocol fee the admin can set, 10% of a provider payout
+pub const MAX_FEE_BPS: u16 = 1_000;
+const MAX_CATEGORY_LEN: usize = 32;
+/// Capacity of `EscrowTerms::skill_name`, in bytes
+pub const MAX_SKILL_NAME_LEN: usize = 64;
+/// Capacity of `EscrowTerms::metadata_uri`, in bytes
+pub const MAX_METADATA_URI_LEN: usize = 256;
+/// Pyth receiver program owning `PriceUpdateV2` accounts
+#[cfg(feature = "oracle")]
+const PYTH_RECEIVER_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// Reputation program owning renters' `Agent` accounts
+const REPUTATION_PROGRAM_ID: Pubkey = pubkey!("J9X4dDqyFL2pG3MZJn4WEEK3Mcku9nG8XJcEo8zB9z2");
+
+/// Run `$call` if the program was built with `$feature`, else fail with `FeatureDisabled`
+macro_rules! gated {
+    ($feature:literal, $handler:ident :: $name:ident ( $($arg:expr),* )) => {{
+        #[cfg(feature = $feature)]
+        {
+            $handler::$name($($arg),*)
+        }
+        #[cfg(not(feature = $feature))]
+        {
+            let _ = ($($arg),*);
+            err!(EscrowError::FeatureDisabled)
+        }
+    }};
+}
 
 #[program]
 pub mod escrow {
     use super::*;
 
-    /// Initialize a new escrow for a skill rental
-    pub fn initialize_escrow(ctx: Context<InitializeEscrow>, terms: EscrowTerms) -> Result<()> {
+    /// Initialize a new escrow for a skill rental. Each `escrow_id` gives the provider a
+    /// separate escrow, so several rentals can run at once.
+    pub fn initialize_escrow(ctx: Context<InitializeEscrow>, terms: EscrowTerms, escrow_id: u64) -> Result<()> {
         require!(
             ctx.accounts.escrow_account.state == EscrowState::Created
                 || ctx.accounts.escrow_account.state == EscrowState::default(),
             EscrowError::InvalidState
         );
+        check_initialize(&terms, escrow_id, &ctx.accounts.config, ctx.accounts.token_mint.decimals)?;
         let escrow = &mut ctx.accounts.escrow_account;
 
         escrow.provider = ctx.accounts.provider.key();
-        escrow.renter = Pubkey::default();
+        escrow.escrow_id = escrow_id;
+        escrow.renter = UNASSIGNED_RENTER;
         escrow.token_mint = ctx.accounts.token_mint.key();
         escrow.provider_token_account = ctx.accounts.provider_token_account.key();
+        escrow.terms_hash = EscrowAccount::compute_terms_hash(&terms, &escrow.token_mint)?;
         escrow.terms = terms;
         escrow.state = EscrowState::Created;
         escrow.created_at = Clock::get()?.unix_timestamp;
+        escrow.version = ESCROW_VERSION;
+
+        let index = &mut ctx.accounts.provider_index;
+        if index.provider == Pubkey::default() {
+            index.provider = ctx.accounts.provider.key();
+            index.bump = ctx.bumps.provider_index;
+        }
+
+        let stats = &mut ctx.accounts.provider_stats;
+        if stats.provider == Pubkey::default() {
+            stats.provider = ctx.accounts.provider.key();
+            stats.bump = ctx.bumps.provider_stats;
+        }
+        stats.record_initialization(Clock::get()?.epoch, ctx.accounts.config.max_initializations_per_epoch)?;
+        ctx.accounts.epoch_stats.record_created();
+
+        let escrow = &ctx.accounts.escrow_account;
+        emit!(EscrowInitialized {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            escrow_id: escrow.escrow_id,
+            token_mint: escrow.token_mint,
+            skill_name: escrow.terms.skill_name.as_str().to_string(),
+            category: escrow.terms.category.clone(),
+            price_usdc: escrow.terms.price_usdc,
+            metadata_uri_hash: hash(escrow.terms.metadata_uri.as_str().as_bytes()).to_bytes(),
+            terms_hash: escrow.terms_hash,
+            created_at: escrow.created_at,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowInitialized,
+            ctx.accounts.escrow_account.key(),
+            Pubkey::default(),
+            ctx.accounts.escrow_account.terms.price_usdc,
+        )?;
+
+        Ok(())
+    }
+
+    /// Provider revises the terms of an unfunded listing; the terms hash is recomputed,
+    /// so renters who reviewed the old terms can no longer fund against them
+    pub fn update_terms(ctx: Context<UpdateTerms>, terms: EscrowTerms) -> Result<()> {
+        require!(ctx.accounts.escrow_account.state == EscrowState::Created, EscrowError::InvalidState);
+        // The mint cannot change, so neither can the precision it was checked against
+        check_terms(&terms, &ctx.accounts.config, ctx.accounts.escrow_account.terms.price_decimals)?;
+        let escrow = &mut ctx.accounts.escrow_account;
+        // The bond vault holds the old amount until `release_bond` returns it
+        require!(
+            !escrow.bond_held || terms.provider_bond == escrow.terms.provider_bond,
+            EscrowError::BondAlreadyFunded
+        );
+        escrow.terms_hash = EscrowAccount::compute_terms_hash(&terms, &escrow.token_mint)?;
+        escrow.terms = terms;
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowTermsUpdated,
+            ctx.accounts.escrow_account.key(),
+            Pubkey::default(),
+            ctx.accounts.escrow_account.terms.price_usdc,
+        )?;
 
         Ok(())
     }
 
-    /// Accept escrow and fund it (USDC transferred from renter to escrow ATA)
-    pub fn accept_escrow(ctx: Context<AcceptEscrow>, amount: u64) -> Result<()> {
+    /// Reprice a listing that has not been funded yet. The terms hash changes with the
+    /// price, so renters must acknowledge the new one when funding.
+    pub fn update_price(ctx: Context<UpdateTerms>, price_usdc: u64) -> Result<()> {
         require!(ctx.accounts.escrow_account.state == EscrowState::Created, EscrowError::InvalidState);
         let escrow = &mut ctx.accounts.escrow_account;
-        escrow.renter = ctx.accounts.renter.key();
-        escrow.amount = amount;
-        escrow.state = EscrowState::Funded;
+        let previous = escrow.terms.price_usdc;
+        escrow.terms.price_usdc = price_usdc;
+        require!(
+            escrow.terms.trial_is_valid(ctx.accounts.config.trial_max_amount),
+            EscrowError::InvalidTrialTerms
+        );
+        escrow.terms_hash = EscrowAccount::compute_terms_hash(&escrow.terms, &escrow.token_mint)?;
+
+        emit!(PriceUpdated {
+            escrow: escrow.key(),
+            previous,
+            price_usdc,
+            terms_hash: escrow.terms_hash,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowTermsUpdated,
+            ctx.accounts.escrow_account.key(),
+            Pubkey::default(),
+            price_usdc,
+        )?;
+
+        Ok(())
+    }
+
+    /// Accept escrow and fund it (USDC transferred from renter to escrow ATA).
+    /// `terms_hash` is the renter's acknowledgment of the exact terms being funded.
+    pub fn accept_escrow(
+        ctx: Context<AcceptEscrow>,
+        amount: u64,
+        overrides: FundOverrides,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .escrow_account
+            .check_fundable(&terms_hash, false, Clock::get()?.unix_timestamp)?;
+
+        // Enforce the listing's concurrency cap across all of the provider's rentals
+        let max_concurrent = ctx.accounts.provider_stats.concurrency_limit(
+            ctx.accounts.escrow_account.terms.max_concurrent_rentals,
+            ctx.accounts.config.failure_streak_threshold,
+        );
+        ctx.accounts.provider_index.reserve_slot(max_concurrent)?;
 
-        let cpi_accounts = Transfer {
+        let score = match &ctx.accounts.renter_reputation {
+            Some(agent) => Some(read_reputation_score(agent, &ctx.accounts.renter.key())?),
+            None => None,
+        };
+        let renter = ctx.accounts.renter.key();
+        let received = received_amount(&ctx.accounts.token_mint, amount)?;
+        fund_escrow(&mut ctx.accounts.escrow_account, renter, amount, received, &overrides, score)?;
+        ctx.accounts.epoch_stats.record_funding(received);
+        let provider = ctx.accounts.escrow_account.provider;
+        let funded_at = ctx.accounts.escrow_account.funded_at;
+        ctx.accounts
+            .engagement
+            .record(provider, renter, ctx.bumps.engagement, funded_at);
+
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.renter_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
             to: ctx.accounts.escrow_token_account.to_account_info(),
             authority: ctx.accounts.renter.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        emit!(EscrowFunded {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider: ctx.accounts.escrow_account.provider,
+            escrow_id: ctx.accounts.escrow_account.escrow_id,
+            renter: ctx.accounts.escrow_account.renter,
+            token_mint: ctx.accounts.escrow_account.token_mint,
+            amount: ctx.accounts.escrow_account.amount,
+            funded_at: ctx.accounts.escrow_account.funded_at,
+            settle_in_credits: ctx.accounts.escrow_account.terms.settle_in_credits,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowFunded,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            ctx.accounts.escrow_account.amount,
+        )?;
 
         Ok(())
     }
 
-    /// Complete task and release USDC to provider
-    pub fn complete_task(ctx: Context<CompleteTask>) -> Result<()> {
-        require!(ctx.accounts.escrow_account.state == EscrowState::Funded, EscrowError::InvalidState);
-        let escrow = &mut ctx.accounts.escrow_account;
-        escrow.state = EscrowState::Completed;
-        escrow.completed_at = Clock::get()?.unix_timestamp;
-        let amount = escrow.amount;
-        let provider = escrow.provider;
+    /// Fund a wrapped-SOL listing straight from the renter's lamports, for renters that
+    /// hold no tokens. `amount` lamports are moved into the escrow's vault and synced,
+    /// so the escrow then settles like any other; release and refund unwrap the payout
+    /// when given the recipient's wallet.
+    pub fn fund_with_sol(
+        ctx: Context<FundWithSol>,
+        amount: u64,
+        overrides: FundOverrides,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .escrow_account
+            .check_fundable(&terms_hash, false, Clock::get()?.unix_timestamp)?;
 
-        let seeds = &[ESCROW_SEED, provider.as_ref(), &[ctx.bumps.escrow_account]];
-        let signer = &[&seeds[..]];
+        let max_concurrent = ctx.accounts.provider_stats.concurrency_limit(
+            ctx.accounts.escrow_account.terms.max_concurrent_rentals,
+            ctx.accounts.config.failure_streak_threshold,
+        );
+        ctx.accounts.provider_index.reserve_slot(max_concurrent)?;
 
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.escrow_token_account.to_account_info(),
-            to: ctx.accounts.provider_token_account.to_account_info(),
-            authority: ctx.accounts.escrow_account.to_account_info(),
+        let score = match &ctx.accounts.renter_reputation {
+            Some(agent) => Some(read_reputation_score(agent, &ctx.accounts.renter.key())?),
+            None => None,
+        };
+        let renter = ctx.accounts.renter.key();
+        // Wrapped SOL has no transfer fee, so the vault is credited in full
+        fund_escrow(&mut ctx.accounts.escrow_account, renter, amount, amount, &overrides, score)?;
+        ctx.accounts.epoch_stats.record_funding(amount);
+        let provider = ctx.accounts.escrow_account.provider;
+        let funded_at = ctx.accounts.escrow_account.funded_at;
+        ctx.accounts
+            .engagement
+            .record(provider, renter, ctx.bumps.engagement, funded_at);
+
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.renter.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+        let cpi_accounts = token_interface::SyncNative {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+        };
+        token_interface::sync_native(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts))?;
+
+        emit!(EscrowFunded {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider: ctx.accounts.escrow_account.provider,
+            escrow_id: ctx.accounts.escrow_account.escrow_id,
+            renter: ctx.accounts.escrow_account.renter,
+            token_mint: ctx.accounts.escrow_account.token_mint,
+            amount: ctx.accounts.escrow_account.amount,
+            funded_at: ctx.accounts.escrow_account.funded_at,
+            settle_in_credits: ctx.accounts.escrow_account.terms.settle_in_credits,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowFunded,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            ctx.accounts.escrow_account.amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Renter asks to rent a listing that `requires_provider_acceptance`. The deposit is
+    /// held in the request's vault until the provider accepts this renter; otherwise it
+    /// can be withdrawn, and after `PROVIDER_ACCEPTANCE_WINDOW_SECONDS` anyone may return it.
+    pub fn request_rental(
+        ctx: Context<RequestRental>,
+        amount: u64,
+        overrides: FundOverrides,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Created, EscrowError::InvalidState);
+        require!(escrow.terms_hash == terms_hash, EscrowError::TermsHashMismatch);
+        require!(escrow.terms.requires_provider_acceptance, EscrowError::ProviderAcceptanceNotRequired);
+        require!(escrow.funding_open(Clock::get()?.unix_timestamp), EscrowError::ListingExpired);
+        // Reject overrides the provider's policy would refuse at acceptance
+        apply_fund_overrides(&mut (**escrow).clone(), &overrides)?;
+
+        let reputation_score = match &ctx.accounts.renter_reputation {
+            Some(agent) => Some(read_reputation_score(agent, &ctx.accounts.renter.key())?),
+            None => None,
+        };
+        let request = &mut ctx.accounts.rental_request;
+        request.escrow = ctx.accounts.escrow_account.key();
+        request.renter = ctx.accounts.renter.key();
+        request.amount = amount;
+        request.overrides = overrides;
+        request.terms_hash = terms_hash;
+        request.reputation_score = reputation_score;
+        request.requested_at = Clock::get()?.unix_timestamp;
+        request.bump = ctx.bumps.rental_request;
+        let escrow = &mut ctx.accounts.escrow_account;
+        escrow.pending_requests = escrow.pending_requests.saturating_add(1);
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.renter_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.request_vault.to_account_info(),
+            authority: ctx.accounts.renter.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        token::transfer(
-            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
             amount,
+            ctx.accounts.token_mint.decimals,
         )?;
 
+        emit!(RentalRequested {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider: ctx.accounts.escrow_account.provider,
+            renter: ctx.accounts.renter.key(),
+            amount,
+            expires_at: ctx.accounts.rental_request.expires_at(),
+        });
         Ok(())
     }
 
-    /// Cancel escrow and refund USDC to renter
-    pub fn cancel_escrow(ctx: Context<CancelEscrow>) -> Result<()> {
-        require!(ctx.accounts.escrow_account.state == EscrowState::Funded, EscrowError::InvalidState);
+    /// Provider accepts a pending request within the acceptance window, funding the
+    /// escrow from the request's deposit
+    pub fn accept_renter(ctx: Context<AcceptRenter>) -> Result<()> {
+        let request = &ctx.accounts.rental_request;
+        require!(ctx.accounts.escrow_account.state == EscrowState::Created, EscrowError::InvalidState);
+        require!(
+            Clock::get()?.unix_timestamp < request.expires_at(),
+            EscrowError::AcceptanceWindowClosed
+        );
+        // Terms edited after the request are not what the renter agreed to
+        require!(ctx.accounts.escrow_account.terms_hash == request.terms_hash, EscrowError::TermsHashMismatch);
+
+        let max_concurrent = ctx.accounts.provider_stats.concurrency_limit(
+            ctx.accounts.escrow_account.terms.max_concurrent_rentals,
+            ctx.accounts.config.failure_streak_threshold,
+        );
+        ctx.accounts.provider_index.reserve_slot(max_concurrent)?;
+
+        let (renter, amount, overrides, score) =
+            (request.renter, request.amount, request.overrides.clone(), request.reputation_score);
+        // The deposit may already have lost a transfer fee on its way into the request
+        // vault, and loses another on its way to the escrow
+        let deposit = ctx.accounts.request_vault.amount;
+        let received = received_amount(&ctx.accounts.token_mint, deposit)?;
         let escrow = &mut ctx.accounts.escrow_account;
-        escrow.state = EscrowState::Cancelled;
-        escrow.cancelled_at = Clock::get()?.unix_timestamp;
-        let amount = escrow.amount;
-        let provider = escrow.provider;
+        escrow.pending_requests = escrow.pending_requests.saturating_sub(1);
+        fund_escrow(escrow, renter, amount, received, &overrides, score)?;
+        ctx.accounts.epoch_stats.record_funding(received);
+        let (provider, funded_at) = (escrow.provider, escrow.funded_at);
+        ctx.accounts
+            .engagement
+            .record(provider, renter, ctx.bumps.engagement, funded_at);
 
-        let seeds = &[ESCROW_SEED, provider.as_ref(), &[ctx.bumps.escrow_account]];
+        let escrow_key = ctx.accounts.escrow_account.key();
+        let seeds = &[
+            RENTAL_REQUEST_SEED,
+            escrow_key.as_ref(),
+            renter.as_ref(),
+            &[ctx.accounts.rental_request.bump],
+        ];
         let signer = &[&seeds[..]];
-
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.escrow_token_account.to_account_info(),
-            to: ctx.accounts.renter_token_account.to_account_info(),
-            authority: ctx.accounts.escrow_account.to_account_info(),
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.request_vault.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.rental_request.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer),
+            deposit,
+            ctx.accounts.token_mint.decimals,
+        )?;
+        harvest_withheld_fees(
+            cpi_program.clone(),
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.request_vault.to_account_info(),
+        )?;
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.request_vault.to_account_info(),
+            destination: ctx.accounts.renter.to_account_info(),
+            authority: ctx.accounts.rental_request.to_account_info(),
         };
+        token_interface::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        emit!(EscrowFunded {
+            escrow: escrow_key,
+            provider: ctx.accounts.escrow_account.provider,
+            escrow_id: ctx.accounts.escrow_account.escrow_id,
+            renter,
+            token_mint: ctx.accounts.escrow_account.token_mint,
+            amount: received,
+            funded_at: ctx.accounts.escrow_account.funded_at,
+            settle_in_credits: false,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowFunded,
+            escrow_key,
+            renter,
+            received,
+        )?;
+
+        Ok(())
+    }
+
+    /// Return a pending request's deposit to the renter: the renter withdrawing or the
+    /// provider declining at any time, or anyone once the acceptance window has closed
+    pub fn withdraw_rental_request(ctx: Context<WithdrawRentalRequest>) -> Result<()> {
+        let request = &ctx.accounts.rental_request;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            request.can_withdraw(&ctx.accounts.authority.key(), &ctx.accounts.escrow_account.provider, now),
+            EscrowError::Unauthorized
+        );
+        let escrow = &mut ctx.accounts.escrow_account;
+        escrow.pending_requests = escrow.pending_requests.saturating_sub(1);
+
+        let amount = ctx.accounts.request_vault.amount;
+        let escrow_key = ctx.accounts.escrow_account.key();
+        let renter = request.renter;
+        let seeds = &[RENTAL_REQUEST_SEED, escrow_key.as_ref(), renter.as_ref(), &[request.bump]];
+        let signer = &[&seeds[..]];
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        token::transfer(
-            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
-            amount,
+        if amount > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.request_vault.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.renter_token_account.to_account_info(),
+                authority: ctx.accounts.rental_request.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer),
+                amount,
+                ctx.accounts.token_mint.decimals,
+            )?;
+        }
+        harvest_withheld_fees(
+            cpi_program.clone(),
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.request_vault.to_account_info(),
         )?;
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.request_vault.to_account_info(),
+            destination: ctx.accounts.renter.to_account_info(),
+            authority: ctx.accounts.rental_request.to_account_info(),
+        };
+        token_interface::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
 
+        emit!(RentalRequestWithdrawn {
+            escrow: escrow_key,
+            renter,
+            amount,
+            by: ctx.accounts.authority.key(),
+        });
         Ok(())
     }
 
-    /// Check if escrow has timed out
-    pub fn check_timeout(ctx: Context<CheckTimeout>) -> Result<bool> {
+    /// Provider submits proof of delivery: the deliverable hash and where it can be fetched,
+    /// optionally committing to an encrypted decryption key. Release is refused until the
+    /// first submission, and accepts whichever one is latest.
+    pub fn submit_deliverable(
+        ctx: Context<SubmitDeliverable>,
+        deliverable_hash: [u8; 32],
+        deliverable_uri: String,
+        encrypted_key_hash: [u8; 32],
+    ) -> Result<()> {
         require!(ctx.accounts.escrow_account.state == EscrowState::Funded, EscrowError::InvalidState);
-        let escrow = &ctx.accounts.escrow_account;
+        require!(deliverable_uri.len() <= MAX_DELIVERABLE_URI_LEN, EscrowError::DeliverableUriTooLong);
+        let escrow = &mut ctx.accounts.escrow_account;
         let now = Clock::get()?.unix_timestamp;
-        Ok(now >= escrow.created_at + escrow.terms.duration_seconds)
+        // Resubmissions only answer a pending revision request
+        if escrow.first_delivered_at == 0 {
+            escrow.first_delivered_at = now;
+        } else {
+            require!(escrow.revision_requested_at > 0, EscrowError::RevisionNotRequested);
+            escrow.revisions = escrow.revisions.saturating_add(1);
+            escrow.revision_requested_at = 0;
+        }
+        let previous_chain = escrow.deliverable_chain;
+        escrow.deliverable_chain = EscrowAccount::chain_deliverable(&previous_chain, &deliverable_hash, &encrypted_key_hash);
+        escrow.deliverable_hash = deliverable_hash;
+        escrow.encrypted_key_hash = encrypted_key_hash;
+        escrow.delivered_at = now;
+
+        emit!(DeliverableSubmitted {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            revision: escrow.revisions,
+            deliverable_hash,
+            deliverable_uri,
+            previous_chain,
+            chain: escrow.deliverable_chain,
+            submitted_at: now,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::DeliverableSubmitted,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            0,
+        )?;
+
+        Ok(())
     }
-}
 
-// ========== Account Structures ==========
+    /// Renter asks for another revision of the delivered work, within the review window.
+    /// `notes_hash` commits to the off-chain feedback the provider is answering.
+    pub fn request_revision(ctx: Context<RequestRevision>, notes_hash: [u8; 32]) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(escrow.first_delivered_at > 0 && escrow.revision_requested_at == 0, EscrowError::InvalidState);
+        require!(escrow.revisions < MAX_DELIVERABLE_REVISIONS, EscrowError::TooManyRevisions);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= escrow.dispute_deadline(), EscrowError::DisputeWindowClosed);
 
-#[account]
-pub struct EscrowAccount {
-    pub provider: Pubkey,
-    pub renter: Pubkey,
-    pub token_mint: Pubkey,
-    pub provider_token_account: Pubkey,
-    pub escrow_token_account: Pubkey,
-    pub terms: EscrowTerms,
-    pub state: EscrowState,
-    pub amount: u64,
-    pub created_at: i64,
-    pub completed_at: i64,
-    pub cancelled_at: i64,
-}
+        escrow.revision_requested_at = now;
+        escrow.revision_notes_hash = notes_hash;
 
-impl EscrowAccount {
-    pub const LEN: usize = 8 + 32 * 5 + 8 + 64 + 8 + 8 + 256 + 64 + 1 + 8 * 4;
-}
+        emit!(RevisionRequested {
+            escrow: escrow.key(),
+            renter: escrow.renter,
+            revision: escrow.revisions + 1,
+            notes_hash,
+            requested_at: now,
+        });
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct EscrowTerms {
-    pub skill_name: String,
-    pub duration_seconds: i64,
-    pub price_usdc: u64,
-    pub metadata_uri: String,
-}
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::RevisionRequested,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            0,
+        )?;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Default)]
-pub enum EscrowState {
-    #[default]
-    Created,
-    Funded,
-    Completed,
-    Cancelled,
-}
+        Ok(())
+    }
 
-// ========== Contexts ==========
+    /// Complete task and release USDC to provider
+    pub fn complete_task(ctx: Context<CompleteTask>) -> Result<()> {
+        ctx.accounts.release.escrow_account.check_key_ciphertext(None)?;
+        release_to_provider(&mut ctx.accounts.release, &ctx.bumps.release)
+    }
 
-#[derive(Accounts)]
-pub struct InitializeEscrow<'info> {
-    #[account(mut)]
-    pub provider: Signer<'info>,
-    #[account(
-        init,
-        payer = provider,
-        seeds = [ESCROW_SEED, provider.key().as_ref()],
-        bump,
-        space = EscrowAccount::LEN
-    )]
-    pub escrow_account: Account<'info, EscrowAccount>,
-    pub token_mint: Account<'info, token::Mint>,
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = provider
-    )]
-    pub provider_token_account: Account<'info, TokenAccount>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-}
+    /// Release USDC to provider while revealing the deliverable key ciphertext
+    /// (re-encrypted to the renter), which must match the committed hash
+    pub fn release_with_key(ctx: Context<CompleteTask>, key_ciphertext: Vec<u8>) -> Result<()> {
+        let escrow = &ctx.accounts.release.escrow_account;
+        escrow.check_key_ciphertext(Some(&key_ciphertext))?;
 
-#[derive(Accounts)]
-pub struct AcceptEscrow<'info> {
-    #[account(mut)]
-    pub renter: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [ESCROW_SEED, escrow_account.provider.as_ref()],
-        bump,
-        has_one = provider_token_account,
-        has_one = token_mint,
-    )]
-    pub escrow_account: Account<'info, EscrowAccount>,
-    /// Provider's token account (must match escrow_account.provider_token_account)
-    pub provider_token_account: Account<'info, TokenAccount>,
-    pub token_mint: Account<'info, token::Mint>,
-    #[account(
-        init_if_needed,
-        payer = renter,
-        associated_token::mint = token_mint,
-        associated_token::authority = escrow_account,
-    )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = renter,
-    )]
-    pub renter_token_account: Account<'info, TokenAccount>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-}
+        emit!(DecryptionKeyReleased {
+            escrow: escrow.key(),
+            renter: escrow.renter,
+            key_ciphertext,
+        });
 
-#[derive(Accounts)]
-pub struct CompleteTask<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [ESCROW_SEED, escrow_account.provider.as_ref()],
-        bump,
-    )]
-    pub escrow_account: Account<'info, EscrowAccount>,
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = escrow_account,
-    )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = escrow_account.provider,
-    )]
-    pub provider_token_account: Account<'info, TokenAccount>,
-    pub token_mint: Account<'info, token::Mint>,
-    pub token_program: Program<'info, Token>,
-}
+        release_to_provider(&mut ctx.accounts.release, &ctx.bumps.release)
+    }
 
-#[derive(Accounts)]
-pub struct CancelEscrow<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [ESCROW_SEED, escrow_account.provider.as_ref()],
-        bump,
-    )]
-    pub escrow_account: Account<'info, EscrowAccount>,
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = escrow_account,
-    )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = escrow_account.renter,
-    )]
-    pub renter_token_account: Account<'info, TokenAccount>,
-    pub token_mint: Account<'info, token::Mint>,
-    pub token_program: Program<'info, Token>,
-}
+    /// Provider walks away from a funded rental, refunding the renter in full. A renter
+    /// who wants out needs the provider's consent through `propose_cancel`, or the
+    /// rental period to lapse undelivered for `refund_after_timeout`.
+    pub fn cancel_escrow(ctx: Context<CancelEscrow>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            ctx.accounts.escrow_account.provider,
+            EscrowError::Unauthorized
+        );
+        refund_to_renter(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct CheckTimeout<'info> {
-    #[account(
-        seeds = [ESCROW_SEED, escrow_account.provider.as_ref()],
-        bump,
-    )]
-    pub escrow_account: Account<'info, EscrowAccount>,
+    /// Provider or renter proposes cancelling a funded rental with a full refund. The
+    /// other party has `CANCEL_PROPOSAL_SECONDS` to `accept_cancel`; proposing again
+    /// replaces the pending proposal.
+    pub fn propose_cancel(ctx: Context<ProposeCancel>) -> Result<()> {
+        ctx.accounts
+            .escrow_account
+            .propose_cancel(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp)?;
+
+        let escrow = &ctx.accounts.escrow_account;
+        emit!(CancelProposed {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            escrow_id: escrow.escrow_id,
+            renter: escrow.renter,
+            proposed_by: escrow.cancel_proposed_by,
+            expires_at: escrow.cancel_expires_at,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::CancelProposed,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            ctx.accounts.escrow_account.amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// The party that did not propose the cancellation co-signs it before it expires,
+    /// refunding the renter in full
+    pub fn accept_cancel(ctx: Context<CancelEscrow>) -> Result<()> {
+        ctx.accounts
+            .escrow_account
+            .check_cancel_acceptance(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp)?;
+        refund_to_renter(ctx)
+    }
+
+    /// Provider collects whatever part of a vesting payout has unlocked since the last
+    /// claim. Vested amounts stay claimable after a post-release dispute freezes the rest.
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.vesting_total > 0, EscrowError::InvalidState);
+        let now = Clock::get()?.unix_timestamp;
+        let claimable = escrow.vested_at(now).saturating_sub(escrow.vested_claimed);
+        require!(claimable > 0, EscrowError::NothingVested);
+
+        escrow.enter_settlement()?;
+        escrow.vested_claimed = escrow.vested_claimed.saturating_add(claimable);
+        if escrow.state == EscrowState::Vesting && escrow.vested_claimed == escrow.vesting_total {
+            escrow.state = EscrowState::Completed;
+        }
+        let provider = escrow.provider;
+        let id_seed = escrow_id_seed(escrow.escrow_id);
+
+        let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[ctx.bumps.escrow_account]];
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let payouts = [(ctx.accounts.provider_token_account.to_account_info(), claimable)];
+        settle_from_vault(&mut ctx.accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+        let escrow = &ctx.accounts.escrow_account;
+        emit!(VestedClaimed {
+            escrow: escrow.key(),
+            provider,
+            amount: claimable,
+            claimed: escrow.vested_claimed,
+            total: escrow.vesting_total,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            provider,
+            ActivityKind::VestedPayoutClaimed,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            claimable,
+        )?;
+
+        Ok(())
+    }
+
+    /// Provider adds a payout milestone to the listing's plan, before or during the
+    /// rental. Milestones may not schedule more than the price (or, once funded, the
+    /// funded amount); whatever they leave unscheduled is paid by the final release.
+    pub fn add_milestone(ctx: Context<AddMilestone>, description_hash: [u8; 32], amount: u64) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(
+            matches!(escrow.state, EscrowState::Created | EscrowState::Funded),
+            EscrowError::InvalidState
+        );
+        require!(
+            !escrow.terms.settle_in_credits && escrow.terms.vesting_seconds == 0 && !escrow.terms.stream_payment,
+            EscrowError::InvalidSettlementMode
+        );
+        let cap = match escrow.state {
+            EscrowState::Funded => escrow.amount.saturating_add(ctx.accounts.milestone_plan.released),
+            _ => escrow.terms.price_usdc,
+        };
+        let escrow_key = escrow.key();
+        let plan = &mut ctx.accounts.milestone_plan;
+        if plan.escrow == Pubkey::default() {
+            plan.escrow = escrow_key;
+            plan.bump = ctx.bumps.milestone_plan;
+        }
+        plan.add(description_hash, amount, cap)?;
+        let index = (plan.milestones.len() - 1) as u8;
+
+        emit!(MilestoneAdded {
+            escrow: escrow_key,
+            index,
+            description_hash,
+            amount,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::MilestoneAdded,
+            escrow_key,
+            ctx.accounts.escrow_account.renter,
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Renter signs off on a milestone's work, letting the provider release its payment
+    pub fn approve_milestone(ctx: Context<ApproveMilestone>, index: u8) -> Result<()> {
+        require!(ctx.accounts.escrow_account.state == EscrowState::Funded, EscrowError::InvalidState);
+        let amount = ctx.accounts.milestone_plan.approve(index)?;
+
+        emit!(MilestoneApproved {
+            escrow: ctx.accounts.escrow_account.key(),
+            renter: ctx.accounts.renter.key(),
+            index,
+            amount,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::MilestoneApproved,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.renter.key(),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Provider of a `terms.stream_payment` rental collects the funded amount pro rata to
+    /// the rental time elapsed, less what earlier claims took; `streaming` feature only
+    pub fn claim_streamed(ctx: Context<ClaimStreamed>) -> Result<()> {
+        gated!("streaming", streaming_handlers::claim_streamed(ctx))
+    }
+
+    /// Provider collects an approved milestone. The payment leaves `escrow.amount`, so
+    /// refunds, rulings and the final release only ever see what is still held.
+    pub fn release_milestone(ctx: Context<ReleaseMilestone>, index: u8) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(escrow.deferred_balance == 0, EscrowError::DeferredBalanceOutstanding);
+        let now = Clock::get()?.unix_timestamp;
+        if ctx.accounts.config.requires_screening(escrow.amount) {
+            require!(
+                escrow.screening_approved
+                    || now >= escrow.funded_at.saturating_add(ctx.accounts.config.screening_delay_seconds),
+                EscrowError::ScreeningPending
+            );
+        }
+        let amount = ctx.accounts.milestone_plan.release(index)?;
+        // Leave enough behind for any SLA penalty owed to the renter at release
+        let remaining = escrow.amount.checked_sub(amount).ok_or(EscrowError::InsufficientFunds)?;
+        require!(remaining >= escrow.sla_penalty()?, EscrowError::InsufficientFunds);
+
+        let fee = ctx.accounts.config.fee_on(amount);
+        let treasury = match fee {
+            0 => None,
+            _ => Some(fee_treasury_account(
+                &ctx.accounts.config,
+                ctx.accounts.fee_token_account.as_ref(),
+                &ctx.accounts.token_mint.key(),
+            )?),
+        };
+
+        escrow.enter_settlement()?;
+        escrow.amount = remaining;
+        escrow.fee_amount = escrow.fee_amount.saturating_add(fee);
+        ctx.accounts.epoch_stats.record_fee(fee);
+        let provider = escrow.provider;
+        let id_seed = escrow_id_seed(escrow.escrow_id);
+        if !escrow.terms.trial {
+            ctx.accounts.provider_stats.record_milestone(amount)?;
+        }
+
+        let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[ctx.bumps.escrow_account]];
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let mut payouts = vec![(ctx.accounts.provider_token_account.to_account_info(), amount - fee)];
+        if let Some(treasury) = treasury {
+            payouts.push((treasury.to_account_info(), fee));
+        }
+        settle_from_vault(&mut ctx.accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+        emit!(MilestoneReleased {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider,
+            index,
+            amount,
+            released: ctx.accounts.milestone_plan.released,
+            remaining,
+        });
+        if let Some(treasury) = treasury {
+            emit!(ProtocolFeeCharged {
+                escrow: ctx.accounts.escrow_account.key(),
+                treasury_token_account: treasury.key(),
+                amount: fee,
+            });
+        }
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            provider,
+            ActivityKind::MilestoneReleased,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// The SLA verifier attests that an observed metric missed its target; each breach
+    /// adds `penalty_bps_per_breach` (capped) to the share refunded to the renter at release
+    pub fn report_sla_breach(
+        ctx: Context<ReportSlaBreach>,
+        metric: SlaMetric,
+        observed: u64,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(!escrow.terms.settle_in_credits, EscrowError::InvalidSettlementMode);
+        let sla = escrow.terms.sla.as_ref().ok_or(EscrowError::SlaNotTracked)?;
+        require!(ctx.accounts.verifier.key() == sla.verifier, EscrowError::Unauthorized);
+        require!(sla.breached(metric, observed).ok_or(EscrowError::SlaNotTracked)?, EscrowError::SlaNotBreached);
+
+        let breaches = escrow.sla_breaches.saturating_add(1);
+        let penalty_bps = sla.penalty_bps(breaches);
+        escrow.sla_breaches = breaches;
+        escrow.sla_penalty_bps = penalty_bps;
+
+        emit!(SlaBreachReported {
+            escrow: escrow.key(),
+            verifier: ctx.accounts.verifier.key(),
+            metric,
+            observed,
+            evidence_hash,
+            breaches: escrow.sla_breaches,
+            penalty_bps: escrow.sla_penalty_bps,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::SlaBreachReported,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            observed,
+        )?;
+
+        Ok(())
+    }
+
+    /// Provider commits to the hash of the API credential or access token issued for the
+    /// rental; called again on every rotation. Each commitment is timestamped in an event
+    /// so a dispute over missing access can be checked against the on-chain history.
+    pub fn commit_access_credential(ctx: Context<CommitAccessCredential>, commitment: [u8; 32]) -> Result<()> {
+        require!(commitment != [0u8; 32], EscrowError::InvalidAccessCommitment);
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+
+        let now = Clock::get()?.unix_timestamp;
+        escrow.access_commitment = commitment;
+        escrow.access_committed_at = now;
+        escrow.access_rotations = escrow.access_rotations.saturating_add(1);
+
+        emit!(AccessCredentialCommitted {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            renter: escrow.renter,
+            commitment,
+            rotation: escrow.access_rotations,
+            committed_at: now,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::AccessCredentialCommitted,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Renter pays down a deferred balance allowed by the listing's funding rule
+    pub fn pay_deferred(ctx: Context<PayDeferred>, amount: u64) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(ctx.accounts.renter.key() == escrow.renter, EscrowError::Unauthorized);
+        require!(amount > 0 && amount <= escrow.deferred_balance, EscrowError::InsufficientFunds);
+        escrow.deferred_balance -= amount;
+        let received = received_amount(&ctx.accounts.token_mint, amount)?;
+        escrow.amount = escrow.amount.checked_add(received).ok_or(EscrowError::InsufficientFunds)?;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.renter_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.renter.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowFunded,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Renter adds `extra_fee` to a funded rental for priority handling. It joins the
+    /// escrowed amount, so it is released or refunded along with the principal.
+    pub fn expedite(ctx: Context<PayDeferred>, extra_fee: u64) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(ctx.accounts.renter.key() == escrow.renter, EscrowError::Unauthorized);
+        require!(!escrow.terms.settle_in_credits, EscrowError::InvalidSettlementMode);
+        require!(extra_fee > 0, EscrowError::InsufficientFunds);
+        let received = received_amount(&ctx.accounts.token_mint, extra_fee)?;
+        escrow.amount = escrow.amount.checked_add(received).ok_or(EscrowError::InsufficientFunds)?;
+        escrow.expedite_fee = escrow.expedite_fee.checked_add(received).ok_or(EscrowError::InsufficientFunds)?;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.renter_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.renter.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            extra_fee,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        let escrow = &ctx.accounts.escrow_account;
+        emit!(EscrowExpedited {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            escrow_id: escrow.escrow_id,
+            renter: escrow.renter,
+            extra_fee,
+            expedite_fee: escrow.expedite_fee,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowExpedited,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            extra_fee,
+        )?;
+
+        Ok(())
+    }
+
+    /// Renter designates a delegate allowed to dispute or claim a
+    /// timeout refund on their behalf; `Pubkey::default()` clears it
+    pub fn set_watchtower(ctx: Context<SetWatchtower>, watchtower: Pubkey) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(ctx.accounts.renter.key() == escrow.renter, EscrowError::Unauthorized);
+        escrow.watchtower = watchtower;
+        Ok(())
+    }
+
+    /// Renter and provider jointly replace the rental's arbiter. Not while a dispute is
+    /// open: a dispute stays with the arbiter it was raised before.
+    pub fn set_arbiter(ctx: Context<SetArbiter>, arbiter: Pubkey) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(
+            escrow.state == EscrowState::Funded || escrow.state == EscrowState::Vesting,
+            EscrowError::InvalidState
+        );
+        require!(escrow.vesting_halted_at == 0, EscrowError::InvalidState);
+        require!(escrow.is_eligible_arbiter(&arbiter), EscrowError::InvalidArbiter);
+        let previous = escrow.arbiter;
+        escrow.arbiter = arbiter;
+
+        emit!(ArbiterChanged {
+            escrow: escrow.key(),
+            previous,
+            arbiter,
+        });
+        Ok(())
+    }
+
+    /// Renter and provider jointly lengthen a funded rental by `extra_seconds`, moving
+    /// its end and timeout refund back so long-running work is not refunded out from
+    /// under them. The terms hash is recomputed over the extended terms.
+    pub fn extend_duration(ctx: Context<ExtendDuration>, extra_seconds: i64) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+        escrow.extend_duration(extra_seconds)?;
+        escrow.terms_hash = EscrowAccount::compute_terms_hash(&escrow.terms, &escrow.token_mint)?;
+
+        emit!(DurationExtended {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            renter: escrow.renter,
+            extra_seconds,
+            duration_seconds: escrow.terms.duration_seconds,
+            rental_ends_at: escrow.rental_ends_at(),
+            terms_hash: escrow.terms_hash,
+        });
+        Ok(())
+    }
+
+    /// Provider registers the key its notifier signs webhook callbacks with, so
+    /// receivers can check them against chain state; `Pubkey::default()` clears it
+    pub fn set_webhook_signer(ctx: Context<SetWebhookSigner>, signer: Pubkey) -> Result<()> {
+        ctx.accounts.escrow_account.webhook_signer = signer;
+        Ok(())
+    }
+
+    /// Provider offers (or withdraws) automatic release on the listing. Renters opt in
+    /// with `FundOverrides::auto_release`; rentals already funded keep what was agreed.
+    pub fn offer_auto_release(ctx: Context<OfferAutoRelease>, offered: bool) -> Result<()> {
+        ctx.accounts.escrow_account.auto_release_offered = offered;
+        Ok(())
+    }
+
+    /// Provider offers (or withdraws) subscriptions on the listing: renters may then
+    /// prepay several periods of `terms.duration_seconds` at the listed price each
+    pub fn offer_subscription(ctx: Context<OfferAutoRelease>, offered: bool) -> Result<()> {
+        ctx.accounts.escrow_account.subscription_offered = offered;
+        Ok(())
+    }
+
+    /// Renter prepays `periods` periods of `amount_per_period`, the listed price. The
+    /// escrow is then `Subscribed`: the provider claims each period once it has elapsed
+    /// with `claim_period`, and the renter may `cancel_subscription` the periods that
+    /// have not started.
+    pub fn fund_subscription(
+        ctx: Context<FundSubscription>,
+        periods: u32,
+        amount_per_period: u64,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Created, EscrowError::InvalidState);
+        require!(escrow.terms_hash == terms_hash, EscrowError::TermsHashMismatch);
+        require!(escrow.subscription_offered, EscrowError::SubscriptionNotOffered);
+        require!(!escrow.terms.settle_in_credits, EscrowError::InvalidSettlementMode);
+        require!(escrow.terms.funding_rule.is_none(), EscrowError::InvalidFundingRule);
+        require!(!escrow.terms.requires_provider_acceptance, EscrowError::ProviderAcceptanceRequired);
+        require!(
+            periods > 0 && escrow.terms.duration_seconds > 0,
+            EscrowError::InvalidSubscription
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(escrow.funding_open(now), EscrowError::ListingExpired);
+
+        let max_concurrent = ctx.accounts.provider_stats.concurrency_limit(
+            escrow.terms.max_concurrent_rentals,
+            ctx.accounts.config.failure_streak_threshold,
+        );
+        ctx.accounts.provider_index.reserve_slot(max_concurrent)?;
+
+        let total = amount_per_period
+            .checked_mul(periods as u64)
+            .ok_or(EscrowError::InvalidSubscription)?;
+        let received = received_amount(&ctx.accounts.token_mint, total)?;
+        let renter = ctx.accounts.renter.key();
+        let escrow = &mut ctx.accounts.escrow_account;
+        fund_escrow(escrow, renter, amount_per_period, received, &FundOverrides::default(), None)?;
+        escrow.state = EscrowState::Subscribed;
+        ctx.accounts.epoch_stats.record_funding(received);
+
+        let plan = &mut ctx.accounts.subscription_plan;
+        plan.escrow = ctx.accounts.escrow_account.key();
+        plan.periods = periods;
+        plan.amount_per_period = amount_per_period;
+        plan.total = received;
+        plan.period_seconds = ctx.accounts.escrow_account.terms.duration_seconds;
+        plan.started_at = now;
+        plan.active_periods = periods;
+        plan.bump = ctx.bumps.subscription_plan;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.renter_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.renter.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            total,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        emit!(SubscriptionFunded {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider: ctx.accounts.escrow_account.provider,
+            renter,
+            periods,
+            amount_per_period,
+            period_seconds: ctx.accounts.subscription_plan.period_seconds,
+            amount: received,
+            funded_at: now,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowFunded,
+            ctx.accounts.escrow_account.key(),
+            renter,
+            received,
+        )?;
+
+        Ok(())
+    }
+
+    /// Provider claims every subscription period that has elapsed and is not yet paid,
+    /// net of the protocol fee. Claiming the last owed period completes the escrow.
+    pub fn claim_period(ctx: Context<ClaimPeriod>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Subscribed, EscrowError::InvalidState);
+        let now = Clock::get()?.unix_timestamp;
+        if ctx.accounts.config.requires_screening(escrow.amount) {
+            require!(
+                escrow.screening_approved
+                    || now >= escrow.funded_at.saturating_add(ctx.accounts.config.screening_delay_seconds),
+                EscrowError::ScreeningPending
+            );
+        }
+        let plan = &mut ctx.accounts.subscription_plan;
+        let amount = plan.claim(now)?;
+        let finished = plan.is_finished();
+        let remaining = escrow.amount.checked_sub(amount).ok_or(EscrowError::InsufficientFunds)?;
+
+        let fee = ctx.accounts.config.fee_on(amount);
+        let treasury = match fee {
+            0 => None,
+            _ => Some(fee_treasury_account(
+                &ctx.accounts.config,
+                ctx.accounts.fee_token_account.as_ref(),
+                &ctx.accounts.token_mint.key(),
+            )?),
+        };
+
+        escrow.enter_settlement()?;
+        escrow.amount = remaining;
+        escrow.fee_amount = escrow.fee_amount.saturating_add(fee);
+        escrow.provider_payout = escrow.provider_payout.saturating_add(amount - fee);
+        ctx.accounts.epoch_stats.record_fee(fee);
+        if finished {
+            escrow.state = EscrowState::Completed;
+            escrow.completed_at = now;
+            ctx.accounts.provider_index.release_slot();
+        }
+        let provider = escrow.provider;
+        let id_seed = escrow_id_seed(escrow.escrow_id);
+        if !escrow.terms.trial {
+            let stats = &mut ctx.accounts.provider_stats;
+            if finished {
+                stats.record_release(amount)?;
+            } else {
+                stats.record_milestone(amount)?;
+            }
+        }
+
+        let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[ctx.bumps.escrow_account]];
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let mut payouts = vec![(ctx.accounts.provider_token_account.to_account_info(), amount - fee)];
+        if let Some(treasury) = treasury {
+            payouts.push((treasury.to_account_info(), fee));
+        }
+        settle_from_vault(&mut ctx.accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+        let plan = &ctx.accounts.subscription_plan;
+        emit!(PeriodClaimed {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider,
+            claimed_periods: plan.claimed_periods,
+            active_periods: plan.active_periods,
+            amount,
+            remaining,
+        });
+        if let Some(treasury) = treasury {
+            emit!(ProtocolFeeCharged {
+                escrow: ctx.accounts.escrow_account.key(),
+                treasury_token_account: treasury.key(),
+                amount: fee,
+            });
+        }
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            provider,
+            ActivityKind::EscrowReleased,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Renter cancels the subscription periods that have not started yet and is refunded
+    /// for them. The period under way is still the provider's to claim once it elapses.
+    pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Subscribed, EscrowError::InvalidState);
+        let now = Clock::get()?.unix_timestamp;
+        let refund = ctx.accounts.subscription_plan.cancel(now)?;
+        escrow.enter_settlement()?;
+        escrow.amount = escrow.amount.checked_sub(refund).ok_or(EscrowError::InsufficientFunds)?;
+        escrow.renter_refund = escrow.renter_refund.saturating_add(refund);
+        escrow.cancelled_at = now;
+        let provider = escrow.provider;
+        let id_seed = escrow_id_seed(escrow.escrow_id);
+
+        let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[ctx.bumps.escrow_account]];
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let payouts = [(ctx.accounts.renter_token_account.to_account_info(), refund)];
+        settle_from_vault(&mut ctx.accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+        let plan = &ctx.accounts.subscription_plan;
+        emit!(SubscriptionCancelled {
+            escrow: ctx.accounts.escrow_account.key(),
+            renter: ctx.accounts.escrow_account.renter,
+            active_periods: plan.active_periods,
+            periods: plan.periods,
+            refund,
+            cancelled_at: now,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            provider,
+            ActivityKind::EscrowRefunded,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            refund,
+        )?;
+
+        Ok(())
+    }
+
+    /// Permissionless: release a delivered rental once `EscrowAccount::auto_release_due`,
+    /// either because both parties agreed at funding to settle automatically or because
+    /// the renter let the review window lapse. Keepers crank this so providers are not
+    /// held hostage by unresponsive renters.
+    pub fn auto_release(ctx: Context<AutoRelease>) -> Result<()> {
+        let escrow = &ctx.accounts.release.escrow_account;
+        require!(
+            escrow.auto_release || escrow.review_window_seconds > 0,
+            EscrowError::AutoReleaseNotAgreed
+        );
+        escrow.check_key_ciphertext(None)?;
+        require!(
+            escrow.auto_release_due(Clock::get()?.unix_timestamp),
+            EscrowError::TimeoutNotElapsed
+        );
+        release_to_provider(&mut ctx.accounts.release, &ctx.bumps.release)
+    }
+
+    /// Publish (or, with `None`, withdraw) the URI of the listing's terms in `language`.
+    /// The caller quotes the canonical terms hash, and a localization published against
+    /// earlier terms is cleared, so every translation on record describes the terms that
+    /// disputes are judged by.
+    pub fn set_terms_localization(
+        ctx: Context<SetTermsLocalization>,
+        terms_hash: [u8; 32],
+        language: String,
+        metadata_uri: Option<String>,
+    ) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.terms_hash == terms_hash, EscrowError::TermsHashMismatch);
+        let escrow_key = escrow.key();
+        let localization = &mut ctx.accounts.terms_localization;
+        if localization.escrow == Pubkey::default() {
+            localization.escrow = escrow_key;
+            localization.bump = ctx.bumps.terms_localization;
+        }
+        localization.set(terms_hash, &language, metadata_uri.clone())?;
+
+        emit!(TermsLocalized {
+            escrow: escrow_key,
+            terms_hash,
+            language,
+            metadata_uri,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless: refund an undelivered rental once its rental period is over, so a
+    /// renter whose provider has disappeared needs nobody's signature to get the deposit
+    /// back. The refund can only go to the renter's token account. This is the only
+    /// timeout refund; a renter's watchtower cranks it like anyone else.
+    pub fn refund_after_timeout(ctx: Context<CancelEscrow>) -> Result<()> {
+        ctx.accounts
+            .escrow_account
+            .check_timeout_refund(Clock::get()?.unix_timestamp)?;
+        refund_to_renter(ctx)
+    }
+
+    /// Accept a micro-rental escrow and fund it into the provider's pooled credit vault
+    pub fn accept_escrow_credit(
+        ctx: Context<AcceptEscrowCredit>,
+        amount: u64,
+        overrides: FundOverrides,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .escrow_account
+            .check_fundable(&terms_hash, true, Clock::get()?.unix_timestamp)?;
+
+        let max_concurrent = ctx.accounts.provider_stats.concurrency_limit(
+            ctx.accounts.escrow_account.terms.max_concurrent_rentals,
+            ctx.accounts.config.failure_streak_threshold,
+        );
+        ctx.accounts.provider_index.reserve_slot(max_concurrent)?;
+
+        let received = received_amount(&ctx.accounts.token_mint, amount)?;
+        ctx.accounts.credit_balance.bind(
+            ctx.accounts.escrow_account.provider,
+            ctx.accounts.token_mint.key(),
+            ctx.bumps.credit_balance,
+        );
+        ctx.accounts.credit_balance.lock(received)?;
+
+        let escrow = &mut ctx.accounts.escrow_account;
+        fund_escrow(escrow, ctx.accounts.renter.key(), amount, received, &overrides, None)?;
+        ctx.accounts.epoch_stats.record_funding(received);
+        let (provider, funded_at) = (escrow.provider, escrow.funded_at);
+        ctx.accounts
+            .engagement
+            .record(provider, ctx.accounts.renter.key(), ctx.bumps.engagement, funded_at);
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.renter_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.credit_vault.to_account_info(),
+            authority: ctx.accounts.renter.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        emit!(EscrowFunded {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider: ctx.accounts.escrow_account.provider,
+            escrow_id: ctx.accounts.escrow_account.escrow_id,
+            renter: ctx.accounts.escrow_account.renter,
+            token_mint: ctx.accounts.escrow_account.token_mint,
+            amount: ctx.accounts.escrow_account.amount,
+            funded_at: ctx.accounts.escrow_account.funded_at,
+            settle_in_credits: ctx.accounts.escrow_account.terms.settle_in_credits,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowFunded,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            ctx.accounts.escrow_account.amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Renter completes a micro-rental by crediting the provider's ledger (no token
+    /// transfer), under the same delivery, deferred-payment and screening rules as
+    /// `complete_task`
+    pub fn complete_task_credit(ctx: Context<CompleteTaskCredit>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = &mut ctx.accounts.escrow_account;
+        escrow.check_releasable(&ctx.accounts.config, true, now)?;
+        escrow.state = EscrowState::Completed;
+        escrow.completed_at = now;
+        let amount = escrow.amount;
+        escrow.allocate_settlement(amount, 0);
+
+        ctx.accounts.provider_index.release_slot();
+        if !ctx.accounts.escrow_account.terms.trial {
+            ctx.accounts.provider_stats.record_release(amount)?;
+        }
+
+        ctx.accounts.credit_balance.settle(amount)?;
+
+        emit!(EscrowSettled {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider: ctx.accounts.escrow_account.provider,
+            escrow_id: ctx.accounts.escrow_account.escrow_id,
+            renter: ctx.accounts.escrow_account.renter,
+            kind: SettlementKind::Released,
+            accounting: settlement_accounting(
+                &ctx.accounts.token_mint,
+                amount,
+                ctx.accounts.price_oracle.as_ref(),
+            )?,
+        });
+
+        accrue_points(
+            Some(&ctx.accounts.config),
+            ctx.accounts.provider_points.as_deref_mut(),
+            amount,
+        )?;
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowReleased,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            ctx.accounts.escrow_account.amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Provider cancels a micro-rental, refunding the renter out of the credit vault
+    pub fn cancel_escrow_credit(ctx: Context<CancelEscrowCredit>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            ctx.accounts.escrow_account.provider,
+            EscrowError::Unauthorized
+        );
+        require!(ctx.accounts.escrow_account.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(ctx.accounts.escrow_account.terms.settle_in_credits, EscrowError::InvalidSettlementMode);
+        let escrow = &mut ctx.accounts.escrow_account;
+        escrow.enter_settlement()?;
+        escrow.state = EscrowState::Cancelled;
+        escrow.cancelled_at = Clock::get()?.unix_timestamp;
+        let amount = escrow.amount;
+        escrow.allocate_settlement(0, amount);
+
+        ctx.accounts.provider_index.release_slot();
+        if !ctx.accounts.escrow_account.terms.trial {
+            ctx.accounts.provider_stats.record_refund();
+        }
+
+        let credit = &mut ctx.accounts.credit_balance;
+        credit.unlock(amount)?;
+        let provider = credit.provider;
+        let mint = credit.token_mint;
+
+        let seeds = &[CREDIT_SEED, provider.as_ref(), mint.as_ref(), &[credit.bump]];
+        let vault = Vault::new(
+            &ctx.accounts.credit_vault,
+            &ctx.accounts.credit_balance,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let payouts = [(ctx.accounts.renter_token_account.to_account_info(), amount)];
+        settle_from_vault(&mut ctx.accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+        emit!(EscrowSettled {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider: ctx.accounts.escrow_account.provider,
+            escrow_id: ctx.accounts.escrow_account.escrow_id,
+            renter: ctx.accounts.escrow_account.renter,
+            kind: SettlementKind::Refunded,
+            accounting: settlement_accounting(
+                &ctx.accounts.token_mint,
+                amount,
+                ctx.accounts.price_oracle.as_ref(),
+            )?,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowRefunded,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            ctx.accounts.escrow_account.amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Withdraw all accumulated credits to the provider in a single token transfer
+    pub fn withdraw_credits(ctx: Context<WithdrawCredits>) -> Result<()> {
+        let credit = &mut ctx.accounts.credit_balance;
+        let amount = credit.available;
+        require!(amount > 0, EscrowError::InsufficientFunds);
+        credit.available = 0;
+        credit.total_withdrawn = credit.total_withdrawn.saturating_add(amount);
+        let provider = credit.provider;
+        let mint = credit.token_mint;
+
+        let seeds = &[CREDIT_SEED, provider.as_ref(), mint.as_ref(), &[credit.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.credit_vault.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.provider_token_account.to_account_info(),
+            authority: ctx.accounts.credit_balance.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.provider.key(),
+            ActivityKind::CreditsWithdrawn,
+            ctx.accounts.credit_balance.key(),
+            Pubkey::default(),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Open a bilateral settlement channel from `payer` to `payee` and deposit into it
+    pub fn open_channel(ctx: Context<OpenChannel>, amount: u64) -> Result<()> {
+        let channel = &mut ctx.accounts.channel;
+        channel.payer = ctx.accounts.payer.key();
+        channel.payee = ctx.accounts.payee.key();
+        channel.token_mint = ctx.accounts.token_mint.key();
+        channel.deposited = received_amount(&ctx.accounts.token_mint, amount)?;
+        channel.bump = ctx.bumps.channel;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.payer_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.channel_vault.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+        Ok(())
+    }
+
+    /// Payer tops up an open channel
+    pub fn fund_channel(ctx: Context<FundChannel>, amount: u64) -> Result<()> {
+        let channel = &mut ctx.accounts.channel;
+        require!(channel.close_requested_at == 0, EscrowError::ChannelClosing);
+        let received = received_amount(&ctx.accounts.token_mint, amount)?;
+        channel.deposited = channel.deposited.checked_add(received).ok_or(EscrowError::InsufficientFunds)?;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.payer_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.channel_vault.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+        Ok(())
+    }
+
+    /// Pay the payee the difference between a payer-signed cumulative balance and what
+    /// was already settled. The preceding instruction must be an Ed25519 verification of
+    /// the payer's signature over `SettlementChannel::message(channel, cumulative, nonce)`.
+    pub fn settle_channel(ctx: Context<SettleChannel>, cumulative: u64, nonce: u64) -> Result<()> {
+        let channel_key = ctx.accounts.channel.key();
+        let channel = &ctx.accounts.channel;
+        require!(nonce > channel.nonce, EscrowError::StaleChannelUpdate);
+        require!(cumulative >= channel.settled, EscrowError::StaleChannelUpdate);
+        require!(cumulative <= channel.deposited, EscrowError::InsufficientFunds);
+
+        let ix_sysvar = ctx.accounts.instructions.to_account_info();
+        let current = instructions_sysvar::load_current_index_checked(&ix_sysvar)? as usize;
+        require!(current > 0, EscrowError::InvalidChannelSignature);
+        let verify_ix = instructions_sysvar::load_instruction_at_checked(current - 1, &ix_sysvar)?;
+        let message = SettlementChannel::message(&channel_key, cumulative, nonce);
+        require!(
+            verifies_ed25519(&verify_ix, &channel.payer, &message),
+            EscrowError::InvalidChannelSignature
+        );
+
+        let delta = cumulative - channel.settled;
+        let (payer, payee, token_mint, bump) = (channel.payer, channel.payee, channel.token_mint, channel.bump);
+        let channel = &mut ctx.accounts.channel;
+        channel.settled = cumulative;
+        channel.nonce = nonce;
+
+        if delta > 0 {
+            let seeds = &[CHANNEL_SEED, payer.as_ref(), payee.as_ref(), token_mint.as_ref(), &[bump]];
+            let signer = &[&seeds[..]];
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.channel_vault.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.payee_token_account.to_account_info(),
+                authority: ctx.accounts.channel.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+                delta,
+                ctx.accounts.token_mint.decimals,
+            )?;
+        }
+
+        emit!(ChannelSettled {
+            channel: channel_key,
+            payer,
+            payee,
+            cumulative,
+            nonce,
+            transferred: delta,
+        });
+        Ok(())
+    }
+
+    /// Payer starts the close delay, during which the payee can still settle
+    pub fn request_channel_close(ctx: Context<RequestChannelClose>) -> Result<()> {
+        let channel = &mut ctx.accounts.channel;
+        require!(channel.close_requested_at == 0, EscrowError::ChannelClosing);
+        channel.close_requested_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    /// After the close delay, refund the unsettled deposit to the payer and close the channel
+    pub fn close_channel(ctx: Context<CloseChannel>) -> Result<()> {
+        let channel = &ctx.accounts.channel;
+        require!(channel.close_requested_at > 0, EscrowError::InvalidState);
+        require!(
+            Clock::get()?.unix_timestamp >= channel.close_requested_at.saturating_add(CHANNEL_CLOSE_DELAY_SECONDS),
+            EscrowError::TimeoutNotElapsed
+        );
+
+        let remaining = ctx.accounts.channel_vault.amount;
+        let seeds = &[
+            CHANNEL_SEED,
+            channel.payer.as_ref(),
+            channel.payee.as_ref(),
+            channel.token_mint.as_ref(),
+            &[channel.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        if remaining > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.channel_vault.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.payer_token_account.to_account_info(),
+                authority: ctx.accounts.channel.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer),
+                remaining,
+                ctx.accounts.token_mint.decimals,
+            )?;
+        }
+        harvest_withheld_fees(
+            cpi_program.clone(),
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.channel_vault.to_account_info(),
+        )?;
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.channel_vault.to_account_info(),
+            destination: ctx.accounts.payer.to_account_info(),
+            authority: ctx.accounts.channel.to_account_info(),
+        };
+        token_interface::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+        Ok(())
+    }
+
+    /// Open a dispute on a funded escrow (provider or renter), within the dispute/review window
+    pub fn dispute(ctx: Context<Dispute>) -> Result<()> {
+        gated!("disputes", dispute_handlers::dispute(ctx))
+    }
+
+    /// Renter, provider or arbiter commits to `hash(secret || signer)` for the dispute's
+    /// randomness seed
+    pub fn commit_dispute_randomness(ctx: Context<CommitDisputeRandomness>, commitment: [u8; 32]) -> Result<()> {
+        gated!("disputes", dispute_handlers::commit_dispute_randomness(ctx, commitment))
+    }
+
+    /// Reveal a committed secret once every party has committed or the commit phase ended
+    pub fn reveal_dispute_randomness(ctx: Context<RevealDisputeRandomness>, secret: [u8; 32]) -> Result<()> {
+        gated!("disputes", dispute_handlers::reveal_dispute_randomness(ctx, secret))
+    }
+
+    /// Mix the revealed secrets with the most recent slot hash into the final seed.
+    /// Callable by anyone once all commitments are revealed or the reveal phase ended;
+    /// parties that never revealed are simply left out.
+    pub fn finalize_dispute_randomness(ctx: Context<FinalizeDisputeRandomness>) -> Result<()> {
+        gated!("disputes", dispute_handlers::finalize_dispute_randomness(ctx))
+    }
+
+    /// Arbiter resolves a dispute in favour of the provider
+    pub fn resolve_dispute_release(ctx: Context<ResolveDisputeRelease>, ruling_hash: [u8; 32]) -> Result<()> {
+        gated!("disputes", dispute_handlers::resolve_dispute_release(ctx, ruling_hash))
+    }
+
+    /// Arbiter resolves a dispute in favour of the renter
+    pub fn resolve_dispute_refund(ctx: Context<ResolveDisputeRefund>, ruling_hash: [u8; 32]) -> Result<()> {
+        gated!("disputes", dispute_handlers::resolve_dispute_refund(ctx, ruling_hash))
+    }
+
+    /// Arbiter splits a disputed escrow: `provider_bps` of the amount to the provider,
+    /// the rest back to the renter
+    pub fn resolve_dispute_split(
+        ctx: Context<ResolveDisputeSplit>,
+        provider_bps: u16,
+        ruling_hash: [u8; 32],
+    ) -> Result<()> {
+        gated!("disputes", dispute_handlers::resolve_dispute_split(ctx, provider_bps, ruling_hash))
+    }
+
+    /// Permissionless: settle a dispute the arbiter left unresolved past its
+    /// `arbitration_deadline`, refunding the renter or, if `Config::default_release` is
+    /// set, releasing to the provider
+    pub fn claim_default_resolution(ctx: Context<ClaimDefaultResolution>) -> Result<()> {
+        gated!("disputes", dispute_handlers::claim_default_resolution(ctx))
+    }
+
+    /// Provider or renter points the arbiter at off-chain proof for the open dispute
+    pub fn submit_evidence(
+        ctx: Context<SubmitEvidence>,
+        evidence_uri: String,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        gated!("disputes", dispute_handlers::submit_evidence(ctx, evidence_uri, evidence_hash))
+    }
+
+    /// Arbiter, or a member of the arbiter council, declares no prior rental with either
+    /// party of the open dispute, checked against the pair's `Engagement` records.
+    /// Required before ruling.
+    pub fn declare_no_conflict(ctx: Context<DeclareNoConflict>) -> Result<()> {
+        gated!("disputes", dispute_handlers::declare_no_conflict(ctx))
+    }
+
+    /// Permissionless: void a declaration contradicted by an `Engagement` between the
+    /// arbiter and a party, and count the conflict against the arbiter
+    pub fn report_conflict(ctx: Context<ReportConflict>) -> Result<()> {
+        gated!("disputes", dispute_handlers::report_conflict(ctx))
+    }
+
+    /// Form an M-of-N arbiter council; escrows use it by naming its address as arbiter
+    pub fn create_council(
+        ctx: Context<CreateCouncil>,
+        council_id: u32,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        gated!("disputes", dispute_handlers::create_council(ctx, council_id, members, threshold))
+    }
+
+    /// Council member proposes a ruling on the open dispute, approving it themselves.
+    /// A different ruling replaces the pending one and clears its approvals.
+    pub fn propose_resolution(
+        ctx: Context<ProposeResolution>,
+        outcome: ResolutionOutcome,
+        ruling_hash: [u8; 32],
+    ) -> Result<()> {
+        gated!("disputes", dispute_handlers::propose_resolution(ctx, outcome, ruling_hash))
+    }
+
+    /// Council member approves the pending ruling; `outcome` and `ruling_hash` must match it
+    pub fn approve_resolution(
+        ctx: Context<ProposeResolution>,
+        outcome: ResolutionOutcome,
+        ruling_hash: [u8; 32],
+    ) -> Result<()> {
+        gated!("disputes", dispute_handlers::approve_resolution(ctx, outcome, ruling_hash))
+    }
+
+    /// Permissionless: settle the dispute as the council's approved proposal rules.
+    /// `remaining_accounts` are the approving members' `ArbiterDeclaration`s; only
+    /// approvals whose declaration still covers the dispute count toward the threshold.
+    pub fn execute_resolution<'info>(ctx: Context<'_, '_, 'info, 'info, ExecuteResolution<'info>>) -> Result<()> {
+        gated!("disputes", dispute_handlers::execute_resolution(ctx))
+    }
+
+    /// Provider exit: refund every funded escrow and close every open listing passed in
+    /// `remaining_accounts` as `[escrow_account, escrow_token_account, renter_token_account,
+    /// token_mint]` quadruples (token accounts and mint are ignored for unfunded listings).
+    /// Every funded escrow's mint must belong to the one `token_program` passed, and each
+    /// listing must be closable as by `cancel_unfunded`.
+    pub fn wind_down<'info>(ctx: Context<'_, '_, 'info, 'info, WindDown<'info>>) -> Result<()> {
+        let chunks = ctx.remaining_accounts.chunks_exact(4);
+        require!(chunks.remainder().is_empty(), EscrowError::InvalidRemainingAccounts);
+        let provider = ctx.accounts.provider.key();
+        let now = Clock::get()?.unix_timestamp;
+        let mut refunded_escrows: u32 = 0;
+        let mut closed_listings: u32 = 0;
+        let mut total_refunded: u64 = 0;
+
+        for chunk in chunks {
+            let escrow_info = &chunk[0];
+            let mut escrow: Account<EscrowAccount> = Account::try_from(escrow_info)?;
+            require!(escrow.version == ESCROW_VERSION, EscrowError::LayoutVersionMismatch);
+            require_keys_eq!(escrow.provider, provider, EscrowError::Unauthorized);
+            let id_seed = escrow_id_seed(escrow.escrow_id);
+            let (expected, bump) =
+                Pubkey::find_program_address(&[ESCROW_SEED, provider.as_ref(), &id_seed], ctx.program_id);
+            require_keys_eq!(escrow_info.key(), expected, EscrowError::InvalidRemainingAccounts);
+
+            match escrow.state {
+                EscrowState::Created => {
+                    escrow.check_closable()?;
+                    escrow.state = EscrowState::Closed;
+                    escrow.cancelled_at = now;
+                    closed_listings += 1;
+
+                    emit!(EscrowCancelled {
+                        escrow: escrow.key(),
+                        provider,
+                        escrow_id: escrow.escrow_id,
+                        cancelled_at: now,
+                    });
+                }
+                EscrowState::Funded => {
+                    require!(!escrow.terms.settle_in_credits, EscrowError::InvalidSettlementMode);
+                    let escrow_token: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(&chunk[1])?;
+                    let renter_token: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(&chunk[2])?;
+                    require_keys_eq!(escrow_token.owner, escrow_info.key(), EscrowError::InvalidRemainingAccounts);
+                    require_keys_eq!(escrow_token.mint, escrow.token_mint, EscrowError::InvalidRemainingAccounts);
+                    require_keys_eq!(renter_token.owner, escrow.assigned_renter()?, EscrowError::InvalidRemainingAccounts);
+                    require_keys_eq!(renter_token.mint, escrow.token_mint, EscrowError::InvalidRemainingAccounts);
+                    let mint: InterfaceAccount<Mint> = InterfaceAccount::try_from(&chunk[3])?;
+                    require_keys_eq!(mint.key(), escrow.token_mint, EscrowError::InvalidRemainingAccounts);
+
+                    let amount = escrow.amount;
+                    escrow.enter_settlement()?;
+                    escrow.state = EscrowState::Cancelled;
+                    escrow.cancelled_at = now;
+                    escrow.allocate_settlement(0, amount);
+
+                    let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[bump]];
+                    let vault = Vault::new(&escrow_token, escrow_info, &mint, &ctx.accounts.token_program);
+                    let payouts = [(renter_token.to_account_info(), amount)];
+                    settle_from_vault(&mut escrow, &vault, &payouts, &[&seeds[..]])?;
+
+                    emit!(EscrowSettled {
+                        escrow: escrow.key(),
+                        provider,
+                        escrow_id: escrow.escrow_id,
+                        renter: escrow.renter,
+                        kind: SettlementKind::Refunded,
+                        accounting: settlement_accounting(&mint, amount, None)?,
+                    });
+
+                    ctx.accounts.provider_index.release_slot();
+                    if !escrow.terms.trial {
+                        ctx.accounts.provider_stats.record_refund();
+                    }
+                    refunded_escrows += 1;
+                    total_refunded = total_refunded.saturating_add(amount);
+                }
+                // Disputes stay with their arbiter; settled escrows need nothing
+                _ => continue,
+            }
+            escrow.exit(ctx.program_id)?;
+        }
+
+        emit!(ProviderWoundDown {
+            provider,
+            refunded_escrows,
+            closed_listings,
+            total_refunded,
+            timestamp: now,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            provider,
+            ActivityKind::ProviderWoundDown,
+            ctx.accounts.provider_index.key(),
+            Pubkey::default(),
+            total_refunded,
+        )?;
+
+        Ok(())
+    }
+
+    /// Retire an unfunded listing: renters may fund it until `sunset_at`, after which
+    /// it can be ended with `expire_listing`. A set sunset can only be brought forward.
+    /// Pending rental requests may still be accepted.
+    pub fn deprecate_listing(ctx: Context<DeprecateListing>, sunset_at: i64) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Created, EscrowError::InvalidState);
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            sunset_at > now && (escrow.sunset_at == 0 || sunset_at < escrow.sunset_at),
+            EscrowError::InvalidSunset
+        );
+        escrow.sunset_at = sunset_at;
+
+        emit!(ListingDeprecated {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            sunset_at,
+            timestamp: now,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::ListingDeprecated,
+            ctx.accounts.escrow_account.key(),
+            Pubkey::default(),
+            sunset_at as u64,
+        )?;
+
+        Ok(())
+    }
+
+    /// Provider withdraws a listing nobody has funded, closing the escrow and any
+    /// milestone plan or localizations passed along, and reclaiming their rent. Pending
+    /// rental requests must be withdrawn first, since their refunds read the listing,
+    /// and any provider bond released.
+    pub fn cancel_unfunded(ctx: Context<CancelUnfunded>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        escrow.check_closable()?;
+
+        emit!(EscrowCancelled {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            escrow_id: escrow.escrow_id,
+            cancelled_at: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::ListingCancelled,
+            ctx.accounts.escrow_account.key(),
+            Pubkey::default(),
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Permissionless: end an unfunded listing once its `funding_deadline` or sunset has passed
+    pub fn expire_listing(ctx: Context<ExpireListing>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Created, EscrowError::InvalidState);
+        let now = Clock::get()?.unix_timestamp;
+        require!(!escrow.funding_open(now), EscrowError::TimeoutNotElapsed);
+        escrow.state = EscrowState::Expired;
+        escrow.cancelled_at = now;
+
+        emit!(ListingExpired {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            funding_deadline: escrow.terms.funding_deadline,
+            sunset_at: escrow.sunset_at,
+            expired_at: now,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::ListingExpired,
+            ctx.accounts.escrow_account.key(),
+            Pubkey::default(),
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Provider locks the listing's `terms.provider_bond` in the escrow's bond vault, as
+    /// its terms require before anyone can fund it
+    pub fn fund_bond(ctx: Context<FundBond>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Created, EscrowError::InvalidState);
+        require!(escrow.terms.provider_bond > 0, EscrowError::InvalidState);
+        require!(!escrow.bond_held, EscrowError::BondAlreadyFunded);
+        escrow.bond_held = true;
+        let bond = escrow.terms.provider_bond;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.provider_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.bond_vault.to_account_info(),
+            authority: ctx.accounts.provider.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            bond,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        emit!(BondFunded {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider: ctx.accounts.escrow_account.provider,
+            amount: received_amount(&ctx.accounts.token_mint, bond)?,
+        });
+
+        Ok(())
+    }
+
+    /// Pay out the provider bond and close its vault. Permissionless once the escrow is
+    /// settled, dividing the bond as `EscrowAccount::bond_payouts` rules; the provider
+    /// may also withdraw it from a listing nobody has funded yet.
+    pub fn release_bond(ctx: Context<ReleaseBond>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.bond_held, EscrowError::BondNotFunded);
+        let held = ctx.accounts.bond_vault.amount;
+        let (to_provider, to_renter) = if escrow.state == EscrowState::Created {
+            require_keys_eq!(ctx.accounts.authority.key(), escrow.provider, EscrowError::Unauthorized);
+            (held, 0)
+        } else {
+            escrow.bond_payouts(held).ok_or(EscrowError::InvalidState)?
+        };
+        ctx.accounts.escrow_account.bond_held = false;
+
+        let escrow = &ctx.accounts.escrow_account;
+        let id_seed = escrow_id_seed(escrow.escrow_id);
+        let seeds = &[ESCROW_SEED, escrow.provider.as_ref(), &id_seed, &[ctx.bumps.escrow_account]];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        for (amount, to) in [
+            (to_provider, Some(&ctx.accounts.provider_token_account)),
+            (to_renter, ctx.accounts.renter_token_account.as_ref()),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            let to = to.ok_or(EscrowError::RenterTokenAccountRequired)?;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.bond_vault.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: ctx.accounts.escrow_account.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer),
+                amount,
+                ctx.accounts.token_mint.decimals,
+            )?;
+        }
+        harvest_withheld_fees(
+            cpi_program.clone(),
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.bond_vault.to_account_info(),
+        )?;
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.bond_vault.to_account_info(),
+            destination: ctx.accounts.provider.to_account_info(),
+            authority: ctx.accounts.escrow_account.to_account_info(),
+        };
+        token_interface::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+        emit!(BondReleased {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider: ctx.accounts.escrow_account.provider,
+            renter: ctx.accounts.escrow_account.renter,
+            to_provider,
+            to_renter,
+        });
+
+        Ok(())
+    }
+
+    /// Provider lists a skill once; renters then `open_rental` against it, each getting an
+    /// independent escrow. Listings fund immediately, so they cannot require provider
+    /// acceptance or a provider bond, or settle in credits.
+    pub fn create_listing(ctx: Context<CreateListing>, listing_id: u16, terms: EscrowTerms) -> Result<()> {
+        check_terms(&terms, &ctx.accounts.config, ctx.accounts.token_mint.decimals)?;
+        require!(
+            !terms.requires_provider_acceptance && terms.provider_bond == 0 && !terms.settle_in_credits,
+            EscrowError::InvalidSettlementMode
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let listing = &mut ctx.accounts.skill_listing;
+        listing.provider = ctx.accounts.provider.key();
+        listing.listing_id = listing_id;
+        listing.token_mint = ctx.accounts.token_mint.key();
+        listing.provider_token_account = ctx.accounts.provider_token_account.key();
+        listing.terms_hash = EscrowAccount::compute_terms_hash(&terms, &listing.token_mint)?;
+        listing.terms = terms;
+        listing.created_at = now;
+        listing.bump = ctx.bumps.skill_listing;
+
+        let index = &mut ctx.accounts.provider_index;
+        if index.provider == Pubkey::default() {
+            index.provider = ctx.accounts.provider.key();
+            index.bump = ctx.bumps.provider_index;
+        }
+        let stats = &mut ctx.accounts.provider_stats;
+        if stats.provider == Pubkey::default() {
+            stats.provider = ctx.accounts.provider.key();
+            stats.bump = ctx.bumps.provider_stats;
+        }
+        stats.record_initialization(Clock::get()?.epoch, ctx.accounts.config.max_initializations_per_epoch)?;
+
+        let listing = &ctx.accounts.skill_listing;
+        emit!(SkillListed {
+            listing: listing.key(),
+            provider: listing.provider,
+            listing_id,
+            token_mint: listing.token_mint,
+            skill_name: listing.terms.skill_name.as_str().to_string(),
+            price_usdc: listing.terms.price_usdc,
+            terms_hash: listing.terms_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Renter opens and funds a new rental of `provider`'s listing. The rental is an
+    /// ordinary escrow at `rental_escrow_id(listing_id, rentals_opened)`, settled by the
+    /// same instructions as any other.
+    pub fn open_rental(
+        ctx: Context<OpenRental>,
+        amount: u64,
+        overrides: FundOverrides,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        let listing = &ctx.accounts.skill_listing;
+        require!(listing.terms_hash == terms_hash, EscrowError::TermsHashMismatch);
+        let max_concurrent = ctx.accounts.provider_stats.concurrency_limit(
+            listing.terms.max_concurrent_rentals,
+            ctx.accounts.config.failure_streak_threshold,
+        );
+        ctx.accounts.provider_index.reserve_slot(max_concurrent)?;
+
+        let score = match &ctx.accounts.renter_reputation {
+            Some(agent) => Some(read_reputation_score(agent, &ctx.accounts.renter.key())?),
+            None => None,
+        };
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = &mut ctx.accounts.escrow_account;
+        escrow.provider = listing.provider;
+        escrow.escrow_id = rental_escrow_id(listing.listing_id, listing.rentals_opened);
+        escrow.renter = UNASSIGNED_RENTER;
+        escrow.token_mint = listing.token_mint;
+        escrow.provider_token_account = listing.provider_token_account;
+        escrow.terms = listing.terms.clone();
+        escrow.terms_hash = listing.terms_hash;
+        escrow.state = EscrowState::Created;
+        escrow.created_at = now;
+        escrow.version = ESCROW_VERSION;
+        require!(escrow.funding_open(now), EscrowError::ListingExpired);
+        let received = received_amount(&ctx.accounts.token_mint, amount)?;
+        fund_escrow(escrow, ctx.accounts.renter.key(), amount, received, &overrides, score)?;
+        ctx.accounts.epoch_stats.record_created();
+        ctx.accounts.epoch_stats.record_funding(received);
+        ctx.accounts
+            .engagement
+            .record(listing.provider, ctx.accounts.renter.key(), ctx.bumps.engagement, now);
+
+        let listing = &mut ctx.accounts.skill_listing;
+        listing.rentals_opened = listing.rentals_opened.checked_add(1).ok_or(EscrowError::InvalidState)?;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.renter_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.renter.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        let escrow = &ctx.accounts.escrow_account;
+        emit!(RentalOpened {
+            listing: ctx.accounts.skill_listing.key(),
+            escrow: escrow.key(),
+            escrow_id: escrow.escrow_id,
+            renter: escrow.renter,
+        });
+        emit!(EscrowFunded {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            escrow_id: escrow.escrow_id,
+            renter: escrow.renter,
+            token_mint: escrow.token_mint,
+            amount: escrow.amount,
+            funded_at: escrow.funded_at,
+            settle_in_credits: false,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowFunded,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            ctx.accounts.escrow_account.amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Provider stops offering a listing and reclaims its rent. Rentals already opened
+    /// are separate escrows and carry on.
+    pub fn close_listing(ctx: Context<CloseListing>) -> Result<()> {
+        emit!(ListingClosed {
+            listing: ctx.accounts.skill_listing.key(),
+            provider: ctx.accounts.provider.key(),
+            rentals_opened: ctx.accounts.skill_listing.rentals_opened,
+        });
+        Ok(())
+    }
+
+    /// Create the demo mint PDA, its own mint authority. Once per deployment; `devnet-demo` only.
+    pub fn create_demo_mint(ctx: Context<CreateDemoMint>) -> Result<()> {
+        gated!("devnet-demo", demo_handlers::create_demo_mint(ctx))
+    }
+
+    /// Mint up to `DEMO_AIRDROP_MAX` demo tokens to the signer's associated token
+    /// account, creating it if needed. Anyone may call it; `devnet-demo` only.
+    pub fn airdrop_demo_tokens(ctx: Context<AirdropDemoTokens>, amount: u64) -> Result<()> {
+        gated!("devnet-demo", demo_handlers::airdrop_demo_tokens(ctx, amount))
+    }
+
+    /// Create the global config PDA; the signer becomes the admin. `fee_treasury` owns
+    /// the token accounts protocol fees are paid into, one per mint.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, fee_bps: u16, fee_treasury: Pubkey) -> Result<()> {
+        require!(cfg!(feature = "fees") || fee_bps == 0, EscrowError::FeatureDisabled);
+        require!(Config::valid_fee(fee_bps, &fee_treasury), EscrowError::InvalidFee);
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.points_schedule = Vec::new();
+        config.redemption_enabled = false;
+        config.bump = ctx.bumps.config;
+        config.features = COMPILED_FEATURES;
+        config.fee_bps = fee_bps;
+        config.fee_treasury = fee_treasury;
+        config.allowed_mints = Vec::new();
+        Ok(())
+    }
+
+    /// Admin sets the protocol fee taken from provider payouts, in basis points
+    pub fn update_fee(ctx: Context<AdminConfig>, fee_bps: u16) -> Result<()> {
+        gated!("fees", fee_handlers::update_fee(ctx, fee_bps))
+    }
+
+    /// Admin moves protocol fees to another treasury owner
+    pub fn update_treasury(ctx: Context<AdminConfig>, fee_treasury: Pubkey) -> Result<()> {
+        gated!("fees", fee_handlers::update_treasury(ctx, fee_treasury))
+    }
+
+    /// Admin refreshes the recorded feature set after upgrading the program binary
+    pub fn sync_config_features(ctx: Context<AdminConfig>) -> Result<()> {
+        ctx.accounts.config.features = COMPILED_FEATURES;
+        Ok(())
+    }
+
+    /// Admin replaces the points emission schedule
+    pub fn set_points_schedule(
+        ctx: Context<AdminConfig>,
+        schedule: Vec<EmissionTier>,
+        redemption_enabled: bool,
+    ) -> Result<()> {
+        require!(schedule.len() <= MAX_EMISSION_TIERS, EscrowError::InvalidEmissionSchedule);
+        require!(
+            schedule.windows(2).all(|w| w[0].starts_at < w[1].starts_at),
+            EscrowError::InvalidEmissionSchedule
+        );
+        let config = &mut ctx.accounts.config;
+        config.points_schedule = schedule;
+        config.redemption_enabled = redemption_enabled;
+        Ok(())
+    }
+
+    /// Admin configures the release screening hook; `Pubkey::default()` disables it
+    pub fn set_screening(
+        ctx: Context<AdminConfig>,
+        screener: Pubkey,
+        threshold: u64,
+        delay_seconds: i64,
+    ) -> Result<()> {
+        require!(delay_seconds >= 0, EscrowError::InvalidScreeningConfig);
+        let config = &mut ctx.accounts.config;
+        config.screener = screener;
+        config.screening_threshold = threshold;
+        config.screening_delay_seconds = delay_seconds;
+        Ok(())
+    }
+
+    /// Admin marks a provider as verified, exempting it from the listing throttle
+    pub fn set_provider_verified(ctx: Context<SetProviderVerified>, verified: bool) -> Result<()> {
+        let stats = &mut ctx.accounts.provider_stats;
+        if stats.provider == Pubkey::default() {
+            stats.provider = ctx.accounts.provider.key();
+            stats.bump = ctx.bumps.provider_stats;
+        }
+        stats.verified = verified;
+        Ok(())
+    }
+
+    /// Admin sets how many escrows an unverified provider may initialize per epoch (0 = unlimited)
+    pub fn set_listing_throttle(ctx: Context<AdminConfig>, max_per_epoch: u16) -> Result<()> {
+        ctx.accounts.config.max_initializations_per_epoch = max_per_epoch;
+        Ok(())
+    }
+
+    /// Admin sets the price cap for trial listings (0 disables new trials)
+    pub fn set_trial_limit(ctx: Context<AdminConfig>, max_amount: u64) -> Result<()> {
+        ctx.accounts.config.trial_max_amount = max_amount;
+        Ok(())
+    }
+
+    /// Admin sets how many refunds in a row throttle a provider to one rental at a time (0 = off)
+    pub fn set_failure_streak_threshold(ctx: Context<AdminConfig>, threshold: u16) -> Result<()> {
+        ctx.accounts.config.failure_streak_threshold = threshold;
+        Ok(())
+    }
+
+    /// Admin sets how long arbiters have to rule on disputes opened from now on (0 = no
+    /// deadline), and whether default judgment releases to the provider or refunds the renter
+    pub fn set_arbitration_window(
+        ctx: Context<AdminConfig>,
+        arbitration_window_seconds: i64,
+        default_release: bool,
+    ) -> Result<()> {
+        require!(arbitration_window_seconds >= 0, EscrowError::InvalidArbitrationWindow);
+        let config = &mut ctx.accounts.config;
+        config.arbitration_window_seconds = arbitration_window_seconds;
+        config.default_release = default_release;
+        Ok(())
+    }
+
+    /// Admin adds `mint` to the allowlist checked when escrows are initialized and funded.
+    /// Until the first mint is allowed, any mint is accepted.
+    pub fn allow_mint(ctx: Context<AdminConfig>, mint: Pubkey) -> Result<()> {
+        let allowed = &mut ctx.accounts.config.allowed_mints;
+        if !allowed.contains(&mint) {
+            require!(allowed.len() < MAX_ALLOWED_MINTS, EscrowError::TooManyAllowedMints);
+            allowed.push(mint);
+        }
+        Ok(())
+    }
+
+    /// Admin removes `mint` from the allowlist; escrows already funded in it still settle.
+    /// Removing the last mint reopens the program to any mint.
+    pub fn disallow_mint(ctx: Context<AdminConfig>, mint: Pubkey) -> Result<()> {
+        ctx.accounts.config.allowed_mints.retain(|allowed| *allowed != mint);
+        Ok(())
+    }
+
+    /// Admin sets the tax rate stated on invoices issued from now on
+    pub fn set_tax_bps(ctx: Context<AdminConfig>, tax_bps: u16) -> Result<()> {
+        require!(tax_bps <= BPS_DENOMINATOR, EscrowError::InvalidTaxRate);
+        ctx.accounts.config.tax_bps = tax_bps;
+        Ok(())
+    }
+
+    /// Admin sets how `compute_risk` weighs its factors; all-zero weights disable it
+    pub fn set_risk_weights(ctx: Context<AdminConfig>, weights: RiskWeights) -> Result<()> {
+        require!(weights.mature_age_seconds >= 0, EscrowError::InvalidRiskWeights);
+        ctx.accounts.config.risk_weights = weights;
+        Ok(())
+    }
+
+    /// Admin creates the protocol's `EpochStats`, counting from the current epoch. Every
+    /// instruction that creates, funds, disputes or charges a fee on an escrow updates it.
+    pub fn initialize_epoch_stats(ctx: Context<InitializeEpochStats>) -> Result<()> {
+        let clock = Clock::get()?;
+        let stats = &mut ctx.accounts.epoch_stats;
+        stats.epoch = clock.epoch;
+        stats.started_at = clock.unix_timestamp;
+        stats.bump = ctx.bumps.epoch_stats;
+        Ok(())
+    }
+
+    /// Permissionless: once the cluster is past the epoch being counted, freeze the
+    /// counters into that epoch's `EpochSnapshot` and start counting the current one
+    pub fn rollover_epoch(ctx: Context<RolloverEpoch>) -> Result<()> {
+        let clock = Clock::get()?;
+        let stats = &mut ctx.accounts.epoch_stats;
+        let (epoch, started_at) = (stats.epoch, stats.started_at);
+        let counters = stats.roll_over(clock.epoch, clock.unix_timestamp)?;
+
+        let snapshot = &mut ctx.accounts.snapshot;
+        snapshot.epoch = epoch;
+        snapshot.next_epoch = clock.epoch;
+        snapshot.started_at = started_at;
+        snapshot.ended_at = clock.unix_timestamp;
+        snapshot.counters = counters;
+        snapshot.bump = ctx.bumps.snapshot;
+
+        emit!(EpochRolledOver {
+            epoch,
+            next_epoch: clock.epoch,
+            counters,
+            rolled_over_by: ctx.accounts.payer.key(),
+        });
+        Ok(())
+    }
+
+    /// Admin queues a recovery action for a funded or disputed escrow the normal flows
+    /// cannot settle: a counterparty's accounts are gone, the arbiter is unreachable, or
+    /// the account state is corrupt. It can run after `RECOVERY_TIMELOCK_SECONDS`;
+    /// `proof_hash` commits to the incident report or settlement proof it rests on.
+    pub fn queue_recovery(ctx: Context<QueueRecovery>, kind: RecoveryKind, proof_hash: [u8; 32]) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(
+            matches!(escrow.state, EscrowState::Funded | EscrowState::Disputed),
+            EscrowError::InvalidState
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let recovery = &mut ctx.accounts.recovery;
+        recovery.escrow = escrow.key();
+        recovery.kind = kind;
+        recovery.proof_hash = proof_hash;
+        recovery.queued_by = ctx.accounts.admin.key();
+        recovery.queued_at = now;
+        recovery.executable_at = now.saturating_add(RECOVERY_TIMELOCK_SECONDS);
+        recovery.bump = ctx.bumps.recovery;
+
+        emit!(RecoveryQueued {
+            escrow: recovery.escrow,
+            kind,
+            proof_hash,
+            queued_by: recovery.queued_by,
+            executable_at: recovery.executable_at,
+        });
+        Ok(())
+    }
+
+    /// Admin drops a queued recovery action, e.g. because the parties settled meanwhile
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>) -> Result<()> {
+        emit!(RecoveryCancelled {
+            escrow: ctx.accounts.recovery.escrow,
+            kind: ctx.accounts.recovery.kind,
+            cancelled_by: ctx.accounts.admin.key(),
+        });
+        Ok(())
+    }
+
+    /// Admin runs a queued `RecoveryKind::Expire` once its timelock has passed
+    pub fn force_expire(ctx: Context<ForceRecovery>) -> Result<()> {
+        require!(ctx.accounts.recovery.kind == RecoveryKind::Expire, EscrowError::RecoveryMismatch);
+        execute_recovery(ctx)
+    }
+
+    /// Admin runs a queued `RecoveryKind::SettleWithProof` once its timelock has passed
+    pub fn force_settle_with_proof(ctx: Context<ForceRecovery>) -> Result<()> {
+        require!(
+            matches!(ctx.accounts.recovery.kind, RecoveryKind::SettleWithProof { .. }),
+            EscrowError::RecoveryMismatch
+        );
+        execute_recovery(ctx)
+    }
+
+    /// Snapshot a settled rental into an immutable `Invoice` for the parties' books.
+    /// Anyone may pay for it; there is one invoice per rental.
+    pub fn issue_invoice(ctx: Context<IssueInvoice>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        let settled_at = match escrow.state {
+            EscrowState::Completed => escrow.completed_at,
+            EscrowState::Cancelled => escrow.cancelled_at,
+            _ => return err!(EscrowError::InvalidState),
+        };
+        require!(escrow.provider_payout > 0 || escrow.renter_refund > 0, EscrowError::InvalidState);
+
+        let tax_bps = ctx.accounts.config.tax_bps;
+        let invoice = &mut ctx.accounts.invoice;
+        invoice.escrow = escrow.key();
+        invoice.provider = escrow.provider;
+        invoice.renter = escrow.renter;
+        invoice.token_mint = escrow.token_mint;
+        invoice.terms_hash = escrow.terms_hash;
+        invoice.amount = escrow.amount;
+        invoice.provider_amount = escrow.provider_payout;
+        invoice.renter_refund = escrow.renter_refund;
+        invoice.fee_amount = escrow.fee_amount;
+        invoice.tax_bps = tax_bps;
+        invoice.tax_amount = Invoice::tax_on(escrow.provider_payout, tax_bps);
+        invoice.funded_at = escrow.funded_at;
+        invoice.settled_at = settled_at;
+        invoice.issued_at = Clock::get()?.unix_timestamp;
+        invoice.bump = ctx.bumps.invoice;
+
+        emit!(InvoiceIssued {
+            invoice: invoice.key(),
+            escrow: invoice.escrow,
+            provider: invoice.provider,
+            renter: invoice.renter,
+            provider_amount: invoice.provider_amount,
+            tax_amount: invoice.tax_amount,
+        });
+        Ok(())
+    }
+
+    /// Screener clears a funded escrow for release before its screening delay runs out
+    pub fn approve_release(ctx: Context<ApproveRelease>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+        escrow.screening_approved = true;
+
+        emit!(ReleaseScreened {
+            escrow: escrow.key(),
+            screener: ctx.accounts.screener.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Open a points account for the signing wallet
+    pub fn open_points_account(ctx: Context<OpenPointsAccount>) -> Result<()> {
+        let points = &mut ctx.accounts.points;
+        points.owner = ctx.accounts.owner.key();
+        points.bump = ctx.bumps.points;
+        Ok(())
+    }
+
+    /// Redeem points; emits `PointsRedeemed` for the rewards program to act on
+    pub fn redeem_points(ctx: Context<RedeemPoints>, amount: u64) -> Result<()> {
+        require!(ctx.accounts.config.redemption_enabled, EscrowError::RedemptionDisabled);
+        let points = &mut ctx.accounts.points;
+        points.balance = points.balance.checked_sub(amount).ok_or(EscrowError::InsufficientPoints)?;
+        points.redeemed = points.redeemed.saturating_add(amount);
+
+        emit!(PointsRedeemed {
+            owner: points.owner,
+            amount,
+            remaining: points.balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Whether the rental period is over, from when `refund_after_timeout` can refund
+    /// an undelivered rental
+    pub fn check_timeout(ctx: Context<CheckTimeout>) -> Result<bool> {
+        require!(ctx.accounts.escrow_account.state == EscrowState::Funded, EscrowError::InvalidState);
+        Ok(Clock::get()?.unix_timestamp >= ctx.accounts.escrow_account.rental_ends_at())
+    }
+
+    /// Return an arbiter's resolution record via return data (simulate to read)
+    pub fn get_arbiter_stats(ctx: Context<GetArbiterStats>) -> Result<ArbiterStatsData> {
+        let stats = &ctx.accounts.arbiter_stats;
+        Ok(ArbiterStatsData {
+            arbiter: stats.arbiter,
+            resolutions: stats.resolutions,
+            mean_latency_seconds: stats.mean_latency_seconds(),
+            max_latency_seconds: stats.max_latency_seconds,
+            last_resolved_at: stats.last_resolved_at,
+        })
+    }
+
+    /// Return what `renter` would agree to by funding the listing now with `overrides`,
+    /// via return data (simulate to read): both parties' reputation, the arbiter's record,
+    /// anything gating the funding, the fee and the effective timeline. Overrides out of
+    /// the listing's policy fail here as they would when funding.
+    pub fn preview_engagement(ctx: Context<PreviewEngagement>, overrides: FundOverrides) -> Result<EngagementPreview> {
+        let renter = ctx.accounts.renter.key();
+        let provider = ctx.accounts.escrow_account.provider;
+        let renter_score = match &ctx.accounts.renter_reputation {
+            Some(agent) => Some(read_reputation_score(agent, &renter)?),
+            None => None,
+        };
+        let mut preview = EngagementPreview::new(
+            ctx.accounts.escrow_account.key(),
+            &ctx.accounts.escrow_account,
+            renter,
+            renter_score,
+            &overrides,
+            &ctx.accounts.config,
+            &ctx.accounts.provider_index,
+            &ctx.accounts.provider_stats,
+            Clock::get()?.unix_timestamp,
+        )?;
+        if let Some(agent) = &ctx.accounts.provider_reputation {
+            preview.provider_score = Some(read_reputation_score(agent, &provider)?);
+        }
+        if let Some(stats) = &ctx.accounts.arbiter_stats {
+            require_keys_eq!(stats.arbiter, preview.arbiter, EscrowError::InvalidArbiter);
+            preview.arbiter_resolutions = stats.resolutions;
+            preview.arbiter_mean_latency_seconds = stats.mean_latency_seconds();
+        }
+        Ok(preview)
+    }
+
+    /// Return the provider's counterparty risk on the listing via return data (simulate
+    /// to read), scored with the config's `RiskWeights` from their dispute and refund
+    /// rates, the age of their reputation account and how much of the price their bond
+    /// covers. Without a reputation account the provider is treated as brand new.
+    pub fn compute_risk(ctx: Context<ComputeRisk>) -> Result<RiskAssessment> {
+        let provider = ctx.accounts.escrow_account.provider;
+        let account_age_seconds = match &ctx.accounts.provider_reputation {
+            Some(agent) => {
+                let created_at = read_reputation_created_at(agent, &provider)?;
+                Clock::get()?.unix_timestamp.saturating_sub(created_at).max(0)
+            }
+            None => 0,
+        };
+        RiskAssessment::new(
+            ctx.accounts.escrow_account.key(),
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.provider_stats,
+            account_age_seconds,
+            &ctx.accounts.config.risk_weights,
+        )
+    }
+
+    /// Dry run of `initialize_escrow` for `provider`: runs its account and argument checks
+    /// and returns the first failure as a `Validation` (simulate to read). Changes nothing.
+    pub fn validate_initialize(
+        ctx: Context<ValidateInitialize>,
+        terms: EscrowTerms,
+        escrow_id: u64,
+    ) -> Result<Validation> {
+        Ok(Validation::of(ctx.accounts.check(&terms, escrow_id)))
+    }
+
+    /// Dry run of `accept_escrow` by `renter`: runs its account, listing, capacity,
+    /// balance and funding-rule checks and returns the first failure as a `Validation`
+    /// (simulate to read). Changes nothing.
+    pub fn validate_fund(
+        ctx: Context<ValidateFund>,
+        amount: u64,
+        overrides: FundOverrides,
+        terms_hash: [u8; 32],
+    ) -> Result<Validation> {
+        Ok(Validation::of(ctx.accounts.check(amount, &overrides, &terms_hash)))
+    }
+
+    /// Dry run of `complete_task`, or of `release_with_key` when `key_ciphertext` is
+    /// given: runs their delivery, deferred-payment, screening and fee treasury checks
+    /// and returns the first failure as a `Validation` (simulate to read). Changes nothing.
+    pub fn validate_release(ctx: Context<ValidateRelease>, key_ciphertext: Option<Vec<u8>>) -> Result<Validation> {
+        Ok(Validation::of(ctx.accounts.check(key_ciphertext.as_deref())))
+    }
+
+    /// Permissionless: commit to a settled escrow's final state for cross-chain consumers.
+    /// The `EscrowSnapshotted` event carries the snapshot and `hash(snapshot.message())`;
+    /// a relayer forwards it with the transaction's slot, and the commitment is returned.
+    pub fn snapshot_escrow(ctx: Context<SnapshotEscrow>) -> Result<[u8; 32]> {
+        let escrow = &ctx.accounts.escrow_account;
+        let snapshot = escrow
+            .snapshot(escrow.key(), Clock::get()?.slot)
+            .ok_or(EscrowError::InvalidState)?;
+        let commitment = snapshot.commitment()?;
+
+        emit!(EscrowSnapshotted { snapshot, commitment });
+
+        Ok(commitment)
+    }
+
+    /// Record that `renter` accepted the escrow's current terms off-chain, e.g. through a
+    /// wallet's message signing, so a dispute can show it even when someone else funded.
+    /// Anyone may submit it. The preceding instruction must be an Ed25519 verification
+    /// of `signature` by `renter` over `TermsAcceptance::message` for these terms and
+    /// `expires_at`, which must not have passed. A later acceptance replaces the record.
+    pub fn record_acceptance(ctx: Context<RecordAcceptance>, expires_at: i64, signature: [u8; 64]) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        let renter = ctx.accounts.renter.key();
+        require!(!escrow.has_renter() || escrow.renter == renter, EscrowError::Unauthorized);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < expires_at, EscrowError::AcceptanceExpired);
+
+        let ix_sysvar = ctx.accounts.instructions.to_account_info();
+        let current = instructions_sysvar::load_current_index_checked(&ix_sysvar)? as usize;
+        require!(current > 0, EscrowError::InvalidAcceptanceSignature);
+        let verify_ix = instructions_sysvar::load_instruction_at_checked(current - 1, &ix_sysvar)?;
+        let message = TermsAcceptance::message(&escrow.key(), &escrow.provider, &renter, &escrow.terms_hash, expires_at);
+        require!(
+            verified_ed25519_signature(&verify_ix, &renter, &message) == Some(signature),
+            EscrowError::InvalidAcceptanceSignature
+        );
+
+        let acceptance = &mut ctx.accounts.acceptance;
+        acceptance.escrow = escrow.key();
+        acceptance.renter = renter;
+        acceptance.terms_hash = escrow.terms_hash;
+        acceptance.expires_at = expires_at;
+        acceptance.signature = signature;
+        acceptance.recorded_at = now;
+        acceptance.bump = ctx.bumps.acceptance;
+
+        emit!(TermsAccepted {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            renter,
+            terms_hash: escrow.terms_hash,
+            expires_at,
+        });
+        Ok(())
+    }
+
+    /// Permissionless: rewrite an escrow left by an older release in the current layout
+    /// so the other instructions accept it again. Fields the old layout lacked take their
+    /// defaults, the account is resized to `EscrowAccount::LEN` and `payer` tops up its
+    /// rent exemption if the new size needs more.
+    pub fn migrate_escrow(ctx: Context<MigrateEscrow>) -> Result<()> {
+        let info = ctx.accounts.escrow_account.to_account_info();
+        let mut escrow = Box::<EscrowAccount>::default();
+        let from = {
+            let data = info.try_borrow_data()?;
+            require!(data.get(..8) == Some(&EscrowAccount::DISCRIMINATOR[..]), EscrowError::UnknownEscrowLayout);
+            let layout = layout::EscrowLayout::from_len(data.len()).ok_or(EscrowError::UnknownEscrowLayout)?;
+            layout::read_escrow(layout, &data[8..], &mut escrow).map_err(|_| EscrowError::UnknownEscrowLayout)?;
+            require!(escrow.version != ESCROW_VERSION, EscrowError::AlreadyMigrated);
+            layout
+        };
+
+        let rent = Rent::get()?.minimum_balance(EscrowAccount::LEN);
+        let shortfall = rent.saturating_sub(info.lamports());
+        if shortfall > 0 {
+            let cpi_accounts = system_program::Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: info.clone(),
+            };
+            system_program::transfer(
+                CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+                shortfall,
+            )?;
+        }
+        info.realloc(EscrowAccount::LEN, false)?;
+        escrow.version = ESCROW_VERSION;
+        escrow.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(EscrowMigrated {
+            escrow: info.key(),
+            provider: escrow.provider,
+            from_layout: from as u8,
+            version: ESCROW_VERSION,
+        });
+        Ok(())
+    }
+}
+
+// ========== Fee Handlers ==========
+
+/// Fee administration, compiled only with the `fees` feature
+#[cfg(feature = "fees")]
+mod fee_handlers {
+    use super::*;
+
+    pub fn update_fee(ctx: Context<AdminConfig>, fee_bps: u16) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(Config::valid_fee(fee_bps, &config.fee_treasury), EscrowError::InvalidFee);
+        config.fee_bps = fee_bps;
+        Ok(())
+    }
+
+    pub fn update_treasury(ctx: Context<AdminConfig>, fee_treasury: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(Config::valid_fee(config.fee_bps, &fee_treasury), EscrowError::InvalidFee);
+        config.fee_treasury = fee_treasury;
+        Ok(())
+    }
+}
+
+// ========== Streaming Handlers ==========
+
+/// Streamed payouts, compiled only with the `streaming` feature
+#[cfg(feature = "streaming")]
+mod streaming_handlers {
+    use super::*;
+
+    /// Like a milestone, a streamed payment leaves `escrow.amount`, so refunds, rulings and
+    /// the final release only ever see what is still held
+    pub fn claim_streamed(ctx: Context<ClaimStreamed>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(escrow.terms.stream_payment, EscrowError::InvalidSettlementMode);
+        require!(escrow.deferred_balance == 0, EscrowError::DeferredBalanceOutstanding);
+        let now = Clock::get()?.unix_timestamp;
+        if ctx.accounts.config.requires_screening(escrow.amount) {
+            require!(
+                escrow.screening_approved
+                    || now >= escrow.funded_at.saturating_add(ctx.accounts.config.screening_delay_seconds),
+                EscrowError::ScreeningPending
+            );
+        }
+        let amount = escrow.streamable_at(now);
+        require!(amount > 0, EscrowError::NothingStreamed);
+        // Leave enough behind for any SLA penalty owed to the renter at release
+        let remaining = escrow.amount.checked_sub(amount).ok_or(EscrowError::InsufficientFunds)?;
+        require!(remaining >= escrow.sla_penalty()?, EscrowError::InsufficientFunds);
+
+        let fee = ctx.accounts.config.fee_on(amount);
+        let treasury = match fee {
+            0 => None,
+            _ => Some(fee_treasury_account(
+                &ctx.accounts.config,
+                ctx.accounts.fee_token_account.as_ref(),
+                &ctx.accounts.token_mint.key(),
+            )?),
+        };
+
+        escrow.enter_settlement()?;
+        escrow.amount = remaining;
+        escrow.claimed_amount = escrow.claimed_amount.saturating_add(amount);
+        escrow.fee_amount = escrow.fee_amount.saturating_add(fee);
+        escrow.provider_payout = escrow.provider_payout.saturating_add(amount - fee);
+        ctx.accounts.epoch_stats.record_fee(fee);
+        let provider = escrow.provider;
+        let id_seed = escrow_id_seed(escrow.escrow_id);
+        if !escrow.terms.trial {
+            ctx.accounts.provider_stats.record_milestone(amount)?;
+        }
+
+        let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[ctx.bumps.escrow_account]];
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let mut payouts = vec![(ctx.accounts.provider_token_account.to_account_info(), amount - fee)];
+        if let Some(treasury) = treasury {
+            payouts.push((treasury.to_account_info(), fee));
+        }
+        settle_from_vault(&mut ctx.accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+        emit!(StreamClaimed {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider,
+            amount,
+            claimed_amount: ctx.accounts.escrow_account.claimed_amount,
+            remaining,
+        });
+        if let Some(treasury) = treasury {
+            emit!(ProtocolFeeCharged {
+                escrow: ctx.accounts.escrow_account.key(),
+                treasury_token_account: treasury.key(),
+                amount: fee,
+            });
+        }
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            provider,
+            ActivityKind::StreamedPayoutClaimed,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            amount,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Pay out a due recovery action and close the escrow. The settlement lock is taken
+/// without being checked: a lock left set on a corrupt account is one of the things
+/// recovery exists to clear. No protocol fee is charged.
+fn execute_recovery(ctx: Context<ForceRecovery>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let recovery = &ctx.accounts.recovery;
+    require!(recovery.is_due(now), EscrowError::TimeoutNotElapsed);
+    let (kind, proof_hash) = (recovery.kind, recovery.proof_hash);
+    let escrow = &mut ctx.accounts.escrow_account;
+    let previous_state = escrow.state.clone();
+    require!(
+        matches!(previous_state, EscrowState::Funded | EscrowState::Disputed),
+        EscrowError::InvalidState
+    );
+    let (to_provider, to_renter) = kind
+        .payouts(escrow.disputed_amount(), ctx.accounts.escrow_token_account.amount)
+        .ok_or(EscrowError::InvalidRecovery)?;
+
+    escrow.settlement_lock = true;
+    let post_release = escrow.vesting_halted_at > 0;
+    let settlement = match (to_provider, to_renter) {
+        (0, _) => SettlementKind::Refunded,
+        (_, 0) => SettlementKind::Released,
+        _ => SettlementKind::Split,
+    };
+    match kind {
+        RecoveryKind::Expire => {
+            escrow.state = EscrowState::Expired;
+            escrow.cancelled_at = now;
+        }
+        RecoveryKind::SettleWithProof { .. } if to_renter == 0 => {
+            escrow.state = EscrowState::Completed;
+            escrow.completed_at = now;
+        }
+        RecoveryKind::SettleWithProof { .. } => {
+            escrow.state = EscrowState::Cancelled;
+            escrow.cancelled_at = now;
+        }
+    }
+    escrow.allocate_settlement(to_provider, to_renter);
+    let provider = escrow.provider;
+    let id_seed = escrow_id_seed(escrow.escrow_id);
+
+    if !post_release {
+        ctx.accounts.provider_index.release_slot();
+        if !ctx.accounts.escrow_account.terms.trial {
+            match to_renter {
+                0 => ctx.accounts.provider_stats.record_release(to_provider)?,
+                _ => ctx.accounts.provider_stats.record_refund(),
+            }
+        }
+    }
+
+    let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[ctx.bumps.escrow_account]];
+    let vault = Vault::new(
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.escrow_account,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_program,
+    );
+    let payouts = [
+        (ctx.accounts.provider_token_account.to_account_info(), to_provider),
+        (ctx.accounts.renter_token_account.to_account_info(), to_renter),
+    ];
+    settle_from_vault(&mut ctx.accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+    emit!(EscrowSettled {
+        escrow: ctx.accounts.escrow_account.key(),
+        provider,
+        escrow_id: ctx.accounts.escrow_account.escrow_id,
+        renter: ctx.accounts.escrow_account.renter,
+        kind: settlement,
+        accounting: settlement_accounting(&ctx.accounts.token_mint, to_provider + to_renter, None)?,
+    });
+    emit!(RecoveryExecuted {
+        escrow: ctx.accounts.escrow_account.key(),
+        kind,
+        proof_hash,
+        previous_state,
+        to_provider,
+        to_renter,
+        executed_by: ctx.accounts.admin.key(),
+    });
+    Ok(())
+}
+
+/// Treasury token account receiving a fee in `mint`; it must belong to `Config::fee_treasury`
+fn fee_treasury_account<'a, 'info>(
+    config: &Config,
+    account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    mint: &Pubkey,
+) -> Result<&'a InterfaceAccount<'info, TokenAccount>> {
+    let account = account.ok_or(EscrowError::FeeTreasuryRequired)?;
+    require_keys_eq!(account.owner, config.fee_treasury, EscrowError::InvalidFeeTreasury);
+    require_keys_eq!(account.mint, *mint, EscrowError::InvalidFeeTreasury);
+    Ok(account)
+}
+
+// ========== Demo Handlers ==========
+
+/// Demo-token faucet for devnet onboarding, compiled only with the `devnet-demo`
+/// feature. Never enable it for a deployment holding real funds.
+#[cfg(feature = "devnet-demo")]
+mod demo_handlers {
+    use super::*;
+
+    pub fn create_demo_mint(ctx: Context<CreateDemoMint>) -> Result<()> {
+        msg!("Demo mint {} created", ctx.accounts.demo_mint.key());
+        Ok(())
+    }
+
+    pub fn airdrop_demo_tokens(ctx: Context<AirdropDemoTokens>, amount: u64) -> Result<()> {
+        require!(amount > 0 && amount <= DEMO_AIRDROP_MAX, EscrowError::DemoAirdropTooLarge);
+        let bump = [ctx.bumps.demo_mint];
+        let signer: &[&[&[u8]]] = &[&[DEMO_MINT_SEED, &bump]];
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::MintTo {
+                    mint: ctx.accounts.demo_mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.demo_mint.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )
+    }
+}
+
+// ========== Dispute Handlers ==========
+
+/// Dispute instructions, compiled only with the `disputes` feature; the program
+/// entry points return `FeatureDisabled` without it
+#[cfg(feature = "disputes")]
+mod dispute_handlers {
+    use super::*;
+
+    pub fn dispute(ctx: Context<Dispute>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(!escrow.terms.settle_in_credits, EscrowError::InvalidSettlementMode);
+        let caller = ctx.accounts.authority.key();
+        let now = Clock::get()?.unix_timestamp;
+        match escrow.state {
+            EscrowState::Funded => {
+                require!(
+                    caller == escrow.provider || escrow.is_renter_or_watchtower(&caller),
+                    EscrowError::Unauthorized
+                );
+                require!(now <= escrow.dispute_deadline(), EscrowError::DisputeWindowClosed);
+            }
+            // Post-release: the renter freezes what has not vested yet
+            EscrowState::Vesting => {
+                require!(escrow.is_renter_or_watchtower(&caller), EscrowError::Unauthorized);
+                require!(now < escrow.vesting_ends_at(), EscrowError::DisputeWindowClosed);
+                escrow.vesting_halted_at = now;
+            }
+            _ => return err!(EscrowError::InvalidState),
+        }
+
+        let post_release = escrow.vesting_halted_at > 0;
+        escrow.state = EscrowState::Disputed;
+        escrow.disputed_at = now;
+        escrow.arbitration_deadline = ctx.accounts.config.arbitration_deadline(now);
+        ctx.accounts.epoch_stats.record_dispute();
+        if !escrow.terms.trial {
+            ctx.accounts.provider_stats.record_dispute();
+        }
+
+        emit!(EscrowDisputed {
+            escrow: escrow.key(),
+            provider: escrow.provider,
+            escrow_id: escrow.escrow_id,
+            renter: escrow.renter,
+            arbiter: escrow.arbiter,
+            raised_by: caller,
+            amount: escrow.disputed_amount(),
+            post_release,
+            disputed_at: now,
+        });
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.escrow_account.provider,
+            ActivityKind::EscrowDisputed,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            ctx.accounts.escrow_account.amount,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn claim_default_resolution(ctx: Context<ClaimDefaultResolution>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Disputed, EscrowError::InvalidState);
+        require!(escrow.arbitration_deadline > 0, EscrowError::NoArbitrationDeadline);
+        let now = Clock::get()?.unix_timestamp;
+        require!(escrow.arbitration_lapsed(now), EscrowError::TimeoutNotElapsed);
+        let release = ctx.accounts.config.default_release;
+        escrow.enter_settlement()?;
+        let amount = escrow.disputed_amount();
+        let post_release = escrow.vesting_halted_at > 0;
+        let (fee, to_provider, to_renter) = if release {
+            escrow.state = EscrowState::Completed;
+            escrow.completed_at = now;
+            // A post-release ruling covers what is left of a payout already charged at release
+            let fee = if post_release { 0 } else { ctx.accounts.config.fee_on(amount) };
+            (fee, amount - fee, 0)
+        } else {
+            escrow.state = EscrowState::Cancelled;
+            escrow.cancelled_at = now;
+            (0, 0, amount)
+        };
+        escrow.allocate_settlement(to_provider, to_renter);
+        escrow.fee_amount = escrow.fee_amount.saturating_add(fee);
+        ctx.accounts.epoch_stats.record_fee(fee);
+        let outcome = match (release, escrow.deferred_balance > 0) {
+            (true, true) => ResolutionOutcome::Defaulted,
+            (true, false) => ResolutionOutcome::ReleasedToProvider,
+            (false, _) => ResolutionOutcome::RefundedToRenter,
+        };
+        let provider = escrow.provider;
+        let arbiter = escrow.arbiter;
+        let deadline = escrow.arbitration_deadline;
+        let id_seed = escrow_id_seed(escrow.escrow_id);
+        let escrow_key = escrow.key();
+        // No ruling was made, so there is no ruling hash and nothing for the arbiter's stats
+        let resolved = escrow.record_resolution(escrow_key, arbiter, outcome, [0u8; 32]);
+
+        if !post_release {
+            ctx.accounts.provider_index.release_slot();
+            if !ctx.accounts.escrow_account.terms.trial {
+                match release {
+                    true => ctx.accounts.provider_stats.record_release(amount)?,
+                    false => ctx.accounts.provider_stats.record_refund(),
+                }
+            }
+        }
+        let treasury = match fee {
+            0 => None,
+            _ => Some(fee_treasury_account(
+                &ctx.accounts.config,
+                ctx.accounts.fee_token_account.as_ref(),
+                &ctx.accounts.token_mint.key(),
+            )?),
+        };
+
+        let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[ctx.bumps.escrow_account]];
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let mut payouts = vec![
+            (ctx.accounts.provider_token_account.to_account_info(), to_provider),
+            (ctx.accounts.renter_token_account.to_account_info(), to_renter),
+        ];
+        if let Some(treasury) = treasury {
+            payouts.push((treasury.to_account_info(), fee));
+        }
+        settle_from_vault(&mut ctx.accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+        emit!(EscrowSettled {
+            escrow: ctx.accounts.escrow_account.key(),
+            provider,
+            escrow_id: ctx.accounts.escrow_account.escrow_id,
+            renter: ctx.accounts.escrow_account.renter,
+            kind: if release { SettlementKind::Released } else { SettlementKind::Refunded },
+            accounting: settlement_accounting(
+                &ctx.accounts.token_mint,
+                amount,
+                ctx.accounts.price_oracle.as_ref(),
+            )?,
+        });
+        emit!(resolved);
+        emit!(DefaultJudgment {
+            escrow: ctx.accounts.escrow_account.key(),
+            arbiter,
+            arbitration_deadline: deadline,
+            released: release,
+            claimed_by: ctx.accounts.cranker.key(),
+        });
+        if let Some(treasury) = treasury {
+            emit!(ProtocolFeeCharged {
+                escrow: ctx.accounts.escrow_account.key(),
+                treasury_token_account: treasury.key(),
+                amount: fee,
+            });
+        }
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            provider,
+            ActivityKind::DisputeResolved,
+            ctx.accounts.escrow_account.key(),
+            ctx.accounts.escrow_account.renter,
+            to_provider,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn submit_evidence(ctx: Context<SubmitEvidence>, evidence_uri: String, evidence_hash: [u8; 32]) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Disputed, EscrowError::InvalidState);
+        let caller = ctx.accounts.authority.key();
+        require!(
+            caller == escrow.provider || escrow.is_renter_or_watchtower(&caller),
+            EscrowError::Unauthorized
+        );
+
+        let evidence = &mut ctx.accounts.evidence;
+        if evidence.escrow == Pubkey::default() {
+            evidence.escrow = escrow.key();
+            evidence.bump = ctx.bumps.evidence;
+        }
+        evidence.start_dispute(escrow.disputed_at);
+        let now = Clock::get()?.unix_timestamp;
+        evidence.add(caller, evidence_uri.clone(), evidence_hash, now)?;
+
+        emit!(EvidenceSubmitted {
+            escrow: escrow.key(),
+            submitted_by: caller,
+            index: (evidence.entries.len() - 1) as u8,
+            evidence_uri,
+            evidence_hash,
+            submitted_at: now,
+        });
+        Ok(())
+    }
+
+    pub fn declare_no_conflict(ctx: Context<DeclareNoConflict>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Disputed, EscrowError::InvalidState);
+        let signer = ctx.accounts.arbiter.key();
+        let authorized = match &ctx.accounts.council {
+            Some(council) => council.key() == escrow.arbiter && council.member_index(&signer).is_some(),
+            None => signer == escrow.arbiter,
+        };
+        require!(authorized, EscrowError::Unauthorized);
+        require!(ctx.accounts.arbiter_stats.conflicts == 0, EscrowError::ArbiterConflict);
+        // Each pair's record only exists once a rental between them was funded
+        let engagements = [
+            &ctx.accounts.arbiter_to_renter,
+            &ctx.accounts.renter_to_arbiter,
+            &ctx.accounts.arbiter_to_provider,
+            &ctx.accounts.provider_to_arbiter,
+        ];
+        require!(
+            engagements.iter().all(|engagement| engagement.data_is_empty()),
+            EscrowError::ArbiterConflict
+        );
+
+        let arbiter = ctx.accounts.arbiter.key();
+        ctx.accounts.arbiter_stats.claim(arbiter, ctx.bumps.arbiter_stats);
+        let declaration = &mut ctx.accounts.declaration;
+        // A voided declaration cannot be renewed for the same dispute
+        require!(
+            !(declaration.violated && declaration.disputed_at == escrow.disputed_at),
+            EscrowError::ArbiterConflict
+        );
+        let now = Clock::get()?.unix_timestamp;
+        declaration.escrow = escrow.key();
+        declaration.arbiter = arbiter;
+        declaration.provider = escrow.provider;
+        declaration.renter = escrow.renter;
+        declaration.disputed_at = escrow.disputed_at;
+        declaration.declared_at = now;
+        declaration.violated = false;
+        declaration.bump = ctx.bumps.declaration;
+
+        emit!(ConflictDeclared {
+            escrow: escrow.key(),
+            arbiter,
+            declared_at: now,
+        });
+        Ok(())
+    }
+
+    pub fn report_conflict(ctx: Context<ReportConflict>) -> Result<()> {
+        let declaration = &mut ctx.accounts.declaration;
+        require!(!declaration.violated, EscrowError::InvalidState);
+        require!(
+            declaration.is_contradicted_by(&ctx.accounts.engagement),
+            EscrowError::NoConflict
+        );
+        declaration.violated = true;
+        let stats = &mut ctx.accounts.arbiter_stats;
+        stats.conflicts = stats.conflicts.saturating_add(1);
+
+        emit!(ConflictReported {
+            escrow: declaration.escrow,
+            arbiter: declaration.arbiter,
+            engagement: ctx.accounts.engagement.key(),
+            reported_by: ctx.accounts.reporter.key(),
+        });
+        Ok(())
+    }
+
+    pub fn create_council(
+        ctx: Context<CreateCouncil>,
+        council_id: u32,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(ArbiterCouncil::is_valid(&members, threshold), EscrowError::InvalidCouncil);
+        let council = &mut ctx.accounts.council;
+        council.creator = ctx.accounts.creator.key();
+        council.council_id = council_id;
+        council.members = members.clone();
+        council.threshold = threshold;
+        council.bump = ctx.bumps.council;
+
+        emit!(CouncilCreated {
+            council: council.key(),
+            members,
+            threshold,
+        });
+        Ok(())
+    }
+
+    pub fn propose_resolution(
+        ctx: Context<ProposeResolution>,
+        outcome: ResolutionOutcome,
+        ruling_hash: [u8; 32],
+    ) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+        if proposal.escrow == Pubkey::default() {
+            proposal.escrow = escrow.key();
+            proposal.council = ctx.accounts.council.key();
+            proposal.bump = ctx.bumps.proposal;
+        }
+        proposal.propose(escrow.disputed_at, outcome, ruling_hash, ctx.accounts.member.key(), now)?;
+        record_council_approval(ctx)
+    }
+
+    pub fn approve_resolution(
+        ctx: Context<ProposeResolution>,
+        outcome: ResolutionOutcome,
+        ruling_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts
+                .proposal
+                .proposes(ctx.accounts.escrow_account.disputed_at, outcome, ruling_hash),
+            EscrowError::ProposalMismatch
+        );
+        record_council_approval(ctx)
+    }
+
+    /// Add the signing member's approval to the pending proposal
+    fn record_council_approval(ctx: Context<ProposeResolution>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Disputed, EscrowError::InvalidState);
+        require!(ctx.accounts.declaration.covers(escrow), EscrowError::ConflictNotDeclared);
+        let member = ctx.accounts.member.key();
+        let index = ctx
+            .accounts
+            .council
+            .member_index(&member)
+            .ok_or(EscrowError::NotCouncilMember)?;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.approve(index);
+
+        emit!(ResolutionApproved {
+            escrow: escrow.key(),
+            council: proposal.council,
+            member,
+            outcome: proposal.outcome,
+            ruling_hash: proposal.ruling_hash,
+            approvals: proposal.approvals.count_ones() as u8,
+        });
+        Ok(())
+    }
+
+    pub fn execute_resolution<'info>(ctx: Context<'_, '_, 'info, 'info, ExecuteResolution<'info>>) -> Result<()> {
+        let escrow_key = ctx.accounts.escrow_account.key();
+        let council = &ctx.accounts.council;
+        let proposal = &ctx.accounts.proposal;
+        require!(
+            ctx.accounts.escrow_account.state == EscrowState::Disputed,
+            EscrowError::InvalidState
+        );
+        require!(
+            proposal.disputed_at == ctx.accounts.escrow_account.disputed_at,
+            EscrowError::ProposalMismatch
+        );
+
+        // Count each approving member once, and only while their declaration stands
+        let mut counted = 0u16;
+        for info in ctx.remaining_accounts {
+            let declaration: Account<ArbiterDeclaration> = Account::try_from(info)?;
+            require_keys_eq!(declaration.escrow, escrow_key, EscrowError::InvalidRemainingAccounts);
+            let Some(index) = council.member_index(&declaration.arbiter) else {
+                continue;
+            };
+            if proposal.approved_by(index) && declaration.covers(&ctx.accounts.escrow_account) {
+                counted |= 1 << index;
+            }
+        }
+        require!(counted.count_ones() >= council.threshold as u32, EscrowError::ThresholdNotMet);
+
+        let (outcome, ruling_hash) = (proposal.outcome, proposal.ruling_hash);
+        let council_key = council.key();
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let accounts = &mut *ctx.accounts;
+        settle_ruling(
+            RulingAccounts {
+                escrow: &mut accounts.escrow_account,
+                escrow_bump: ctx.bumps.escrow_account,
+                vault,
+                provider_token_account: Some(accounts.provider_token_account.to_account_info()),
+                renter_token_account: Some(accounts.renter_token_account.to_account_info()),
+                fee_token_account: accounts.fee_token_account.as_ref(),
+                token_mint: &accounts.token_mint,
+                price_oracle: accounts.price_oracle.as_ref(),
+                config: Some(&accounts.config),
+                epoch_stats: Some(&mut accounts.epoch_stats),
+                provider_index: &mut accounts.provider_index,
+                provider_stats: &mut accounts.provider_stats,
+                provider_points: accounts.provider_points.as_deref_mut(),
+                arbiter_stats: &mut accounts.arbiter_stats,
+                arbiter_stats_bump: ctx.bumps.arbiter_stats,
+                activity: &accounts.activity,
+                activity_bumps: &ctx.bumps.activity,
+            },
+            council_key,
+            outcome,
+            ruling_hash,
+        )
+    }
+
+    pub fn commit_dispute_randomness(ctx: Context<CommitDisputeRandomness>, commitment: [u8; 32]) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Disputed, EscrowError::InvalidState);
+        let party = escrow
+            .dispute_party(&ctx.accounts.party.key())
+            .ok_or(EscrowError::Unauthorized)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now < escrow.disputed_at.saturating_add(RANDOMNESS_COMMIT_SECONDS),
+            EscrowError::RandomnessPhaseClosed
+        );
+
+        let randomness = &mut ctx.accounts.randomness;
+        if randomness.escrow == Pubkey::default() {
+            randomness.escrow = escrow.key();
+            randomness.disputed_at = escrow.disputed_at;
+            randomness.bump = ctx.bumps.randomness;
+        } else if randomness.disputed_at != escrow.disputed_at {
+            // A previous dispute on this PDA; start over
+            **randomness = DisputeRandomness {
+                escrow: escrow.key(),
+                disputed_at: escrow.disputed_at,
+                bump: ctx.bumps.randomness,
+                ..Default::default()
+            };
+        }
+        require!(commitment != [0u8; 32], EscrowError::InvalidCommitment);
+        require!(randomness.commitments[party] == [0u8; 32], EscrowError::AlreadyCommitted);
+        randomness.commitments[party] = commitment;
+        Ok(())
+    }
+
+    pub fn reveal_dispute_randomness(ctx: Context<RevealDisputeRandomness>, secret: [u8; 32]) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        let party_key = ctx.accounts.party.key();
+        let party = escrow.dispute_party(&party_key).ok_or(EscrowError::Unauthorized)?;
+        let now = Clock::get()?.unix_timestamp;
+        let commit_end = escrow.disputed_at.saturating_add(RANDOMNESS_COMMIT_SECONDS);
+
+        let randomness = &mut ctx.accounts.randomness;
+        require!(randomness.disputed_at == escrow.disputed_at, EscrowError::InvalidState);
+        require!(!randomness.finalized, EscrowError::InvalidState);
+        require!(
+            randomness.all_committed() || now >= commit_end,
+            EscrowError::RandomnessPhaseClosed
+        );
+        require!(
+            now < commit_end.saturating_add(RANDOMNESS_REVEAL_SECONDS),
+            EscrowError::RandomnessPhaseClosed
+        );
+        require!(!randomness.revealed[party], EscrowError::AlreadyCommitted);
+        require!(
+            DisputeRandomness::commitment(&secret, &party_key) == randomness.commitments[party],
+            EscrowError::InvalidCommitment
+        );
+
+        for (acc, byte) in randomness.accumulator.iter_mut().zip(secret) {
+            *acc ^= byte;
+        }
+        randomness.revealed[party] = true;
+        Ok(())
+    }
+
+    pub fn finalize_dispute_randomness(ctx: Context<FinalizeDisputeRandomness>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        let now = Clock::get()?.unix_timestamp;
+        let reveal_end = escrow
+            .disputed_at
+            .saturating_add(RANDOMNESS_COMMIT_SECONDS)
+            .saturating_add(RANDOMNESS_REVEAL_SECONDS);
+
+        let randomness = &mut ctx.accounts.randomness;
+        require!(randomness.disputed_at == escrow.disputed_at, EscrowError::InvalidState);
+        require!(!randomness.finalized, EscrowError::InvalidState);
+        require!(
+            randomness.all_revealed() || now >= reveal_end,
+            EscrowError::RandomnessPhaseClosed
+        );
+        require!(randomness.revealed.iter().any(|r| *r), EscrowError::InvalidCommitment);
+
+        let slot_hashes = ctx.accounts.slot_hashes.try_borrow_data()?;
+        // Layout: u64 entry count, then (slot: u64, hash: [u8; 32]) newest first
+        let recent = slot_hashes.get(16..48).ok_or(EscrowError::InvalidState)?;
+        randomness.seed = hashv(&[&randomness.accumulator, recent]).to_bytes();
+        randomness.finalized = true;
+
+        emit!(DisputeRandomnessFinalized {
+            escrow: randomness.escrow,
+            seed: randomness.seed,
+        });
+        Ok(())
+    }
+
+    pub fn resolve_dispute_release(ctx: Context<ResolveDisputeRelease>, ruling_hash: [u8; 32]) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Disputed, EscrowError::InvalidState);
+        require!(ctx.accounts.declaration.covers(escrow), EscrowError::ConflictNotDeclared);
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let accounts = &mut *ctx.accounts;
+        settle_ruling(
+            RulingAccounts {
+                escrow: &mut accounts.escrow_account,
+                escrow_bump: ctx.bumps.escrow_account,
+                vault,
+                provider_token_account: Some(accounts.provider_token_account.to_account_info()),
+                renter_token_account: None,
+                fee_token_account: accounts.fee_token_account.as_ref(),
+                token_mint: &accounts.token_mint,
+                price_oracle: accounts.price_oracle.as_ref(),
+                config: Some(&accounts.config),
+                epoch_stats: Some(&mut accounts.epoch_stats),
+                provider_index: &mut accounts.provider_index,
+                provider_stats: &mut accounts.provider_stats,
+                provider_points: accounts.provider_points.as_deref_mut(),
+                arbiter_stats: &mut accounts.arbiter_stats,
+                arbiter_stats_bump: ctx.bumps.arbiter_stats,
+                activity: &accounts.activity,
+                activity_bumps: &ctx.bumps.activity,
+            },
+            accounts.arbiter.key(),
+            ResolutionOutcome::ReleasedToProvider,
+            ruling_hash,
+        )
+    }
+
+    pub fn resolve_dispute_refund(ctx: Context<ResolveDisputeRefund>, ruling_hash: [u8; 32]) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Disputed, EscrowError::InvalidState);
+        require!(ctx.accounts.declaration.covers(escrow), EscrowError::ConflictNotDeclared);
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let accounts = &mut *ctx.accounts;
+        settle_ruling(
+            RulingAccounts {
+                escrow: &mut accounts.escrow_account,
+                escrow_bump: ctx.bumps.escrow_account,
+                vault,
+                provider_token_account: None,
+                renter_token_account: Some(accounts.renter_token_account.to_account_info()),
+                fee_token_account: None,
+                token_mint: &accounts.token_mint,
+                price_oracle: accounts.price_oracle.as_ref(),
+                config: None,
+                epoch_stats: None,
+                provider_index: &mut accounts.provider_index,
+                provider_stats: &mut accounts.provider_stats,
+                provider_points: None,
+                arbiter_stats: &mut accounts.arbiter_stats,
+                arbiter_stats_bump: ctx.bumps.arbiter_stats,
+                activity: &accounts.activity,
+                activity_bumps: &ctx.bumps.activity,
+            },
+            accounts.arbiter.key(),
+            ResolutionOutcome::RefundedToRenter,
+            ruling_hash,
+        )
+    }
+
+    pub fn resolve_dispute_split(
+        ctx: Context<ResolveDisputeSplit>,
+        provider_bps: u16,
+        ruling_hash: [u8; 32],
+    ) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+        require!(escrow.state == EscrowState::Disputed, EscrowError::InvalidState);
+        require!(ctx.accounts.declaration.covers(escrow), EscrowError::ConflictNotDeclared);
+        let vault = Vault::new(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.escrow_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        );
+        let accounts = &mut *ctx.accounts;
+        settle_ruling(
+            RulingAccounts {
+                escrow: &mut accounts.escrow_account,
+                escrow_bump: ctx.bumps.escrow_account,
+                vault,
+                provider_token_account: Some(accounts.provider_token_account.to_account_info()),
+                renter_token_account: Some(accounts.renter_token_account.to_account_info()),
+                fee_token_account: accounts.fee_token_account.as_ref(),
+                token_mint: &accounts.token_mint,
+                price_oracle: accounts.price_oracle.as_ref(),
+                config: Some(&accounts.config),
+                epoch_stats: Some(&mut accounts.epoch_stats),
+                provider_index: &mut accounts.provider_index,
+                provider_stats: &mut accounts.provider_stats,
+                provider_points: None,
+                arbiter_stats: &mut accounts.arbiter_stats,
+                arbiter_stats_bump: ctx.bumps.arbiter_stats,
+                activity: &accounts.activity,
+                activity_bumps: &ctx.bumps.activity,
+            },
+            accounts.arbiter.key(),
+            ResolutionOutcome::Split(provider_bps),
+            ruling_hash,
+        )
+    }
+
+    /// What a ruling settles through, borrowed from the resolving handler's context.
+    /// A payee account may be omitted only when the ruling pays it nothing, and the
+    /// fee accounts only when it charges no fee.
+    struct RulingAccounts<'a, 'info> {
+        escrow: &'a mut Account<'info, EscrowAccount>,
+        escrow_bump: u8,
+        vault: Vault<'info>,
+        provider_token_account: Option<AccountInfo<'info>>,
+        renter_token_account: Option<AccountInfo<'info>>,
+        fee_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+        token_mint: &'a InterfaceAccount<'info, Mint>,
+        price_oracle: Option<&'a UncheckedAccount<'info>>,
+        config: Option<&'a Account<'info, Config>>,
+        epoch_stats: Option<&'a mut Account<'info, EpochStats>>,
+        provider_index: &'a mut Account<'info, ProviderIndex>,
+        provider_stats: &'a mut Account<'info, ProviderStats>,
+        provider_points: Option<&'a mut PointsAccount>,
+        arbiter_stats: &'a mut Account<'info, ArbiterStats>,
+        arbiter_stats_bump: u8,
+        activity: &'a ActivityAccounts<'info>,
+        activity_bumps: &'a ActivityAccountsBumps,
+    }
+
+    /// Settle a disputed escrow as `arbiter` (an arbiter or council) ruled: charge the
+    /// fee on the provider's share, pay the shares out, record the ruling and the
+    /// parties' stats, and accrue points on a release. A post-release dispute only moves what is left
+    /// of a payout already charged and counted at release.
+    fn settle_ruling(
+        accounts: RulingAccounts<'_, '_>,
+        arbiter: Pubkey,
+        outcome: ResolutionOutcome,
+        ruling_hash: [u8; 32],
+    ) -> Result<()> {
+        let RulingAccounts {
+            escrow,
+            escrow_bump,
+            vault,
+            provider_token_account,
+            renter_token_account,
+            fee_token_account,
+            token_mint,
+            price_oracle,
+            config,
+            epoch_stats,
+            provider_index,
+            provider_stats,
+            provider_points,
+            arbiter_stats,
+            arbiter_stats_bump,
+            activity,
+            activity_bumps,
+        } = accounts;
+        let now = Clock::get()?.unix_timestamp;
+        let amount = escrow.disputed_amount();
+        let post_release = escrow.vesting_halted_at > 0;
+        let (fee, to_provider, to_renter) = escrow.ruling_shares(config.map(|config| &**config), outcome)?;
+        let kind = match outcome {
+            ResolutionOutcome::RefundedToRenter => SettlementKind::Refunded,
+            ResolutionOutcome::Split(_) => SettlementKind::Split,
+            _ => SettlementKind::Released,
+        };
+
+        escrow.enter_settlement()?;
+        if kind == SettlementKind::Refunded {
+            escrow.state = EscrowState::Cancelled;
+            escrow.cancelled_at = now;
+        } else {
+            escrow.state = EscrowState::Completed;
+            escrow.completed_at = now;
+        }
+        escrow.allocate_settlement(to_provider, to_renter);
+        escrow.fee_amount = escrow.fee_amount.saturating_add(fee);
+        if let Some(epoch_stats) = epoch_stats {
+            epoch_stats.record_fee(fee);
+        }
+        // Releasing ends the rental, so any deferred balance can no longer be paid
+        let outcome = match outcome {
+            ResolutionOutcome::ReleasedToProvider if escrow.deferred_balance > 0 => ResolutionOutcome::Defaulted,
+            outcome => outcome,
+        };
+        let escrow_key = escrow.key();
+        let (provider, renter) = (escrow.provider, escrow.renter);
+        let id_seed = escrow_id_seed(escrow.escrow_id);
+        let resolved = escrow.record_resolution(escrow_key, arbiter, outcome, ruling_hash);
+        arbiter_stats.record_resolution(arbiter, arbiter_stats_bump, resolved.disputed_at, now);
+
+        if !post_release {
+            provider_index.release_slot();
+            if !escrow.terms.trial {
+                match kind {
+                    SettlementKind::Refunded => provider_stats.record_refund(),
+                    _ => provider_stats.record_release(to_provider + fee)?,
+                }
+            }
+        }
+        let accrues_points = kind == SettlementKind::Released && !post_release;
+        let treasury = match fee {
+            0 => None,
+            _ => Some(fee_treasury_account(
+                config.ok_or(EscrowError::FeeTreasuryRequired)?,
+                fee_token_account,
+                &token_mint.key(),
+            )?),
+        };
+
+        let mut payouts = Vec::new();
+        for (account, share) in [(provider_token_account, to_provider), (renter_token_account, to_renter)] {
+            if share > 0 {
+                payouts.push((account.ok_or(EscrowError::InvalidState)?, share));
+            }
+        }
+        if let Some(treasury) = treasury {
+            payouts.push((treasury.to_account_info(), fee));
+        }
+        let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[escrow_bump]];
+        settle_from_vault(escrow, &vault, &payouts, &[&seeds[..]])?;
+
+        emit!(EscrowSettled {
+            escrow: escrow_key,
+            provider,
+            escrow_id: escrow.escrow_id,
+            renter,
+            kind,
+            accounting: settlement_accounting(token_mint, amount, price_oracle)?,
+        });
+        emit!(resolved);
+        if let Some(treasury) = treasury {
+            emit!(ProtocolFeeCharged {
+                escrow: escrow_key,
+                treasury_token_account: treasury.key(),
+                amount: fee,
+            });
+        }
+
+        if accrues_points {
+            accrue_points(config.map(|config| &**config), provider_points, amount)?;
+        }
+
+        activity.log(
+            activity_bumps,
+            provider,
+            ActivityKind::DisputeResolved,
+            escrow_key,
+            renter,
+            to_provider,
+        )
+    }
+}
+
+/// Checks of `initialize_escrow`'s arguments, shared with `validate_initialize`
+fn check_initialize(terms: &EscrowTerms, escrow_id: u64, config: &Config, mint_decimals: u8) -> Result<()> {
+    require!(escrow_id & RENTAL_ESCROW_ID_FLAG == 0, EscrowError::ReservedEscrowId);
+    check_terms(terms, config, mint_decimals)
+}
+
+/// Listing terms every new or edited listing must satisfy
+fn check_terms(terms: &EscrowTerms, config: &Config, mint_decimals: u8) -> Result<()> {
+    require!(terms.price_decimals == mint_decimals, EscrowError::MintDecimalsMismatch);
+    require!(terms.override_policy.is_valid(terms), EscrowError::InvalidOverridePolicy);
+    require!(terms.sla.as_ref().is_none_or(SlaTerms::is_valid), EscrowError::InvalidSlaTerms);
+    require!(terms.text_is_valid(), EscrowError::InvalidTermsText);
+    require!(terms.category.len() <= MAX_CATEGORY_LEN, EscrowError::CategoryTooLong);
+    require!(
+        terms.funding_rule.as_ref().is_none_or(|rule| rule.is_valid() && !terms.settle_in_credits),
+        EscrowError::InvalidFundingRule
+    );
+    require!(
+        !((terms.requires_provider_acceptance || terms.provider_bond > 0) && terms.settle_in_credits),
+        EscrowError::InvalidSettlementMode
+    );
+    require!(
+        !terms.stream_payment || (terms.duration_seconds > 0 && !terms.settle_in_credits),
+        EscrowError::InvalidSettlementMode
+    );
+    require!(terms.trial_is_valid(config.trial_max_amount), EscrowError::InvalidTrialTerms);
+    require!(terms.credit_price_is_valid(mint_decimals), EscrowError::CreditPriceTooHigh);
+    require!(
+        terms.vesting_seconds >= 0 && (terms.vesting_seconds == 0 || !terms.settle_in_credits),
+        EscrowError::InvalidVestingTerms
+    );
+    require!(
+        terms.funding_deadline == 0 || terms.funding_deadline > Clock::get()?.unix_timestamp,
+        EscrowError::ListingExpired
+    );
+    Ok(())
+}
+
+
+/// Move a `Created` escrow to `Funded` for `renter`, applying the renter's overrides and
+/// the listing's funding rule for reputation `score`. The caller moves the tokens;
+/// `amount` is what the renter paid toward the price and `received` what reached the
+/// vault after any transfer fee, which is all the escrow can later pay out.
+fn fund_escrow(
+    escrow: &mut EscrowAccount,
+    renter: Pubkey,
+    amount: u64,
+    received: u64,
+    overrides: &FundOverrides,
+    score: Option<i64>,
+) -> Result<()> {
+    require!(escrow.terms.accepts_funding(amount), EscrowError::AmountMismatch);
+    require!(escrow.terms.provider_bond == 0 || escrow.bond_held, EscrowError::BondNotFunded);
+    apply_fund_overrides(escrow, overrides)?;
+    let now = Clock::get()?.unix_timestamp;
+    escrow.deferred_balance = 0;
+    escrow.deferred_due_at = 0;
+    if let Some(rule) = escrow.terms.funding_rule.clone() {
+        let price = escrow.terms.price_usdc;
+        let upfront = (price as u128 * rule.upfront_bps(score) as u128 / BPS_DENOMINATOR as u128) as u64;
+        require!(amount >= upfront, EscrowError::InsufficientFunds);
+        escrow.deferred_balance = price.saturating_sub(amount);
+        if escrow.deferred_balance > 0 {
+            escrow.deferred_due_at = now.saturating_add(rule.deferred_due_seconds);
+        }
+    }
+    escrow.begin_rental(renter, received, now);
+    Ok(())
+}
+
+/// Apply the renter's fund-time overrides within the ranges the provider pre-authorized
+fn apply_fund_overrides(escrow: &mut EscrowAccount, overrides: &FundOverrides) -> Result<()> {
+    let policy = &escrow.terms.override_policy;
+
+    escrow.dispute_window_seconds = match overrides.dispute_window_seconds {
+        Some(window) => {
+            require!(
+                (policy.min_dispute_window_seconds..=policy.max_dispute_window_seconds).contains(&window),
+                EscrowError::OverrideOutOfRange
+            );
+            window
+        }
+        None => escrow.terms.dispute_window_seconds,
+    };
+    escrow.review_window_seconds = match overrides.review_window_seconds {
+        Some(window) => {
+            require!(
+                (policy.min_review_window_seconds..=policy.max_review_window_seconds).contains(&window),
+                EscrowError::OverrideOutOfRange
+            );
+            window
+        }
+        None => escrow.terms.review_window_seconds,
+    };
+    escrow.arbiter = match overrides.arbiter {
+        Some(arbiter) => {
+            require!(policy.allow_arbiter_override, EscrowError::OverrideOutOfRange);
+            arbiter
+        }
+        None => escrow.terms.arbiter,
+    };
+    require!(
+        !overrides.auto_release || escrow.auto_release_offered,
+        EscrowError::OverrideOutOfRange
+    );
+    escrow.auto_release = overrides.auto_release;
+
+    Ok(())
+}
+
+/// Credit the provider's points account per the active emission tier, if both the
+/// config and the points account were supplied
+fn accrue_points(config: Option<&Config>, points: Option<&mut PointsAccount>, basis: u64) -> Result<()> {
+    let (Some(config), Some(points)) = (config, points) else {
+        return Ok(());
+    };
+    let now = Clock::get()?.unix_timestamp;
+    let Some(tier) = config.points_schedule.iter().rev().find(|t| t.starts_at <= now) else {
+        return Ok(());
+    };
+    let earned = (basis as u128 * tier.points_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    points.balance = points.balance.saturating_add(earned);
+    points.lifetime_earned = points.lifetime_earned.saturating_add(earned);
+    Ok(())
+}
+
+fn refund_to_renter(ctx: Context<CancelEscrow>) -> Result<()> {
+    require!(ctx.accounts.escrow_account.state == EscrowState::Funded, EscrowError::InvalidState);
+    require!(!ctx.accounts.escrow_account.terms.settle_in_credits, EscrowError::InvalidSettlementMode);
+    let escrow = &mut ctx.accounts.escrow_account;
+    escrow.enter_settlement()?;
+    escrow.state = EscrowState::Cancelled;
+    escrow.cancelled_at = Clock::get()?.unix_timestamp;
+    let amount = escrow.amount;
+    escrow.allocate_settlement(0, amount);
+    let provider = escrow.provider;
+    let id_seed = escrow_id_seed(escrow.escrow_id);
+
+    ctx.accounts.provider_index.release_slot();
+    if !ctx.accounts.escrow_account.terms.trial {
+        ctx.accounts.provider_stats.record_refund();
+    }
+
+    let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[ctx.bumps.escrow_account]];
+    let mut vault = Vault::new(
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.escrow_account,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_program,
+    );
+    let mut payouts = Vec::new();
+    if let Some(renter) = &ctx.accounts.renter {
+        require!(is_wrapped_sol(&ctx.accounts.token_mint.key()), EscrowError::NotWrappedSol);
+        vault.unwrap_into = Some(renter.to_account_info());
+    } else {
+        let renter_token_account = ctx
+            .accounts
+            .renter_token_account
+            .as_ref()
+            .ok_or(EscrowError::RenterTokenAccountRequired)?;
+        payouts.push((renter_token_account.to_account_info(), amount));
+    }
+    settle_from_vault(&mut ctx.accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+    emit!(EscrowSettled {
+        escrow: ctx.accounts.escrow_account.key(),
+        provider: ctx.accounts.escrow_account.provider,
+        escrow_id: ctx.accounts.escrow_account.escrow_id,
+        renter: ctx.accounts.escrow_account.renter,
+        kind: SettlementKind::Refunded,
+        accounting: settlement_accounting(
+            &ctx.accounts.token_mint,
+            amount,
+            ctx.accounts.price_oracle.as_ref(),
+        )?,
+    });
+
+    ctx.accounts.activity.log(
+        &ctx.bumps.activity,
+        ctx.accounts.escrow_account.provider,
+        ActivityKind::EscrowRefunded,
+        ctx.accounts.escrow_account.key(),
+        ctx.accounts.escrow_account.renter,
+        ctx.accounts.escrow_account.amount,
+    )?;
+
+    Ok(())
+}
+
+fn release_to_provider(accounts: &mut ReleaseAccounts, bumps: &ReleaseAccountsBumps) -> Result<()> {
+    let escrow = &mut accounts.escrow_account;
+    let now = Clock::get()?.unix_timestamp;
+    escrow.check_releasable(&accounts.config, false, now)?;
+    // Past due, the provider takes what was funded and the default is published
+    if escrow.deferred_balance > 0 {
+        emit!(DeferredPaymentDefaulted {
+            escrow: escrow.key(),
+            renter: escrow.renter,
+            outstanding: escrow.deferred_balance,
+            due_at: escrow.deferred_due_at,
+        });
+    }
+    escrow.enter_settlement()?;
+    escrow.state = EscrowState::Completed;
+    escrow.completed_at = now;
+    // The hash and chain stop changing here, so the accepted submission stays on record
+    emit!(DeliverableAccepted {
+        escrow: escrow.key(),
+        renter: escrow.renter,
+        revision: escrow.revisions,
+        deliverable_hash: escrow.deliverable_hash,
+        chain: escrow.deliverable_chain,
+        accepted_at: now,
+    });
+    let penalty = escrow.sla_penalty()?;
+    let amount = escrow.amount.checked_sub(penalty).ok_or(EscrowError::InsufficientFunds)?;
+    let fee = accounts.config.fee_on(amount);
+    let payout = amount - fee;
+    escrow.allocate_settlement(payout, penalty);
+    escrow.fee_amount = escrow.fee_amount.saturating_add(fee);
+    accounts.epoch_stats.record_fee(fee);
+    let provider = escrow.provider;
+    let id_seed = escrow_id_seed(escrow.escrow_id);
+    // Vesting payouts stay in the vault for `claim_vested`; the fee is taken up front
+    let vesting = escrow.terms.vesting_seconds > 0;
+    if vesting {
+        escrow.state = EscrowState::Vesting;
+        escrow.vesting_total = payout;
+        escrow.vesting_started_at = now;
+        emit!(VestingStarted {
+            escrow: escrow.key(),
+            provider,
+            total: payout,
+            ends_at: escrow.vesting_ends_at(),
+        });
+    }
+    let treasury = match fee {
+        0 => None,
+        _ => Some(fee_treasury_account(
+            &accounts.config,
+            accounts.fee_token_account.as_ref(),
+            &accounts.token_mint.key(),
+        )?),
+    };
+
+    accounts.provider_index.release_slot();
+    if !accounts.escrow_account.terms.trial {
+        accounts.provider_stats.record_release(amount)?;
+    }
+
+    let seeds = &[ESCROW_SEED, provider.as_ref(), &id_seed, &[bumps.escrow_account]];
+    require!(
+        accounts.provider.is_none() || is_wrapped_sol(&accounts.token_mint.key()),
+        EscrowError::NotWrappedSol
+    );
+    let mut vault = Vault::new(
+        &accounts.escrow_token_account,
+        &accounts.escrow_account,
+        &accounts.token_mint,
+        &accounts.token_program,
+    );
+    // An unwrapped payout leaves the vault last, once the fee and any penalty are out
+    vault.unwrap_into = accounts.provider.as_ref().filter(|_| !vesting).map(|p| p.to_account_info());
+    let mut payouts = Vec::new();
+    if !vesting && vault.unwrap_into.is_none() {
+        payouts.push((accounts.provider_token_account.to_account_info(), payout));
+    }
+    if let Some(treasury) = treasury {
+        payouts.push((treasury.to_account_info(), fee));
+    }
+    // SLA penalty goes back to the renter
+    if penalty > 0 {
+        let renter_token_account = accounts
+            .renter_token_account
+            .as_ref()
+            .ok_or(EscrowError::RenterTokenAccountRequired)?;
+        payouts.push((renter_token_account.to_account_info(), penalty));
+    }
+    settle_from_vault(&mut accounts.escrow_account, &vault, &payouts, &[&seeds[..]])?;
+
+    emit!(EscrowSettled {
+        escrow: accounts.escrow_account.key(),
+        provider: accounts.escrow_account.provider,
+        escrow_id: accounts.escrow_account.escrow_id,
+        renter: accounts.escrow_account.renter,
+        kind: SettlementKind::Released,
+        accounting: settlement_accounting(
+            &accounts.token_mint,
+            amount,
+            accounts.price_oracle.as_ref(),
+        )?,
+    });
+    if let Some(treasury) = treasury {
+        emit!(ProtocolFeeCharged {
+            escrow: accounts.escrow_account.key(),
+            treasury_token_account: treasury.key(),
+            amount: fee,
+        });
+    }
+
+    accrue_points(
+        Some(&accounts.config),
+        accounts.provider_points.as_deref_mut(),
+        amount,
+    )?;
+
+    accounts.activity.log(
+        &bumps.activity,
+        accounts.escrow_account.provider,
+        ActivityKind::EscrowReleased,
+        accounts.escrow_account.key(),
+        accounts.escrow_account.renter,
+        accounts.escrow_account.amount,
+    )?;
+
+    Ok(())
+}
+
+/// Whether `ix` is a single-signature Ed25519 program instruction verifying `signer`'s
+/// signature over exactly `message`, with all data inline
+pub fn verifies_ed25519(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> bool {
+    verified_ed25519_signature(ix, signer, message).is_some()
+}
+
+/// The signature `ix` verifies, if it passes `verifies_ed25519`
+pub fn verified_ed25519_signature(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> Option<[u8; 64]> {
+    // num_signatures (1) + padding (1) + seven u16 offsets (14)
+    const DATA_START: usize = 16;
+    let data = &ix.data;
+    if ix.program_id != ed25519_program::ID || data.len() < DATA_START || data[0] != 1 {
+        return None;
+    }
+    let field = |i: usize| u16::from_le_bytes([data[2 + 2 * i], data[3 + 2 * i]]) as usize;
+    let (sig_offset, sig_ix, key_offset, key_ix, msg_offset, msg_len, msg_ix) =
+        (field(0), field(1), field(2), field(3), field(4), field(5), field(6));
+    // Everything must come from this instruction, not another one in the transaction
+    let inline = u16::MAX as usize;
+    if sig_ix != inline || key_ix != inline || msg_ix != inline {
+        return None;
+    }
+    let signature = data.get(sig_offset..sig_offset + 64)?.try_into().ok()?;
+    (data.get(key_offset..key_offset + 32) == Some(signer.as_ref())
+        && data.get(msg_offset..msg_offset + msg_len) == Some(message))
+    .then_some(signature)
+}
+
+/// Reputation score of `renter` from its reputation `Agent` account
+fn read_reputation_score(agent: &UncheckedAccount, renter: &Pubkey) -> Result<i64> {
+    read_agent_field(agent, renter, 0)
+}
+
+/// When `owner` registered with the reputation program
+fn read_reputation_created_at(agent: &UncheckedAccount, owner: &Pubkey) -> Result<i64> {
+    // reputation_score, total_ratings and rating_sum precede it
+    read_agent_field(agent, owner, 24)
+}
+
+/// The i64 `offset` bytes past the strings of `owner`'s reputation `Agent`
+fn read_agent_field(agent: &UncheckedAccount, owner: &Pubkey, offset: usize) -> Result<i64> {
+    require_keys_eq!(*agent.owner, REPUTATION_PROGRAM_ID, EscrowError::InvalidReputationAccount);
+    let (expected, _) = Pubkey::find_program_address(&[b"agent", owner.as_ref()], &REPUTATION_PROGRAM_ID);
+    require_keys_eq!(agent.key(), expected, EscrowError::InvalidReputationAccount);
+
+    let data = agent.try_borrow_data()?;
+    require!(
+        data.len() >= 72 && data[..8] == hash(b"account:Agent").to_bytes()[..8],
+        EscrowError::InvalidReputationAccount
+    );
+    // discriminator, authority, state, then the `name` and `bio` strings
+    let mut start = 72;
+    for _ in 0..2 {
+        let len = data
+            .get(start..start + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .ok_or(EscrowError::InvalidReputationAccount)?;
+        start += 4 + len;
+    }
+    let field = data
+        .get(start + offset..start + offset + 8)
+        .ok_or(EscrowError::InvalidReputationAccount)?;
+    Ok(i64::from_le_bytes(field.try_into().unwrap()))
+}
+
+/// Token-2022 transfer-fee configuration of `mint`, if it has one
+fn transfer_fee_config(mint: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if *mint.owner != token_interface::spl_token_2022::ID {
+        return Ok(None);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<token_interface::spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// What a program vault is credited with when `amount` of `mint` is transferred into it:
+/// the amount less any Token-2022 transfer fee withheld in the current epoch
+fn received_amount(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    received_at_epoch(&mint.to_account_info(), amount, Clock::get()?.epoch)
+}
+
+/// `received_amount` for a transfer made in `epoch`
+pub fn received_at_epoch(mint: &AccountInfo, amount: u64, epoch: u64) -> Result<u64> {
+    let Some(config) = transfer_fee_config(mint)? else {
+        return Ok(amount);
+    };
+    let fee = config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(EscrowError::InsufficientFunds)?;
+    Ok(amount - fee)
+}
+
+/// Sweep transfer fees withheld in `vault` to the mint; Token-2022 refuses to close an
+/// account that still holds them
+fn harvest_withheld_fees<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+) -> Result<()> {
+    if transfer_fee_config(&mint)?.is_none() {
+        return Ok(());
+    }
+    let cpi_accounts = token_interface::HarvestWithheldTokensToMint { token_program_id: token_program.clone(), mint };
+    token_interface::harvest_withheld_tokens_to_mint(CpiContext::new(token_program, cpi_accounts), vec![vault])
+}
+
+/// Whether `mint` is wrapped SOL, under either token program
+pub fn is_wrapped_sol(mint: &Pubkey) -> bool {
+    *mint == anchor_spl::token::spl_token::native_mint::ID || *mint == token_interface::spl_token_2022::native_mint::ID
+}
+
+/// A program-owned token account and what it takes to pay out of it
+struct Vault<'info> {
+    account: AccountInfo<'info>,
+    /// The PDA owning `account`, which signs the transfers
+    authority: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    decimals: u8,
+    token_program: AccountInfo<'info>,
+    /// Wallet a wrapped-SOL vault is closed into once the payouts are out
+    unwrap_into: Option<AccountInfo<'info>>,
+}
+
+impl<'info> Vault<'info> {
+    fn new(
+        account: &impl ToAccountInfo<'info>,
+        authority: &impl ToAccountInfo<'info>,
+        mint: &InterfaceAccount<'info, Mint>,
+        token_program: &impl ToAccountInfo<'info>,
+    ) -> Self {
+        Self {
+            account: account.to_account_info(),
+            authority: authority.to_account_info(),
+            mint: mint.to_account_info(),
+            decimals: mint.decimals,
+            token_program: token_program.to_account_info(),
+            unwrap_into: None,
+        }
+    }
+}
+
+/// Pay each `(token account, amount)` of `payouts` out of `vault`, signed with `signer`,
+/// for an escrow the caller has moved to its settled state under `enter_settlement`.
+/// Zero payouts are skipped; the lock is released once every transfer has landed.
+fn settle_from_vault<'info>(
+    escrow: &mut Account<'info, EscrowAccount>,
+    vault: &Vault<'info>,
+    payouts: &[(AccountInfo<'info>, u64)],
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    // Persist the lock and new state before any CPI can observe the account
+    escrow.exit(&crate::ID)?;
+
+    for (to, amount) in payouts.iter().filter(|(_, amount)| *amount > 0) {
+        let cpi_accounts = TransferChecked {
+            from: vault.account.clone(),
+            mint: vault.mint.clone(),
+            to: to.clone(),
+            authority: vault.authority.clone(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(vault.token_program.clone(), cpi_accounts, signer),
+            *amount,
+            vault.decimals,
+        )?;
+    }
+    if let Some(wallet) = &vault.unwrap_into {
+        unwrap_vault(
+            vault.token_program.clone(),
+            vault.account.clone(),
+            wallet.clone(),
+            vault.authority.clone(),
+            signer,
+        )?;
+    }
+    escrow.exit_settlement();
+    Ok(())
+}
+
+/// Pay out what is left in a wrapped-SOL `vault` as lamports by closing it into
+/// `recipient`, who also receives the vault's rent
+fn unwrap_vault<'info>(
+    token_program: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    recipient: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_accounts = CloseAccount {
+        account: vault,
+        destination: recipient,
+        authority: escrow,
+    };
+    token_interface::close_account(CpiContext::new_with_signer(token_program, cpi_accounts, signer))
+}
+
+/// Accounting metadata attached to settlement events
+fn settlement_accounting(
+    mint: &InterfaceAccount<Mint>,
+    amount: u64,
+    price_oracle: Option<&UncheckedAccount>,
+) -> Result<AmountMetadata> {
+    let usd_quote = match price_oracle {
+        #[cfg(feature = "oracle")]
+        Some(oracle) => Some(read_usd_quote(oracle)?),
+        #[cfg(not(feature = "oracle"))]
+        Some(_) => return err!(EscrowError::FeatureDisabled),
+        None => None,
+    };
+    Ok(AmountMetadata {
+        mint: mint.key(),
+        decimals: mint.decimals,
+        amount,
+        amount_decimal: format_amount(amount, mint.decimals),
+        usd_quote,
+    })
+}
+
+/// Escrow id of the `rental`-th rental opened against listing `listing_id`
+pub fn rental_escrow_id(listing_id: u16, rental: u32) -> u64 {
+    RENTAL_ESCROW_ID_FLAG | (listing_id as u64) << 32 | rental as u64
+}
+
+/// Last `EscrowAccount` seed. Escrow 0 uses an empty seed, so a provider's original
+/// escrow keeps the `[ESCROW_SEED, provider]` address it had before escrow ids.
+pub fn escrow_id_seed(escrow_id: u64) -> Vec<u8> {
+    if escrow_id == 0 {
+        Vec::new()
+    } else {
+        escrow_id.to_le_bytes().to_vec()
+    }
+}
+
+/// Render base units as a fixed-point decimal string, e.g. 1_500_000 @ 6 -> "1.500000"
+fn format_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let scale = 10u128.pow(decimals as u32);
+    let whole = amount as u128 / scale;
+    let frac = amount as u128 % scale;
+    format!("{}.{:0width$}", whole, frac, width = decimals as usize)
+}
+
+/// Decode the price message of a Pyth `PriceUpdateV2` account
+#[cfg(feature = "oracle")]
+fn read_usd_quote(oracle: &UncheckedAccount) -> Result<UsdQuote> {
+    require_keys_eq!(*oracle.owner, PYTH_RECEIVER_ID, EscrowError::InvalidOracle);
+    let data = oracle.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == hash(b"account:PriceUpdateV2").to_bytes()[..8],
+        EscrowError::InvalidOracle
+    );
+    // discriminator (8) + write_authority (32) + verification_level (1 or 2)
+    let offset = match data.get(40) {
+        Some(0) => 42,
+        Some(1) => 41,
+        _ => return err!(EscrowError::InvalidOracle),
+    };
+    let msg = data.get(offset..offset + 32 + 8 + 8 + 4 + 8).ok_or(EscrowError::InvalidOracle)?;
+    let mut feed_id = [0u8; 32];
+    feed_id.copy_from_slice(&msg[..32]);
+    let read_i64 = |at: usize| i64::from_le_bytes(msg[at..at + 8].try_into().unwrap());
+    Ok(UsdQuote {
+        feed_id,
+        price: read_i64(32),
+        expo: i32::from_le_bytes(msg[48..52].try_into().unwrap()),
+        publish_time: read_i64(52),
+    })
+}
+
+// ========== Account Structures ==========
+
+#[account]
+#[derive(Default)]
+pub struct EscrowAccount {
+    pub provider: Pubkey,
+    /// `UNASSIGNED_RENTER` until funded; read through `assigned_renter` when paying out
+    pub renter: Pubkey,
+    pub token_mint: Pubkey,
+    pub provider_token_account: Pubkey,
+    pub escrow_token_account: Pubkey,
+    pub terms: EscrowTerms,
+    pub state: EscrowState,
+    /// Held in the vault for this rental; `release_milestone` draws it down
+    pub amount: u64,
+    pub created_at: i64,
+    pub completed_at: i64,
+    pub cancelled_at: i64,
+    pub deliverable_hash: [u8; 32],
+    /// Hash of the decryption key ciphertext revealed at release (zero = no key escrow)
+    pub encrypted_key_hash: [u8; 32],
+    pub delivered_at: i64,
+    /// Effective process parameters, fixed at fund time
+    pub dispute_window_seconds: i64,
+    pub review_window_seconds: i64,
+    pub arbiter: Pubkey,
+    pub funded_at: i64,
+    pub disputed_at: i64,
+    /// Set for the duration of a settlement; persisted before any outgoing CPI
+    pub settlement_lock: bool,
+    /// SHA-256 over the mint and borsh-encoded terms; renters must echo it when funding
+    pub terms_hash: [u8; 32],
+    /// Renter-appointed delegate that may dispute or claim a timeout refund
+    pub watchtower: Pubkey,
+    /// Verifier-attested SLA breaches and the resulting share refunded at release
+    pub sla_breaches: u8,
+    pub sla_penalty_bps: u16,
+    /// Set by the configured screener; see `Config::requires_screening`
+    pub screening_approved: bool,
+    /// Part of the price the renter may pay after funding, per the listing's funding rule
+    pub deferred_balance: u64,
+    pub deferred_due_at: i64,
+    /// Hash of the latest access credential issued for this rental (zero = none yet)
+    pub access_commitment: [u8; 32],
+    pub access_committed_at: i64,
+    /// Number of commitments this rental, including the first issuance
+    pub access_rotations: u16,
+    /// How the arbiter settled this rental's dispute, if there was one
+    pub resolution: Option<ResolutionOutcome>,
+    /// Hash of the arbiter's written ruling
+    pub ruling_hash: [u8; 32],
+    /// First `submit_deliverable`; `delivered_at` tracks the latest revision
+    pub first_delivered_at: i64,
+    /// Resubmissions after the first deliverable
+    pub revisions: u16,
+    /// Pending `request_revision` (0 = none)
+    pub revision_requested_at: i64,
+    pub revision_notes_hash: [u8; 32],
+    /// `hash(previous || deliverable_hash || encrypted_key_hash)` over every submission
+    pub deliverable_chain: [u8; 32],
+    /// Provider payout held back for vesting at release
+    pub vesting_total: u64,
+    pub vesting_started_at: i64,
+    pub vested_claimed: u64,
+    /// Post-release dispute that froze vesting (0 = none)
+    pub vesting_halted_at: i64,
+    /// How settlement divided the escrow, including amounts still vesting
+    pub provider_payout: u64,
+    pub renter_refund: u64,
+    /// Ed25519 key that signs this listing's off-chain webhook callbacks (default = none)
+    pub webhook_signer: Pubkey,
+    /// Set by `deprecate_listing`: no new funding from this moment on (0 = not deprecated)
+    pub sunset_at: i64,
+    /// Provider-chosen id, part of the PDA seeds; see `escrow_id_seed`
+    pub escrow_id: u64,
+    /// Protocol fees paid to the treasury out of this rental; `provider_payout` is net of them
+    pub fee_amount: u64,
+    /// Rental requests whose deposits still reference this listing; it cannot be
+    /// closed while any are open
+    pub pending_requests: u16,
+    /// Priority fees the renter added with `expedite`, included in `amount`
+    pub expedite_fee: u64,
+    /// Pending `propose_cancel`, if any; `accept_cancel` must come from the other party
+    pub cancel_proposed_by: CancelProposer,
+    pub cancel_proposed_at: i64,
+    /// The proposal can be accepted up to this time
+    pub cancel_expires_at: i64,
+    /// After this, an unresolved dispute can be settled by `claim_default_resolution`
+    /// (0 = no deadline)
+    pub arbitration_deadline: i64,
+    /// Set by `offer_auto_release`: renters may opt into automatic release when funding
+    pub auto_release_offered: bool,
+    /// Both parties agreed at funding that the rental releases without the renter's
+    /// approval; see `auto_release_due`
+    pub auto_release: bool,
+    /// `fund_bond` deposited `terms.provider_bond` in the bond vault and `release_bond`
+    /// has not paid it out yet
+    pub bond_held: bool,
+    /// Set by `offer_subscription`: renters may prepay several periods with `fund_subscription`
+    pub subscription_offered: bool,
+    /// Paid out of a `terms.stream_payment` rental by `claim_streamed` so far
+    pub claimed_amount: u64,
+    /// Layout that last wrote the account, `ESCROW_VERSION` once created or migrated by
+    /// this release (0 = written before versioning)
+    pub version: u8,
+    pub _reserved: Reserved<{ ESCROW_RESERVE }>,
+}
+
+impl EscrowAccount {
+    pub const LEN: usize = 8 + 32 * 5
+        + FixedString::<MAX_SKILL_NAME_LEN>::LEN + 8 + 8 + FixedString::<MAX_METADATA_URI_LEN>::LEN // terms
+        + 1 + 8 * 4 + 2 + 1 + 32 + 32 + 8
+        + 8 + 8 + 32 + 33 // terms: windows, arbiter, override policy
+        + 1 + SlaTerms::LEN // terms: sla
+        + 4 + MAX_CATEGORY_LEN // terms: category
+        + 1 + FundingRule::LEN // terms: funding rule
+        + 1 // terms: requires provider acceptance
+        + 1 // terms: trial
+        + 8 // terms: funding deadline
+        + 8 // terms: vesting
+        + 1 // terms: price decimals
+        + 8 // terms: provider bond
+        + 8 + 8 + 32 + 8 + 8
+        + 1 + 32
+        + 32
+        + 1 + 2
+        + 1
+        + 8 + 8
+        + 32 + 8 + 2
+        + 1 + ResolutionOutcome::LEN + 32
+        + 8 + 2 + 8 + 32 + 32
+        + 8 + 8 + 8 + 8
+        + 8 + 8
+        + 32
+        + 8 + 8 + 8 + 2 + 8 + 1 + 8 + 8 + 8 + 1 + 1 + 1 + 1 + 8 + 1 + ESCROW_RESERVE;
+
+    pub fn compute_terms_hash(terms: &EscrowTerms, token_mint: &Pubkey) -> Result<[u8; 32]> {
+        let encoded = terms.try_to_vec()?;
+        Ok(hashv(&[token_mint.as_ref(), &encoded]).to_bytes())
+    }
+
+    /// Take the settlement lock, failing if a settlement is already in progress
+    pub fn enter_settlement(&mut self) -> Result<()> {
+        require!(!self.settlement_lock, EscrowError::ReentrancyDetected);
+        self.settlement_lock = true;
+        Ok(())
+    }
+
+    pub fn exit_settlement(&mut self) {
+        self.settlement_lock = false;
+    }
+
+    /// Last moment a dispute may be opened: `dispute_window_seconds` after a pending
+    /// revision request, `review_window_seconds` after the latest delivery, otherwise
+    /// `dispute_window_seconds` after the rental period ends
+    pub fn dispute_deadline(&self) -> i64 {
+        if self.revision_requested_at > 0 {
+            self.revision_requested_at.saturating_add(self.dispute_window_seconds)
+        } else if self.delivered_at > 0 {
+            self.delivered_at.saturating_add(self.review_window_seconds)
+        } else {
+            self.funded_at
+                .saturating_add(self.terms.duration_seconds)
+                .saturating_add(self.dispute_window_seconds)
+        }
+    }
+
+    /// Part of `vesting_total` unlocked by `now`; vesting stops at `vesting_halted_at`
+    pub fn vested_at(&self, now: i64) -> u64 {
+        let duration = self.terms.vesting_seconds;
+        if duration <= 0 {
+            return self.vesting_total;
+        }
+        let until = if self.vesting_halted_at > 0 { now.min(self.vesting_halted_at) } else { now };
+        let elapsed = until.saturating_sub(self.vesting_started_at).clamp(0, duration);
+        (self.vesting_total as u128 * elapsed as u128 / duration as u128) as u64
+    }
+
+    pub fn vesting_ends_at(&self) -> i64 {
+        self.vesting_started_at.saturating_add(self.terms.vesting_seconds)
+    }
+
+    /// Amount an arbiter rules on: the escrowed amount, or after a post-release dispute
+    /// the payout that had not vested when the dispute was opened
+    pub fn disputed_amount(&self) -> u64 {
+        if self.vesting_halted_at > 0 {
+            self.vesting_total.saturating_sub(self.vested_at(self.vesting_halted_at))
+        } else {
+            self.amount
+        }
+    }
+
+    /// Provider and renter shares of a split ruling awarding `provider_bps` of the
+    /// disputed amount to the provider, rounded in the renter's favour. Both sides must
+    /// get something; a one-sided ruling is a release or a refund.
+    pub fn split_disputed(&self, provider_bps: u16) -> Result<(u64, u64)> {
+        require!((1..BPS_DENOMINATOR).contains(&provider_bps), EscrowError::InvalidSplit);
+        let amount = self.disputed_amount();
+        let provider_share = (amount as u128)
+            .checked_mul(provider_bps as u128)
+            .and_then(|scaled| scaled.checked_div(BPS_DENOMINATOR as u128))
+            .and_then(|share| u64::try_from(share).ok())
+            .ok_or(EscrowError::InvalidSplit)?;
+        let renter_share = amount.checked_sub(provider_share).ok_or(EscrowError::InvalidSplit)?;
+        Ok((provider_share, renter_share))
+    }
+
+    /// Fee, provider and renter shares of a ruling of `outcome`. The protocol fee comes
+    /// out of whatever the provider is awarded, except in a post-release dispute, whose
+    /// payout was charged at release; `config` is omitted only by a refund, which pays
+    /// the provider nothing.
+    pub fn ruling_shares(&self, config: Option<&Config>, outcome: ResolutionOutcome) -> Result<(u64, u64, u64)> {
+        let (provider_share, renter_share) = match outcome {
+            ResolutionOutcome::ReleasedToProvider => (self.disputed_amount(), 0),
+            ResolutionOutcome::RefundedToRenter => (0, self.disputed_amount()),
+            ResolutionOutcome::Split(provider_bps) => self.split_disputed(provider_bps)?,
+            ResolutionOutcome::Defaulted => return err!(EscrowError::InvalidRuling),
+        };
+        let fee = match config {
+            Some(config) if self.vesting_halted_at == 0 => config.fee_on(provider_share),
+            _ => 0,
+        };
+        Ok((fee, provider_share - fee, renter_share))
+    }
+
+    /// Provider and renter shares of a provider bond of `bond`, or `None` until the
+    /// escrow is settled. A refund ruling forfeits the bond to the renter and a split
+    /// forfeits the renter's share of it; any other ending returns it to the provider.
+    pub fn bond_payouts(&self, bond: u64) -> Option<(u64, u64)> {
+        if !matches!(
+            self.state,
+            EscrowState::Completed | EscrowState::Cancelled | EscrowState::Closed | EscrowState::Expired
+        ) {
+            return None;
+        }
+        let to_provider = match self.resolution {
+            Some(ResolutionOutcome::RefundedToRenter) => 0,
+            Some(ResolutionOutcome::Split(provider_bps)) => {
+                (bond as u128 * provider_bps as u128 / BPS_DENOMINATOR as u128) as u64
+            }
+            _ => bond,
+        };
+        Some((to_provider, bond - to_provider))
+    }
+
+    /// Record a settlement's division. A post-release ruling only rules on the unvested
+    /// part, which was allocated to the provider at release, so the renter's share moves
+    /// out of the existing payout.
+    pub fn allocate_settlement(&mut self, to_provider: u64, to_renter: u64) {
+        if self.vesting_halted_at > 0 {
+            self.provider_payout = self.provider_payout.saturating_sub(to_renter);
+        } else {
+            // On top of anything milestones or streaming already paid out
+            self.provider_payout = self.provider_payout.saturating_add(to_provider);
+        }
+        self.renter_refund = self.renter_refund.saturating_add(to_renter);
+    }
+
+    /// Whether a renter may still fund the listing at `now`
+    pub fn funding_open(&self, now: i64) -> bool {
+        (self.terms.funding_deadline == 0 || now <= self.terms.funding_deadline)
+            && (self.sunset_at == 0 || now < self.sunset_at)
+    }
+
+    /// A listing can be withdrawn only while unfunded, with no rental request waiting on
+    /// it (their refunds read the listing) and no provider bond still held
+    pub fn check_closable(&self) -> Result<()> {
+        require!(self.state == EscrowState::Created, EscrowError::InvalidState);
+        require!(self.pending_requests == 0, EscrowError::RentalRequestsPending);
+        require!(!self.bond_held, EscrowError::BondAlreadyFunded);
+        Ok(())
+    }
+
+    /// Listing-side checks of a funding against `terms_hash` at `now`, through the credit
+    /// ledger if `credits`; the amount, overrides and provider capacity are checked separately
+    pub fn check_fundable(&self, terms_hash: &[u8; 32], credits: bool, now: i64) -> Result<()> {
+        require!(self.state == EscrowState::Created, EscrowError::InvalidState);
+        require!(self.terms_hash == *terms_hash, EscrowError::TermsHashMismatch);
+        require!(self.terms.settle_in_credits == credits, EscrowError::InvalidSettlementMode);
+        require!(self.funding_open(now), EscrowError::ListingExpired);
+        require!(!self.terms.requires_provider_acceptance, EscrowError::ProviderAcceptanceRequired);
+        Ok(())
+    }
+
+    /// Whether the renter could release the rental at `now`. A deferred balance blocks
+    /// release until it is paid or falls overdue, and a release large enough to need
+    /// screening waits for the screener or the screening delay. `credits` as for
+    /// `check_fundable`.
+    pub fn check_releasable(&self, config: &Config, credits: bool, now: i64) -> Result<()> {
+        require!(self.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(self.terms.settle_in_credits == credits, EscrowError::InvalidSettlementMode);
+        require!(self.first_delivered_at > 0, EscrowError::NotDelivered);
+        require!(
+            self.deferred_balance == 0 || now > self.deferred_due_at,
+            EscrowError::DeferredBalanceOutstanding
+        );
+        if config.requires_screening(self.amount) {
+            require!(
+                self.screening_approved || now >= self.funded_at.saturating_add(config.screening_delay_seconds),
+                EscrowError::ScreeningPending
+            );
+        }
+        Ok(())
+    }
+
+    /// A release must reveal the key ciphertext exactly when the deliverable committed to
+    /// one, and it must hash to that commitment
+    pub fn check_key_ciphertext(&self, key_ciphertext: Option<&[u8]>) -> Result<()> {
+        let committed = self.encrypted_key_hash != [0u8; 32];
+        match key_ciphertext {
+            None => require!(!committed, EscrowError::DecryptionKeyRequired),
+            Some(key_ciphertext) => {
+                require!(key_ciphertext.len() <= MAX_KEY_CIPHERTEXT_LEN, EscrowError::KeyCiphertextTooLong);
+                require!(committed, EscrowError::InvalidState);
+                require!(
+                    hash(key_ciphertext).to_bytes() == self.encrypted_key_hash,
+                    EscrowError::DecryptionKeyMismatch
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Link a submission into the deliverable revision chain
+    pub fn chain_deliverable(previous: &[u8; 32], deliverable_hash: &[u8; 32], encrypted_key_hash: &[u8; 32]) -> [u8; 32] {
+        hashv(&[previous, deliverable_hash, encrypted_key_hash]).to_bytes()
+    }
+
+    /// End of the rental period; from then on `refund_after_timeout` refunds an undelivered escrow
+    pub fn rental_ends_at(&self) -> i64 {
+        self.funded_at.saturating_add(self.terms.duration_seconds)
+    }
+
+    /// What `claim_streamed` would pay at `now`: everything funded, claimed or still held,
+    /// pro rata to the rental time elapsed, less earlier claims
+    pub fn streamable_at(&self, now: i64) -> u64 {
+        let duration = self.terms.duration_seconds;
+        if duration <= 0 {
+            return 0;
+        }
+        let elapsed = now.saturating_sub(self.funded_at).clamp(0, duration);
+        let funded = self.amount.saturating_add(self.claimed_amount);
+        let due = (funded as u128 * elapsed as u128 / duration as u128) as u64;
+        due.saturating_sub(self.claimed_amount)
+    }
+
+    /// Lengthen the rental period by a positive `extra_seconds`
+    pub fn extend_duration(&mut self, extra_seconds: i64) -> Result<()> {
+        require!(extra_seconds > 0, EscrowError::InvalidExtension);
+        self.terms.duration_seconds = self
+            .terms
+            .duration_seconds
+            .checked_add(extra_seconds)
+            .ok_or(EscrowError::InvalidExtension)?;
+        Ok(())
+    }
+
+    /// Whether `auto_release` may release at `now`. Something must have been delivered
+    /// with no revision pending; then either both parties agreed to auto-release and the
+    /// rental period is over, or the renter let the review window after the latest
+    /// delivery lapse without releasing or disputing. A zero review window leaves release
+    /// to the renter. Undelivered rentals are left to `refund_after_timeout` instead.
+    pub fn auto_release_due(&self, now: i64) -> bool {
+        let agreed = self.auto_release && now >= self.rental_ends_at();
+        let review_lapsed = self.review_window_seconds > 0 && now > self.dispute_deadline();
+        self.state == EscrowState::Funded
+            && self.first_delivered_at > 0
+            && self.revision_requested_at == 0
+            && (agreed || review_lapsed)
+    }
+
+    /// Whether `refund_after_timeout` may refund at `now`: nothing was ever delivered and
+    /// the rental period is over. Later revisions do not restart this clock.
+    pub fn check_timeout_refund(&self, now: i64) -> Result<()> {
+        require!(self.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(self.first_delivered_at == 0, EscrowError::InvalidState);
+        require!(now >= self.rental_ends_at(), EscrowError::TimeoutNotElapsed);
+        Ok(())
+    }
+
+    /// Amount owed back to the renter for reported SLA breaches
+    pub fn sla_penalty(&self) -> Result<u64> {
+        let penalty = (self.amount as u128)
+            .checked_mul(self.sla_penalty_bps as u128)
+            .ok_or(EscrowError::InsufficientFunds)?
+            / BPS_DENOMINATOR as u128;
+        Ok(penalty as u64)
+    }
+
+    /// An arbiter must be a third party: neither renter nor provider, nor the default key
+    pub fn is_eligible_arbiter(&self, arbiter: &Pubkey) -> bool {
+        *arbiter != Pubkey::default() && *arbiter != self.renter && *arbiter != self.provider
+    }
+
+    /// Slot of `key` in `DisputeRandomness`: renter, provider, arbiter
+    pub fn dispute_party(&self, key: &Pubkey) -> Option<usize> {
+        [self.renter, self.provider, self.arbiter]
+            .iter()
+            .position(|party| *party != Pubkey::default() && party == key)
+    }
+
+    /// Whether the arbiter has missed the deadline, opening default judgment
+    pub fn arbitration_lapsed(&self, now: i64) -> bool {
+        self.arbitration_deadline > 0 && now > self.arbitration_deadline
+    }
+
+    /// Start a rental for `renter` with `received` in the vault at `now`, clearing
+    /// everything a previous rental of this listing left behind
+    pub fn begin_rental(&mut self, renter: Pubkey, received: u64, now: i64) {
+        self.renter = renter;
+        self.watchtower = Pubkey::default();
+        self.sla_breaches = 0;
+        self.sla_penalty_bps = 0;
+        self.screening_approved = false;
+        self.access_commitment = [0u8; 32];
+        self.access_committed_at = 0;
+        self.access_rotations = 0;
+        self.resolution = None;
+        self.ruling_hash = [0u8; 32];
+        self.deliverable_hash = [0u8; 32];
+        self.encrypted_key_hash = [0u8; 32];
+        self.delivered_at = 0;
+        self.first_delivered_at = 0;
+        self.revisions = 0;
+        self.revision_requested_at = 0;
+        self.revision_notes_hash = [0u8; 32];
+        self.deliverable_chain = [0u8; 32];
+        self.vesting_total = 0;
+        self.vesting_started_at = 0;
+        self.vested_claimed = 0;
+        self.vesting_halted_at = 0;
+        self.provider_payout = 0;
+        self.renter_refund = 0;
+        self.claimed_amount = 0;
+        self.fee_amount = 0;
+        self.expedite_fee = 0;
+        self.clear_cancel_proposal();
+        self.amount = received;
+        self.state = EscrowState::Funded;
+        self.funded_at = now;
+    }
+
+    /// Record `caller`'s proposal to cancel this funded rental at `now`, replacing any
+    /// pending one; only the provider and renter may propose
+    pub fn propose_cancel(&mut self, caller: &Pubkey, now: i64) -> Result<()> {
+        require!(self.state == EscrowState::Funded, EscrowError::InvalidState);
+        require!(!self.terms.settle_in_credits, EscrowError::InvalidSettlementMode);
+        self.cancel_proposed_by = if *caller == self.provider {
+            CancelProposer::Provider
+        } else if *caller == self.renter {
+            CancelProposer::Renter
+        } else {
+            return err!(EscrowError::Unauthorized);
+        };
+        self.cancel_proposed_at = now;
+        self.cancel_expires_at = now.saturating_add(CANCEL_PROPOSAL_SECONDS);
+        Ok(())
+    }
+
+    /// Whether `caller` may `accept_cancel` at `now`: a proposal is pending, has not
+    /// expired, and `caller` is the party that did not make it
+    pub fn check_cancel_acceptance(&self, caller: &Pubkey, now: i64) -> Result<()> {
+        let counterparty = match self.cancel_proposed_by {
+            CancelProposer::None => return err!(EscrowError::NoCancelProposal),
+            CancelProposer::Provider => self.renter,
+            CancelProposer::Renter => self.provider,
+        };
+        require_keys_eq!(*caller, counterparty, EscrowError::Unauthorized);
+        require!(now <= self.cancel_expires_at, EscrowError::CancelProposalExpired);
+        Ok(())
+    }
+
+    pub fn clear_cancel_proposal(&mut self) {
+        self.cancel_proposed_by = CancelProposer::None;
+        self.cancel_proposed_at = 0;
+        self.cancel_expires_at = 0;
+    }
+
+    pub fn is_renter_or_watchtower(&self, key: &Pubkey) -> bool {
+        self.has_renter() && (*key == self.renter || (self.watchtower != Pubkey::default() && *key == self.watchtower))
+    }
+
+    /// Record the arbiter's ruling and build the matching `DisputeResolved` event
+    pub fn record_resolution(
+        &mut self,
+        escrow: Pubkey,
+        arbiter: Pubkey,
+        outcome: ResolutionOutcome,
+        ruling_hash: [u8; 32],
+    ) -> DisputeResolved {
+        self.resolution = Some(outcome);
+        self.ruling_hash = ruling_hash;
+        DisputeResolved {
+            escrow,
+            provider: self.provider,
+            renter: self.renter,
+            arbiter,
+            outcome,
+            ruling_hash,
+            amount: self.disputed_amount(),
+            disputed_at: self.disputed_at,
+        }
+    }
+
+    pub fn has_renter(&self) -> bool {
+        self.renter != UNASSIGNED_RENTER
+    }
+
+    /// Final state of the escrow as of `slot`, or `None` while it can still change
+    pub fn snapshot(&self, escrow: Pubkey, slot: u64) -> Option<EscrowSnapshot> {
+        let settled_at = match self.state {
+            EscrowState::Completed => self.completed_at,
+            EscrowState::Cancelled | EscrowState::Closed | EscrowState::Expired => self.cancelled_at,
+            _ => return None,
+        };
+        Some(EscrowSnapshot {
+            escrow,
+            provider: self.provider,
+            renter: self.renter,
+            token_mint: self.token_mint,
+            state: self.state.clone(),
+            amount: self.amount,
+            provider_payout: self.provider_payout,
+            renter_refund: self.renter_refund,
+            resolution: self.resolution,
+            terms_hash: self.terms_hash,
+            deliverable_chain: self.deliverable_chain,
+            settled_at,
+            slot,
+        })
+    }
+
+    /// The funding renter, or `RenterUnassigned` for a listing nobody has funded
+    pub fn assigned_renter(&self) -> Result<Pubkey> {
+        require!(self.has_renter(), EscrowError::RenterUnassigned);
+        Ok(self.renter)
+    }
+}
+
+/// Space set aside for future fields; see `ACCOUNT_RESERVE`. A newtype because
+/// arrays this long do not implement `Default`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reserved<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Default for Reserved<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+/// UTF-8 text in a fixed `N`-byte buffer: `len` bytes of text, then zeros. Unlike a
+/// `String` it always serializes to `LEN` bytes, so the fields after it keep fixed
+/// offsets and the text itself can be matched by `memcmp` filters.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct FixedString<const N: usize> {
+    pub len: u16,
+    pub bytes: [u8; N],
+}
+
+impl<const N: usize> FixedString<N> {
+    /// 2 (length) + N
+    pub const LEN: usize = 2 + N;
+
+    /// `None` if `text` is longer than `N` bytes
+    pub fn new(text: &str) -> Option<Self> {
+        let mut bytes = [0; N];
+        bytes.get_mut(..text.len())?.copy_from_slice(text.as_bytes());
+        Some(Self {
+            len: text.len() as u16,
+            bytes,
+        })
+    }
+
+    /// In bounds, UTF-8 and zero-padded, so equal text always has equal bytes
+    pub fn is_valid(&self) -> bool {
+        let len = self.len as usize;
+        len <= N && std::str::from_utf8(&self.bytes[..len]).is_ok() && self.bytes[len..].iter().all(|b| *b == 0)
+    }
+
+    /// The text, or "" if the buffer is not `is_valid`
+    pub fn as_str(&self) -> &str {
+        self.bytes
+            .get(..self.len as usize)
+            .and_then(|text| std::str::from_utf8(text).ok())
+            .unwrap_or_default()
+    }
+
+    /// `text` cut at the last character boundary that fits, for strings written before
+    /// the fields had a capacity
+    pub fn truncated(text: &str) -> Self {
+        let mut end = text.len().min(N);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        Self::new(&text[..end]).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> Default for FixedString<N> {
+    fn default() -> Self {
+        Self { len: 0, bytes: [0; N] }
+    }
+}
+
+impl<const N: usize> std::fmt::Debug for FixedString<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// Historical `EscrowAccount` layouts and a reader for each, so `migrate_escrow` and
+/// off-chain indexers can load accounts written by any release.
+///
+/// After the discriminator, the data length identifies the layout that wrote an
+/// account. Fields the layout did not have yet are left at their defaults. Since
+/// [`EscrowLayout::V13`] accounts end in a zeroed reserve, and later fields are carved
+/// out of it without changing the length; those read as zero on older accounts, which
+/// is the same default the older layouts decode to, so they need no new variant.
+pub mod layout {
+    use super::*;
+    use std::io;
+
+    /// `EscrowAccount` layouts, oldest first; `ESCROW_VERSION` is the current one's
+    /// discriminant
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    #[repr(u8)]
+    pub enum EscrowLayout {
+        /// Original deployment: terms were skill, duration, price and metadata only
+        V0,
+        /// First layout with published golden vectors: windows, arbiter, SLA, watchtower, screening
+        V1,
+        /// `terms.category`
+        V2,
+        /// `terms.funding_rule` and the deferred balance
+        V3,
+        /// Access credential commitments
+        V4,
+        /// Recorded dispute resolution
+        V5,
+        /// `terms.requires_provider_acceptance`
+        V6,
+        /// Deliverable revision chain
+        V7,
+        /// `terms.trial`
+        V8,
+        /// `terms.funding_deadline`
+        V9,
+        /// Provider payout vesting
+        V10,
+        /// Recorded settlement allocation
+        V11,
+        /// Registered webhook signing key
+        V12,
+        /// `ACCOUNT_RESERVE` bytes reserved for future fields
+        V13,
+        /// Fixed-size `terms.skill_name` and `terms.metadata_uri`
+        V14,
+        /// `terms.price_decimals`
+        V15,
+        /// `terms.provider_bond`
+        V16,
+        /// `terms.stream_payment` and the streamed `claimed_amount`
+        V17,
+    }
+
+    impl EscrowLayout {
+        pub const ALL: [EscrowLayout; 18] = [
+            Self::V0,
+            Self::V1,
+            Self::V2,
+            Self::V3,
+            Self::V4,
+            Self::V5,
+            Self::V6,
+            Self::V7,
+            Self::V8,
+            Self::V9,
+            Self::V10,
+            Self::V11,
+            Self::V12,
+            Self::V13,
+            Self::V14,
+            Self::V15,
+            Self::V16,
+            Self::V17,
+        ];
+        pub const CURRENT: EscrowLayout = Self::V17;
+
+        /// Allocated size of accounts written with this layout
+        pub fn account_len(self) -> usize {
+            const V0: usize = 8 + 32 * 5 + 8 + 64 + 8 + 8 + 256 + 64 + 1 + 8 * 4;
+            const V1: usize = V0 + 2 + 1 + 32 + 32 + 8 + 8 + 8 + 32 + 33 + 1 + SlaTerms::LEN + 8 + 8 + 32 + 8 + 8 + 1 + 32 + 32 + 1 + 2 + 1;
+            const V2: usize = V1 + 4 + 32;
+            const V3: usize = V2 + 1 + FundingRule::LEN + 8 + 8;
+            const V4: usize = V3 + 32 + 8 + 2;
+            const V5: usize = V4 + 1 + ResolutionOutcome::LEN + 32;
+            const V6: usize = V5 + 1;
+            const V7: usize = V6 + 8 + 2 + 8 + 32 + 32;
+            const V8: usize = V7 + 1;
+            const V9: usize = V8 + 8;
+            const V10: usize = V9 + 8 + 8 + 8 + 8 + 8;
+            const V11: usize = V10 + 8 + 8;
+            const V12: usize = V11 + 32;
+            const V16: usize = EscrowAccount::LEN - 1 - 8;
+            const V15: usize = V16 - 8;
+            const V14: usize = V15 - 1;
+            // Until V14, skill name, duration, price and metadata URI shared the budget V0
+            // gave them; V14 sized the two text fields to their capacities
+            const V13: usize = V14 - (FixedString::<MAX_SKILL_NAME_LEN>::LEN + 8 + 8 + FixedString::<MAX_METADATA_URI_LEN>::LEN)
+                + (8 + 64 + 8 + 8 + 256 + 64);
+            match self {
+                Self::V0 => V0,
+                Self::V1 => V1,
+                Self::V2 => V2,
+                Self::V3 => V3,
+                Self::V4 => V4,
+                Self::V5 => V5,
+                Self::V6 => V6,
+                Self::V7 => V7,
+                Self::V8 => V8,
+                Self::V9 => V9,
+                Self::V10 => V10,
+                Self::V11 => V11,
+                Self::V12 => V12,
+                Self::V13 => V13,
+                Self::V14 => V14,
+                Self::V15 => V15,
+                Self::V16 => V16,
+                Self::V17 => EscrowAccount::LEN,
+            }
+        }
+
+        pub fn from_len(len: usize) -> Option<Self> {
+            Self::ALL.into_iter().find(|layout| layout.account_len() == len)
+        }
+    }
+
+    /// Read the account `body` (the data after the discriminator) written by `layout`
+    /// into `escrow`, which the caller starts from defaults. In place because the
+    /// account is too large to move around the on-chain stack.
+    pub fn read_escrow(layout: EscrowLayout, body: &[u8], escrow: &mut EscrowAccount) -> io::Result<()> {
+        let buf = &mut &body[..];
+        escrow.provider = read(buf)?;
+        escrow.renter = read(buf)?;
+        escrow.token_mint = read(buf)?;
+        escrow.provider_token_account = read(buf)?;
+        escrow.escrow_token_account = read(buf)?;
+        read_terms(buf, layout, &mut escrow.terms)?;
+        escrow.state = read(buf)?;
+        escrow.amount = read(buf)?;
+        escrow.created_at = read(buf)?;
+        escrow.completed_at = read(buf)?;
+        escrow.cancelled_at = read(buf)?;
+        if layout == EscrowLayout::V0 {
+            return Ok(());
+        }
+
+        escrow.deliverable_hash = read(buf)?;
+        escrow.encrypted_key_hash = read(buf)?;
+        escrow.delivered_at = read(buf)?;
+        escrow.dispute_window_seconds = read(buf)?;
+        escrow.review_window_seconds = read(buf)?;
+        escrow.arbiter = read(buf)?;
+        escrow.funded_at = read(buf)?;
+        escrow.disputed_at = read(buf)?;
+        escrow.settlement_lock = read(buf)?;
+        escrow.terms_hash = read(buf)?;
+        escrow.watchtower = read(buf)?;
+        escrow.sla_breaches = read(buf)?;
+        escrow.sla_penalty_bps = read(buf)?;
+        escrow.screening_approved = read(buf)?;
+        if layout >= EscrowLayout::V3 {
+            escrow.deferred_balance = read(buf)?;
+            escrow.deferred_due_at = read(buf)?;
+        }
+        if layout >= EscrowLayout::V4 {
+            escrow.access_commitment = read(buf)?;
+            escrow.access_committed_at = read(buf)?;
+            escrow.access_rotations = read(buf)?;
+        }
+        if layout >= EscrowLayout::V5 {
+            escrow.resolution = read(buf)?;
+            escrow.ruling_hash = read(buf)?;
+        }
+        if layout >= EscrowLayout::V7 {
+            escrow.first_delivered_at = read(buf)?;
+            escrow.revisions = read(buf)?;
+            escrow.revision_requested_at = read(buf)?;
+            escrow.revision_notes_hash = read(buf)?;
+            escrow.deliverable_chain = read(buf)?;
+        }
+        if layout >= EscrowLayout::V10 {
+            escrow.vesting_total = read(buf)?;
+            escrow.vesting_started_at = read(buf)?;
+            escrow.vested_claimed = read(buf)?;
+            escrow.vesting_halted_at = read(buf)?;
+        }
+        if layout >= EscrowLayout::V11 {
+            escrow.provider_payout = read(buf)?;
+            escrow.renter_refund = read(buf)?;
+        }
+        if layout >= EscrowLayout::V12 {
+            escrow.webhook_signer = read(buf)?;
+        }
+        if layout >= EscrowLayout::V13 {
+            escrow.sunset_at = read(buf)?;
+            escrow.escrow_id = read(buf)?;
+            escrow.fee_amount = read(buf)?;
+            escrow.pending_requests = read(buf)?;
+            escrow.expedite_fee = read(buf)?;
+            escrow.cancel_proposed_by = read(buf)?;
+            escrow.cancel_proposed_at = read(buf)?;
+            escrow.cancel_expires_at = read(buf)?;
+            escrow.arbitration_deadline = read(buf)?;
+            escrow.auto_release_offered = read(buf)?;
+            escrow.auto_release = read(buf)?;
+            escrow.bond_held = read(buf)?;
+            escrow.subscription_offered = read(buf)?;
+            if layout >= EscrowLayout::V17 {
+                escrow.claimed_amount = read(buf)?;
+            }
+            escrow.version = read(buf)?;
+            escrow._reserved = read(buf)?;
+        }
+        Ok(())
+    }
+
+    fn read_terms(buf: &mut &[u8], layout: EscrowLayout, terms: &mut EscrowTerms) -> io::Result<()> {
+        terms.skill_name = read_text(buf, layout)?;
+        if layout >= EscrowLayout::V2 {
+            terms.category = read(buf)?;
+        }
+        terms.duration_seconds = read(buf)?;
+        terms.price_usdc = read(buf)?;
+        terms.metadata_uri = read_text(buf, layout)?;
+        if layout == EscrowLayout::V0 {
+            return Ok(());
+        }
+        terms.max_concurrent_rentals = read(buf)?;
+        terms.settle_in_credits = read(buf)?;
+        terms.dispute_window_seconds = read(buf)?;
+        terms.review_window_seconds = read(buf)?;
+        terms.arbiter = read(buf)?;
+        terms.override_policy = read(buf)?;
+        terms.sla = read(buf)?;
+        if layout >= EscrowLayout::V3 {
+            terms.funding_rule = read(buf)?;
+        }
+        if layout >= EscrowLayout::V6 {
+            terms.requires_provider_acceptance = read(buf)?;
+        }
+        if layout >= EscrowLayout::V8 {
+            terms.trial = read(buf)?;
+        }
+        if layout >= EscrowLayout::V9 {
+            terms.funding_deadline = read(buf)?;
+        }
+        if layout >= EscrowLayout::V10 {
+            terms.vesting_seconds = read(buf)?;
+        }
+        if layout >= EscrowLayout::V15 {
+            terms.price_decimals = read(buf)?;
+        }
+        if layout >= EscrowLayout::V16 {
+            terms.provider_bond = read(buf)?;
+        }
+        if layout >= EscrowLayout::V17 {
+            terms.stream_payment = read(buf)?;
+        }
+        Ok(())
+    }
+
+    /// A text field of the terms; layouts before V14 stored an unbounded `String`
+    fn read_text<const N: usize>(buf: &mut &[u8], layout: EscrowLayout) -> io::Result<FixedString<N>> {
+        if layout >= EscrowLayout::V14 {
+            read(buf)
+        } else {
+            Ok(FixedString::truncated(&read::<String>(buf)?))
+        }
+    }
+
+    fn read<T: AnchorDeserialize>(buf: &mut &[u8]) -> io::Result<T> {
+        T::deserialize(buf)
+    }
+}
+
+/// A skill offered to any number of renters; each `open_rental` creates its own escrow
+#[account]
+pub struct SkillListing {
+    pub provider: Pubkey,
+    pub listing_id: u16,
+    pub token_mint: Pubkey,
+    pub provider_token_account: Pubkey,
+    pub terms: EscrowTerms,
+    pub terms_hash: [u8; 32],
+    /// Rentals opened so far; the next gets `rental_escrow_id(listing_id, rentals_opened)`
+    pub rentals_opened: u32,
+    pub created_at: i64,
+    pub bump: u8,
+    pub _reserved: Reserved<{ ACCOUNT_RESERVE }>,
+}
+
+impl SkillListing {
+    /// 8 (discriminator) + 32 + 2 + 32 + 32 + terms + 32 + 4 + 8 + 1 + reserve
+    pub const LEN: usize = 8 + 32 + 2 + 32 + 32 + EscrowTerms::LEN + 32 + 4 + 8 + 1 + ACCOUNT_RESERVE;
+}
+
+/// Per-provider rental index, shared by all of the provider's escrows
+#[account]
+pub struct ProviderIndex {
+    pub provider: Pubkey,
+    /// Number of escrows currently funded and not yet settled
+    pub active_rentals: u16,
+    pub bump: u8,
+}
+
+impl ProviderIndex {
+    /// 8 (discriminator) + 32 + 2 + 1
+    pub const LEN: usize = 8 + 32 + 2 + 1;
+
+    /// Whether another rental fits under `max_concurrent`; zero means unlimited
+    pub fn has_slot(&self, max_concurrent: u16) -> bool {
+        max_concurrent == 0 || self.active_rentals < max_concurrent
+    }
+
+    /// Count a newly funded rental, refusing it once the provider is at capacity
+    pub fn reserve_slot(&mut self, max_concurrent: u16) -> Result<()> {
+        require!(self.has_slot(max_concurrent), EscrowError::ProviderAtCapacity);
+        self.active_rentals = self.active_rentals.checked_add(1).ok_or(EscrowError::ProviderAtCapacity)?;
+        Ok(())
+    }
+
+    /// Free the slot of a rental that settled
+    pub fn release_slot(&mut self) {
+        self.active_rentals = self.active_rentals.saturating_sub(1);
+    }
+}
+
+/// Lifetime settlement counters per provider, for fee tiers, badges and leaderboards
+#[account]
+pub struct ProviderStats {
+    pub provider: Pubkey,
+    /// Base units released to the provider, across all mints
+    pub lifetime_volume: u64,
+    pub lifetime_releases: u64,
+    pub lifetime_refunds: u64,
+    pub bump: u8,
+    /// Epoch of the latest `initialize_escrow` and how many happened in it
+    pub last_init_epoch: u64,
+    pub epoch_initializations: u16,
+    /// Set by the config admin; verified providers are not throttled
+    pub verified: bool,
+    /// Refunds since the provider's last release, counting timeouts and lost disputes
+    pub failure_streak: u16,
+    /// Disputes opened on the provider's rentals, by either party
+    pub lifetime_disputes: u64,
+}
+
+impl ProviderStats {
+    /// 8 (discriminator) + 32 + 8 + 8 + 8 + 1 + 8 + 2 + 1 + 2 + 8
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 1 + 8 + 2 + 1 + 2 + 8;
+
+    /// Count an initialization in `epoch`, failing once an unverified provider exceeds `max_per_epoch`
+    pub fn record_initialization(&mut self, epoch: u64, max_per_epoch: u16) -> Result<()> {
+        if epoch != self.last_init_epoch {
+            self.last_init_epoch = epoch;
+            self.epoch_initializations = 0;
+        }
+        self.epoch_initializations = self.epoch_initializations.saturating_add(1);
+        require!(
+            self.verified || max_per_epoch == 0 || self.epoch_initializations <= max_per_epoch,
+            EscrowError::ListingRateLimited
+        );
+        Ok(())
+    }
+
+    pub fn record_release(&mut self, amount: u64) -> Result<()> {
+        self.lifetime_volume = self.lifetime_volume.checked_add(amount).ok_or(EscrowError::InsufficientFunds)?;
+        self.lifetime_releases = self.lifetime_releases.saturating_add(1);
+        self.failure_streak = 0;
+        Ok(())
+    }
+
+    pub fn record_refund(&mut self) {
+        self.lifetime_refunds = self.lifetime_refunds.saturating_add(1);
+        self.failure_streak = self.failure_streak.saturating_add(1);
+    }
+
+    pub fn record_dispute(&mut self) {
+        self.lifetime_disputes = self.lifetime_disputes.saturating_add(1);
+    }
+
+    /// Concurrency cap for new rentals: the listing's `max_concurrent` (0 = unlimited),
+    /// or a single rental at a time while the failure streak exceeds `threshold`
+    /// (0 = never throttle). A release ends the streak.
+    pub fn concurrency_limit(&self, max_concurrent: u16, threshold: u16) -> u16 {
+        if threshold > 0 && self.failure_streak > threshold {
+            1
+        } else {
+            max_concurrent
+        }
+    }
+
+    /// Add a milestone payout to the volume; the rental's release is counted once, at the end
+    pub fn record_milestone(&mut self, amount: u64) -> Result<()> {
+        self.lifetime_volume = self.lifetime_volume.checked_add(amount).ok_or(EscrowError::InsufficientFunds)?;
+        Ok(())
+    }
+}
+
+/// Resolution record of one arbiter, across every escrow naming them
+#[account]
+pub struct ArbiterStats {
+    pub arbiter: Pubkey,
+    pub resolutions: u64,
+    /// Seconds from `disputed_at` to the ruling, summed over all resolutions
+    pub total_latency_seconds: u64,
+    pub max_latency_seconds: u64,
+    pub last_resolved_at: i64,
+    pub bump: u8,
+    /// Conflicts of interest reported against the arbiter's declarations
+    pub conflicts: u16,
+    pub _reserved: Reserved<{ ARBITER_STATS_RESERVE }>,
+}
+
+impl ArbiterStats {
+    /// 8 (discriminator) + 32 + 8 + 8 + 8 + 8 + 1 + 2 + reserve
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 1 + 2 + ARBITER_STATS_RESERVE;
+
+    /// Bind a freshly created account to its arbiter
+    pub fn claim(&mut self, arbiter: Pubkey, bump: u8) {
+        if self.arbiter == Pubkey::default() {
+            self.arbiter = arbiter;
+            self.bump = bump;
+        }
+    }
+
+    pub fn record_resolution(&mut self, arbiter: Pubkey, bump: u8, disputed_at: i64, now: i64) {
+        self.claim(arbiter, bump);
+        let latency = now.saturating_sub(disputed_at).max(0) as u64;
+        self.resolutions = self.resolutions.saturating_add(1);
+        self.total_latency_seconds = self.total_latency_seconds.saturating_add(latency);
+        self.max_latency_seconds = self.max_latency_seconds.max(latency);
+        self.last_resolved_at = now;
+    }
+
+    pub fn mean_latency_seconds(&self) -> u64 {
+        self.total_latency_seconds.checked_div(self.resolutions).unwrap_or(0)
+    }
+}
+
+/// Protocol-wide activity counted over an epoch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct EpochCounters {
+    /// Listings initialized and rentals opened from skill listings
+    pub escrows_created: u64,
+    /// Base units funded into escrows, across all mints
+    pub volume: u64,
+    pub disputes: u64,
+    /// Protocol fees charged, in base units across all mints
+    pub fees: u64,
+}
+
+impl EpochCounters {
+    /// 8 + 8 + 8 + 8
+    pub const LEN: usize = 8 + 8 + 8 + 8;
+}
+
+/// The protocol's running counters for the current epoch. `rollover_epoch` freezes them
+/// into an `EpochSnapshot` once the cluster has moved on, and starts counting again.
+#[account]
+pub struct EpochStats {
+    /// Epoch the counters started in
+    pub epoch: u64,
+    pub started_at: i64,
+    pub counters: EpochCounters,
+    pub bump: u8,
+    pub _reserved: Reserved<{ ACCOUNT_RESERVE }>,
+}
+
+impl EpochStats {
+    /// 8 (discriminator) + 8 + 8 + counters + 1 + reserve
+    pub const LEN: usize = 8 + 8 + 8 + EpochCounters::LEN + 1 + ACCOUNT_RESERVE;
+
+    pub fn record_created(&mut self) {
+        self.counters.escrows_created = self.counters.escrows_created.saturating_add(1);
+    }
+
+    pub fn record_funding(&mut self, amount: u64) {
+        self.counters.volume = self.counters.volume.saturating_add(amount);
+    }
+
+    pub fn record_dispute(&mut self) {
+        self.counters.disputes = self.counters.disputes.saturating_add(1);
+    }
+
+    pub fn record_fee(&mut self, fee: u64) {
+        self.counters.fees = self.counters.fees.saturating_add(fee);
+    }
+
+    /// End the counting period at cluster `epoch` and time `now`, returning what it
+    /// counted. A period runs until someone rolls it over, so one cranked late also
+    /// covers the epochs it skipped.
+    pub fn roll_over(&mut self, epoch: u64, now: i64) -> Result<EpochCounters> {
+        require!(epoch > self.epoch, EscrowError::EpochNotOver);
+        let counters = self.counters;
+        self.epoch = epoch;
+        self.started_at = now;
+        self.counters = EpochCounters::default();
+        Ok(counters)
+    }
+}
+
+/// Frozen counters of one past epoch, written by `rollover_epoch`
+#[account]
+pub struct EpochSnapshot {
+    pub epoch: u64,
+    /// First epoch after the period; more than `epoch + 1` if the rollover came late
+    pub next_epoch: u64,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub counters: EpochCounters,
+    pub bump: u8,
+}
+
+impl EpochSnapshot {
+    /// 8 (discriminator) + 8 + 8 + 8 + 8 + counters + 1
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + EpochCounters::LEN + 1;
+}
+
+/// Rental history of one provider-renter pair, which arbiter declarations are checked against
+#[account]
+pub struct Engagement {
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub rentals: u32,
+    pub first_funded_at: i64,
+    pub last_funded_at: i64,
+    pub bump: u8,
+}
+
+impl Engagement {
+    /// 8 (discriminator) + 32 + 32 + 4 + 8 + 8 + 1
+    pub const LEN: usize = 8 + 32 + 32 + 4 + 8 + 8 + 1;
+
+    pub fn record(&mut self, provider: Pubkey, renter: Pubkey, bump: u8, funded_at: i64) {
+        if self.rentals == 0 {
+            self.provider = provider;
+            self.renter = renter;
+            self.bump = bump;
+            self.first_funded_at = funded_at;
+        }
+        self.rentals = self.rentals.saturating_add(1);
+        self.last_funded_at = funded_at;
+    }
+
+    pub fn involves(&self, key: &Pubkey) -> bool {
+        self.provider == *key || self.renter == *key
+    }
+}
+
+/// An arbiter's statement that they have never rented to or from either party of a
+/// dispute, required before they can rule on it
+#[account]
+pub struct ArbiterDeclaration {
+    pub escrow: Pubkey,
+    pub arbiter: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    /// `disputed_at` of the dispute the declaration covers
+    pub disputed_at: i64,
+    pub declared_at: i64,
+    /// Set once a conflict is reported; the arbiter can no longer rule on the dispute
+    pub violated: bool,
+    pub bump: u8,
+}
+
+impl ArbiterDeclaration {
+    /// 8 (discriminator) + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 1
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 1;
+
+    /// Whether the declaration lets its arbiter rule on `escrow`'s current dispute
+    pub fn covers(&self, escrow: &EscrowAccount) -> bool {
+        !self.violated && self.disputed_at > 0 && self.disputed_at == escrow.disputed_at
+    }
+
+    /// Whether `engagement` contradicts the declaration: a rental between the arbiter and
+    /// either party, first funded no later than `CONFLICT_COOLING_OFF_SECONDS` after it
+    pub fn is_contradicted_by(&self, engagement: &Engagement) -> bool {
+        engagement.rentals > 0
+            && engagement.involves(&self.arbiter)
+            && (engagement.involves(&self.provider) || engagement.involves(&self.renter))
+            && engagement.first_funded_at <= self.declared_at.saturating_add(CONFLICT_COOLING_OFF_SECONDS)
+    }
+}
+
+/// M-of-N arbiter. Named as an escrow's `arbiter`, it can only rule through a
+/// `ResolutionProposal` approved by `threshold` of its members; being a PDA, it never
+/// signs the single-arbiter resolve instructions.
+#[account]
+pub struct ArbiterCouncil {
+    pub creator: Pubkey,
+    pub council_id: u32,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub bump: u8,
+    pub _reserved: Reserved<{ ACCOUNT_RESERVE }>,
+}
+
+impl ArbiterCouncil {
+    /// 8 (discriminator) + 32 + 4 + 4 + MAX_COUNCIL_MEMBERS members + 1 + 1 + reserve
+    pub const LEN: usize = 8 + 32 + 4 + 4 + MAX_COUNCIL_MEMBERS * 32 + 1 + 1 + ACCOUNT_RESERVE;
+
+    /// Distinct, non-default members, at most `MAX_COUNCIL_MEMBERS`, and a threshold
+    /// between one and their number
+    pub fn is_valid(members: &[Pubkey], threshold: u8) -> bool {
+        let distinct = members
+            .iter()
+            .enumerate()
+            .all(|(i, member)| *member != Pubkey::default() && !members[..i].contains(member));
+        distinct && members.len() <= MAX_COUNCIL_MEMBERS && threshold >= 1 && threshold as usize <= members.len()
+    }
+
+    pub fn member_index(&self, key: &Pubkey) -> Option<usize> {
+        self.members.iter().position(|member| member == key)
+    }
+}
+
+/// A council member's proposed ruling on one dispute, and which members approved it
+#[account]
+pub struct ResolutionProposal {
+    pub escrow: Pubkey,
+    pub council: Pubkey,
+    /// `disputed_at` of the dispute the proposal rules on
+    pub disputed_at: i64,
+    pub outcome: ResolutionOutcome,
+    pub ruling_hash: [u8; 32],
+    pub proposed_by: Pubkey,
+    pub proposed_at: i64,
+    /// Bit `i` is set once `members[i]` approved
+    pub approvals: u16,
+    pub bump: u8,
+}
+
+impl ResolutionProposal {
+    /// 8 (discriminator) + 32 + 32 + 8 + outcome + 32 + 32 + 8 + 2 + 1
+    pub const LEN: usize = 8 + 32 + 32 + 8 + ResolutionOutcome::LEN + 32 + 32 + 8 + 2 + 1;
+
+    /// Put `outcome` up for `disputed_at`'s dispute. Approvals carry over only when
+    /// the same ruling is proposed again for the same dispute; a different ruling cannot
+    /// displace an approved one for the same dispute until `RESOLUTION_PROPOSAL_SECONDS`
+    /// after it was proposed, so no member can wipe the others' approvals at will.
+    pub fn propose(
+        &mut self,
+        disputed_at: i64,
+        outcome: ResolutionOutcome,
+        ruling_hash: [u8; 32],
+        proposed_by: Pubkey,
+        now: i64,
+    ) -> Result<()> {
+        require!(outcome != ResolutionOutcome::Defaulted, EscrowError::InvalidRuling);
+        if let ResolutionOutcome::Split(provider_bps) = outcome {
+            require!((1..BPS_DENOMINATOR).contains(&provider_bps), EscrowError::InvalidSplit);
+        }
+        if !self.proposes(disputed_at, outcome, ruling_hash) {
+            require!(
+                self.disputed_at != disputed_at
+                    || self.approvals == 0
+                    || now >= self.proposed_at.saturating_add(RESOLUTION_PROPOSAL_SECONDS),
+                EscrowError::ProposalPending
+            );
+            self.disputed_at = disputed_at;
+            self.outcome = outcome;
+            self.ruling_hash = ruling_hash;
+            self.proposed_by = proposed_by;
+            self.proposed_at = now;
+            self.approvals = 0;
+        }
+        Ok(())
+    }
+
+    pub fn proposes(&self, disputed_at: i64, outcome: ResolutionOutcome, ruling_hash: [u8; 32]) -> bool {
+        self.disputed_at == disputed_at && self.outcome == outcome && self.ruling_hash == ruling_hash
+    }
+
+    pub fn approve(&mut self, member_index: usize) {
+        self.approvals |= 1 << member_index;
+    }
+
+    pub fn approved_by(&self, member_index: usize) -> bool {
+        self.approvals & (1 << member_index) != 0
+    }
+}
+
+/// Admin recovery action queued against one escrow, executable from `executable_at`
+#[account]
+pub struct RecoveryAction {
+    pub escrow: Pubkey,
+    pub kind: RecoveryKind,
+    /// Hash of the incident report or settlement proof the action rests on
+    pub proof_hash: [u8; 32],
+    pub queued_by: Pubkey,
+    pub queued_at: i64,
+    pub executable_at: i64,
+    pub bump: u8,
+}
+
+impl RecoveryAction {
+    /// 8 (discriminator) + 32 + kind + 32 + 32 + 8 + 8 + 1
+    pub const LEN: usize = 8 + 32 + RecoveryKind::LEN + 32 + 32 + 8 + 8 + 1;
+
+    pub fn is_due(&self, now: i64) -> bool {
+        now >= self.executable_at
+    }
+}
+
+/// Periodic payout schedule of a subscription escrow. The renter prepaid `periods`
+/// periods of `period_seconds` each, the first starting at funding; the provider claims
+/// each once it has elapsed, and the renter may cancel those that have not started.
+#[account]
+pub struct SubscriptionPlan {
+    pub escrow: Pubkey,
+    pub periods: u32,
+    /// The listed price the renter paid for each period
+    pub amount_per_period: u64,
+    /// What reached the vault for all periods, after any transfer fee
+    pub total: u64,
+    pub period_seconds: i64,
+    pub started_at: i64,
+    /// Periods paid to the provider so far
+    pub claimed_periods: u32,
+    /// Periods owed to the provider: all of them, or after a cancellation those that
+    /// had started by then
+    pub active_periods: u32,
+    pub cancelled_at: i64,
+    pub bump: u8,
+    pub _reserved: Reserved<{ ACCOUNT_RESERVE }>,
+}
+
+impl SubscriptionPlan {
+    /// 8 (discriminator) + 32 + 4 + 8 + 8 + 8 + 8 + 4 + 4 + 8 + 1 + reserve
+    pub const LEN: usize = 8 + 32 + 4 + 8 + 8 + 8 + 8 + 4 + 4 + 8 + 1 + ACCOUNT_RESERVE;
+
+    /// Periods that have begun by `now`, counting the one under way
+    pub fn started_periods(&self, now: i64) -> u32 {
+        if now < self.started_at {
+            return 0;
+        }
+        let started = (now - self.started_at) / self.period_seconds + 1;
+        started.min(self.active_periods as i64) as u32
+    }
+
+    /// Owed periods that have fully elapsed by `now`
+    pub fn elapsed_periods(&self, now: i64) -> u32 {
+        let elapsed = now.saturating_sub(self.started_at).max(0) / self.period_seconds;
+        elapsed.min(self.active_periods as i64) as u32
+    }
+
+    /// Vault share of the first `count` periods; the last period takes any rounding remainder
+    pub fn amount_through(&self, count: u32) -> u64 {
+        (self.total as u128 * count as u128 / self.periods as u128) as u64
+    }
+
+    /// Mark every elapsed, unclaimed period at `now` claimed, returning their amount
+    pub fn claim(&mut self, now: i64) -> Result<u64> {
+        let elapsed = self.elapsed_periods(now);
+        require!(elapsed > self.claimed_periods, EscrowError::NoPeriodDue);
+        let amount = self.amount_through(elapsed) - self.amount_through(self.claimed_periods);
+        self.claimed_periods = elapsed;
+        Ok(amount)
+    }
+
+    /// Drop the periods that have not started at `now`, returning the renter's refund
+    pub fn cancel(&mut self, now: i64) -> Result<u64> {
+        require!(self.cancelled_at == 0, EscrowError::InvalidState);
+        let started = self.started_periods(now);
+        require!(started < self.active_periods, EscrowError::InvalidState);
+        let refund = self.amount_through(self.active_periods) - self.amount_through(started);
+        self.active_periods = started;
+        self.cancelled_at = now;
+        Ok(refund)
+    }
+
+    /// Every owed period has been paid
+    pub fn is_finished(&self) -> bool {
+        self.claimed_periods == self.active_periods
+    }
+}
+
+/// Incremental payout schedule of one escrow. Each milestone is approved by the renter
+/// and then paid to the provider out of the escrow vault, ahead of the final release.
+#[account]
+pub struct MilestonePlan {
+    pub escrow: Pubkey,
+    pub milestones: Vec<Milestone>,
+    /// Paid to the provider so far by `release_milestone`
+    pub released: u64,
+    pub bump: u8,
+    pub _reserved: Reserved<{ ACCOUNT_RESERVE }>,
+}
+
+impl MilestonePlan {
+    /// 8 (discriminator) + 32 + 4 + MAX_MILESTONES milestones + 8 + 1 + reserve
+    pub const LEN: usize = 8 + 32 + 4 + MAX_MILESTONES * Milestone::LEN + 8 + 1 + ACCOUNT_RESERVE;
+
+    /// Sum of every milestone's amount, released or not
+    pub fn scheduled(&self) -> u64 {
+        self.milestones.iter().fold(0u64, |sum, m| sum.saturating_add(m.amount))
+    }
+
+    /// Append a pending milestone, keeping the schedule within `cap`
+    pub fn add(&mut self, description_hash: [u8; 32], amount: u64, cap: u64) -> Result<()> {
+        require!(amount > 0, EscrowError::InvalidMilestone);
+        require!(self.milestones.len() < MAX_MILESTONES, EscrowError::TooManyMilestones);
+        let scheduled = self.scheduled().checked_add(amount).ok_or(EscrowError::MilestonesExceedEscrow)?;
+        require!(scheduled <= cap, EscrowError::MilestonesExceedEscrow);
+        self.milestones.push(Milestone {
+            description_hash,
+            amount,
+            state: MilestoneState::Pending,
+        });
+        Ok(())
+    }
+
+    /// Mark a pending milestone approved, returning its amount
+    pub fn approve(&mut self, index: u8) -> Result<u64> {
+        let milestone = self.milestones.get_mut(index as usize).ok_or(EscrowError::InvalidMilestone)?;
+        require!(milestone.state == MilestoneState::Pending, EscrowError::InvalidMilestone);
+        milestone.state = MilestoneState::Approved;
+        Ok(milestone.amount)
+    }
+
+    /// Mark an approved milestone released, returning the amount to pay out
+    pub fn release(&mut self, index: u8) -> Result<u64> {
+        let milestone = self.milestones.get_mut(index as usize).ok_or(EscrowError::InvalidMilestone)?;
+        require!(milestone.state == MilestoneState::Approved, EscrowError::MilestoneNotApproved);
+        milestone.state = MilestoneState::Released;
+        let amount = milestone.amount;
+        self.released = self.released.checked_add(amount).ok_or(EscrowError::InsufficientFunds)?;
+        Ok(amount)
+    }
+}
+
+/// Pointers to off-chain proof submitted by the parties to a dispute, for the arbiter.
+/// Entries from an earlier dispute on the same escrow are dropped when a new one opens.
+#[account]
+pub struct DisputeEvidence {
+    pub escrow: Pubkey,
+    /// Dispute the entries belong to
+    pub disputed_at: i64,
+    pub entries: Vec<EvidenceEntry>,
+    pub bump: u8,
+    pub _reserved: Reserved<{ ACCOUNT_RESERVE }>,
+}
+
+impl DisputeEvidence {
+    /// 8 (discriminator) + 32 + 8 + 4 + MAX_EVIDENCE_ENTRIES entries + 1 + reserve
+    pub const LEN: usize = 8 + 32 + 8 + 4 + MAX_EVIDENCE_ENTRIES * EvidenceEntry::LEN + 1 + ACCOUNT_RESERVE;
+
+    /// Scope the entries to the dispute opened at `disputed_at`
+    pub fn start_dispute(&mut self, disputed_at: i64) {
+        if self.disputed_at != disputed_at {
+            self.disputed_at = disputed_at;
+            self.entries.clear();
+        }
+    }
+
+    pub fn add(&mut self, submitted_by: Pubkey, uri: String, hash: [u8; 32], submitted_at: i64) -> Result<()> {
+        require!(uri.len() <= MAX_EVIDENCE_URI_LEN, EscrowError::EvidenceUriTooLong);
+        require!(self.entries.len() < MAX_EVIDENCE_ENTRIES, EscrowError::TooManyEvidenceEntries);
+        self.entries.push(EvidenceEntry {
+            submitted_by,
+            uri,
+            hash,
+            submitted_at,
+        });
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EvidenceEntry {
+    pub submitted_by: Pubkey,
+    /// Where the proof is stored (max `MAX_EVIDENCE_URI_LEN` bytes)
+    pub uri: String,
+    /// Hash of the proof, to check a fetched copy against
+    pub hash: [u8; 32],
+    pub submitted_at: i64,
+}
+
+impl EvidenceEntry {
+    /// 32 + 4 + MAX_EVIDENCE_URI_LEN + 32 + 8
+    pub const LEN: usize = 32 + 4 + MAX_EVIDENCE_URI_LEN + 32 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Milestone {
+    /// Hash of the off-chain description of the work this milestone pays for
+    pub description_hash: [u8; 32],
+    pub amount: u64,
+    pub state: MilestoneState,
+}
+
+impl Milestone {
+    /// 32 + 8 + 1
+    pub const LEN: usize = 32 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MilestoneState {
+    Pending,
+    Approved,
+    Released,
+}
+
+/// Translated term documents of one listing, all anchored to a single canonical terms
+/// hash. Readers should ignore it unless `terms_hash` matches the escrow's.
+#[account]
+pub struct TermsLocalization {
+    pub escrow: Pubkey,
+    /// `EscrowAccount::terms_hash` the entries were published against
+    pub terms_hash: [u8; 32],
+    pub entries: Vec<LocalizedTerms>,
+    pub bump: u8,
+    pub _reserved: Reserved<{ ACCOUNT_RESERVE }>,
+}
+
+impl TermsLocalization {
+    /// 8 (discriminator) + 32 + 32 + 4 + MAX_LOCALIZATIONS entries + 1 + reserve
+    pub const LEN: usize = 8 + 32 + 32 + 4 + MAX_LOCALIZATIONS * LocalizedTerms::LEN + 1 + ACCOUNT_RESERVE;
+
+    /// Set or, with `None`, remove the URI for `language`. Entries published against
+    /// other terms are dropped first.
+    pub fn set(&mut self, terms_hash: [u8; 32], language: &str, metadata_uri: Option<String>) -> Result<()> {
+        require!(LocalizedTerms::is_valid_language(language), EscrowError::InvalidLanguageTag);
+        if self.terms_hash != terms_hash {
+            self.terms_hash = terms_hash;
+            self.entries.clear();
+        }
+        let existing = self.entries.iter().position(|entry| entry.language == language);
+        match (metadata_uri, existing) {
+            (Some(uri), _) if uri.is_empty() || uri.len() > MAX_LOCALIZED_URI_LEN => {
+                return err!(EscrowError::InvalidLocalizedUri)
+            }
+            (Some(uri), Some(index)) => self.entries[index].metadata_uri = uri,
+            (Some(uri), None) => {
+                require!(self.entries.len() < MAX_LOCALIZATIONS, EscrowError::TooManyLocalizations);
+                self.entries.push(LocalizedTerms {
+                    language: language.to_string(),
+                    metadata_uri: uri,
+                });
+            }
+            (None, Some(index)) => {
+                self.entries.remove(index);
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// URI of the terms in `language`, if published against `terms_hash`
+    pub fn uri_for(&self, terms_hash: &[u8; 32], language: &str) -> Option<&str> {
+        if self.terms_hash != *terms_hash {
+            return None;
+        }
+        self.entries
+            .iter()
+            .find(|entry| entry.language == language)
+            .map(|entry| entry.metadata_uri.as_str())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LocalizedTerms {
+    /// BCP 47 language tag
+    pub language: String,
+    pub metadata_uri: String,
+}
+
+impl LocalizedTerms {
+    /// (4 + MAX_LANGUAGE_TAG_LEN) + (4 + MAX_LOCALIZED_URI_LEN)
+    pub const LEN: usize = 4 + MAX_LANGUAGE_TAG_LEN + 4 + MAX_LOCALIZED_URI_LEN;
+
+    /// ASCII letters, digits and hyphens, starting with a letter
+    pub fn is_valid_language(language: &str) -> bool {
+        (2..=MAX_LANGUAGE_TAG_LEN).contains(&language.len())
+            && language.starts_with(|c: char| c.is_ascii_alphabetic())
+            && language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    }
+}
+
+/// Per-provider, per-mint credit ledger for micro-rentals.
+///
+/// Funds for credit-settled escrows are pooled in one vault (ATA owned by this PDA);
+/// completion only moves `locked` into `available`, and `withdraw_credits` batches
+/// the actual token transfer.
+#[account]
+pub struct CreditBalance {
+    pub provider: Pubkey,
+    pub token_mint: Pubkey,
+    /// Funded but not yet settled
+    pub locked: u64,
+    /// Earned and withdrawable by the provider
+    pub available: u64,
+    pub total_withdrawn: u64,
+    pub bump: u8,
+}
+
+impl CreditBalance {
+    /// 8 (discriminator) + 32 + 32 + 8 + 8 + 8 + 1
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+
+    /// Bind a freshly created ledger to `provider` and `token_mint`
+    pub fn bind(&mut self, provider: Pubkey, token_mint: Pubkey, bump: u8) {
+        if self.provider == Pubkey::default() {
+            self.provider = provider;
+            self.token_mint = token_mint;
+            self.bump = bump;
+        }
+    }
+
+    /// Hold a funded rental's `amount` until it settles
+    pub fn lock(&mut self, amount: u64) -> Result<()> {
+        self.locked = self.locked.checked_add(amount).ok_or(EscrowError::InsufficientFunds)?;
+        Ok(())
+    }
+
+    /// Move a completed rental's `amount` to the provider's withdrawable balance
+    pub fn settle(&mut self, amount: u64) -> Result<()> {
+        self.unlock(amount)?;
+        self.available = self.available.checked_add(amount).ok_or(EscrowError::InsufficientFunds)?;
+        Ok(())
+    }
+
+    /// Release a refunded rental's `amount`; the caller pays it back out of the vault
+    pub fn unlock(&mut self, amount: u64) -> Result<()> {
+        self.locked = self.locked.checked_sub(amount).ok_or(EscrowError::InsufficientFunds)?;
+        Ok(())
+    }
+}
+
+/// Global program configuration
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Points emission tiers, ordered by `starts_at`; the latest started tier applies
+    pub points_schedule: Vec<EmissionTier>,
+    pub redemption_enabled: bool,
+    pub bump: u8,
+    /// Compliance screener that must approve large releases (default key = disabled)
+    pub screener: Pubkey,
+    /// Releases of at least this many base units are screened
+    pub screening_threshold: u64,
+    /// After this long from funding without a screener decision, release proceeds
+    pub screening_delay_seconds: i64,
+    /// `initialize_escrow` calls allowed per provider per epoch (0 = unlimited)
+    pub max_initializations_per_epoch: u16,
+    /// `FEATURE_*` bits compiled into the deployed binary
+    pub features: u8,
+    /// Largest price a trial listing may charge (0 = trials disabled)
+    pub trial_max_amount: u64,
+    /// Tax rate stated on invoices, applied to the provider's payout
+    pub tax_bps: u16,
+    /// Protocol fee on provider payouts, at most `MAX_FEE_BPS`
+    pub fee_bps: u16,
+    /// Owner of the token accounts fees are paid into, one per mint
+    pub fee_treasury: Pubkey,
+    /// Providers whose failure streak exceeds this take one rental at a time (0 = off)
+    pub failure_streak_threshold: u16,
+    /// Time an arbiter has to rule on a newly opened dispute (0 = no deadline)
+    pub arbitration_window_seconds: i64,
+    /// Default judgment releases to the provider instead of refunding the renter
+    pub default_release: bool,
+    /// Mints escrows may be priced and funded in (empty = any mint)
+    pub allowed_mints: Vec<Pubkey>,
+    /// How `compute_risk` weighs its factors (all zero = risk scoring disabled)
+    pub risk_weights: RiskWeights,
+}
+
+impl Config {
+    /// 8 + 32 + (4 + 4 * 10) + 1 + 1 + 32 + 8 + 8 + 2 + 1 + 8 + 2 + 2 + 32 + 2 + 8 + 1 + (4 + 8 * 32)
+    /// + 16
+    pub const LEN: usize = 8
+        + 32
+        + 4
+        + MAX_EMISSION_TIERS * 10
+        + 1
+        + 1
+        + 32
+        + 8
+        + 8
+        + 2
+        + 1
+        + 8
+        + 2
+        + 2
+        + 32
+        + 2
+        + 8
+        + 1
+        + 4
+        + MAX_ALLOWED_MINTS * 32
+        + RiskWeights::LEN;
+
+    /// A fee needs somewhere to go; a zero fee may leave the treasury unset
+    pub fn valid_fee(fee_bps: u16, fee_treasury: &Pubkey) -> bool {
+        fee_bps <= MAX_FEE_BPS && (fee_bps == 0 || *fee_treasury != Pubkey::default())
+    }
+
+    /// Protocol fee withheld from a provider payout of `amount`, rounded down
+    pub fn fee_on(&self, amount: u64) -> u64 {
+        (amount as u128 * self.fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Deadline for ruling on a dispute opened at `disputed_at` (0 = none)
+    pub fn arbitration_deadline(&self, disputed_at: i64) -> i64 {
+        match self.arbitration_window_seconds {
+            0 => 0,
+            window => disputed_at.saturating_add(window),
+        }
+    }
+
+    pub fn supports(&self, feature: u8) -> bool {
+        self.features & feature != 0
+    }
+
+    /// Whether escrows may use `mint`; an empty allowlist admits every mint
+    pub fn allows_mint(&self, mint: &Pubkey) -> bool {
+        self.allowed_mints.is_empty() || self.allowed_mints.contains(mint)
+    }
+
+    pub fn requires_screening(&self, amount: u64) -> bool {
+        self.screener != Pubkey::default() && amount >= self.screening_threshold
+    }
+}
+
+/// Weights of the factors `compute_risk` combines. Each factor is a risk from 0 to
+/// `BPS_DENOMINATOR`; the score is their weighted mean, scaled to 0..=100.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RiskWeights {
+    pub disputes: u16,
+    pub cancellations: u16,
+    pub account_age: u16,
+    pub bond_coverage: u16,
+    /// Reputation account age from which a provider carries no age risk
+    pub mature_age_seconds: i64,
+}
+
+impl RiskWeights {
+    pub const LEN: usize = 2 + 2 + 2 + 2 + 8;
+
+    pub fn total(&self) -> u32 {
+        self.disputes as u32 + self.cancellations as u32 + self.account_age as u32 + self.bond_coverage as u32
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EmissionTier {
+    pub starts_at: i64,
+    /// Points per 10_000 base units settled
+    pub points_bps: u16,
+}
+
+/// Protocol points balance for a wallet, credited on successful settlement
+#[account]
+pub struct PointsAccount {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub lifetime_earned: u64,
+    pub redeemed: u64,
+    pub bump: u8,
+}
+
+impl PointsAccount {
+    /// 8 + 32 + 8 + 8 + 8 + 1
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 1;
+}
+
+/// Bilateral payment channel for agent pairs that transact often.
+///
+/// The payer deposits once and signs cumulative balance updates off-chain per rental;
+/// `settle_channel` moves only the delta since the last settlement.
+#[account]
+pub struct SettlementChannel {
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub token_mint: Pubkey,
+    pub deposited: u64,
+    /// Cumulative amount paid out to the payee
+    pub settled: u64,
+    /// Nonce of the latest settled update
+    pub nonce: u64,
+    pub close_requested_at: i64,
+    pub bump: u8,
+}
+
+impl SettlementChannel {
+    /// 8 + 32 * 3 + 8 + 8 + 8 + 8 + 1
+    pub const LEN: usize = 8 + 32 * 3 + 8 + 8 + 8 + 8 + 1;
+
+    /// Bytes the payer signs for a balance update
+    pub fn message(channel: &Pubkey, cumulative: u64, nonce: u64) -> Vec<u8> {
+        [
+            CHANNEL_MESSAGE_PREFIX,
+            channel.as_ref(),
+            &cumulative.to_le_bytes(),
+            &nonce.to_le_bytes(),
+        ]
+        .concat()
+    }
+}
+
+/// Per-rental invoice issued after settlement, for renters' and providers' accounting
+#[account]
+pub struct Invoice {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub token_mint: Pubkey,
+    pub terms_hash: [u8; 32],
+    /// Amount the renter funded
+    pub amount: u64,
+    /// Paid (or vesting) to the provider, before tax
+    pub provider_amount: u64,
+    pub renter_refund: u64,
+    /// Protocol fee withheld from the provider's payout
+    pub fee_amount: u64,
+    pub tax_bps: u16,
+    pub tax_amount: u64,
+    pub funded_at: i64,
+    pub settled_at: i64,
+    pub issued_at: i64,
+    pub bump: u8,
+}
+
+impl Invoice {
+    /// 8 + 32 * 5 + 8 * 4 + 2 + 8 + 8 * 3 + 1
+    pub const LEN: usize = 8 + 32 * 5 + 8 * 4 + 2 + 8 + 8 * 3 + 1;
+
+    pub fn tax_on(amount: u64, tax_bps: u16) -> u64 {
+        (amount as u128 * tax_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+/// A renter's off-chain acceptance of an escrow's terms, recorded by `record_acceptance`
+#[account]
+pub struct TermsAcceptance {
+    pub escrow: Pubkey,
+    pub renter: Pubkey,
+    /// `EscrowAccount::terms_hash` when the renter signed
+    pub terms_hash: [u8; 32],
+    pub expires_at: i64,
+    /// The renter's Ed25519 signature over `message`
+    pub signature: [u8; 64],
+    pub recorded_at: i64,
+    pub bump: u8,
+}
+
+impl TermsAcceptance {
+    /// 8 + 32 * 3 + 8 + 64 + 8 + 1
+    pub const LEN: usize = 8 + 32 * 3 + 8 + 64 + 8 + 1;
+
+    /// Text the renter signs, readable when a wallet shows it for approval
+    pub fn message(escrow: &Pubkey, provider: &Pubkey, renter: &Pubkey, terms_hash: &[u8; 32], expires_at: i64) -> Vec<u8> {
+        let terms_hash: String = terms_hash.iter().map(|b| format!("{b:02x}")).collect();
+        let body = format!(
+            "\nI accept the terms of escrow {escrow}\nterms hash: {terms_hash}\nprovider: {provider}\nrenter: {renter}\nexpires at: {expires_at}"
+        );
+        [ACCEPTANCE_MESSAGE_PREFIX, body.as_bytes()].concat()
+    }
+}
+
+/// A renter's deposit on a listing that `requires_provider_acceptance`, held until the
+/// provider accepts the renter or the deposit is returned
+#[account]
+pub struct RentalRequest {
+    pub escrow: Pubkey,
+    pub renter: Pubkey,
+    pub amount: u64,
+    pub overrides: FundOverrides,
+    /// Terms the renter agreed to; acceptance fails if the listing changed since
+    pub terms_hash: [u8; 32],
+    /// Renter's reputation when requesting, applied to the listing's funding rule
+    pub reputation_score: Option<i64>,
+    pub requested_at: i64,
+    pub bump: u8,
+}
+
+impl RentalRequest {
+    /// 8 + 32 + 32 + 8 + overrides (9 + 9 + 33 + 1) + 32 + 9 + 8 + 1
+    pub const LEN: usize = 8 + 32 + 32 + 8 + (9 + 9 + 33 + 1) + 32 + 9 + 8 + 1;
+
+    /// End of the provider's acceptance window
+    pub fn expires_at(&self) -> i64 {
+        self.requested_at.saturating_add(PROVIDER_ACCEPTANCE_WINDOW_SECONDS)
+    }
+
+    /// Renter and provider may return the deposit at any time, anyone else after expiry
+    pub fn can_withdraw(&self, authority: &Pubkey, provider: &Pubkey, now: i64) -> bool {
+        authority == &self.renter || authority == provider || now >= self.expires_at()
+    }
+}
+
+/// Commit-reveal randomness for a dispute (panel selection, tie-breaks).
+///
+/// Slots are renter, provider, arbiter. Each party commits to `hash(secret || party)`,
+/// reveals once everyone committed, and the XOR of revealed secrets is hashed with a
+/// slot hash taken at finalization, so no single submitter controls the seed.
+#[account]
+#[derive(Default)]
+pub struct DisputeRandomness {
+    pub escrow: Pubkey,
+    /// Dispute this round belongs to
+    pub disputed_at: i64,
+    pub commitments: [[u8; 32]; 3],
+    pub revealed: [bool; 3],
+    pub accumulator: [u8; 32],
+    pub seed: [u8; 32],
+    pub finalized: bool,
+    pub bump: u8,
+}
+
+impl DisputeRandomness {
+    /// 8 + 32 + 8 + 96 + 3 + 32 + 32 + 1 + 1
+    pub const LEN: usize = 8 + 32 + 8 + 32 * 3 + 3 + 32 + 32 + 1 + 1;
+
+    pub fn commitment(secret: &[u8; 32], party: &Pubkey) -> [u8; 32] {
+        hashv(&[secret, party.as_ref()]).to_bytes()
+    }
+
+    pub fn all_committed(&self) -> bool {
+        self.commitments.iter().all(|c| *c != [0u8; 32])
+    }
+
+    /// Every party that committed has revealed
+    pub fn all_revealed(&self) -> bool {
+        self.commitments
+            .iter()
+            .zip(self.revealed)
+            .all(|(c, revealed)| *c == [0u8; 32] || revealed)
+    }
+
+    /// Uniform-enough index in `0..n` from the finalized seed and a caller-chosen `round`
+    pub fn select(&self, round: u64, n: u64) -> Option<u64> {
+        if !self.finalized || n == 0 {
+            return None;
+        }
+        let digest = hashv(&[&self.seed, &round.to_le_bytes()]).to_bytes();
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&digest[..8]);
+        Some(u64::from_le_bytes(word) % n)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct EscrowTerms {
+    /// At a fixed offset in `EscrowAccount`, so listings can be filtered by skill
+    pub skill_name: FixedString<{ MAX_SKILL_NAME_LEN }>,
+    /// Discovery category, e.g. "code-review" (max 32 bytes)
+    pub category: String,
+    pub duration_seconds: i64,
+    pub price_usdc: u64,
+    pub metadata_uri: FixedString<{ MAX_METADATA_URI_LEN }>,
+    /// Max escrows the provider will have funded at once (0 = unlimited)
+    pub max_concurrent_rentals: u16,
+    /// Settle through the provider's credit ledger instead of a per-escrow vault
+    pub settle_in_credits: bool,
+    /// Default time after the rental period ends during which a dispute can be opened
+    pub dispute_window_seconds: i64,
+    /// Default time after delivery during which the renter can review and dispute
+    pub review_window_seconds: i64,
+    /// Default arbiter for disputes
+    pub arbiter: Pubkey,
+    /// Ranges within which the renter may override the defaults at fund time
+    pub override_policy: OverridePolicy,
+    /// Machine-checkable service levels, attested by `SlaTerms::verifier`
+    pub sla: Option<SlaTerms>,
+    /// Upfront requirement by renter reputation; `price_usdc` is the full price
+    pub funding_rule: Option<FundingRule>,
+    /// Renters deposit via `request_rental` and the provider picks who to `accept_renter`
+    pub requires_provider_acceptance: bool,
+    /// Low-stakes integration test: price capped by `Config::trial_max_amount`, no
+    /// funding rule, and settlements are left out of `ProviderStats`
+    pub trial: bool,
+    /// Unix time after which the listing can no longer be funded and anyone may
+    /// `expire_listing` it (0 = never)
+    pub funding_deadline: i64,
+    /// Released payouts vest to the provider linearly over this long, during which the
+    /// renter may still dispute the unvested part (0 = paid out at release)
+    pub vesting_seconds: i64,
+    /// Decimals of the mint `price_usdc` is denominated in; the listing's mint must have
+    /// exactly these, so a price written for a 6-decimal stablecoin cannot be charged in
+    /// a 9-decimal one
+    pub price_decimals: u8,
+    /// Deposit the provider must lock with `fund_bond` before the listing can be funded
+    /// (0 = none). A dispute settled in the renter's favour pays them the bond, in
+    /// proportion to their share of a split; otherwise it returns to the provider.
+    pub provider_bond: u64,
+    /// While funded, the provider may `claim_streamed` the price pro rata to the rental
+    /// time elapsed; what has been streamed is no longer refundable or disputable
+    pub stream_payment: bool,
+}
+
+impl EscrowTerms {
+    pub const LEN: usize = FixedString::<MAX_SKILL_NAME_LEN>::LEN
+        + 4 + MAX_CATEGORY_LEN
+        + 8 + 8
+        + FixedString::<MAX_METADATA_URI_LEN>::LEN
+        + 2 + 1 + 8 + 8 + 32 + 33 // concurrency, credits, windows, arbiter, override policy
+        + 1 + SlaTerms::LEN
+        + 1 + FundingRule::LEN
+        + 1 + 1 + 8 + 8 // provider acceptance, trial, funding deadline, vesting
+        + 1 // price decimals
+        + 8 // provider bond
+        + 1; // stream payment
+
+    pub fn text_is_valid(&self) -> bool {
+        self.skill_name.is_valid() && self.metadata_uri.is_valid()
+    }
+
+    /// Non-trial terms always pass; trials need trials enabled, no funding rule and a
+    /// price within `trial_max_amount`
+    pub fn trial_is_valid(&self, trial_max_amount: u64) -> bool {
+        !self.trial || (trial_max_amount > 0 && self.funding_rule.is_none() && self.price_usdc <= trial_max_amount)
+    }
+
+    /// Token-settled terms always pass; credit-settled ones are priced within
+    /// `MAX_CREDIT_PRICE_TOKENS` of a mint with `mint_decimals`
+    pub fn credit_price_is_valid(&self, mint_decimals: u8) -> bool {
+        let max_price = 10u64
+            .checked_pow(mint_decimals as u32)
+            .and_then(|unit| unit.checked_mul(MAX_CREDIT_PRICE_TOKENS))
+            .unwrap_or(u64::MAX);
+        !self.settle_in_credits || self.price_usdc <= max_price
+    }
+
+    /// Whether a renter may fund `amount`: the exact price, or with a funding rule no
+    /// more than the price (the rule decides the minimum)
+    pub fn accepts_funding(&self, amount: u64) -> bool {
+        match self.funding_rule {
+            Some(_) => amount <= self.price_usdc,
+            None => amount == self.price_usdc,
+        }
+    }
+}
+
+/// How much of the price a renter must fund upfront, by reputation score.
+///
+/// Renters without a reputation account, or scoring below `full_prefund_below`, fund
+/// 100%. Scores from `deferred_from` up fund `deferred_upfront_bps`; the rest pay
+/// `standard_upfront_bps`. Any remainder is due `deferred_due_seconds` after funding.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FundingRule {
+    pub full_prefund_below: i64,
+    pub standard_upfront_bps: u16,
+    pub deferred_from: i64,
+    pub deferred_upfront_bps: u16,
+    pub deferred_due_seconds: i64,
+}
+
+impl FundingRule {
+    /// 8 + 2 + 8 + 2 + 8
+    pub const LEN: usize = 8 + 2 + 8 + 2 + 8;
+
+    pub fn is_valid(&self) -> bool {
+        self.full_prefund_below <= self.deferred_from
+            && self.standard_upfront_bps <= BPS_DENOMINATOR
+            && self.deferred_upfront_bps <= BPS_DENOMINATOR
+            && self.deferred_due_seconds >= 0
+    }
+
+    pub fn upfront_bps(&self, score: Option<i64>) -> u16 {
+        match score {
+            Some(score) if score >= self.deferred_from => self.deferred_upfront_bps,
+            Some(score) if score >= self.full_prefund_below => self.standard_upfront_bps,
+            _ => BPS_DENOMINATOR,
+        }
+    }
+}
+
+/// Version byte prefixed to `SlaTerms::canonical_bytes`
+pub const SLA_SCHEMA_VERSION: u8 = 1;
+
+/// Service-level targets for a rental. Off-chain tooling should exchange these via
+/// `canonical_bytes`, which is exactly what the terms hash commits to.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SlaTerms {
+    /// Max response latency in milliseconds
+    pub max_latency_ms: Option<u32>,
+    /// Min uptime over the rental, in basis points
+    pub min_uptime_bps: Option<u16>,
+    /// Max revision rounds before the deliverable is accepted
+    pub max_revisions: Option<u8>,
+    /// Only key allowed to call `report_sla_breach`
+    pub verifier: Pubkey,
+    pub penalty_bps_per_breach: u16,
+    pub max_penalty_bps: u16,
+}
+
+impl SlaTerms {
+    /// 5 + 3 + 2 (options) + 32 + 2 + 2
+    pub const LEN: usize = 5 + 3 + 2 + 32 + 2 + 2;
+
+    pub fn is_valid(&self) -> bool {
+        self.min_uptime_bps.is_none_or(|bps| bps <= BPS_DENOMINATOR)
+            && self.penalty_bps_per_breach <= self.max_penalty_bps
+            && self.max_penalty_bps <= BPS_DENOMINATOR
+            && self.verifier != Pubkey::default()
+    }
+
+    /// Whether `observed` misses the target for `metric`; `None` if the metric isn't tracked
+    pub fn breached(&self, metric: SlaMetric, observed: u64) -> Option<bool> {
+        match metric {
+            SlaMetric::LatencyMs => self.max_latency_ms.map(|max| observed > max as u64),
+            SlaMetric::UptimeBps => self.min_uptime_bps.map(|min| observed < min as u64),
+            SlaMetric::Revisions => self.max_revisions.map(|max| observed > max as u64),
+        }
+    }
+
+    pub fn penalty_bps(&self, breaches: u8) -> u16 {
+        self.penalty_bps_per_breach
+            .saturating_mul(breaches as u16)
+            .min(self.max_penalty_bps)
+    }
+
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![SLA_SCHEMA_VERSION];
+        self.serialize(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn from_canonical_bytes(bytes: &[u8]) -> Result<Self> {
+        match bytes.split_first() {
+            Some((&SLA_SCHEMA_VERSION, mut body)) => Ok(Self::deserialize(&mut body)?),
+            _ => err!(EscrowError::InvalidSlaTerms),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlaMetric {
+    LatencyMs,
+    UptimeBps,
+    Revisions,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct OverridePolicy {
+    pub min_dispute_window_seconds: i64,
+    pub max_dispute_window_seconds: i64,
+    pub min_review_window_seconds: i64,
+    pub max_review_window_seconds: i64,
+    pub allow_arbiter_override: bool,
+}
+
+impl OverridePolicy {
+    /// Ranges must be well-formed and contain the listing's defaults
+    pub fn is_valid(&self, terms: &EscrowTerms) -> bool {
+        self.min_dispute_window_seconds >= 0
+            && self.min_review_window_seconds >= 0
+            && (self.min_dispute_window_seconds..=self.max_dispute_window_seconds)
+                .contains(&terms.dispute_window_seconds)
+            && (self.min_review_window_seconds..=self.max_review_window_seconds)
+                .contains(&terms.review_window_seconds)
+    }
+}
+
+/// Per-deal overrides the renter may request when funding
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct FundOverrides {
+    pub dispute_window_seconds: Option<i64>,
+    pub review_window_seconds: Option<i64>,
+    pub arbiter: Option<Pubkey>,
+    /// Opt into automatic release at the end of the rental; the listing must offer it
+    pub auto_release: bool,
+}
+
+/// Party that proposed a mutual cancellation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CancelProposer {
+    #[default]
+    None,
+    Provider,
+    Renter,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Default)]
+pub enum EscrowState {
+    #[default]
+    Created,
+    Funded,
+    Completed,
+    Cancelled,
+    Disputed,
+    /// Unfunded listing withdrawn by the provider
+    Closed,
+    /// Unfunded listing whose `funding_deadline` or sunset passed
+    Expired,
+    /// Released; the provider's payout unlocks linearly over `terms.vesting_seconds`
+    Vesting,
+    /// Prepaid for several periods; the provider claims each as it elapses, see
+    /// `SubscriptionPlan`
+    Subscribed,
+}
+
+// ========== Contexts ==========
+
+#[derive(Accounts)]
+#[instruction(terms: EscrowTerms, escrow_id: u64)]
+pub struct InitializeEscrow<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        init,
+        payer = provider,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_id)],
+        bump,
+        space = EscrowAccount::LEN
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        seeds = [PROVIDER_INDEX_SEED, provider.key().as_ref()],
+        bump,
+        space = ProviderIndex::LEN
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        seeds = [PROVIDER_STATS_SEED, provider.key().as_ref()],
+        bump,
+        space = ProviderStats::LEN
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(constraint = config.allows_mint(&token_mint.key()) @ EscrowError::InvalidMint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTerms<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptEscrow<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider_token_account,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        init_if_needed,
+        payer = renter,
+        space = Engagement::LEN,
+        seeds = [ENGAGEMENT_SEED, escrow_account.provider.as_ref(), renter.key().as_ref()],
+        bump,
+    )]
+    pub engagement: Account<'info, Engagement>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    /// Provider's token account (must match escrow_account.provider_token_account)
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(constraint = config.allows_mint(&token_mint.key()) @ EscrowError::InvalidMint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = renter,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: renter's reputation `Agent`, validated in `read_reputation_score`
+    pub renter_reputation: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundWithSol<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider_token_account,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        init_if_needed,
+        payer = renter,
+        space = Engagement::LEN,
+        seeds = [ENGAGEMENT_SEED, escrow_account.provider.as_ref(), renter.key().as_ref()],
+        bump,
+    )]
+    pub engagement: Account<'info, Engagement>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    /// Provider's token account (must match escrow_account.provider_token_account)
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        constraint = is_wrapped_sol(&token_mint.key()) @ EscrowError::NotWrappedSol,
+        constraint = config.allows_mint(&token_mint.key()) @ EscrowError::InvalidMint,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = renter,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: renter's reputation `Agent`, validated in `read_reputation_score`
+    pub renter_reputation: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRental<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        init,
+        payer = renter,
+        seeds = [RENTAL_REQUEST_SEED, escrow_account.key().as_ref(), renter.key().as_ref()],
+        bump,
+        space = RentalRequest::LEN
+    )]
+    pub rental_request: Account<'info, RentalRequest>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = renter,
+        associated_token::mint = token_mint,
+        associated_token::authority = rental_request,
+        associated_token::token_program = token_program,
+    )]
+    pub request_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: renter's reputation `Agent`, validated in `read_reputation_score`
+    pub renter_reputation: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptRenter<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, provider.key().as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        seeds = [PROVIDER_STATS_SEED, provider.key().as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = Engagement::LEN,
+        seeds = [ENGAGEMENT_SEED, provider.key().as_ref(), renter.key().as_ref()],
+        bump,
+    )]
+    pub engagement: Account<'info, Engagement>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(
+        mut,
+        seeds = [RENTAL_REQUEST_SEED, escrow_account.key().as_ref(), renter.key().as_ref()],
+        bump = rental_request.bump,
+        has_one = renter,
+        close = renter,
+    )]
+    pub rental_request: Account<'info, RentalRequest>,
+    /// CHECK: the request's renter, refunded the request's rent
+    #[account(mut)]
+    pub renter: UncheckedAccount<'info>,
+    /// Writable so fees withheld in the vault can be harvested before it is closed
+    #[account(mut, constraint = config.allows_mint(&token_mint.key()) @ EscrowError::InvalidMint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = rental_request,
+        associated_token::token_program = token_program,
+    )]
+    pub request_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawRentalRequest<'info> {
+    /// Renter, provider, or anyone once the acceptance window has closed
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [RENTAL_REQUEST_SEED, escrow_account.key().as_ref(), renter.key().as_ref()],
+        bump = rental_request.bump,
+        has_one = renter,
+        close = renter,
+    )]
+    pub rental_request: Account<'info, RentalRequest>,
+    /// CHECK: the request's renter, refunded the deposit and rent
+    #[account(mut)]
+    pub renter: UncheckedAccount<'info>,
+    /// Writable, as in `AcceptRenter`
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = rental_request,
+        associated_token::token_program = token_program,
+    )]
+    pub request_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct PayDeferred<'info> {
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitDeliverable<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = provider_token_account,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddMilestone<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        seeds = [MILESTONES_SEED, escrow_account.key().as_ref()],
+        bump,
+        space = MilestonePlan::LEN
+    )]
+    pub milestone_plan: Account<'info, MilestonePlan>,
+    pub system_program: Program<'info, System>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveMilestone<'info> {
+    pub renter: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = renter,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [MILESTONES_SEED, escrow_account.key().as_ref()],
+        bump = milestone_plan.bump,
+    )]
+    pub milestone_plan: Account<'info, MilestonePlan>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseMilestone<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = provider_token_account,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [MILESTONES_SEED, escrow_account.key().as_ref()],
+        bump = milestone_plan.bump,
+    )]
+    pub milestone_plan: Account<'info, MilestonePlan>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, provider.key().as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Required so the screening hook and protocol fee cannot be skipped
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    /// Treasury token account for the mint; required only when a fee is owed
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStreamed<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = provider_token_account,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, provider.key().as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Required so the screening hook and protocol fee cannot be skipped
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    /// Treasury token account for the mint; required only when a fee is owed
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct IssueInvoice<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [INVOICE_SEED, escrow_account.key().as_ref(), &escrow_account.funded_at.to_le_bytes()],
+        bump,
+        space = Invoice::LEN
+    )]
+    pub invoice: Account<'info, Invoice>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireListing<'info> {
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundBond<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        init,
+        payer = provider,
+        seeds = [BOND_VAULT_SEED, escrow_account.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = escrow_account,
+        token::token_program = token_program,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseBond<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    /// CHECK: receives the bond vault's rent; checked against `escrow_account.provider`
+    #[account(mut)]
+    pub provider: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [BOND_VAULT_SEED, escrow_account.key().as_ref()],
+        bump,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account.provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Required only when the renter is owed part of the bond
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account.renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u16)]
+pub struct CreateListing<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        init,
+        payer = provider,
+        seeds = [SKILL_LISTING_SEED, provider.key().as_ref(), &listing_id.to_le_bytes()],
+        bump,
+        space = SkillListing::LEN
+    )]
+    pub skill_listing: Account<'info, SkillListing>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        seeds = [PROVIDER_INDEX_SEED, provider.key().as_ref()],
+        bump,
+        space = ProviderIndex::LEN
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        seeds = [PROVIDER_STATS_SEED, provider.key().as_ref()],
+        bump,
+        space = ProviderStats::LEN
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(constraint = config.allows_mint(&token_mint.key()) @ EscrowError::InvalidMint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRental<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [SKILL_LISTING_SEED, skill_listing.provider.as_ref(), &skill_listing.listing_id.to_le_bytes()],
+        bump = skill_listing.bump,
+        has_one = token_mint,
+    )]
+    pub skill_listing: Account<'info, SkillListing>,
+    #[account(
+        init,
+        payer = renter,
+        seeds = [
+            ESCROW_SEED,
+            skill_listing.provider.as_ref(),
+            &escrow_id_seed(rental_escrow_id(skill_listing.listing_id, skill_listing.rentals_opened)),
+        ],
+        bump,
+        space = EscrowAccount::LEN
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, skill_listing.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        seeds = [PROVIDER_STATS_SEED, skill_listing.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        init_if_needed,
+        payer = renter,
+        space = Engagement::LEN,
+        seeds = [ENGAGEMENT_SEED, skill_listing.provider.as_ref(), renter.key().as_ref()],
+        bump,
+    )]
+    pub engagement: Account<'info, Engagement>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(constraint = config.allows_mint(&token_mint.key()) @ EscrowError::InvalidMint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = renter,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: renter's reputation `Agent`, validated in `read_reputation_score`
+    pub renter_reputation: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseListing<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [SKILL_LISTING_SEED, provider.key().as_ref(), &skill_listing.listing_id.to_le_bytes()],
+        bump = skill_listing.bump,
+        has_one = provider,
+        close = provider,
+    )]
+    pub skill_listing: Account<'info, SkillListing>,
+}
+
+#[derive(Accounts)]
+pub struct CreateDemoMint<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [DEMO_MINT_SEED],
+        bump,
+        mint::decimals = DEMO_MINT_DECIMALS,
+        mint::authority = demo_mint,
+    )]
+    pub demo_mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AirdropDemoTokens<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(mut, seeds = [DEMO_MINT_SEED], bump)]
+    pub demo_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        associated_token::mint = demo_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct CancelUnfunded<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        close = provider,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [MILESTONES_SEED, escrow_account.key().as_ref()],
+        bump = milestone_plan.bump,
+        close = provider,
+    )]
+    pub milestone_plan: Option<Account<'info, MilestonePlan>>,
+    #[account(
+        mut,
+        seeds = [TERMS_LOCALIZATION_SEED, escrow_account.key().as_ref()],
+        bump = terms_localization.bump,
+        close = provider,
+    )]
+    pub terms_localization: Option<Account<'info, TermsLocalization>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeprecateListing<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRevision<'info> {
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = renter,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CommitAccessCredential<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteTask<'info> {
+    #[account(
+        mut,
+        constraint = release.escrow_account.is_renter_or_watchtower(&authority.key()) @ EscrowError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+    pub release: ReleaseAccounts<'info>,
+}
+
+/// As `CompleteTask`, but anyone may crank it; `auto_release` checks the release is due
+#[derive(Accounts)]
+pub struct AutoRelease<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    pub release: ReleaseAccounts<'info>,
+}
+
+/// Accounts a release to the provider settles through
+#[derive(Accounts)]
+pub struct ReleaseAccounts<'info> {
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.has_renter() @ EscrowError::RenterUnassigned,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account.provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Required only when an SLA penalty is owed
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account.renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK: optional Pyth `PriceUpdateV2` account, validated in `read_usd_quote`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    /// Required so the screening hook and protocol fee cannot be skipped
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(
+        mut,
+        seeds = [POINTS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_points.bump,
+    )]
+    pub provider_points: Option<Account<'info, PointsAccount>>,
+    /// Treasury token account for the mint; required only when a fee is owed
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Provider's wallet; with a wrapped-SOL escrow, the payout is unwrapped into it
+    #[account(mut, address = escrow_account.provider)]
+    pub provider: Option<SystemAccount<'info>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelEscrow<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.has_renter() @ EscrowError::RenterUnassigned,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Required unless the refund is unwrapped into `renter`
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account.renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK: optional Pyth `PriceUpdateV2` account, validated in `read_usd_quote`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    /// Renter's wallet; with a wrapped-SOL escrow, the refund is unwrapped into it
+    #[account(mut, address = escrow_account.renter)]
+    pub renter: Option<SystemAccount<'info>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeCancel<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.has_renter() @ EscrowError::RenterUnassigned,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptEscrowCredit<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        init_if_needed,
+        payer = renter,
+        space = Engagement::LEN,
+        seeds = [ENGAGEMENT_SEED, escrow_account.provider.as_ref(), renter.key().as_ref()],
+        bump,
+    )]
+    pub engagement: Account<'info, Engagement>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(
+        init_if_needed,
+        payer = renter,
+        seeds = [CREDIT_SEED, escrow_account.provider.as_ref(), token_mint.key().as_ref()],
+        bump,
+        space = CreditBalance::LEN
+    )]
+    pub credit_balance: Account<'info, CreditBalance>,
+    #[account(constraint = config.allows_mint(&token_mint.key()) @ EscrowError::InvalidMint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = renter,
+        associated_token::mint = token_mint,
+        associated_token::authority = credit_balance,
+        associated_token::token_program = token_program,
+    )]
+    pub credit_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteTaskCredit<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.is_renter_or_watchtower(&authority.key()) @ EscrowError::Unauthorized,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        mut,
+        seeds = [CREDIT_SEED, escrow_account.provider.as_ref(), escrow_account.token_mint.as_ref()],
+        bump = credit_balance.bump,
+    )]
+    pub credit_balance: Account<'info, CreditBalance>,
+    #[account(address = escrow_account.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: optional Pyth `PriceUpdateV2` account, validated in `read_usd_quote`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    /// Required so the screening hook cannot be skipped
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POINTS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_points.bump,
+    )]
+    pub provider_points: Option<Account<'info, PointsAccount>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelEscrowCredit<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = token_mint,
+        constraint = escrow_account.has_renter() @ EscrowError::RenterUnassigned,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        mut,
+        seeds = [CREDIT_SEED, escrow_account.provider.as_ref(), token_mint.key().as_ref()],
+        bump = credit_balance.bump,
+    )]
+    pub credit_balance: Account<'info, CreditBalance>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = credit_balance,
+        associated_token::token_program = token_program,
+    )]
+    pub credit_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account.renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK: optional Pyth `PriceUpdateV2` account, validated in `read_usd_quote`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCredits<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CREDIT_SEED, provider.key().as_ref(), token_mint.key().as_ref()],
+        bump = credit_balance.bump,
+        has_one = provider,
+    )]
+    pub credit_balance: Account<'info, CreditBalance>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = credit_balance,
+        associated_token::token_program = token_program,
+    )]
+    pub credit_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReportSlaBreach<'info> {
+    pub verifier: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetProviderVerified<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: only used as a seed
+    pub provider: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [PROVIDER_STATS_SEED, provider.key().as_ref()],
+        bump,
+        space = ProviderStats::LEN
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveRelease<'info> {
+    pub screener: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = screener,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetWatchtower<'info> {
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetArbiter<'info> {
+    pub provider: Signer<'info>,
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = renter,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendDuration<'info> {
+    pub provider: Signer<'info>,
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = renter,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetTermsLocalization<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        seeds = [TERMS_LOCALIZATION_SEED, escrow_account.key().as_ref()],
+        bump,
+        space = TermsLocalization::LEN
+    )]
+    pub terms_localization: Account<'info, TermsLocalization>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetWebhookSigner<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+}
+
+#[derive(Accounts)]
+pub struct OfferAutoRelease<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+}
+
+#[derive(Accounts)]
+pub struct FundSubscription<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        init,
+        payer = renter,
+        seeds = [SUBSCRIPTION_SEED, escrow_account.key().as_ref()],
+        bump,
+        space = SubscriptionPlan::LEN
+    )]
+    pub subscription_plan: Account<'info, SubscriptionPlan>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(constraint = config.allows_mint(&token_mint.key()) @ EscrowError::InvalidMint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = renter,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPeriod<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = provider_token_account,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [SUBSCRIPTION_SEED, escrow_account.key().as_ref()],
+        bump = subscription_plan.bump,
+    )]
+    pub subscription_plan: Account<'info, SubscriptionPlan>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, provider.key().as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, provider.key().as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Required so the screening hook and protocol fee cannot be skipped
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    /// Treasury token account for the mint; required only when a fee is owed
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSubscription<'info> {
+    pub renter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = renter,
+        has_one = token_mint,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [SUBSCRIPTION_SEED, escrow_account.key().as_ref()],
+        bump = subscription_plan.bump,
+    )]
+    pub subscription_plan: Account<'info, SubscriptionPlan>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Dispute<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenChannel<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: only used as a seed and recorded as the payee
+    pub payee: UncheckedAccount<'info>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [CHANNEL_SEED, payer.key().as_ref(), payee.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        space = SettlementChannel::LEN
+    )]
+    pub channel: Account<'info, SettlementChannel>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = channel,
+        associated_token::token_program = token_program,
+    )]
+    pub channel_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct FundChannel<'info> {
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CHANNEL_SEED, payer.key().as_ref(), channel.payee.as_ref(), channel.token_mint.as_ref()],
+        bump = channel.bump,
+        has_one = payer,
+        has_one = token_mint,
+    )]
+    pub channel: Account<'info, SettlementChannel>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = channel,
+        associated_token::token_program = token_program,
+    )]
+    pub channel_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SettleChannel<'info> {
+    #[account(
+        mut,
+        seeds = [CHANNEL_SEED, channel.payer.as_ref(), channel.payee.as_ref(), channel.token_mint.as_ref()],
+        bump = channel.bump,
+        has_one = token_mint,
+    )]
+    pub channel: Account<'info, SettlementChannel>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = channel,
+        associated_token::token_program = token_program,
+    )]
+    pub channel_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = channel.payee,
+        associated_token::token_program = token_program,
+    )]
+    pub payee_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK: instructions sysvar, used to find the Ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RequestChannelClose<'info> {
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CHANNEL_SEED, payer.key().as_ref(), channel.payee.as_ref(), channel.token_mint.as_ref()],
+        bump = channel.bump,
+        has_one = payer,
+    )]
+    pub channel: Account<'info, SettlementChannel>,
+}
+
+#[derive(Accounts)]
+pub struct CloseChannel<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CHANNEL_SEED, payer.key().as_ref(), channel.payee.as_ref(), channel.token_mint.as_ref()],
+        bump = channel.bump,
+        has_one = payer,
+        has_one = token_mint,
+        close = payer,
+    )]
+    pub channel: Account<'info, SettlementChannel>,
+    /// Writable to harvest withheld transfer fees before the channel vault closes
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = channel,
+        associated_token::token_program = token_program,
+    )]
+    pub channel_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CommitDisputeRandomness<'info> {
+    #[account(mut)]
+    pub party: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        init_if_needed,
+        payer = party,
+        seeds = [RANDOMNESS_SEED, escrow_account.key().as_ref()],
+        bump,
+        space = DisputeRandomness::LEN
+    )]
+    pub randomness: Account<'info, DisputeRandomness>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitEvidence<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [EVIDENCE_SEED, escrow_account.key().as_ref()],
+        bump,
+        space = DisputeEvidence::LEN
+    )]
+    pub evidence: Account<'info, DisputeEvidence>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeclareNoConflict<'info> {
+    #[account(mut)]
+    pub arbiter: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    /// The escrow's arbiter council, when `arbiter` declares as one of its members
+    #[account(
+        seeds = [COUNCIL_SEED, council.creator.as_ref(), &council.council_id.to_le_bytes()],
+        bump = council.bump,
+    )]
+    pub council: Option<Account<'info, ArbiterCouncil>>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        space = ArbiterDeclaration::LEN,
+        seeds = [DECLARATION_SEED, escrow_account.key().as_ref(), arbiter.key().as_ref()],
+        bump,
+    )]
+    pub declaration: Account<'info, ArbiterDeclaration>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        space = ArbiterStats::LEN,
+        seeds = [ARBITER_STATS_SEED, arbiter.key().as_ref()],
+        bump,
+    )]
+    pub arbiter_stats: Account<'info, ArbiterStats>,
+    /// CHECK: `Engagement` of the arbiter as provider to the renter; must not exist
+    #[account(seeds = [ENGAGEMENT_SEED, arbiter.key().as_ref(), escrow_account.renter.as_ref()], bump)]
+    pub arbiter_to_renter: UncheckedAccount<'info>,
+    /// CHECK: `Engagement` of the renter as provider to the arbiter; must not exist
+    #[account(seeds = [ENGAGEMENT_SEED, escrow_account.renter.as_ref(), arbiter.key().as_ref()], bump)]
+    pub renter_to_arbiter: UncheckedAccount<'info>,
+    /// CHECK: `Engagement` of the arbiter as provider to the provider; must not exist
+    #[account(seeds = [ENGAGEMENT_SEED, arbiter.key().as_ref(), escrow_account.provider.as_ref()], bump)]
+    pub arbiter_to_provider: UncheckedAccount<'info>,
+    /// CHECK: `Engagement` of the provider as provider to the arbiter; must not exist
+    #[account(seeds = [ENGAGEMENT_SEED, escrow_account.provider.as_ref(), arbiter.key().as_ref()], bump)]
+    pub provider_to_arbiter: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(council_id: u32)]
+pub struct CreateCouncil<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = ArbiterCouncil::LEN,
+        seeds = [COUNCIL_SEED, creator.key().as_ref(), &council_id.to_le_bytes()],
+        bump,
+    )]
+    pub council: Account<'info, ArbiterCouncil>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeResolution<'info> {
+    #[account(mut)]
+    pub member: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        constraint = escrow_account.arbiter == council.key() @ EscrowError::NotCouncilMember,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        seeds = [COUNCIL_SEED, council.creator.as_ref(), &council.council_id.to_le_bytes()],
+        bump = council.bump,
+    )]
+    pub council: Account<'info, ArbiterCouncil>,
+    #[account(
+        init_if_needed,
+        payer = member,
+        space = ResolutionProposal::LEN,
+        seeds = [PROPOSAL_SEED, escrow_account.key().as_ref()],
+        bump,
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+    #[account(
+        seeds = [DECLARATION_SEED, escrow_account.key().as_ref(), member.key().as_ref()],
+        bump = declaration.bump,
+    )]
+    pub declaration: Account<'info, ArbiterDeclaration>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteResolution<'info> {
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = renter,
+        constraint = escrow_account.arbiter == council.key() @ EscrowError::NotCouncilMember,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        seeds = [COUNCIL_SEED, council.creator.as_ref(), &council.council_id.to_le_bytes()],
+        bump = council.bump,
+    )]
+    pub council: Account<'info, ArbiterCouncil>,
+    #[account(
+        seeds = [PROPOSAL_SEED, escrow_account.key().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = ArbiterStats::LEN,
+        seeds = [ARBITER_STATS_SEED, council.key().as_ref()],
+        bump,
+    )]
+    pub arbiter_stats: Account<'info, ArbiterStats>,
+    /// Required so the protocol fee cannot be skipped
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.provider`; ATA authority only
+    pub provider: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = executor,
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.renter`; ATA authority only
+    pub renter: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = executor,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: optional Pyth `PriceUpdateV2` account, validated in `read_usd_quote`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    /// Treasury token account for the mint; required only when a fee is owed
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [POINTS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_points.bump,
+    )]
+    pub provider_points: Option<Account<'info, PointsAccount>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReportConflict<'info> {
+    pub reporter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [DECLARATION_SEED, declaration.escrow.as_ref(), declaration.arbiter.as_ref()],
+        bump = declaration.bump,
+    )]
+    pub declaration: Account<'info, ArbiterDeclaration>,
+    #[account(
+        seeds = [ENGAGEMENT_SEED, engagement.provider.as_ref(), engagement.renter.as_ref()],
+        bump = engagement.bump,
+    )]
+    pub engagement: Account<'info, Engagement>,
+    #[account(
+        mut,
+        seeds = [ARBITER_STATS_SEED, declaration.arbiter.as_ref()],
+        bump = arbiter_stats.bump,
+    )]
+    pub arbiter_stats: Account<'info, ArbiterStats>,
+}
+
+#[derive(Accounts)]
+pub struct RevealDisputeRandomness<'info> {
+    pub party: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, escrow_account.key().as_ref()],
+        bump = randomness.bump,
+    )]
+    pub randomness: Account<'info, DisputeRandomness>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeDisputeRandomness<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, escrow_account.key().as_ref()],
+        bump = randomness.bump,
+    )]
+    pub randomness: Account<'info, DisputeRandomness>,
+    /// CHECK: SlotHashes sysvar, read raw since it is too large to deserialize on-chain
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDisputeRelease<'info> {
+    #[account(mut)]
+    pub arbiter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = arbiter,
+        has_one = provider,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        seeds = [DECLARATION_SEED, escrow_account.key().as_ref(), arbiter.key().as_ref()],
+        bump = declaration.bump,
+    )]
+    pub declaration: Account<'info, ArbiterDeclaration>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        space = ArbiterStats::LEN,
+        seeds = [ARBITER_STATS_SEED, arbiter.key().as_ref()],
+        bump,
+    )]
+    pub arbiter_stats: Account<'info, ArbiterStats>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.provider`; ATA authority only
+    pub provider: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: optional Pyth `PriceUpdateV2` account, validated in `read_usd_quote`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    /// Required so the protocol fee cannot be skipped
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(
+        mut,
+        seeds = [POINTS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_points.bump,
+    )]
+    pub provider_points: Option<Account<'info, PointsAccount>>,
+    /// Treasury token account for the mint; required only when a fee is owed
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDisputeRefund<'info> {
+    #[account(mut)]
+    pub arbiter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = arbiter,
+        has_one = renter,
+        constraint = escrow_account.has_renter() @ EscrowError::RenterUnassigned,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        seeds = [DECLARATION_SEED, escrow_account.key().as_ref(), arbiter.key().as_ref()],
+        bump = declaration.bump,
+    )]
+    pub declaration: Account<'info, ArbiterDeclaration>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        space = ArbiterStats::LEN,
+        seeds = [ARBITER_STATS_SEED, arbiter.key().as_ref()],
+        bump,
+    )]
+    pub arbiter_stats: Account<'info, ArbiterStats>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.renter`; ATA authority only
+    pub renter: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: optional Pyth `PriceUpdateV2` account, validated in `read_usd_quote`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDisputeSplit<'info> {
+    #[account(mut)]
+    pub arbiter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = arbiter,
+        has_one = provider,
+        has_one = renter,
+        constraint = escrow_account.has_renter() @ EscrowError::RenterUnassigned,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        seeds = [DECLARATION_SEED, escrow_account.key().as_ref(), arbiter.key().as_ref()],
+        bump = declaration.bump,
+    )]
+    pub declaration: Account<'info, ArbiterDeclaration>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        space = ArbiterStats::LEN,
+        seeds = [ARBITER_STATS_SEED, arbiter.key().as_ref()],
+        bump,
+    )]
+    pub arbiter_stats: Account<'info, ArbiterStats>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.provider`; ATA authority only
+    pub provider: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.renter`; ATA authority only
+    pub renter: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: optional Pyth `PriceUpdateV2` account, validated in `read_usd_quote`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    /// Required so the protocol fee on the provider's share cannot be skipped
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    /// Treasury token account for the mint; required only when a fee is owed
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDefaultResolution<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = renter,
+        constraint = escrow_account.has_renter() @ EscrowError::RenterUnassigned,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.provider`; ATA authority only
+    pub provider: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.renter`; ATA authority only
+    pub renter: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: optional Pyth `PriceUpdateV2` account, validated in `read_usd_quote`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+    /// Treasury token account for the mint; required only when a fee is owed
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WindDown<'info> {
+    pub provider: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, provider.key().as_ref()],
+        bump = provider_index.bump,
+        has_one = provider,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, provider.key().as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [CONFIG_SEED],
+        bump,
+        space = Config::LEN
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeEpochStats<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = EpochStats::LEN,
+        seeds = [EPOCH_STATS_SEED],
+        bump,
+    )]
+    pub epoch_stats: Account<'info, EpochStats>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RolloverEpoch<'info> {
+    /// Anyone; pays for the snapshot account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, seeds = [EPOCH_STATS_SEED], bump = epoch_stats.bump)]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(
+        init,
+        payer = payer,
+        space = EpochSnapshot::LEN,
+        seeds = [EPOCH_SNAPSHOT_SEED, &epoch_stats.epoch.to_le_bytes()],
+        bump,
+    )]
+    pub snapshot: Account<'info, EpochSnapshot>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueRecovery<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        init,
+        payer = admin,
+        space = RecoveryAction::LEN,
+        seeds = [RECOVERY_SEED, escrow_account.key().as_ref()],
+        bump,
+    )]
+    pub recovery: Account<'info, RecoveryAction>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRecovery<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [RECOVERY_SEED, recovery.escrow.as_ref()],
+        bump = recovery.bump,
+        close = admin,
+    )]
+    pub recovery: Account<'info, RecoveryAction>,
+}
+
+#[derive(Accounts)]
+pub struct ForceRecovery<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+        has_one = provider,
+        has_one = renter,
+        has_one = token_mint,
+        constraint = escrow_account.has_renter() @ EscrowError::RenterUnassigned,
+        constraint = escrow_account.version == ESCROW_VERSION @ EscrowError::LayoutVersionMismatch,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        mut,
+        seeds = [RECOVERY_SEED, escrow_account.key().as_ref()],
+        bump = recovery.bump,
+        close = admin,
+    )]
+    pub recovery: Account<'info, RecoveryAction>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        mut,
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_account,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.provider`; ATA authority only
+    pub provider: UncheckedAccount<'info>,
+    /// Recreated at the admin's expense if the provider closed it
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: address pinned to `escrow_account.renter`; ATA authority only
+    pub renter: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPointsAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [POINTS_SEED, owner.key().as_ref()],
+        bump,
+        space = PointsAccount::LEN
+    )]
+    pub points: Account<'info, PointsAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemPoints<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POINTS_SEED, owner.key().as_ref()],
+        bump = points.bump,
+        has_one = owner,
+    )]
+    pub points: Account<'info, PointsAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+/// Optional accounts mirroring a state change into the provider's activity feed;
+/// logging is skipped when they are omitted
+#[derive(Accounts)]
+pub struct ActivityAccounts<'info> {
+    pub activity_log_program: Option<Program<'info, ActivityLog>>,
+    /// CHECK: feed PDA; address checked in `log`, contents validated by the activity-log program
+    #[account(mut)]
+    pub activity_feed: Option<UncheckedAccount<'info>>,
+    /// CHECK: this program's signer PDA for activity-log CPIs
+    #[account(seeds = [ACTIVITY_AUTHORITY_SEED], bump)]
+    pub activity_authority: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> ActivityAccounts<'info> {
+    pub fn log(
+        &self,
+        bumps: &ActivityAccountsBumps,
+        agent: Pubkey,
+        kind: ActivityKind,
+        subject: Pubkey,
+        counterparty: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let (Some(program), Some(feed), Some(authority), Some(bump)) = (
+            &self.activity_log_program,
+            &self.activity_feed,
+            &self.activity_authority,
+            bumps.activity_authority,
+        ) else {
+            return Ok(());
+        };
+        let (expected_feed, _) =
+            Pubkey::find_program_address(&[activity_log::FEED_SEED, agent.as_ref()], &activity_log::ID);
+        require_keys_eq!(feed.key(), expected_feed, EscrowError::InvalidActivityFeed);
+
+        let seeds = &[ACTIVITY_AUTHORITY_SEED, &[bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = activity_log::cpi::accounts::LogActivity {
+            feed: feed.to_account_info(),
+            authority: authority.to_account_info(),
+        };
+        activity_log::cpi::log_activity(
+            CpiContext::new_with_signer(program.to_account_info(), cpi_accounts, signer),
+            kind,
+            subject,
+            counterparty,
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct CheckTimeout<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SnapshotEscrow<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+}
+
+#[derive(Accounts)]
+pub struct GetArbiterStats<'info> {
+    #[account(
+        seeds = [ARBITER_STATS_SEED, arbiter_stats.arbiter.as_ref()],
+        bump = arbiter_stats.bump,
+    )]
+    pub arbiter_stats: Account<'info, ArbiterStats>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewEngagement<'info> {
+    /// CHECK: prospective renter; only its key is read
+    pub renter: UncheckedAccount<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    /// CHECK: optional reputation `Agent` of the renter, validated in `read_reputation_score`
+    pub renter_reputation: Option<UncheckedAccount<'info>>,
+    /// CHECK: optional reputation `Agent` of the provider, validated in `read_reputation_score`
+    pub provider_reputation: Option<UncheckedAccount<'info>>,
+    /// Record of the arbiter the rental would be assigned, if they have ruled before
+    #[account(
+        seeds = [ARBITER_STATS_SEED, arbiter_stats.arbiter.as_ref()],
+        bump = arbiter_stats.bump,
+    )]
+    pub arbiter_stats: Option<Account<'info, ArbiterStats>>,
+}
+
+#[derive(Accounts)]
+pub struct ComputeRisk<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    /// CHECK: optional reputation `Agent` of the provider, validated in `read_agent_field`
+    pub provider_reputation: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+#[instruction(terms: EscrowTerms, escrow_id: u64)]
+pub struct ValidateInitialize<'info> {
+    /// CHECK: prospective provider; only its key is read
+    pub provider: UncheckedAccount<'info>,
+    /// CHECK: the escrow address `initialize_escrow` would create; must be empty
+    #[account(seeds = [ESCROW_SEED, provider.key().as_ref(), &escrow_id_seed(escrow_id)], bump)]
+    pub escrow_account: UncheckedAccount<'info>,
+    /// The provider's stats, once their first listing has created them
+    #[account(seeds = [PROVIDER_STATS_SEED, provider.key().as_ref()], bump = provider_stats.bump)]
+    pub provider_stats: Option<Account<'info, ProviderStats>>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: the provider's associated token account, checked in `validate_initialize`
+    pub provider_token_account: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ValidateInitialize<'info> {
+    fn check(&self, terms: &EscrowTerms, escrow_id: u64) -> Result<()> {
+        require!(self.escrow_account.data_is_empty(), EscrowError::EscrowIdTaken);
+        require!(self.config.allows_mint(&self.token_mint.key()), EscrowError::InvalidMint);
+        let provider_ata = get_associated_token_address_with_program_id(
+            &self.provider.key(),
+            &self.token_mint.key(),
+            &self.token_program.key(),
+        );
+        require_keys_eq!(self.provider_token_account.key(), provider_ata, ErrorCode::ConstraintAssociated);
+        require!(!self.provider_token_account.data_is_empty(), ErrorCode::AccountNotInitialized);
+        check_initialize(terms, escrow_id, &self.config, self.token_mint.decimals)?;
+        if let Some(stats) = &self.provider_stats {
+            let mut stats = (**stats).clone();
+            stats.record_initialization(Clock::get()?.epoch, self.config.max_initializations_per_epoch)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ValidateFund<'info> {
+    /// CHECK: prospective renter; only its key is read
+    pub renter: UncheckedAccount<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(
+        seeds = [PROVIDER_INDEX_SEED, escrow_account.provider.as_ref()],
+        bump = provider_index.bump,
+    )]
+    pub provider_index: Account<'info, ProviderIndex>,
+    #[account(
+        seeds = [PROVIDER_STATS_SEED, escrow_account.provider.as_ref()],
+        bump = provider_stats.bump,
+    )]
+    pub provider_stats: Account<'info, ProviderStats>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// The renter's associated token account, if they have one
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program,
+    )]
+    pub renter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: renter's reputation `Agent`, validated in `read_reputation_score`
+    pub renter_reputation: Option<UncheckedAccount<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ValidateFund<'info> {
+    fn check(&self, amount: u64, overrides: &FundOverrides, terms_hash: &[u8; 32]) -> Result<()> {
+        let escrow = &self.escrow_account;
+        let renter = self.renter.key();
+        require!(escrow.version == ESCROW_VERSION, EscrowError::LayoutVersionMismatch);
+        require_keys_eq!(self.token_mint.key(), escrow.token_mint, ErrorCode::ConstraintHasOne);
+        require!(self.config.allows_mint(&escrow.token_mint), EscrowError::InvalidMint);
+        let renter_token = self.renter_token_account.as_ref().ok_or(ErrorCode::AccountNotInitialized)?;
+        escrow.check_fundable(terms_hash, false, Clock::get()?.unix_timestamp)?;
+
+        let max_concurrent = self
+            .provider_stats
+            .concurrency_limit(escrow.terms.max_concurrent_rentals, self.config.failure_streak_threshold);
+        (*self.provider_index).clone().reserve_slot(max_concurrent)?;
+        let score = match &self.renter_reputation {
+            Some(agent) => Some(read_reputation_score(agent, &renter)?),
+            None => None,
+        };
+        let received = received_amount(&self.token_mint, amount)?;
+        fund_escrow(&mut (**escrow).clone(), renter, amount, received, overrides, score)?;
+        require!(renter_token.amount >= amount, EscrowError::InsufficientFunds);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ValidateRelease<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// Treasury token account for the mint; required only when a fee is owed
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+impl<'info> ValidateRelease<'info> {
+    fn check(&self, key_ciphertext: Option<&[u8]>) -> Result<()> {
+        let escrow = &self.escrow_account;
+        require!(escrow.has_renter(), EscrowError::RenterUnassigned);
+        require!(escrow.version == ESCROW_VERSION, EscrowError::LayoutVersionMismatch);
+        escrow.check_key_ciphertext(key_ciphertext)?;
+        escrow.check_releasable(&self.config, false, Clock::get()?.unix_timestamp)?;
+
+        let amount = escrow.amount.checked_sub(escrow.sla_penalty()?).ok_or(EscrowError::InsufficientFunds)?;
+        if self.config.fee_on(amount) > 0 {
+            fee_treasury_account(&self.config, self.fee_token_account.as_ref(), &escrow.token_mint)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RecordAcceptance<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [ESCROW_SEED, escrow_account.provider.as_ref(), &escrow_id_seed(escrow_account.escrow_id)],
+        bump,
+    )]
+    pub escrow_account: Account<'info, EscrowAccount>,
+    /// CHECK: the wallet whose signature the Ed25519 verification checks
+    pub renter: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = TermsAcceptance::LEN,
+        seeds = [ACCEPTANCE_SEED, escrow_account.key().as_ref(), renter.key().as_ref()],
+        bump,
+    )]
+    pub acceptance: Account<'info, TermsAcceptance>,
+    /// CHECK: instructions sysvar, used to find the Ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateEscrow<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: an escrow of any layout; `migrate_escrow` checks the discriminator and
+    /// recognizes the layout by its length
+    #[account(mut, owner = crate::ID @ EscrowError::UnknownEscrowLayout)]
+    pub escrow_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// ========== Events ==========
+
+#[event]
+pub struct DecryptionKeyReleased {
+    pub escrow: Pubkey,
+    pub renter: Pubkey,
+    pub key_ciphertext: Vec<u8>,
+}
+
+#[event]
+pub struct ProviderWoundDown {
+    pub provider: Pubkey,
+    pub refunded_escrows: u32,
+    pub closed_listings: u32,
+    pub total_refunded: u64,
+    pub timestamp: i64,
+}
+
+/// Arbiter's ruling on a disputed escrow
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolutionOutcome {
+    ReleasedToProvider,
+    RefundedToRenter,
+    /// Provider's share in basis points; the renter receives the rest
+    Split(u16),
+    /// Released to the provider while the renter still owed a deferred balance
+    Defaulted,
+}
+
+impl ResolutionOutcome {
+    /// Variant tag + largest payload (`Split`)
+    pub const LEN: usize = 1 + 2;
+}
+
+/// How a queued recovery action settles a stuck escrow
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecoveryKind {
+    /// Refund the renter what the escrow still holds
+    Expire,
+    /// Pay out exactly these amounts, as established by the action's proof
+    SettleWithProof { to_provider: u64, to_renter: u64 },
+}
+
+impl RecoveryKind {
+    /// Variant tag + largest payload (`SettleWithProof`)
+    pub const LEN: usize = 1 + 8 + 8;
+
+    /// Provider and renter payouts for an escrow whose `disputed_amount` is `held` and
+    /// whose vault holds `vault_balance`; `None` if the vault cannot cover them
+    pub fn payouts(&self, held: u64, vault_balance: u64) -> Option<(u64, u64)> {
+        match *self {
+            Self::Expire => Some((0, held.min(vault_balance))),
+            Self::SettleWithProof { to_provider, to_renter } => {
+                (to_provider.checked_add(to_renter)? <= vault_balance).then_some((to_provider, to_renter))
+            }
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum SettlementKind {
+    Released,
+    Refunded,
+    /// Arbiter divided the escrow between provider and renter
+    Split,
+}
+
+/// Compact final state of a settled escrow, committed to by `snapshot_escrow`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct EscrowSnapshot {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub token_mint: Pubkey,
+    pub state: EscrowState,
+    pub amount: u64,
+    pub provider_payout: u64,
+    pub renter_refund: u64,
+    pub resolution: Option<ResolutionOutcome>,
+    pub terms_hash: [u8; 32],
+    pub deliverable_chain: [u8; 32],
+    pub settled_at: i64,
+    /// Slot the snapshot was taken in
+    pub slot: u64,
+}
+
+impl EscrowSnapshot {
+    /// Bytes a foreign-chain verifier hashes: the domain prefix, the program id, then
+    /// the borsh-encoded snapshot
+    pub fn message(&self) -> Result<Vec<u8>> {
+        Ok([SNAPSHOT_MESSAGE_PREFIX, crate::ID.as_ref(), &self.try_to_vec()?].concat())
+    }
+
+    pub fn commitment(&self) -> Result<[u8; 32]> {
+        Ok(hash(&self.message()?).to_bytes())
+    }
+}
+
+/// Return data of `get_arbiter_stats`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ArbiterStatsData {
+    pub arbiter: Pubkey,
+    pub resolutions: u64,
+    pub mean_latency_seconds: u64,
+    pub max_latency_seconds: u64,
+    pub last_resolved_at: i64,
+}
+
+/// Return data of `preview_engagement`: what funding a listing now would commit the
+/// renter to. Reputation fields are `None` or zero when the account was not passed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EngagementPreview {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    /// Reputation-program scores
+    pub provider_score: Option<i64>,
+    pub renter_score: Option<i64>,
+    pub provider_releases: u64,
+    pub provider_refunds: u64,
+    /// Refunds since the provider's last release
+    pub provider_failure_streak: u16,
+    pub provider_verified: bool,
+    /// Arbiter after overrides, with the disputes they have ruled on and how long that took
+    pub arbiter: Pubkey,
+    pub arbiter_resolutions: u64,
+    pub arbiter_mean_latency_seconds: u64,
+    /// Why funding would fail right now (`None` = it would go through)
+    pub blocked_by: Option<FundingBlock>,
+    /// The renter must `request_rental` and wait for the provider to accept
+    pub requires_provider_acceptance: bool,
+    /// Release waits for the compliance screener at this price
+    pub requires_screening: bool,
+    pub trial: bool,
+    /// Deposit the provider forfeits to the renter if a dispute is ruled their way
+    pub provider_bond: u64,
+    pub price: u64,
+    /// Due at funding for this renter's score; the rest is due by `deferred_due_at`
+    pub upfront: u64,
+    pub deferred: u64,
+    pub deferred_due_at: i64,
+    /// Protocol fee withheld from the provider at release, and what they receive net of it
+    pub protocol_fee: u64,
+    pub provider_payout: u64,
+    /// Last moment the listing can be funded (0 = open-ended)
+    pub funding_expires_at: i64,
+    /// Timeline if funded now
+    pub rental_ends_at: i64,
+    pub dispute_window_seconds: i64,
+    pub review_window_seconds: i64,
+    /// Last moment to dispute if nothing is delivered
+    pub dispute_deadline: i64,
+    pub vesting_seconds: i64,
+    /// Time the arbiter has to rule once a dispute is opened (0 = no deadline)
+    pub arbitration_window_seconds: i64,
+    pub auto_release: bool,
+}
+
+/// Why a listing cannot be funded at the moment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FundingBlock {
+    /// Already funded or no longer listed
+    NotOpen,
+    /// Past its funding deadline or sunset
+    ListingExpired,
+    BondNotFunded,
+    ProviderAtCapacity,
+}
+
+impl EngagementPreview {
+    /// Preview of `renter`, scoring `renter_score`, funding the listing at `escrow` with
+    /// `overrides` at `now`. The provider's score and the arbiter's record are left for
+    /// the caller to fill in.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        escrow: Pubkey,
+        listing: &EscrowAccount,
+        renter: Pubkey,
+        renter_score: Option<i64>,
+        overrides: &FundOverrides,
+        config: &Config,
+        index: &ProviderIndex,
+        stats: &ProviderStats,
+        now: i64,
+    ) -> Result<Self> {
+        // Fund a copy, so the windows and deadlines are exactly those funding would fix
+        let mut funded = listing.clone();
+        apply_fund_overrides(&mut funded, overrides)?;
+        funded.funded_at = now;
+        let terms = &listing.terms;
+
+        let max_concurrent = stats.concurrency_limit(terms.max_concurrent_rentals, config.failure_streak_threshold);
+        let blocked_by = if listing.state != EscrowState::Created {
+            Some(FundingBlock::NotOpen)
+        } else if !listing.funding_open(now) {
+            Some(FundingBlock::ListingExpired)
+        } else if terms.provider_bond > 0 && !listing.bond_held {
+            Some(FundingBlock::BondNotFunded)
+        } else if !index.has_slot(max_concurrent) {
+            Some(FundingBlock::ProviderAtCapacity)
+        } else {
+            None
+        };
+
+        let price = terms.price_usdc;
+        let (upfront, deferred_due_at) = match &terms.funding_rule {
+            Some(rule) => {
+                let upfront = (price as u128 * rule.upfront_bps(renter_score) as u128 / BPS_DENOMINATOR as u128) as u64;
+                let due_at = if upfront < price { now.saturating_add(rule.deferred_due_seconds) } else { 0 };
+                (upfront, due_at)
+            }
+            None => (price, 0),
+        };
+        let protocol_fee = config.fee_on(price);
+        let sunset = (listing.sunset_at > 0).then(|| listing.sunset_at - 1);
+        let deadline = (terms.funding_deadline > 0).then_some(terms.funding_deadline);
+
+        Ok(Self {
+            escrow,
+            provider: listing.provider,
+            renter,
+            provider_score: None,
+            renter_score,
+            provider_releases: stats.lifetime_releases,
+            provider_refunds: stats.lifetime_refunds,
+            provider_failure_streak: stats.failure_streak,
+            provider_verified: stats.verified,
+            arbiter: funded.arbiter,
+            arbiter_resolutions: 0,
+            arbiter_mean_latency_seconds: 0,
+            blocked_by,
+            requires_provider_acceptance: terms.requires_provider_acceptance,
+            requires_screening: config.requires_screening(price),
+            trial: terms.trial,
+            provider_bond: terms.provider_bond,
+            price,
+            upfront,
+            deferred: price - upfront,
+            deferred_due_at,
+            protocol_fee,
+            provider_payout: price - protocol_fee,
+            funding_expires_at: sunset.into_iter().chain(deadline).min().unwrap_or(0),
+            rental_ends_at: funded.rental_ends_at(),
+            dispute_window_seconds: funded.dispute_window_seconds,
+            review_window_seconds: funded.review_window_seconds,
+            dispute_deadline: funded.dispute_deadline(),
+            vesting_seconds: terms.vesting_seconds,
+            arbitration_window_seconds: config.arbitration_window_seconds,
+            auto_release: funded.auto_release,
+        })
+    }
+}
+
+/// Return data of `compute_risk`: the provider's risk as a counterparty on a listing,
+/// from 0 (lowest) to 100, with each factor that went into it as a risk in basis points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RiskAssessment {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub score: u8,
+    /// Disputes per settled rental
+    pub dispute_risk_bps: u16,
+    /// Refunds per settled rental, counting timeouts and lost disputes
+    pub cancellation_risk_bps: u16,
+    pub account_age_seconds: i64,
+    /// How far the account is from `RiskWeights::mature_age_seconds`
+    pub age_risk_bps: u16,
+    /// Share of the price not covered by the listing's provider bond
+    pub bond_risk_bps: u16,
+    pub weights: RiskWeights,
+}
+
+impl RiskAssessment {
+    pub fn new(
+        escrow: Pubkey,
+        listing: &EscrowAccount,
+        stats: &ProviderStats,
+        account_age_seconds: i64,
+        weights: &RiskWeights,
+    ) -> Result<Self> {
+        let total = weights.total();
+        require!(total > 0, EscrowError::RiskScoringDisabled);
+        let settled = stats.lifetime_releases.saturating_add(stats.lifetime_refunds);
+        let dispute_risk_bps = risk_bps(stats.lifetime_disputes, settled);
+        let cancellation_risk_bps = risk_bps(stats.lifetime_refunds, settled);
+        let age_risk_bps = match weights.mature_age_seconds {
+            0 => 0,
+            mature => {
+                BPS_DENOMINATOR - risk_bps(account_age_seconds.clamp(0, mature) as u64, mature as u64)
+            }
+        };
+        let bond_risk_bps = BPS_DENOMINATOR - risk_bps(listing.terms.provider_bond, listing.terms.price_usdc);
+
+        let weighted = weights.disputes as u64 * dispute_risk_bps as u64
+            + weights.cancellations as u64 * cancellation_risk_bps as u64
+            + weights.account_age as u64 * age_risk_bps as u64
+            + weights.bond_coverage as u64 * bond_risk_bps as u64;
+        let score = weighted * 100 / (total as u64 * BPS_DENOMINATOR as u64);
+        Ok(Self {
+            escrow,
+            provider: listing.provider,
+            score: score as u8,
+            dispute_risk_bps,
+            cancellation_risk_bps,
+            account_age_seconds,
+            age_risk_bps,
+            bond_risk_bps,
+            weights: *weights,
+        })
+    }
+}
+
+/// Return data of the `validate_*` dry runs: `None` if the instruction would pass every
+/// check, otherwise the first error it would fail with
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Validation {
+    pub failure: Option<ValidationFailure>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ValidationFailure {
+    /// Custom error code (`EscrowError` or Anchor), or a runtime `ProgramError` as `u64`
+    pub code: u64,
+    pub name: String,
+    pub message: String,
+}
+
+impl Validation {
+    pub fn of(result: Result<()>) -> Self {
+        let failure = result.err().map(|error| match error {
+            Error::AnchorError(error) => ValidationFailure {
+                code: error.error_code_number as u64,
+                name: error.error_name,
+                message: error.error_msg,
+            },
+            Error::ProgramError(error) => ValidationFailure {
+                code: u64::from(error.program_error.clone()),
+                name: format!("{:?}", error.program_error),
+                message: error.program_error.to_string(),
+            },
+        });
+        Self { failure }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// `part` of `whole` in basis points, capped at `BPS_DENOMINATOR`; any part of nothing is all of it
+fn risk_bps(part: u64, whole: u64) -> u16 {
+    match whole {
+        0 if part > 0 => BPS_DENOMINATOR,
+        0 => 0,
+        _ => (part as u128 * BPS_DENOMINATOR as u128 / whole as u128).min(BPS_DENOMINATOR as u128) as u16,
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UsdQuote {
+    pub feed_id: [u8; 32],
+    /// USD price per whole token is `price * 10^expo`
+    pub price: i64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+/// ISO-4217-style amount metadata so accounting consumers don't need to join mint data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AmountMetadata {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    /// Amount in base units
+    pub amount: u64,
+    /// Amount normalized by `decimals`, e.g. "12.500000"
+    pub amount_decimal: String,
+    pub usd_quote: Option<UsdQuote>,
+}
+
+#[event]
+pub struct SlaBreachReported {
+    pub escrow: Pubkey,
+    pub verifier: Pubkey,
+    pub metric: SlaMetric,
+    pub observed: u64,
+    pub evidence_hash: [u8; 32],
+    pub breaches: u8,
+    pub penalty_bps: u16,
+}
+
+/// Single record of a dispute's outcome, so history queries need not infer it from state
+#[event]
+pub struct DisputeResolved {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub arbiter: Pubkey,
+    pub outcome: ResolutionOutcome,
+    pub ruling_hash: [u8; 32],
+    /// What the ruling divided: the escrow, or only the unvested payout after a release
+    pub amount: u64,
+    pub disputed_at: i64,
+}
+
+#[event]
+pub struct AccessCredentialCommitted {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub commitment: [u8; 32],
+    pub rotation: u16,
+    pub committed_at: i64,
+}
+
+#[event]
+pub struct ReleaseScreened {
+    pub escrow: Pubkey,
+    pub screener: Pubkey,
+    pub timestamp: i64,
+}
+
+/// The arbiter let `arbitration_deadline` pass without ruling and the dispute was
+/// settled by default judgment
+#[event]
+pub struct DefaultJudgment {
+    pub escrow: Pubkey,
+    pub arbiter: Pubkey,
+    pub arbitration_deadline: i64,
+    /// Released to the provider rather than refunded to the renter
+    pub released: bool,
+    pub claimed_by: Pubkey,
+}
+
+#[event]
+pub struct RecoveryQueued {
+    pub escrow: Pubkey,
+    pub kind: RecoveryKind,
+    pub proof_hash: [u8; 32],
+    pub queued_by: Pubkey,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct RecoveryCancelled {
+    pub escrow: Pubkey,
+    pub kind: RecoveryKind,
+    pub cancelled_by: Pubkey,
+}
+
+/// `rollover_epoch` froze the counters of `epoch` into its `EpochSnapshot`
+#[event]
+pub struct EpochRolledOver {
+    pub epoch: u64,
+    pub next_epoch: u64,
+    pub counters: EpochCounters,
+    pub rolled_over_by: Pubkey,
+}
+
+/// The provider locked `amount` as the listing's bond, after any transfer fee
+#[event]
+pub struct BondFunded {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BondReleased {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    /// `UNASSIGNED_RENTER` when withdrawn from an unfunded listing
+    pub renter: Pubkey,
+    pub to_provider: u64,
+    pub to_renter: u64,
+}
+
+/// A queued recovery action settled its escrow out of `previous_state`
+#[event]
+pub struct RecoveryExecuted {
+    pub escrow: Pubkey,
+    pub kind: RecoveryKind,
+    pub proof_hash: [u8; 32],
+    pub previous_state: EscrowState,
+    pub to_provider: u64,
+    pub to_renter: u64,
+    pub executed_by: Pubkey,
+}
+
+/// A party to a dispute submitted evidence; `index` is its entry in `DisputeEvidence`
+#[event]
+pub struct EvidenceSubmitted {
+    pub escrow: Pubkey,
+    pub submitted_by: Pubkey,
+    pub index: u8,
+    pub evidence_uri: String,
+    pub evidence_hash: [u8; 32],
+    pub submitted_at: i64,
+}
+
+/// An arbiter declared no prior rental with either party of a dispute
+#[event]
+pub struct ConflictDeclared {
+    pub escrow: Pubkey,
+    pub arbiter: Pubkey,
+    pub declared_at: i64,
+}
+
+/// An arbiter council was formed; name `council` as an escrow's arbiter to use it
+#[event]
+pub struct CouncilCreated {
+    pub council: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+/// A council member proposed a ruling, or approved one already proposed
+#[event]
+pub struct ResolutionApproved {
+    pub escrow: Pubkey,
+    pub council: Pubkey,
+    pub member: Pubkey,
+    pub outcome: ResolutionOutcome,
+    pub ruling_hash: [u8; 32],
+    pub approvals: u8,
+}
+
+/// A rental between an arbiter and a party voided the arbiter's declaration
+#[event]
+pub struct ConflictReported {
+    pub escrow: Pubkey,
+    pub arbiter: Pubkey,
+    pub engagement: Pubkey,
+    pub reported_by: Pubkey,
+}
+
+#[event]
+pub struct DisputeRandomnessFinalized {
+    pub escrow: Pubkey,
+    pub seed: [u8; 32],
+}
+
+/// Emitted when a listing is created, with enough of the terms to render it
+/// without fetching the account
+#[event]
+pub struct EscrowInitialized {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub escrow_id: u64,
+    pub token_mint: Pubkey,
+    pub skill_name: String,
+    pub category: String,
+    pub price_usdc: u64,
+    /// SHA-256 of `metadata_uri`, to check a fetched URI against
+    pub metadata_uri_hash: [u8; 32],
+    pub terms_hash: [u8; 32],
+    pub created_at: i64,
+}
+
+#[event]
+pub struct ChannelSettled {
+    pub channel: Pubkey,
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub cumulative: u64,
+    pub nonce: u64,
+    pub transferred: u64,
+}
+
+/// A deliverable or revision was submitted; `chain` links it to every earlier submission
+#[event]
+pub struct DeliverableSubmitted {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    /// 0 for the first submission
+    pub revision: u16,
+    pub deliverable_hash: [u8; 32],
+    /// Where the deliverable can be fetched; empty if handed over off-chain
+    pub deliverable_uri: String,
+    pub previous_chain: [u8; 32],
+    pub chain: [u8; 32],
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct DeliverableAccepted {
+    pub escrow: Pubkey,
+    pub renter: Pubkey,
+    /// Submission the release settled on; 0 for the first
+    pub revision: u16,
+    pub deliverable_hash: [u8; 32],
+    pub chain: [u8; 32],
+    pub accepted_at: i64,
+}
+
+#[event]
+pub struct RevisionRequested {
+    pub escrow: Pubkey,
+    pub renter: Pubkey,
+    /// Revision number the provider is asked for
+    pub revision: u16,
+    pub notes_hash: [u8; 32],
+    pub requested_at: i64,
+}
+
+#[event]
+pub struct InvoiceIssued {
+    pub invoice: Pubkey,
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub provider_amount: u64,
+    pub tax_amount: u64,
+}
+
+/// A release put the provider's payout on a vesting schedule
+#[event]
+pub struct VestingStarted {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub total: u64,
+    pub ends_at: i64,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub amount: u64,
+    /// Cumulative amount claimed so far
+    pub claimed: u64,
+    pub total: u64,
+}
+
+#[event]
+pub struct ListingExpired {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub funding_deadline: i64,
+    pub sunset_at: i64,
+    pub expired_at: i64,
+}
+
+/// Commitment to a settled escrow, for relaying to other chains
+#[event]
+pub struct EscrowSnapshotted {
+    pub snapshot: EscrowSnapshot,
+    /// `hash(snapshot.message())`
+    pub commitment: [u8; 32],
+}
+
+/// The provider added a payout milestone to an escrow's plan
+#[event]
+pub struct MilestoneAdded {
+    pub escrow: Pubkey,
+    pub index: u8,
+    pub description_hash: [u8; 32],
+    pub amount: u64,
+}
+
+#[event]
+pub struct MilestoneApproved {
+    pub escrow: Pubkey,
+    pub renter: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct StreamClaimed {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub amount: u64,
+    /// Streamed to the provider so far, this claim included
+    pub claimed_amount: u64,
+    /// Still held in escrow
+    pub remaining: u64,
+}
+
+#[event]
+pub struct TermsAccepted {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub terms_hash: [u8; 32],
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct EscrowMigrated {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    /// `EscrowLayout` the account was written in
+    pub from_layout: u8,
+    pub version: u8,
+}
+
+#[event]
+pub struct SubscriptionFunded {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub periods: u32,
+    pub amount_per_period: u64,
+    pub period_seconds: i64,
+    /// Held in escrow for all periods, after any transfer fee
+    pub amount: u64,
+    pub funded_at: i64,
+}
+
+#[event]
+pub struct PeriodClaimed {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    /// Periods paid so far, of those owed
+    pub claimed_periods: u32,
+    pub active_periods: u32,
+    pub amount: u64,
+    /// Still held in escrow for later periods
+    pub remaining: u64,
+}
+
+#[event]
+pub struct SubscriptionCancelled {
+    pub escrow: Pubkey,
+    pub renter: Pubkey,
+    /// Periods the provider is still owed, of those prepaid
+    pub active_periods: u32,
+    pub periods: u32,
+    pub refund: u64,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct MilestoneReleased {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+    /// Cumulative amount released through milestones
+    pub released: u64,
+    /// Still held in escrow for the final settlement
+    pub remaining: u64,
+}
+
+/// A provider listed a skill for any number of rentals
+#[event]
+pub struct SkillListed {
+    pub listing: Pubkey,
+    pub provider: Pubkey,
+    pub listing_id: u16,
+    pub token_mint: Pubkey,
+    pub skill_name: String,
+    pub price_usdc: u64,
+    pub terms_hash: [u8; 32],
+}
+
+/// A renter opened a rental of a listing; `EscrowFunded` follows for the new escrow
+#[event]
+pub struct RentalOpened {
+    pub listing: Pubkey,
+    pub escrow: Pubkey,
+    pub escrow_id: u64,
+    pub renter: Pubkey,
+}
+
+#[event]
+pub struct ListingClosed {
+    pub listing: Pubkey,
+    pub provider: Pubkey,
+    pub rentals_opened: u32,
+}
+
+/// The provider withdrew an unfunded listing and closed its escrow account
+#[event]
+pub struct EscrowCancelled {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub escrow_id: u64,
+    pub cancelled_at: i64,
+}
+
+/// A party proposed cancelling a funded rental; the other may accept until `expires_at`
+#[event]
+pub struct CancelProposed {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub escrow_id: u64,
+    pub renter: Pubkey,
+    pub proposed_by: CancelProposer,
+    pub expires_at: i64,
+}
+
+/// The provider repriced an unfunded listing
+#[event]
+pub struct PriceUpdated {
+    pub escrow: Pubkey,
+    pub previous: u64,
+    pub price_usdc: u64,
+    pub terms_hash: [u8; 32],
+}
+
+/// Renter and provider agreed on a new arbiter for the rental
+#[event]
+pub struct ArbiterChanged {
+    pub escrow: Pubkey,
+    pub previous: Pubkey,
+    pub arbiter: Pubkey,
+}
+
+/// Renter and provider agreed to lengthen the rental
+#[event]
+pub struct DurationExtended {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub extra_seconds: i64,
+    pub duration_seconds: i64,
+    pub rental_ends_at: i64,
+    pub terms_hash: [u8; 32],
+}
+
+/// A release paid part of the provider's payout to the protocol treasury
+#[event]
+pub struct ProtocolFeeCharged {
+    pub escrow: Pubkey,
+    pub treasury_token_account: Pubkey,
+    pub amount: u64,
+}
+
+/// The provider published or withdrew a translation of a listing's terms
+#[event]
+pub struct TermsLocalized {
+    pub escrow: Pubkey,
+    /// Canonical terms the translation renders; disputes are judged by these
+    pub terms_hash: [u8; 32],
+    pub language: String,
+    /// `None` when the language was withdrawn
+    pub metadata_uri: Option<String>,
+}
+
+/// The provider is retiring a listing; it stops accepting renters at `sunset_at`
+#[event]
+pub struct ListingDeprecated {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub sunset_at: i64,
+    pub timestamp: i64,
+}
+
+/// A renter deposited on a listing awaiting the provider's acceptance
+#[event]
+pub struct RentalRequested {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub renter: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+}
+
+/// A pending rental request's deposit went back to the renter
+#[event]
+pub struct RentalRequestWithdrawn {
+    pub escrow: Pubkey,
+    pub renter: Pubkey,
+    pub amount: u64,
+    /// Renter withdrawing, provider declining, or a crank after expiry
+    pub by: Pubkey,
+}
+
+/// A deferred balance went unpaid past its due date and the escrow was released anyway
+#[event]
+pub struct DeferredPaymentDefaulted {
+    pub escrow: Pubkey,
+    pub renter: Pubkey,
+    pub outstanding: u64,
+    pub due_at: i64,
+}
+
+/// Emitted when a renter funds an escrow; providers watch for it to start work
+#[event]
+pub struct EscrowFunded {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub escrow_id: u64,
+    pub renter: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub funded_at: i64,
+    pub settle_in_credits: bool,
+}
+
+/// The renter paid for priority; providers order their work by `expedite_fee`
+#[event]
+pub struct EscrowExpedited {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub escrow_id: u64,
+    pub renter: Pubkey,
+    pub extra_fee: u64,
+    /// Priority fees paid on the rental so far
+    pub expedite_fee: u64,
+}
+
+/// A party opened a dispute; `amount` is what the arbiter will rule on
+#[event]
+pub struct EscrowDisputed {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub escrow_id: u64,
+    pub renter: Pubkey,
+    pub arbiter: Pubkey,
+    /// Renter, watchtower or provider
+    pub raised_by: Pubkey,
+    pub amount: u64,
+    /// Raised against a vesting payout, freezing its unvested part
+    pub post_release: bool,
+    pub disputed_at: i64,
+}
+
+/// A rental paid out: `kind` says whether to the provider, back to the renter, or split
+#[event]
+pub struct EscrowSettled {
+    pub escrow: Pubkey,
+    pub provider: Pubkey,
+    pub escrow_id: u64,
+    pub renter: Pubkey,
+    pub kind: SettlementKind,
+    pub accounting: AmountMetadata,
+}
+
+#[event]
+pub struct PointsRedeemed {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+    pub timestamp: i64,
 }
 
 // ========== Errors ==========
@@ -287,4 +10464,214 @@ pub enum EscrowError {
     Unauthorized,
     #[msg("Insufficient funds")]
     InsufficientFunds,
+    #[msg("Provider has reached its concurrent rental limit")]
+    ProviderAtCapacity,
+    #[msg("Instruction does not match the escrow's settlement mode")]
+    InvalidSettlementMode,
+    #[msg("Escrow has a committed decryption key; release with the key ciphertext")]
+    DecryptionKeyRequired,
+    #[msg("Key ciphertext does not match the committed hash")]
+    DecryptionKeyMismatch,
+    #[msg("Key ciphertext too long (max 512 bytes)")]
+    KeyCiphertextTooLong,
+    #[msg("Override ranges must be well-formed and contain the listing defaults")]
+    InvalidOverridePolicy,
+    #[msg("Requested override is outside the provider's authorized range")]
+    OverrideOutOfRange,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Remaining accounts do not match the expected layout")]
+    InvalidRemainingAccounts,
+    #[msg("Price oracle account is not a valid Pyth price update")]
+    InvalidOracle,
+    #[msg("Settlement already in progress")]
+    ReentrancyDetected,
+    #[msg("Terms hash does not match the escrow's current terms")]
+    TermsHashMismatch,
+    #[msg("Activity feed does not belong to this agent")]
+    InvalidActivityFeed,
+    #[msg("Emission schedule must have at most 4 tiers in ascending start order")]
+    InvalidEmissionSchedule,
+    #[msg("Points redemption is not enabled")]
+    RedemptionDisabled,
+    #[msg("Insufficient points balance")]
+    InsufficientPoints,
+    #[msg("Invalid SLA terms")]
+    InvalidSlaTerms,
+    #[msg("Escrow does not track this SLA metric")]
+    SlaNotTracked,
+    #[msg("Observed value meets the SLA target")]
+    SlaNotBreached,
+    #[msg("Renter token account required to pay the SLA penalty")]
+    RenterTokenAccountRequired,
+    #[msg("Randomness commit or reveal phase is not open")]
+    RandomnessPhaseClosed,
+    #[msg("Commitment missing or does not match the revealed secret")]
+    InvalidCommitment,
+    #[msg("Already committed or revealed")]
+    AlreadyCommitted,
+    #[msg("Invalid screening configuration")]
+    InvalidScreeningConfig,
+    #[msg("Release awaits screener approval or the end of the screening delay")]
+    ScreeningPending,
+    #[msg("Too many escrows initialized this epoch")]
+    ListingRateLimited,
+    #[msg("Category too long (max 32 bytes)")]
+    CategoryTooLong,
+    #[msg("Invalid funding rule")]
+    InvalidFundingRule,
+    #[msg("Reputation account does not belong to the renter")]
+    InvalidReputationAccount,
+    #[msg("Deferred balance must be paid before release")]
+    DeferredBalanceOutstanding,
+    #[msg("Channel is closing")]
+    ChannelClosing,
+    #[msg("Channel update is older than the last settlement")]
+    StaleChannelUpdate,
+    #[msg("Missing or invalid payer signature for channel update")]
+    InvalidChannelSignature,
+    #[msg("Access credential commitment must be non-zero")]
+    InvalidAccessCommitment,
+    #[msg("Escrow has no renter yet")]
+    RenterUnassigned,
+    #[msg("Split must give each party a non-zero share")]
+    InvalidSplit,
+    #[msg("This deployment was built without the required feature")]
+    FeatureDisabled,
+    #[msg("Listing requires the provider to accept renters; use request_rental")]
+    ProviderAcceptanceRequired,
+    #[msg("Listing does not require provider acceptance; use accept_escrow")]
+    ProviderAcceptanceNotRequired,
+    #[msg("Provider acceptance window has closed")]
+    AcceptanceWindowClosed,
+    #[msg("Deliverable already submitted; the renter has not requested a revision")]
+    RevisionNotRequested,
+    #[msg("Maximum number of deliverable revisions reached")]
+    TooManyRevisions,
+    #[msg("Trial listings need trials enabled, no funding rule, and a price within the trial cap")]
+    InvalidTrialTerms,
+    #[msg("Listing funding deadline has passed")]
+    ListingExpired,
+    #[msg("Vesting period must be non-negative and cannot be combined with credit settlement")]
+    InvalidVestingTerms,
+    #[msg("Nothing has vested since the last claim")]
+    NothingVested,
+    #[msg("Tax rate cannot exceed 100%")]
+    InvalidTaxRate,
+    #[msg("Sunset must be in the future and earlier than any sunset already set")]
+    InvalidSunset,
+    #[msg("Milestone does not exist, has no amount, or is not pending")]
+    InvalidMilestone,
+    #[msg("Escrow already has the maximum number of milestones")]
+    TooManyMilestones,
+    #[msg("Milestones would schedule more than the escrow holds")]
+    MilestonesExceedEscrow,
+    #[msg("Milestone has not been approved by the renter")]
+    MilestoneNotApproved,
+    #[msg("Language must be a BCP 47 tag of at most 16 characters")]
+    InvalidLanguageTag,
+    #[msg("Localized terms URI must be 1-200 characters")]
+    InvalidLocalizedUri,
+    #[msg("Listing already has the maximum number of localizations")]
+    TooManyLocalizations,
+    #[msg("Fee exceeds MAX_FEE_BPS or has no treasury")]
+    InvalidFee,
+    #[msg("A protocol fee is owed; pass the treasury token account")]
+    FeeTreasuryRequired,
+    #[msg("Token account does not belong to the fee treasury or has the wrong mint")]
+    InvalidFeeTreasury,
+    #[msg("Skill name or metadata URI is not zero-padded UTF-8 within its capacity")]
+    InvalidTermsText,
+    #[msg("Arbiter must be a third party to the rental")]
+    InvalidArbiter,
+    #[msg("Funded amount does not match the listed price")]
+    AmountMismatch,
+    #[msg("Listing still has pending rental requests")]
+    RentalRequestsPending,
+    #[msg("Escrow ids with the rental flag are assigned by open_rental")]
+    ReservedEscrowId,
+    #[msg("Demo airdrops must be between 1 and DEMO_AIRDROP_MAX base units")]
+    DemoAirdropTooLarge,
+    #[msg("No cancellation has been proposed")]
+    NoCancelProposal,
+    #[msg("Cancellation proposal has expired")]
+    CancelProposalExpired,
+    #[msg("Evidence URI too long")]
+    EvidenceUriTooLong,
+    #[msg("Dispute already holds the maximum number of evidence entries")]
+    TooManyEvidenceEntries,
+    #[msg("Arbitration window must not be negative")]
+    InvalidArbitrationWindow,
+    #[msg("Dispute has no arbitration deadline")]
+    NoArbitrationDeadline,
+    #[msg("Arbiter has rented to or from a party of the dispute")]
+    ArbiterConflict,
+    #[msg("Arbiter has not declared freedom from conflicts for this dispute")]
+    ConflictNotDeclared,
+    #[msg("Engagement does not contradict the declaration")]
+    NoConflict,
+    #[msg("Council needs distinct members and a threshold between one and their number")]
+    InvalidCouncil,
+    #[msg("Signer is not a member of the escrow's arbiter council")]
+    NotCouncilMember,
+    #[msg("Proposal is for another ruling or dispute")]
+    ProposalMismatch,
+    #[msg("Not enough council members approved the proposal")]
+    ThresholdNotMet,
+    #[msg("A council can only release, refund or split")]
+    InvalidRuling,
+    #[msg("Mint decimals differ from the listing's price decimals")]
+    MintDecimalsMismatch,
+    #[msg("The queued recovery action is of a different kind")]
+    RecoveryMismatch,
+    #[msg("Recovery payouts exceed what the escrow vault holds")]
+    InvalidRecovery,
+    #[msg("Mint is not on the config allowlist")]
+    InvalidMint,
+    #[msg("Mint allowlist is full")]
+    TooManyAllowedMints,
+    #[msg("Only wrapped-SOL escrows can be funded or paid out in SOL")]
+    NotWrappedSol,
+    #[msg("The parties did not agree to automatic release when funding")]
+    AutoReleaseNotAgreed,
+    #[msg("Deliverable URI too long")]
+    DeliverableUriTooLong,
+    #[msg("The provider has not submitted a deliverable")]
+    NotDelivered,
+    #[msg("The epoch being counted has not ended yet")]
+    EpochNotOver,
+    #[msg("The listing requires a provider bond that has not been deposited")]
+    BondNotFunded,
+    #[msg("The provider bond is already deposited")]
+    BondAlreadyFunded,
+    #[msg("A duration extension must add a positive number of seconds")]
+    InvalidExtension,
+    #[msg("The listing does not offer subscriptions")]
+    SubscriptionNotOffered,
+    #[msg("A subscription needs at least one period of non-zero length")]
+    InvalidSubscription,
+    #[msg("No subscription period is due to be claimed")]
+    NoPeriodDue,
+    #[msg("Risk weights are invalid")]
+    InvalidRiskWeights,
+    #[msg("Risk scoring is disabled until the admin sets risk weights")]
+    RiskScoringDisabled,
+    #[msg("Nothing has streamed since the last claim")]
+    NothingStreamed,
+    #[msg("The escrow was written by an older layout; call migrate_escrow first")]
+    LayoutVersionMismatch,
+    #[msg("The account is not an escrow of any known layout")]
+    UnknownEscrowLayout,
+    #[msg("The escrow is already on the current layout")]
+    AlreadyMigrated,
+    #[msg("The acceptance signature has expired")]
+    AcceptanceExpired,
+    #[msg("Terms acceptance signature missing or invalid")]
+    InvalidAcceptanceSignature,
+    #[msg("The provider already has an escrow with this id")]
+    EscrowIdTaken,
+    #[msg("Credit-settled listings are capped at MAX_CREDIT_PRICE_TOKENS")]
+    CreditPriceTooHigh,
+    #[msg("Another ruling has approvals and is still pending")]
+    ProposalPending,
 }
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::EscrowAccount;
+
+#[test]
+fn arbiter_must_be_a_third_party() {
+    let escrow = EscrowAccount {
+        provider: Pubkey::new_unique(),
+        renter: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    assert!(escrow.is_eligible_arbiter(&Pubkey::new_unique()));
+    assert!(!escrow.is_eligible_arbiter(&escrow.provider));
+    assert!(!escrow.is_eligible_arbiter(&escrow.renter));
+    assert!(!escrow.is_eligible_arbiter(&Pubkey::default()));
+}
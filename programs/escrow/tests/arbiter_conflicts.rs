@@ -0,0 +1,89 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{ArbiterDeclaration, Engagement, EscrowAccount, EscrowState, CONFLICT_COOLING_OFF_SECONDS};
+
+const DECLARED_AT: i64 = 1_700_000_000;
+
+fn disputed() -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        renter: Pubkey::new_unique(),
+        arbiter: Pubkey::new_unique(),
+        state: EscrowState::Disputed,
+        disputed_at: DECLARED_AT - 600,
+        ..Default::default()
+    }
+}
+
+fn declaration(escrow: &EscrowAccount) -> ArbiterDeclaration {
+    ArbiterDeclaration {
+        escrow: Pubkey::new_unique(),
+        arbiter: escrow.arbiter,
+        provider: escrow.provider,
+        renter: escrow.renter,
+        disputed_at: escrow.disputed_at,
+        declared_at: DECLARED_AT,
+        violated: false,
+        bump: 0,
+    }
+}
+
+fn engagement(provider: Pubkey, renter: Pubkey, funded_at: i64) -> Engagement {
+    let mut engagement = Engagement {
+        provider: Pubkey::default(),
+        renter: Pubkey::default(),
+        rentals: 0,
+        first_funded_at: 0,
+        last_funded_at: 0,
+        bump: 0,
+    };
+    engagement.record(provider, renter, 255, funded_at);
+    engagement
+}
+
+#[test]
+fn engagement_keeps_the_first_rental_of_the_pair() {
+    let (provider, renter) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut engagement = engagement(provider, renter, 1_000);
+    engagement.record(provider, renter, 255, 5_000);
+    assert_eq!(engagement.rentals, 2);
+    assert_eq!(engagement.first_funded_at, 1_000);
+    assert_eq!(engagement.last_funded_at, 5_000);
+    assert!(engagement.involves(&provider) && engagement.involves(&renter));
+}
+
+#[test]
+fn declaration_covers_only_its_own_dispute() {
+    let mut escrow = disputed();
+    let mut declaration = declaration(&escrow);
+    assert!(declaration.covers(&escrow));
+
+    // A later dispute on the same escrow needs a fresh declaration
+    escrow.disputed_at += 10_000;
+    assert!(!declaration.covers(&escrow));
+
+    escrow.disputed_at = declaration.disputed_at;
+    declaration.violated = true;
+    assert!(!declaration.covers(&escrow));
+}
+
+#[test]
+fn rentals_with_a_party_within_the_cooling_off_contradict_the_declaration() {
+    let escrow = disputed();
+    let declaration = declaration(&escrow);
+    let bribe = engagement(escrow.arbiter, escrow.renter, DECLARED_AT + 3_600);
+    assert!(declaration.is_contradicted_by(&bribe));
+    let hired = engagement(escrow.provider, escrow.arbiter, DECLARED_AT + CONFLICT_COOLING_OFF_SECONDS);
+    assert!(declaration.is_contradicted_by(&hired));
+
+    let later = engagement(escrow.arbiter, escrow.renter, DECLARED_AT + CONFLICT_COOLING_OFF_SECONDS + 1);
+    assert!(!declaration.is_contradicted_by(&later));
+}
+
+#[test]
+fn unrelated_pairs_do_not_contradict_the_declaration() {
+    let escrow = disputed();
+    let declaration = declaration(&escrow);
+    // The parties renting from each other is the dispute itself
+    assert!(!declaration.is_contradicted_by(&engagement(escrow.provider, escrow.renter, DECLARED_AT)));
+    assert!(!declaration.is_contradicted_by(&engagement(escrow.arbiter, Pubkey::new_unique(), DECLARED_AT)));
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::{AnchorSerialize, Pubkey};
+use escrow::{
+    ArbiterCouncil, EscrowError, Reserved, ResolutionOutcome, ResolutionProposal, MAX_COUNCIL_MEMBERS,
+    RESOLUTION_PROPOSAL_SECONDS,
+};
+
+const DISPUTED_AT: i64 = 1_700_000_000;
+
+fn members(n: usize) -> Vec<Pubkey> {
+    (0..n).map(|_| Pubkey::new_unique()).collect()
+}
+
+fn proposal() -> ResolutionProposal {
+    ResolutionProposal {
+        escrow: Pubkey::new_unique(),
+        council: Pubkey::new_unique(),
+        disputed_at: 0,
+        outcome: ResolutionOutcome::RefundedToRenter,
+        ruling_hash: [0; 32],
+        proposed_by: Pubkey::default(),
+        proposed_at: 0,
+        approvals: 0,
+        bump: 0,
+    }
+}
+
+#[test]
+fn councils_need_distinct_members_and_a_reachable_threshold() {
+    let three = members(3);
+    assert!(ArbiterCouncil::is_valid(&three, 1));
+    assert!(ArbiterCouncil::is_valid(&three, 3));
+    assert!(!ArbiterCouncil::is_valid(&three, 0));
+    assert!(!ArbiterCouncil::is_valid(&three, 4));
+    assert!(!ArbiterCouncil::is_valid(&[three[0], three[1], three[0]], 2));
+    assert!(!ArbiterCouncil::is_valid(&[three[0], Pubkey::default()], 1));
+    assert!(!ArbiterCouncil::is_valid(&members(MAX_COUNCIL_MEMBERS + 1), 2));
+}
+
+#[test]
+fn a_full_council_fits_its_len() {
+    let council = ArbiterCouncil {
+        creator: Pubkey::new_unique(),
+        council_id: u32::MAX,
+        members: members(MAX_COUNCIL_MEMBERS),
+        threshold: MAX_COUNCIL_MEMBERS as u8,
+        bump: 0,
+        _reserved: Reserved::default(),
+    };
+    assert_eq!(8 + council.try_to_vec().unwrap().len(), ArbiterCouncil::LEN);
+}
+
+#[test]
+fn approvals_accumulate_only_on_the_same_ruling() {
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let split = ResolutionOutcome::Split(7_000);
+    let mut proposal = proposal();
+    proposal.propose(DISPUTED_AT, split, [1; 32], first, 10).unwrap();
+    proposal.approve(0);
+
+    // Proposing the same ruling again keeps what was approved
+    proposal.propose(DISPUTED_AT, split, [1; 32], second, 20).unwrap();
+    proposal.approve(1);
+    assert!(proposal.approved_by(0) && proposal.approved_by(1));
+    assert_eq!(proposal.proposed_by, first);
+
+    // A different ruling starts over once the approved one has stood its time
+    proposal
+        .propose(DISPUTED_AT, ResolutionOutcome::RefundedToRenter, [1; 32], second, 10 + RESOLUTION_PROPOSAL_SECONDS)
+        .unwrap();
+    assert_eq!(proposal.approvals, 0);
+    assert_eq!(proposal.proposed_by, second);
+
+    // So does a later dispute on the same escrow, at once
+    proposal.approve(1);
+    proposal.propose(DISPUTED_AT + 1, ResolutionOutcome::RefundedToRenter, [1; 32], second, 40).unwrap();
+    assert_eq!(proposal.approvals, 0);
+}
+
+#[test]
+fn an_approved_ruling_cannot_be_displaced_while_it_stands() {
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut proposal = proposal();
+    proposal.propose(DISPUTED_AT, ResolutionOutcome::Split(7_000), [1; 32], first, 100).unwrap();
+
+    // Nobody has approved yet, so it can still be replaced
+    proposal.propose(DISPUTED_AT, ResolutionOutcome::Split(6_000), [1; 32], first, 110).unwrap();
+    proposal.approve(0);
+
+    for (outcome, ruling_hash) in [
+        (ResolutionOutcome::RefundedToRenter, [1; 32]),
+        (ResolutionOutcome::Split(6_000), [2; 32]),
+    ] {
+        assert_eq!(
+            proposal.propose(DISPUTED_AT, outcome, ruling_hash, second, 110 + RESOLUTION_PROPOSAL_SECONDS - 1).unwrap_err(),
+            EscrowError::ProposalPending.into()
+        );
+    }
+    assert!(proposal.approved_by(0));
+    assert_eq!(proposal.proposed_by, first);
+}
+
+#[test]
+fn councils_cannot_propose_default_judgment_or_one_sided_splits() {
+    let mut proposal = proposal();
+    let member = Pubkey::new_unique();
+    assert_eq!(
+        proposal.propose(DISPUTED_AT, ResolutionOutcome::Defaulted, [0; 32], member, 0).unwrap_err(),
+        EscrowError::InvalidRuling.into()
+    );
+    assert_eq!(
+        proposal.propose(DISPUTED_AT, ResolutionOutcome::Split(10_000), [0; 32], member, 0).unwrap_err(),
+        EscrowError::InvalidSplit.into()
+    );
+}
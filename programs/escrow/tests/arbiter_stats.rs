@@ -0,0 +1,38 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{ArbiterStats, Reserved};
+
+fn stats() -> ArbiterStats {
+    ArbiterStats {
+        arbiter: Pubkey::default(),
+        resolutions: 0,
+        total_latency_seconds: 0,
+        max_latency_seconds: 0,
+        last_resolved_at: 0,
+        bump: 0,
+        conflicts: 0,
+        _reserved: Reserved::default(),
+    }
+}
+
+#[test]
+fn first_resolution_claims_the_account() {
+    let arbiter = Pubkey::new_unique();
+    let mut stats = stats();
+    stats.record_resolution(arbiter, 254, 1_000, 4_600);
+    assert_eq!(stats.arbiter, arbiter);
+    assert_eq!(stats.bump, 254);
+    assert_eq!(stats.resolutions, 1);
+    assert_eq!(stats.last_resolved_at, 4_600);
+}
+
+#[test]
+fn latency_is_averaged_and_its_maximum_kept() {
+    let arbiter = Pubkey::new_unique();
+    let mut stats = stats();
+    assert_eq!(stats.mean_latency_seconds(), 0);
+    stats.record_resolution(arbiter, 254, 0, 1_000);
+    stats.record_resolution(arbiter, 254, 10_000, 13_000);
+    assert_eq!(stats.resolutions, 2);
+    assert_eq!(stats.mean_latency_seconds(), 2_000);
+    assert_eq!(stats.max_latency_seconds, 3_000);
+}
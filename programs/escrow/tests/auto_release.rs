@@ -0,0 +1,81 @@
+use escrow::{EscrowAccount, EscrowState, EscrowTerms};
+
+const FUNDED_AT: i64 = 1_700_000_000;
+const DURATION: i64 = 3_600;
+
+fn agreed() -> EscrowAccount {
+    EscrowAccount {
+        state: EscrowState::Funded,
+        funded_at: FUNDED_AT,
+        first_delivered_at: FUNDED_AT + 600,
+        delivered_at: FUNDED_AT + 600,
+        auto_release_offered: true,
+        auto_release: true,
+        terms: EscrowTerms {
+            duration_seconds: DURATION,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn releases_once_the_rental_period_ends() {
+    let escrow = agreed();
+    assert!(!escrow.auto_release_due(FUNDED_AT + DURATION - 1));
+    assert!(escrow.auto_release_due(FUNDED_AT + DURATION));
+}
+
+#[test]
+fn needs_the_renter_to_have_opted_in() {
+    // The provider's offer alone is not agreement
+    let escrow = EscrowAccount {
+        auto_release: false,
+        ..agreed()
+    };
+    assert!(!escrow.auto_release_due(i64::MAX));
+}
+
+#[test]
+fn releases_without_agreement_once_the_review_window_lapses() {
+    let escrow = EscrowAccount {
+        auto_release_offered: false,
+        auto_release: false,
+        review_window_seconds: 900,
+        delivered_at: FUNDED_AT + 1_200,
+        ..agreed()
+    };
+    // The renter may still release or dispute up to the deadline itself
+    assert!(!escrow.auto_release_due(FUNDED_AT + 2_100));
+    assert!(escrow.auto_release_due(FUNDED_AT + 2_101));
+
+    // A resubmission restarts the window
+    let resubmitted = EscrowAccount {
+        delivered_at: FUNDED_AT + 2_000,
+        ..escrow
+    };
+    assert!(!resubmitted.auto_release_due(FUNDED_AT + 2_101));
+}
+
+#[test]
+fn leaves_undelivered_disputed_and_revised_rentals_alone() {
+    let undelivered = EscrowAccount {
+        first_delivered_at: 0,
+        delivered_at: 0,
+        ..agreed()
+    };
+    assert!(!undelivered.auto_release_due(i64::MAX));
+
+    let disputed = EscrowAccount {
+        state: EscrowState::Disputed,
+        ..agreed()
+    };
+    assert!(!disputed.auto_release_due(i64::MAX));
+
+    let revising = EscrowAccount {
+        revision_requested_at: FUNDED_AT + 900,
+        review_window_seconds: 900,
+        ..agreed()
+    };
+    assert!(!revising.auto_release_due(i64::MAX));
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowError, ProviderIndex};
+
+fn index(active_rentals: u16) -> ProviderIndex {
+    ProviderIndex {
+        provider: Pubkey::new_unique(),
+        active_rentals,
+        bump: 255,
+    }
+}
+
+#[test]
+fn funding_stops_at_the_listing_limit() {
+    let mut index = index(0);
+    index.reserve_slot(2).unwrap();
+    index.reserve_slot(2).unwrap();
+    assert_eq!(index.reserve_slot(2).unwrap_err(), EscrowError::ProviderAtCapacity.into());
+    assert_eq!(index.active_rentals, 2);
+
+    // A settlement frees the slot for the next renter
+    index.release_slot();
+    assert!(index.has_slot(2));
+    index.reserve_slot(2).unwrap();
+}
+
+#[test]
+fn zero_means_unlimited() {
+    let mut index = index(500);
+    assert!(index.has_slot(0));
+    index.reserve_slot(0).unwrap();
+    assert_eq!(index.active_rentals, 501);
+}
+
+#[test]
+fn counter_neither_wraps_nor_underflows() {
+    assert_eq!(index(u16::MAX).reserve_slot(0).unwrap_err(), EscrowError::ProviderAtCapacity.into());
+
+    let mut empty = index(0);
+    empty.release_slot();
+    assert_eq!(empty.active_rentals, 0);
+}
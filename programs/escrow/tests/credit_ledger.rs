@@ -0,0 +1,133 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{Config, CreditBalance, EscrowAccount, EscrowError, EscrowState, EscrowTerms, MAX_CREDIT_PRICE_TOKENS};
+
+fn ledger() -> CreditBalance {
+    CreditBalance {
+        provider: Pubkey::default(),
+        token_mint: Pubkey::default(),
+        locked: 0,
+        available: 0,
+        total_withdrawn: 0,
+        bump: 0,
+    }
+}
+
+fn config() -> Config {
+    Config {
+        admin: Pubkey::new_unique(),
+        points_schedule: vec![],
+        redemption_enabled: false,
+        bump: 0,
+        screener: Pubkey::default(),
+        screening_threshold: 0,
+        screening_delay_seconds: 0,
+        max_initializations_per_epoch: 0,
+        features: 0,
+        trial_max_amount: 0,
+        tax_bps: 0,
+        fee_bps: 0,
+        fee_treasury: Pubkey::default(),
+        failure_streak_threshold: 0,
+        arbitration_window_seconds: 0,
+        default_release: false,
+        allowed_mints: vec![],
+        risk_weights: Default::default(),
+    }
+}
+
+fn credit_rental() -> EscrowAccount {
+    EscrowAccount {
+        state: EscrowState::Funded,
+        renter: Pubkey::new_unique(),
+        amount: 5_000,
+        terms: EscrowTerms {
+            settle_in_credits: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn completions_move_locked_credits_to_available() {
+    let mut ledger = ledger();
+    let provider = Pubkey::new_unique();
+    ledger.bind(provider, Pubkey::new_unique(), 254);
+    ledger.bind(Pubkey::new_unique(), Pubkey::new_unique(), 1);
+    assert_eq!((ledger.provider, ledger.bump), (provider, 254));
+
+    ledger.lock(5_000).unwrap();
+    ledger.lock(3_000).unwrap();
+    ledger.settle(5_000).unwrap();
+    ledger.unlock(3_000).unwrap();
+    assert_eq!((ledger.locked, ledger.available), (0, 5_000));
+
+    // Nothing can settle or refund more than was funded
+    assert_eq!(ledger.settle(1).unwrap_err(), EscrowError::InsufficientFunds.into());
+    assert_eq!(ledger.unlock(1).unwrap_err(), EscrowError::InsufficientFunds.into());
+    assert_eq!(ledger.available, 5_000);
+}
+
+#[test]
+fn credit_and_token_paths_refuse_each_others_listings() {
+    let mut listing = EscrowAccount {
+        terms_hash: [1; 32],
+        ..credit_rental()
+    };
+    listing.state = EscrowState::Created;
+    listing.check_fundable(&[1; 32], true, 0).unwrap();
+    assert_eq!(
+        listing.check_fundable(&[1; 32], false, 0).unwrap_err(),
+        EscrowError::InvalidSettlementMode.into()
+    );
+
+    let mut rental = credit_rental();
+    rental.first_delivered_at = 10;
+    rental.check_releasable(&config(), true, 20).unwrap();
+    assert_eq!(
+        rental.check_releasable(&config(), false, 20).unwrap_err(),
+        EscrowError::InvalidSettlementMode.into()
+    );
+}
+
+#[test]
+fn credit_rentals_release_only_after_a_delivery() {
+    let mut rental = credit_rental();
+    assert_eq!(
+        rental.check_releasable(&config(), true, 20).unwrap_err(),
+        EscrowError::NotDelivered.into()
+    );
+    rental.first_delivered_at = 10;
+    rental.check_releasable(&config(), true, 20).unwrap();
+}
+
+#[test]
+fn only_the_renter_side_completes_a_credit_rental() {
+    let mut rental = credit_rental();
+    rental.provider = Pubkey::new_unique();
+    rental.watchtower = Pubkey::new_unique();
+    assert!(rental.is_renter_or_watchtower(&rental.renter));
+    assert!(rental.is_renter_or_watchtower(&rental.watchtower));
+    assert!(!rental.is_renter_or_watchtower(&rental.provider));
+    assert!(!rental.is_renter_or_watchtower(&Pubkey::new_unique()));
+}
+
+#[test]
+fn credit_listings_are_capped_at_micro_rental_prices() {
+    let cap = MAX_CREDIT_PRICE_TOKENS * 1_000_000;
+    let mut terms = EscrowTerms {
+        settle_in_credits: true,
+        price_usdc: cap,
+        ..Default::default()
+    };
+    assert!(terms.credit_price_is_valid(6));
+    terms.price_usdc = cap + 1;
+    assert!(!terms.credit_price_is_valid(6));
+    // The cap is in whole tokens, so it scales with the mint
+    assert!(terms.credit_price_is_valid(9));
+
+    // Token-settled listings pay the protocol fee and are not capped
+    terms.settle_in_credits = false;
+    terms.price_usdc = u64::MAX;
+    assert!(terms.credit_price_is_valid(6));
+}
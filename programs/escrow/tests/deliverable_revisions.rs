@@ -0,0 +1,37 @@
+use escrow::EscrowAccount;
+
+fn escrow() -> EscrowAccount {
+    EscrowAccount {
+        funded_at: 1_000,
+        dispute_window_seconds: 500,
+        review_window_seconds: 200,
+        first_delivered_at: 2_000,
+        delivered_at: 3_000,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn review_window_runs_from_the_latest_revision() {
+    assert_eq!(escrow().dispute_deadline(), 3_200);
+}
+
+#[test]
+fn pending_revision_request_holds_the_dispute_window_open() {
+    let escrow = EscrowAccount {
+        revision_requested_at: 3_100,
+        ..escrow()
+    };
+    assert_eq!(escrow.dispute_deadline(), 3_600);
+}
+
+#[test]
+fn chain_commits_to_every_submission_in_order() {
+    let first = EscrowAccount::chain_deliverable(&[0; 32], &[1; 32], &[0; 32]);
+    let second = EscrowAccount::chain_deliverable(&first, &[2; 32], &[0; 32]);
+    let reordered = EscrowAccount::chain_deliverable(&EscrowAccount::chain_deliverable(&[0; 32], &[2; 32], &[0; 32]), &[1; 32], &[0; 32]);
+
+    assert_ne!(first, second);
+    assert_ne!(second, reordered);
+    assert_ne!(first, EscrowAccount::chain_deliverable(&[0; 32], &[1; 32], &[3; 32]));
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::{AnchorSerialize, Pubkey};
+use escrow::{DisputeEvidence, EscrowError, Reserved, MAX_EVIDENCE_ENTRIES, MAX_EVIDENCE_URI_LEN};
+
+fn evidence() -> DisputeEvidence {
+    DisputeEvidence {
+        escrow: Pubkey::new_unique(),
+        disputed_at: 0,
+        entries: Vec::new(),
+        bump: 0,
+        _reserved: Reserved::default(),
+    }
+}
+
+#[test]
+fn entries_are_scoped_to_the_current_dispute() {
+    let mut evidence = evidence();
+    let renter = Pubkey::new_unique();
+    evidence.start_dispute(1_000);
+    evidence.add(renter, "ipfs://logs".to_string(), [1; 32], 1_100).unwrap();
+    evidence.start_dispute(1_000);
+    evidence.add(renter, "ipfs://screenshot".to_string(), [2; 32], 1_200).unwrap();
+    assert_eq!(evidence.entries.len(), 2);
+
+    // A later dispute on the same escrow starts from an empty list
+    evidence.start_dispute(5_000);
+    assert!(evidence.entries.is_empty());
+    assert_eq!(evidence.disputed_at, 5_000);
+}
+
+#[test]
+fn oversized_uris_are_rejected() {
+    let mut evidence = evidence();
+    let uri = "x".repeat(MAX_EVIDENCE_URI_LEN + 1);
+    assert_eq!(
+        evidence.add(Pubkey::new_unique(), uri, [0; 32], 0).unwrap_err(),
+        EscrowError::EvidenceUriTooLong.into()
+    );
+}
+
+#[test]
+fn full_evidence_list_fits_its_len() {
+    let mut evidence = evidence();
+    let uri = "x".repeat(MAX_EVIDENCE_URI_LEN);
+    for i in 0..MAX_EVIDENCE_ENTRIES {
+        evidence.add(Pubkey::new_unique(), uri.clone(), [i as u8; 32], i as i64).unwrap();
+    }
+    assert_eq!(
+        evidence.add(Pubkey::new_unique(), uri, [0; 32], 0).unwrap_err(),
+        EscrowError::TooManyEvidenceEntries.into()
+    );
+    assert_eq!(8 + evidence.try_to_vec().unwrap().len(), DisputeEvidence::LEN);
+}
@@ -0,0 +1,158 @@
+//! The arbitration path of one rental, step by step, through the account helpers the
+//! dispute instructions are built on
+
+use anchor_lang::prelude::Pubkey;
+use escrow::{Config, DisputeRandomness, EscrowAccount, EscrowError, EscrowState, EscrowTerms, ResolutionOutcome};
+
+const FUNDED_AT: i64 = 1_700_000_000;
+
+fn funded() -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        renter: Pubkey::new_unique(),
+        arbiter: Pubkey::new_unique(),
+        terms: EscrowTerms {
+            duration_seconds: 3_600,
+            vesting_seconds: 1_000,
+            ..Default::default()
+        },
+        state: EscrowState::Funded,
+        amount: 10_000,
+        funded_at: FUNDED_AT,
+        dispute_window_seconds: 86_400,
+        review_window_seconds: 600,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn delivered_rental_is_disputed_randomized_and_split() {
+    let mut escrow = funded();
+    escrow.delivered_at = FUNDED_AT + 100;
+    // Delivery starts the review window; the rental-period window no longer applies
+    assert_eq!(escrow.dispute_deadline(), FUNDED_AT + 700);
+    escrow.state = EscrowState::Disputed;
+    escrow.disputed_at = FUNDED_AT + 650;
+
+    // Every party, arbiter included, holds one randomness slot
+    let parties = [escrow.renter, escrow.provider, escrow.arbiter];
+    let mut randomness = DisputeRandomness {
+        disputed_at: escrow.disputed_at,
+        ..Default::default()
+    };
+    for (slot, party) in parties.iter().enumerate() {
+        assert_eq!(escrow.dispute_party(party), Some(slot));
+        randomness.commitments[slot] = DisputeRandomness::commitment(&[slot as u8 + 1; 32], party);
+    }
+    assert_eq!(escrow.dispute_party(&Pubkey::new_unique()), None);
+    assert!(randomness.all_committed());
+    randomness.revealed = [true, false, true];
+    assert!(!randomness.all_revealed());
+    randomness.revealed[1] = true;
+    randomness.seed = [4; 32];
+    randomness.finalized = true;
+    assert!(randomness.select(0, 3).is_some());
+
+    // The ruling settles once; a second settlement cannot start while one is running
+    escrow.enter_settlement().unwrap();
+    assert_eq!(escrow.enter_settlement().unwrap_err(), EscrowError::ReentrancyDetected.into());
+    let arbiter = escrow.arbiter;
+    let event = escrow.record_resolution(Pubkey::new_unique(), arbiter, ResolutionOutcome::Split(6_000), [8; 32]);
+    escrow.allocate_settlement(6_000, 4_000);
+    escrow.exit_settlement();
+
+    assert_eq!(event.amount, 10_000);
+    assert_eq!(escrow.resolution, Some(ResolutionOutcome::Split(6_000)));
+    assert_eq!((escrow.provider_payout, escrow.renter_refund), (6_000, 4_000));
+}
+
+#[test]
+fn undelivered_rental_can_be_disputed_until_the_window_after_the_period() {
+    let escrow = funded();
+    assert_eq!(escrow.dispute_deadline(), FUNDED_AT + 3_600 + 86_400);
+
+    let revision = EscrowAccount {
+        delivered_at: FUNDED_AT + 100,
+        revision_requested_at: FUNDED_AT + 200,
+        ..funded()
+    };
+    assert_eq!(revision.dispute_deadline(), FUNDED_AT + 200 + 86_400);
+}
+
+#[test]
+fn ruling_after_release_only_reaches_the_unvested_payout() {
+    // Released into vesting: the whole escrow was allocated to the provider
+    let mut escrow = funded();
+    escrow.state = EscrowState::Vesting;
+    escrow.vesting_total = 10_000;
+    escrow.vesting_started_at = FUNDED_AT + 4_000;
+    escrow.allocate_settlement(10_000, 0);
+
+    // Disputed a quarter of the way through: what already vested stays with the provider
+    escrow.vesting_halted_at = FUNDED_AT + 4_250;
+    escrow.state = EscrowState::Disputed;
+    assert_eq!(escrow.vested_at(FUNDED_AT + 5_000), 2_500);
+    assert_eq!(escrow.disputed_amount(), 7_500);
+
+    let arbiter = escrow.arbiter;
+    let event = escrow.record_resolution(Pubkey::new_unique(), arbiter, ResolutionOutcome::RefundedToRenter, [1; 32]);
+    escrow.allocate_settlement(0, escrow.disputed_amount());
+    assert_eq!(event.amount, 7_500);
+    assert_eq!((escrow.provider_payout, escrow.renter_refund), (2_500, 7_500));
+}
+
+#[test]
+fn only_a_third_party_can_take_over_arbitration() {
+    let escrow = funded();
+    assert!(escrow.is_eligible_arbiter(&Pubkey::new_unique()));
+    assert!(!escrow.is_eligible_arbiter(&escrow.renter));
+    assert!(!escrow.is_eligible_arbiter(&escrow.provider));
+}
+
+#[test]
+fn unanswered_dispute_opens_default_judgment_after_the_window() {
+    let config = Config {
+        arbitration_window_seconds: 3 * 86_400,
+        ..config()
+    };
+    let mut escrow = funded();
+    escrow.state = EscrowState::Disputed;
+    escrow.disputed_at = FUNDED_AT + 100;
+    escrow.arbitration_deadline = config.arbitration_deadline(escrow.disputed_at);
+    assert_eq!(escrow.arbitration_deadline, FUNDED_AT + 100 + 3 * 86_400);
+
+    assert!(!escrow.arbitration_lapsed(escrow.arbitration_deadline));
+    assert!(escrow.arbitration_lapsed(escrow.arbitration_deadline + 1));
+}
+
+#[test]
+fn disputes_opened_without_a_window_never_lapse() {
+    let mut escrow = funded();
+    escrow.state = EscrowState::Disputed;
+    escrow.arbitration_deadline = config().arbitration_deadline(FUNDED_AT);
+    assert_eq!(escrow.arbitration_deadline, 0);
+    assert!(!escrow.arbitration_lapsed(i64::MAX));
+}
+
+fn config() -> Config {
+    Config {
+        admin: Pubkey::new_unique(),
+        points_schedule: vec![],
+        redemption_enabled: false,
+        bump: 0,
+        screener: Pubkey::default(),
+        screening_threshold: 0,
+        screening_delay_seconds: 0,
+        max_initializations_per_epoch: 0,
+        features: 0,
+        trial_max_amount: 0,
+        tax_bps: 0,
+        fee_bps: 0,
+        fee_treasury: Pubkey::default(),
+        failure_streak_threshold: 0,
+        arbitration_window_seconds: 0,
+        default_release: false,
+        allowed_mints: vec![],
+        risk_weights: Default::default(),
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{DisputeRandomness, EscrowAccount};
+
+#[test]
+fn commitment_binds_secret_to_party() {
+    let secret = [7u8; 32];
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    assert_ne!(DisputeRandomness::commitment(&secret, &a), DisputeRandomness::commitment(&secret, &b));
+    assert_eq!(DisputeRandomness::commitment(&secret, &a), DisputeRandomness::commitment(&secret, &a));
+}
+
+#[test]
+fn dispute_parties_map_to_fixed_slots() {
+    let escrow = EscrowAccount {
+        renter: Pubkey::new_unique(),
+        provider: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    assert_eq!(escrow.dispute_party(&escrow.renter), Some(0));
+    assert_eq!(escrow.dispute_party(&escrow.provider), Some(1));
+    // An unset arbiter must not match the default key
+    assert_eq!(escrow.dispute_party(&Pubkey::default()), None);
+}
+
+#[test]
+fn reveal_tracking_ignores_parties_that_never_committed() {
+    let mut randomness = DisputeRandomness {
+        commitments: [[1; 32], [2; 32], [0; 32]],
+        ..Default::default()
+    };
+    assert!(!randomness.all_committed());
+    assert!(!randomness.all_revealed());
+    randomness.revealed = [true, true, false];
+    assert!(randomness.all_revealed());
+}
+
+#[test]
+fn select_requires_a_finalized_seed() {
+    let mut randomness = DisputeRandomness {
+        seed: [9; 32],
+        ..Default::default()
+    };
+    assert_eq!(randomness.select(0, 5), None);
+    randomness.finalized = true;
+    let pick = randomness.select(0, 5).unwrap();
+    assert!(pick < 5);
+    assert_eq!(randomness.select(0, 5), Some(pick));
+    assert_eq!(randomness.select(0, 0), None);
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use escrow::{EscrowAccount, EscrowError, EscrowState, ResolutionOutcome};
+
+#[test]
+fn resolution_is_recorded_and_reported() {
+    let mut escrow = EscrowAccount {
+        provider: Pubkey::new_unique(),
+        renter: Pubkey::new_unique(),
+        state: EscrowState::Disputed,
+        amount: 10_000_000,
+        disputed_at: 1_700_000_000,
+        ..Default::default()
+    };
+    let (key, arbiter) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let event = escrow.record_resolution(key, arbiter, ResolutionOutcome::Split(2_500), [3; 32]);
+
+    assert_eq!(escrow.resolution, Some(ResolutionOutcome::Split(2_500)));
+    assert_eq!(escrow.ruling_hash, [3; 32]);
+    assert_eq!(event.escrow, key);
+    assert_eq!(event.arbiter, arbiter);
+    assert_eq!(event.renter, escrow.renter);
+    assert_eq!(event.outcome, ResolutionOutcome::Split(2_500));
+    assert_eq!(event.amount, 10_000_000);
+    assert_eq!(event.disputed_at, 1_700_000_000);
+}
+
+#[test]
+fn outcome_fits_its_len() {
+    for outcome in [
+        ResolutionOutcome::ReleasedToProvider,
+        ResolutionOutcome::RefundedToRenter,
+        ResolutionOutcome::Split(u16::MAX),
+        ResolutionOutcome::Defaulted,
+    ] {
+        let bytes = outcome.try_to_vec().unwrap();
+        assert!(bytes.len() <= ResolutionOutcome::LEN);
+        assert_eq!(ResolutionOutcome::try_from_slice(&bytes).unwrap(), outcome);
+    }
+}
+
+#[test]
+fn split_divides_the_disputed_amount_without_losing_units() {
+    let escrow = EscrowAccount {
+        state: EscrowState::Disputed,
+        amount: 10_000_001,
+        ..Default::default()
+    };
+    assert_eq!(escrow.split_disputed(6_000).unwrap(), (6_000_000, 4_000_001));
+    assert_eq!(escrow.split_disputed(0).unwrap_err(), EscrowError::InvalidSplit.into());
+    assert_eq!(escrow.split_disputed(10_000).unwrap_err(), EscrowError::InvalidSplit.into());
+
+    let whole = EscrowAccount {
+        amount: u64::MAX,
+        ..escrow
+    };
+    let (provider, renter) = whole.split_disputed(9_999).unwrap();
+    assert_eq!(provider.checked_add(renter), Some(u64::MAX));
+}
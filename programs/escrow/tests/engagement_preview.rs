@@ -0,0 +1,171 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{
+    Config, EngagementPreview, EscrowAccount, EscrowState, EscrowTerms, FundOverrides, FundingBlock, FundingRule,
+    OverridePolicy, ProviderIndex, ProviderStats,
+};
+
+const NOW: i64 = 1_700_000_000;
+const PRICE: u64 = 10_000_000;
+
+fn config() -> Config {
+    Config {
+        admin: Pubkey::new_unique(),
+        points_schedule: vec![],
+        redemption_enabled: false,
+        bump: 0,
+        screener: Pubkey::new_unique(),
+        screening_threshold: 5_000_000,
+        screening_delay_seconds: 0,
+        max_initializations_per_epoch: 0,
+        features: 0,
+        trial_max_amount: 0,
+        tax_bps: 0,
+        fee_bps: 250,
+        fee_treasury: Pubkey::new_unique(),
+        failure_streak_threshold: 2,
+        arbitration_window_seconds: 86_400,
+        default_release: false,
+        allowed_mints: vec![],
+        risk_weights: Default::default(),
+    }
+}
+
+fn stats() -> ProviderStats {
+    ProviderStats {
+        provider: Pubkey::new_unique(),
+        lifetime_volume: 90_000_000,
+        lifetime_releases: 9,
+        lifetime_refunds: 1,
+        bump: 255,
+        last_init_epoch: 0,
+        epoch_initializations: 0,
+        verified: true,
+        failure_streak: 0,
+        lifetime_disputes: 0,
+    }
+}
+
+fn index(active_rentals: u16) -> ProviderIndex {
+    ProviderIndex {
+        provider: Pubkey::new_unique(),
+        active_rentals,
+        bump: 255,
+    }
+}
+
+fn listing() -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        state: EscrowState::Created,
+        terms: EscrowTerms {
+            duration_seconds: 3_600,
+            price_usdc: PRICE,
+            max_concurrent_rentals: 2,
+            dispute_window_seconds: 7_200,
+            review_window_seconds: 900,
+            arbiter: Pubkey::new_unique(),
+            override_policy: OverridePolicy {
+                min_dispute_window_seconds: 3_600,
+                max_dispute_window_seconds: 14_400,
+                min_review_window_seconds: 900,
+                max_review_window_seconds: 900,
+                allow_arbiter_override: false,
+            },
+            funding_deadline: NOW + 600,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn preview(listing: &EscrowAccount, overrides: &FundOverrides, score: Option<i64>, active: u16) -> EngagementPreview {
+    let escrow = Pubkey::new_unique();
+    EngagementPreview::new(escrow, listing, Pubkey::new_unique(), score, overrides, &config(), &index(active), &stats(), NOW)
+        .unwrap()
+}
+
+#[test]
+fn shows_the_timeline_and_fee_funding_would_fix() {
+    let overrides = FundOverrides {
+        dispute_window_seconds: Some(14_400),
+        ..Default::default()
+    };
+    let preview = preview(&listing(), &overrides, None, 0);
+    assert_eq!(preview.blocked_by, None);
+    assert_eq!((preview.upfront, preview.deferred), (PRICE, 0));
+    assert_eq!((preview.protocol_fee, preview.provider_payout), (250_000, 9_750_000));
+    assert!(preview.requires_screening);
+    assert_eq!(preview.funding_expires_at, NOW + 600);
+    assert_eq!(preview.rental_ends_at, NOW + 3_600);
+    assert_eq!(preview.dispute_window_seconds, 14_400);
+    assert_eq!(preview.dispute_deadline, NOW + 3_600 + 14_400);
+    assert_eq!(preview.arbitration_window_seconds, 86_400);
+    assert_eq!((preview.provider_releases, preview.provider_refunds), (9, 1));
+    assert!(preview.provider_verified);
+}
+
+#[test]
+fn splits_the_price_by_the_renters_score() {
+    let mut listing = listing();
+    listing.terms.funding_rule = Some(FundingRule {
+        full_prefund_below: 40,
+        standard_upfront_bps: 5_000,
+        deferred_from: 80,
+        deferred_upfront_bps: 2_000,
+        deferred_due_seconds: 86_400,
+    });
+    let trusted = preview(&listing, &FundOverrides::default(), Some(90), 0);
+    assert_eq!((trusted.upfront, trusted.deferred), (2_000_000, 8_000_000));
+    assert_eq!(trusted.deferred_due_at, NOW + 86_400);
+
+    let unknown = preview(&listing, &FundOverrides::default(), None, 0);
+    assert_eq!((unknown.upfront, unknown.deferred, unknown.deferred_due_at), (PRICE, 0, 0));
+}
+
+#[test]
+fn names_what_would_block_funding() {
+    assert_eq!(
+        preview(&listing(), &FundOverrides::default(), None, 2).blocked_by,
+        Some(FundingBlock::ProviderAtCapacity)
+    );
+
+    let mut bonded = listing();
+    bonded.terms.provider_bond = 1_000_000;
+    assert_eq!(
+        preview(&bonded, &FundOverrides::default(), None, 0).blocked_by,
+        Some(FundingBlock::BondNotFunded)
+    );
+
+    let mut expired = listing();
+    expired.sunset_at = NOW;
+    assert_eq!(
+        preview(&expired, &FundOverrides::default(), None, 0).blocked_by,
+        Some(FundingBlock::ListingExpired)
+    );
+
+    let funded = EscrowAccount {
+        state: EscrowState::Funded,
+        ..listing()
+    };
+    assert_eq!(preview(&funded, &FundOverrides::default(), None, 0).blocked_by, Some(FundingBlock::NotOpen));
+}
+
+#[test]
+fn rejects_overrides_outside_the_policy() {
+    let overrides = FundOverrides {
+        review_window_seconds: Some(60),
+        ..Default::default()
+    };
+    let result = EngagementPreview::new(
+        Pubkey::new_unique(),
+        &listing(),
+        Pubkey::new_unique(),
+        None,
+        &overrides,
+        &config(),
+        &index(0),
+        &stats(),
+        NOW,
+    );
+    assert!(result.is_err());
+}
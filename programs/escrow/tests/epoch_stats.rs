@@ -0,0 +1,55 @@
+use escrow::{EpochCounters, EpochStats, Reserved};
+
+fn counting(epoch: u64) -> EpochStats {
+    EpochStats {
+        epoch,
+        started_at: 1_700_000_000,
+        counters: EpochCounters::default(),
+        bump: 255,
+        _reserved: Reserved::default(),
+    }
+}
+
+#[test]
+fn rollover_returns_the_period_and_starts_a_fresh_one() {
+    let mut stats = counting(611);
+    stats.record_created();
+    stats.record_funding(25_000_000);
+    stats.record_funding(5_000_000);
+    stats.record_dispute();
+    stats.record_fee(250_000);
+
+    let counted = stats.roll_over(612, 1_700_172_800).unwrap();
+    assert_eq!(
+        counted,
+        EpochCounters {
+            escrows_created: 1,
+            volume: 30_000_000,
+            disputes: 1,
+            fees: 250_000,
+        }
+    );
+    assert_eq!(stats.epoch, 612);
+    assert_eq!(stats.started_at, 1_700_172_800);
+    assert_eq!(stats.counters, EpochCounters::default());
+}
+
+#[test]
+fn rollover_waits_for_the_epoch_to_end() {
+    let mut stats = counting(611);
+    stats.record_created();
+    assert!(stats.roll_over(611, 1_700_100_000).is_err());
+    assert_eq!(stats.counters.escrows_created, 1);
+
+    // A late crank closes one period spanning the skipped epochs
+    assert!(stats.roll_over(615, 1_700_600_000).is_ok());
+    assert_eq!(stats.epoch, 615);
+}
+
+#[test]
+fn counters_saturate_instead_of_failing_settlements() {
+    let mut stats = counting(611);
+    stats.record_funding(u64::MAX);
+    stats.record_funding(1);
+    assert_eq!(stats.counters.volume, u64::MAX);
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{escrow_id_seed, ESCROW_SEED};
+
+fn address(provider: &Pubkey, escrow_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[ESCROW_SEED, provider.as_ref(), &escrow_id_seed(escrow_id)], &escrow::ID).0
+}
+
+#[test]
+fn escrow_zero_keeps_the_original_address() {
+    let provider = Pubkey::new_unique();
+    let original = Pubkey::find_program_address(&[ESCROW_SEED, provider.as_ref()], &escrow::ID).0;
+    assert_eq!(address(&provider, 0), original);
+}
+
+#[test]
+fn each_id_is_a_separate_escrow() {
+    let provider = Pubkey::new_unique();
+    assert_ne!(address(&provider, 0), address(&provider, 1));
+    assert_ne!(address(&provider, 1), address(&provider, 2));
+    assert_ne!(address(&provider, 1), address(&Pubkey::new_unique(), 1));
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountSerialize, AnchorSerialize, Discriminator};
+use escrow::layout::{read_escrow, EscrowLayout};
+use escrow::{EscrowAccount, EscrowState, ESCROW_VERSION};
+
+fn read(layout: EscrowLayout, data: &[u8]) -> EscrowAccount {
+    let mut escrow = EscrowAccount::default();
+    read_escrow(layout, &data[8..], &mut escrow).unwrap();
+    escrow
+}
+
+#[test]
+fn version_names_the_current_layout() {
+    assert_eq!(ESCROW_VERSION, EscrowLayout::CURRENT as u8);
+    assert_eq!(EscrowLayout::from_len(EscrowAccount::LEN), Some(EscrowLayout::CURRENT));
+}
+
+#[test]
+fn original_layout_reads_as_unversioned() {
+    let mut data = EscrowAccount::DISCRIMINATOR.to_vec();
+    for key in 1..=5u8 {
+        data.extend_from_slice(&[key; 32]);
+    }
+    ("translate".to_string(), 3_600i64, 5_000_000u64, "ipfs://v0".to_string()).serialize(&mut data).unwrap();
+    (EscrowState::Funded, 5_000_000u64, 100i64, 0i64, 0i64).serialize(&mut data).unwrap();
+    data.resize(EscrowLayout::V0.account_len(), 0);
+
+    let escrow = read(EscrowLayout::V0, &data);
+    assert_eq!(escrow.provider, Pubkey::new_from_array([1; 32]));
+    assert_eq!(escrow.terms.skill_name.as_str(), "translate");
+    assert_eq!(escrow.state, EscrowState::Funded);
+    assert_eq!(escrow.amount, 5_000_000);
+    assert_eq!(escrow.version, 0);
+}
+
+#[test]
+fn current_layout_keeps_its_version_byte() {
+    let escrow = EscrowAccount {
+        escrow_id: 7,
+        claimed_amount: 1_000,
+        ..Default::default()
+    };
+    let mut unversioned = Vec::new();
+    escrow.try_serialize(&mut unversioned).unwrap();
+    let mut migrated = Vec::new();
+    EscrowAccount {
+        version: ESCROW_VERSION,
+        ..escrow
+    }
+    .try_serialize(&mut migrated)
+    .unwrap();
+    // Carved from the reserve, so migrating changes one byte and not the size
+    let differing = unversioned.iter().zip(&migrated).filter(|(a, b)| a != b).count();
+    assert_eq!((unversioned.len(), differing), (migrated.len(), 1));
+
+    unversioned.resize(EscrowAccount::LEN, 0);
+    migrated.resize(EscrowAccount::LEN, 0);
+    let read_back = read(EscrowLayout::CURRENT, &unversioned);
+    assert_eq!((read_back.escrow_id, read_back.claimed_amount, read_back.version), (7, 1_000, 0));
+    assert_eq!(read(EscrowLayout::CURRENT, &migrated).version, ESCROW_VERSION);
+}
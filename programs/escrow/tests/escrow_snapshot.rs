@@ -0,0 +1,53 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowState, ResolutionOutcome, SNAPSHOT_MESSAGE_PREFIX};
+
+fn settled() -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        renter: Pubkey::new_unique(),
+        state: EscrowState::Completed,
+        amount: 25_000_000,
+        provider_payout: 15_000_000,
+        renter_refund: 10_000_000,
+        resolution: Some(ResolutionOutcome::Split(6_000)),
+        completed_at: 1_700_003_000,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn open_escrows_have_no_snapshot() {
+    for state in [EscrowState::Created, EscrowState::Funded, EscrowState::Disputed, EscrowState::Vesting] {
+        let escrow = EscrowAccount { state, ..settled() };
+        assert!(escrow.snapshot(Pubkey::new_unique(), 1).is_none());
+    }
+}
+
+#[test]
+fn snapshot_records_the_settlement() {
+    let escrow = settled();
+    let snapshot = escrow.snapshot(Pubkey::new_unique(), 42).unwrap();
+    assert_eq!(snapshot.settled_at, 1_700_003_000);
+    assert_eq!(snapshot.provider_payout + snapshot.renter_refund, snapshot.amount);
+    assert_eq!(snapshot.slot, 42);
+
+    let expired = EscrowAccount {
+        state: EscrowState::Expired,
+        cancelled_at: 1_700_086_401,
+        ..Default::default()
+    };
+    assert_eq!(expired.snapshot(Pubkey::new_unique(), 42).unwrap().settled_at, 1_700_086_401);
+}
+
+#[test]
+fn commitment_covers_every_field() {
+    let escrow = settled();
+    let key = Pubkey::new_unique();
+    let snapshot = escrow.snapshot(key, 42).unwrap();
+    assert!(snapshot.message().unwrap().starts_with(SNAPSHOT_MESSAGE_PREFIX));
+    assert_eq!(snapshot.commitment().unwrap(), escrow.snapshot(key, 42).unwrap().commitment().unwrap());
+    assert_ne!(snapshot.commitment().unwrap(), escrow.snapshot(key, 43).unwrap().commitment().unwrap());
+
+    let refunded = EscrowAccount { renter_refund: 9_999_999, ..settled() };
+    assert_ne!(snapshot.commitment().unwrap(), refunded.snapshot(key, 42).unwrap().commitment().unwrap());
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::ProviderStats;
+
+fn stats() -> ProviderStats {
+    ProviderStats {
+        provider: Pubkey::new_unique(),
+        lifetime_volume: 0,
+        lifetime_releases: 0,
+        lifetime_refunds: 0,
+        bump: 255,
+        last_init_epoch: 0,
+        epoch_initializations: 0,
+        verified: false,
+        failure_streak: 0,
+        lifetime_disputes: 0,
+    }
+}
+
+#[test]
+fn refunds_beyond_the_threshold_throttle_to_one_rental() {
+    let mut stats = stats();
+    for _ in 0..3 {
+        stats.record_refund();
+    }
+    assert_eq!(stats.concurrency_limit(5, 3), 5);
+    assert_eq!(stats.concurrency_limit(0, 3), 0);
+
+    stats.record_refund();
+    assert_eq!(stats.failure_streak, 4);
+    assert_eq!(stats.concurrency_limit(5, 3), 1);
+    assert_eq!(stats.concurrency_limit(0, 3), 1);
+}
+
+#[test]
+fn a_release_ends_the_streak() {
+    let mut stats = stats();
+    for _ in 0..4 {
+        stats.record_refund();
+    }
+    stats.record_release(1_000_000).unwrap();
+    assert_eq!(stats.failure_streak, 0);
+    assert_eq!(stats.lifetime_refunds, 4);
+    assert_eq!(stats.concurrency_limit(5, 3), 5);
+}
+
+#[test]
+fn zero_threshold_never_throttles() {
+    let mut stats = stats();
+    for _ in 0..100 {
+        stats.record_refund();
+    }
+    assert_eq!(stats.concurrency_limit(5, 0), 5);
+}
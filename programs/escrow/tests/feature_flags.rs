@@ -0,0 +1,41 @@
+use escrow::{
+    Config, COMPILED_FEATURES, FEATURE_DEVNET_DEMO, FEATURE_DISPUTES, FEATURE_FEES, FEATURE_ORACLE, FEATURE_STREAMING,
+};
+
+#[test]
+fn compiled_features_match_cargo_features() {
+    assert_eq!(COMPILED_FEATURES & FEATURE_FEES != 0, cfg!(feature = "fees"));
+    assert_eq!(COMPILED_FEATURES & FEATURE_DISPUTES != 0, cfg!(feature = "disputes"));
+    assert_eq!(COMPILED_FEATURES & FEATURE_STREAMING != 0, cfg!(feature = "streaming"));
+    assert_eq!(COMPILED_FEATURES & FEATURE_ORACLE != 0, cfg!(feature = "oracle"));
+    assert_eq!(COMPILED_FEATURES & FEATURE_DEVNET_DEMO != 0, cfg!(feature = "devnet-demo"));
+}
+
+#[test]
+fn config_reports_recorded_features() {
+    let config = Config {
+        admin: Default::default(),
+        points_schedule: vec![],
+        redemption_enabled: false,
+        bump: 0,
+        screener: Default::default(),
+        screening_threshold: 0,
+        screening_delay_seconds: 0,
+        max_initializations_per_epoch: 0,
+        features: FEATURE_DISPUTES | FEATURE_ORACLE,
+        trial_max_amount: 0,
+        tax_bps: 0,
+        fee_bps: 0,
+        fee_treasury: Default::default(),
+        failure_streak_threshold: 0,
+        arbitration_window_seconds: 0,
+        default_release: false,
+        allowed_mints: vec![],
+        risk_weights: Default::default(),
+    };
+    assert!(config.supports(FEATURE_DISPUTES));
+    assert!(config.supports(FEATURE_ORACLE));
+    assert!(!config.supports(FEATURE_FEES));
+    assert!(!config.supports(FEATURE_STREAMING));
+    assert!(!config.supports(FEATURE_DEVNET_DEMO));
+}
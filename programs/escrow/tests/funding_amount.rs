@@ -0,0 +1,36 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowTerms, FundingRule};
+
+fn listing(price_usdc: u64) -> EscrowTerms {
+    EscrowTerms {
+        price_usdc,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn listings_fund_at_exactly_their_price() {
+    let terms = listing(25_000_000);
+    assert!(terms.accepts_funding(25_000_000));
+    assert!(!terms.accepts_funding(24_999_999));
+    assert!(!terms.accepts_funding(25_000_001));
+}
+
+#[test]
+fn funding_rules_allow_partial_funding_up_to_the_price() {
+    let terms = EscrowTerms {
+        funding_rule: Some(FundingRule::default()),
+        ..listing(25_000_000)
+    };
+    assert!(terms.accepts_funding(5_000_000));
+    assert!(terms.accepts_funding(25_000_000));
+    assert!(!terms.accepts_funding(25_000_001));
+}
+
+#[test]
+fn repricing_changes_the_terms_hash() {
+    let mint = Pubkey::new_unique();
+    let before = EscrowAccount::compute_terms_hash(&listing(25_000_000), &mint).unwrap();
+    let after = EscrowAccount::compute_terms_hash(&listing(30_000_000), &mint).unwrap();
+    assert_ne!(before, after);
+}
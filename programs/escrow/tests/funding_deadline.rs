@@ -0,0 +1,42 @@
+use escrow::{EscrowAccount, EscrowTerms};
+
+fn listing(funding_deadline: i64) -> EscrowAccount {
+    EscrowAccount {
+        terms: EscrowTerms {
+            funding_deadline,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn listing_without_deadline_stays_open() {
+    assert!(listing(0).funding_open(i64::MAX));
+}
+
+#[test]
+fn funding_closes_after_the_deadline() {
+    let escrow = listing(1_700_000_000);
+    assert!(escrow.funding_open(1_700_000_000));
+    assert!(!escrow.funding_open(1_700_000_001));
+}
+
+#[test]
+fn funding_closes_at_the_sunset() {
+    let escrow = EscrowAccount {
+        sunset_at: 1_700_000_000,
+        ..listing(0)
+    };
+    assert!(escrow.funding_open(1_699_999_999));
+    assert!(!escrow.funding_open(1_700_000_000));
+}
+
+#[test]
+fn earlier_of_deadline_and_sunset_wins() {
+    let escrow = EscrowAccount {
+        sunset_at: 1_800_000_000,
+        ..listing(1_700_000_000)
+    };
+    assert!(!escrow.funding_open(1_700_000_001));
+}
@@ -0,0 +1,29 @@
+use escrow::FundingRule;
+
+fn rule() -> FundingRule {
+    FundingRule {
+        full_prefund_below: 40,
+        standard_upfront_bps: 5_000,
+        deferred_from: 80,
+        deferred_upfront_bps: 1_000,
+        deferred_due_seconds: 15 * 86_400,
+    }
+}
+
+#[test]
+fn upfront_share_follows_reputation_tiers() {
+    let rule = rule();
+    assert_eq!(rule.upfront_bps(None), 10_000);
+    assert_eq!(rule.upfront_bps(Some(39)), 10_000);
+    assert_eq!(rule.upfront_bps(Some(40)), 5_000);
+    assert_eq!(rule.upfront_bps(Some(79)), 5_000);
+    assert_eq!(rule.upfront_bps(Some(80)), 1_000);
+}
+
+#[test]
+fn rejects_inverted_tiers_and_out_of_range_shares() {
+    assert!(rule().is_valid());
+    assert!(!FundingRule { deferred_from: 10, ..rule() }.is_valid());
+    assert!(!FundingRule { standard_upfront_bps: 10_001, ..rule() }.is_valid());
+    assert!(!FundingRule { deferred_due_seconds: -1, ..rule() }.is_valid());
+}
@@ -0,0 +1,865 @@
+//! Borsh test vectors for every account and instruction payload.
+//!
+//! External SDKs decode these layouts byte-for-byte, so any change here must be
+//! intentional. Regenerate with `UPDATE_GOLDEN=1 cargo test -p escrow --test golden_layouts`
+//! and review the diff of `tests/golden/layouts.txt`.
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountSerialize, InstructionData};
+use ::escrow::instruction as ix;
+use ::escrow::{
+    ArbiterCouncil, ArbiterDeclaration, ArbiterStats, Config, CreditBalance, DisputeEvidence, DisputeRandomness, EmissionTier, Engagement, EpochCounters, EpochSnapshot, EpochStats, EscrowAccount, EscrowState, EscrowTerms, FixedString, FundOverrides,
+    EvidenceEntry, FundingRule, LocalizedTerms, Milestone, MilestonePlan, MilestoneState,
+    OverridePolicy, PointsAccount, Invoice, RecoveryAction, RecoveryKind, ResolutionProposal, ProviderIndex, ProviderStats, RentalRequest, Reserved, ResolutionOutcome, RiskWeights, SettlementChannel, SkillListing, SubscriptionPlan, SlaMetric, SlaTerms, TermsAcceptance, TermsLocalization, ESCROW_VERSION,
+};
+
+fn epoch_counters() -> EpochCounters {
+    EpochCounters {
+        escrows_created: 42,
+        volume: 1_250_000_000,
+        disputes: 3,
+        fees: 12_500_000,
+    }
+}
+
+fn key(n: u8) -> Pubkey {
+    Pubkey::new_from_array([n; 32])
+}
+
+fn terms() -> EscrowTerms {
+    EscrowTerms {
+        skill_name: FixedString::new("code-review").unwrap(),
+        category: "development".to_string(),
+        duration_seconds: 3_600,
+        price_usdc: 25_000_000,
+        metadata_uri: FixedString::new("ipfs://terms").unwrap(),
+        max_concurrent_rentals: 3,
+        settle_in_credits: false,
+        dispute_window_seconds: 86_400,
+        review_window_seconds: 43_200,
+        arbiter: key(9),
+        override_policy: OverridePolicy {
+            min_dispute_window_seconds: 3_600,
+            max_dispute_window_seconds: 172_800,
+            min_review_window_seconds: 3_600,
+            max_review_window_seconds: 86_400,
+            allow_arbiter_override: true,
+        },
+        sla: Some(SlaTerms {
+            max_latency_ms: Some(500),
+            min_uptime_bps: Some(9_950),
+            max_revisions: None,
+            verifier: key(10),
+            penalty_bps_per_breach: 500,
+            max_penalty_bps: 2_000,
+        }),
+        funding_rule: Some(FundingRule {
+            full_prefund_below: 40,
+            standard_upfront_bps: 5_000,
+            deferred_from: 80,
+            deferred_upfront_bps: 0,
+            deferred_due_seconds: 15 * 86_400,
+        }),
+        requires_provider_acceptance: true,
+        trial: false,
+        funding_deadline: 1_700_086_400,
+        vesting_seconds: 0,
+        price_decimals: 6,
+        provider_bond: 5_000_000,
+        stream_payment: true,
+    }
+}
+
+fn account<T: AccountSerialize>(value: &T) -> Vec<u8> {
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    data
+}
+
+fn accounts() -> Vec<(&'static str, Vec<u8>)> {
+    let escrow = EscrowAccount {
+        provider: key(1),
+        renter: key(2),
+        token_mint: key(3),
+        provider_token_account: key(4),
+        escrow_token_account: key(5),
+        terms: terms(),
+        state: EscrowState::Funded,
+        amount: 25_000_000,
+        created_at: 1_700_000_000,
+        funded_at: 1_700_000_100,
+        deliverable_hash: [6; 32],
+        dispute_window_seconds: 86_400,
+        review_window_seconds: 43_200,
+        arbiter: key(9),
+        terms_hash: [7; 32],
+        watchtower: key(8),
+        sla_breaches: 1,
+        sla_penalty_bps: 500,
+        deferred_balance: 12_500_000,
+        deferred_due_at: 1_701_296_100,
+        access_commitment: [16; 32],
+        access_committed_at: 1_700_000_900,
+        access_rotations: 2,
+        resolution: Some(ResolutionOutcome::Split(6_000)),
+        ruling_hash: [17; 32],
+        delivered_at: 1_700_000_800,
+        first_delivered_at: 1_700_000_500,
+        revisions: 1,
+        revision_notes_hash: [18; 32],
+        deliverable_chain: [19; 32],
+        vesting_total: 20_000_000,
+        vesting_started_at: 1_700_001_000,
+        vested_claimed: 5_000_000,
+        vesting_halted_at: 1_700_002_000,
+        provider_payout: 20_000_000,
+        renter_refund: 5_000_000,
+        webhook_signer: key(15),
+        sunset_at: 1_700_050_000,
+        escrow_id: 7,
+        fee_amount: 500_000,
+        auto_release_offered: true,
+        auto_release: true,
+        bond_held: true,
+        subscription_offered: true,
+        claimed_amount: 2_500_000,
+        version: ESCROW_VERSION,
+        ..Default::default()
+    };
+    vec![
+        ("EscrowAccount", account(&escrow)),
+        (
+            "ProviderIndex",
+            account(&ProviderIndex {
+                provider: key(1),
+                active_rentals: 2,
+                bump: 254,
+            }),
+        ),
+        (
+            "ProviderStats",
+            account(&ProviderStats {
+                provider: key(1),
+                lifetime_volume: 75_000_000,
+                lifetime_releases: 3,
+                lifetime_refunds: 1,
+                bump: 253,
+                last_init_epoch: 500,
+                epoch_initializations: 2,
+                verified: true,
+                failure_streak: 2,
+                lifetime_disputes: 1,
+            }),
+        ),
+        (
+            "SettlementChannel",
+            account(&SettlementChannel {
+                payer: key(2),
+                payee: key(1),
+                token_mint: key(3),
+                deposited: 100_000_000,
+                settled: 42_000_000,
+                nonce: 17,
+                close_requested_at: 0,
+                bump: 252,
+            }),
+        ),
+        (
+            "RentalRequest",
+            account(&RentalRequest {
+                escrow: key(13),
+                renter: key(2),
+                amount: 25_000_000,
+                overrides: FundOverrides {
+                    dispute_window_seconds: Some(7_200),
+                    review_window_seconds: None,
+                    arbiter: Some(key(14)),
+                    auto_release: true,
+                },
+                terms_hash: [7; 32],
+                reputation_score: Some(85),
+                requested_at: 1_700_000_050,
+                bump: 248,
+            }),
+        ),
+        (
+            "Invoice",
+            account(&Invoice {
+                escrow: key(13),
+                provider: key(1),
+                renter: key(2),
+                token_mint: key(3),
+                terms_hash: [7; 32],
+                amount: 25_000_000,
+                provider_amount: 20_000_000,
+                renter_refund: 5_000_000,
+                fee_amount: 0,
+                tax_bps: 825,
+                tax_amount: 1_650_000,
+                funded_at: 1_700_000_100,
+                settled_at: 1_700_003_000,
+                issued_at: 1_700_003_100,
+                bump: 247,
+            }),
+        ),
+        (
+            "CreditBalance",
+            account(&CreditBalance {
+                provider: key(1),
+                token_mint: key(3),
+                locked: 1_000,
+                available: 2_000,
+                total_withdrawn: 3_000,
+                bump: 252,
+            }),
+        ),
+        (
+            "Config",
+            account(&Config {
+                admin: key(11),
+                points_schedule: vec![EmissionTier {
+                    starts_at: 1_700_000_000,
+                    points_bps: 100,
+                }],
+                redemption_enabled: true,
+                bump: 251,
+                screener: key(12),
+                screening_threshold: 10_000_000_000,
+                screening_delay_seconds: 86_400,
+                max_initializations_per_epoch: 5,
+                features: 0b1011,
+                trial_max_amount: 5_000_000,
+                tax_bps: 825,
+                fee_bps: 250,
+                fee_treasury: key(14),
+                failure_streak_threshold: 3,
+                arbitration_window_seconds: 259_200,
+                default_release: false,
+                allowed_mints: vec![key(3)],
+                risk_weights: RiskWeights {
+                    disputes: 4,
+                    cancellations: 3,
+                    account_age: 2,
+                    bond_coverage: 1,
+                    mature_age_seconds: 15_552_000,
+                },
+            }),
+        ),
+        (
+            "PointsAccount",
+            account(&PointsAccount {
+                owner: key(1),
+                balance: 10,
+                lifetime_earned: 20,
+                redeemed: 10,
+                bump: 250,
+            }),
+        ),
+        (
+            "DisputeRandomness",
+            account(&DisputeRandomness {
+                escrow: key(13),
+                disputed_at: 1_700_000_200,
+                commitments: [[1; 32], [2; 32], [0; 32]],
+                revealed: [true, false, false],
+                accumulator: [3; 32],
+                seed: [0; 32],
+                finalized: false,
+                bump: 249,
+            }),
+        ),
+        (
+            "ArbiterStats",
+            account(&ArbiterStats {
+                arbiter: key(9),
+                resolutions: 4,
+                total_latency_seconds: 345_600,
+                max_latency_seconds: 172_800,
+                last_resolved_at: 1_700_200_000,
+                bump: 246,
+                conflicts: 1,
+                _reserved: Reserved::default(),
+            }),
+        ),
+        (
+            "Engagement",
+            account(&Engagement {
+                provider: key(3),
+                renter: key(2),
+                rentals: 3,
+                first_funded_at: 1_700_000_000,
+                last_funded_at: 1_700_300_000,
+                bump: 242,
+            }),
+        ),
+        (
+            "ArbiterDeclaration",
+            account(&ArbiterDeclaration {
+                escrow: key(13),
+                arbiter: key(9),
+                provider: key(3),
+                renter: key(2),
+                disputed_at: 1_700_100_000,
+                declared_at: 1_700_100_600,
+                violated: false,
+                bump: 241,
+            }),
+        ),
+        (
+            "ArbiterCouncil",
+            account(&ArbiterCouncil {
+                creator: key(1),
+                council_id: 7,
+                members: vec![key(9), key(10), key(11)],
+                threshold: 2,
+                bump: 240,
+                _reserved: Reserved::default(),
+            }),
+        ),
+        (
+            "ResolutionProposal",
+            account(&ResolutionProposal {
+                escrow: key(13),
+                council: key(14),
+                disputed_at: 1_700_100_000,
+                outcome: ResolutionOutcome::Split(6_000),
+                ruling_hash: [24; 32],
+                proposed_by: key(10),
+                proposed_at: 1_700_150_000,
+                approvals: 0b011,
+                bump: 239,
+            }),
+        ),
+        (
+            "RecoveryAction",
+            account(&RecoveryAction {
+                escrow: key(13),
+                kind: RecoveryKind::SettleWithProof {
+                    to_provider: 15_000_000,
+                    to_renter: 10_000_000,
+                },
+                proof_hash: [25; 32],
+                queued_by: key(15),
+                queued_at: 1_700_200_000,
+                executable_at: 1_700_459_200,
+                bump: 238,
+            }),
+        ),
+        (
+            "EpochStats",
+            account(&EpochStats {
+                epoch: 612,
+                started_at: 1_700_000_000,
+                counters: epoch_counters(),
+                bump: 236,
+                _reserved: Reserved::default(),
+            }),
+        ),
+        (
+            "EpochSnapshot",
+            account(&EpochSnapshot {
+                epoch: 611,
+                next_epoch: 612,
+                started_at: 1_699_827_200,
+                ended_at: 1_700_000_000,
+                counters: epoch_counters(),
+                bump: 235,
+            }),
+        ),
+        (
+            "DisputeEvidence",
+            account(&DisputeEvidence {
+                escrow: key(13),
+                disputed_at: 1_700_100_000,
+                entries: vec![EvidenceEntry {
+                    submitted_by: key(2),
+                    uri: "ipfs://evidence".to_string(),
+                    hash: [23; 32],
+                    submitted_at: 1_700_100_500,
+                }],
+                bump: 243,
+                _reserved: Reserved::default(),
+            }),
+        ),
+        (
+            "MilestonePlan",
+            account(&MilestonePlan {
+                escrow: key(13),
+                milestones: vec![
+                    Milestone {
+                        description_hash: [21; 32],
+                        amount: 10_000_000,
+                        state: MilestoneState::Released,
+                    },
+                    Milestone {
+                        description_hash: [22; 32],
+                        amount: 5_000_000,
+                        state: MilestoneState::Pending,
+                    },
+                ],
+                released: 10_000_000,
+                bump: 245,
+                _reserved: Reserved::default(),
+            }),
+        ),
+        (
+            "SubscriptionPlan",
+            account(&SubscriptionPlan {
+                escrow: key(13),
+                periods: 4,
+                amount_per_period: 10_000_000,
+                total: 40_000_000,
+                period_seconds: 3_600,
+                started_at: 1_700_000_000,
+                claimed_periods: 1,
+                active_periods: 3,
+                cancelled_at: 1_700_005_000,
+                bump: 244,
+                _reserved: Reserved::default(),
+            }),
+        ),
+        (
+            "TermsAcceptance",
+            account(&TermsAcceptance {
+                escrow: key(13),
+                renter: key(2),
+                terms_hash: [7; 32],
+                expires_at: 1_700_086_400,
+                signature: [5; 64],
+                recorded_at: 1_700_000_000,
+                bump: 243,
+            }),
+        ),
+        (
+            "TermsLocalization",
+            account(&TermsLocalization {
+                escrow: key(13),
+                terms_hash: [7; 32],
+                entries: vec![LocalizedTerms {
+                    language: "pt-BR".to_string(),
+                    metadata_uri: "ipfs://terms-pt-br".to_string(),
+                }],
+                bump: 244,
+                _reserved: Reserved::default(),
+            }),
+        ),
+        (
+            "SkillListing",
+            account(&SkillListing {
+                provider: key(1),
+                listing_id: 4,
+                token_mint: key(3),
+                provider_token_account: key(4),
+                terms: terms(),
+                terms_hash: [7; 32],
+                rentals_opened: 12,
+                created_at: 1_700_000_000,
+                bump: 243,
+                _reserved: Reserved::default(),
+            }),
+        ),
+    ]
+}
+
+fn instructions() -> Vec<(&'static str, Vec<u8>)> {
+    let overrides = FundOverrides {
+        dispute_window_seconds: Some(7_200),
+        review_window_seconds: None,
+        arbiter: Some(key(14)),
+        auto_release: true,
+    };
+    vec![
+        (
+            "initialize_escrow",
+            ix::InitializeEscrow {
+                terms: terms(),
+                escrow_id: 7,
+            }
+            .data(),
+        ),
+        ("update_terms", ix::UpdateTerms { terms: terms() }.data()),
+        ("update_price", ix::UpdatePrice { price_usdc: 30_000_000 }.data()),
+        (
+            "accept_escrow",
+            ix::AcceptEscrow {
+                amount: 25_000_000,
+                overrides: overrides.clone(),
+                terms_hash: [7; 32],
+            }
+            .data(),
+        ),
+        (
+            "fund_with_sol",
+            ix::FundWithSol {
+                amount: 25_000_000,
+                overrides: overrides.clone(),
+                terms_hash: [7; 32],
+            }
+            .data(),
+        ),
+        (
+            "request_rental",
+            ix::RequestRental {
+                amount: 25_000_000,
+                overrides: overrides.clone(),
+                terms_hash: [7; 32],
+            }
+            .data(),
+        ),
+        ("accept_renter", ix::AcceptRenter {}.data()),
+        ("withdraw_rental_request", ix::WithdrawRentalRequest {}.data()),
+        (
+            "submit_deliverable",
+            ix::SubmitDeliverable {
+                deliverable_hash: [6; 32],
+                deliverable_uri: "ipfs://deliverable".into(),
+                encrypted_key_hash: [0; 32],
+            }
+            .data(),
+        ),
+        ("request_revision", ix::RequestRevision { notes_hash: [18; 32] }.data()),
+        ("complete_task", ix::CompleteTask {}.data()),
+        ("release_with_key", ix::ReleaseWithKey { key_ciphertext: vec![1, 2, 3] }.data()),
+        ("cancel_escrow", ix::CancelEscrow {}.data()),
+        ("propose_cancel", ix::ProposeCancel {}.data()),
+        ("accept_cancel", ix::AcceptCancel {}.data()),
+        ("claim_vested", ix::ClaimVested {}.data()),
+        (
+            "report_sla_breach",
+            ix::ReportSlaBreach {
+                metric: SlaMetric::LatencyMs,
+                observed: 750,
+                evidence_hash: [15; 32],
+            }
+            .data(),
+        ),
+        ("commit_access_credential", ix::CommitAccessCredential { commitment: [16; 32] }.data()),
+        ("pay_deferred", ix::PayDeferred { amount: 12_500_000 }.data()),
+        ("expedite", ix::Expedite { extra_fee: 2_000_000 }.data()),
+        ("open_channel", ix::OpenChannel { amount: 100_000_000 }.data()),
+        ("fund_channel", ix::FundChannel { amount: 50_000_000 }.data()),
+        (
+            "settle_channel",
+            ix::SettleChannel {
+                cumulative: 42_000_000,
+                nonce: 17,
+            }
+            .data(),
+        ),
+        ("request_channel_close", ix::RequestChannelClose {}.data()),
+        ("close_channel", ix::CloseChannel {}.data()),
+        ("set_watchtower", ix::SetWatchtower { watchtower: key(8) }.data()),
+        ("set_arbiter", ix::SetArbiter { arbiter: key(10) }.data()),
+        ("extend_duration", ix::ExtendDuration { extra_seconds: 86_400 }.data()),
+        ("set_webhook_signer", ix::SetWebhookSigner { signer: key(15) }.data()),
+        ("offer_auto_release", ix::OfferAutoRelease { offered: true }.data()),
+        ("auto_release", ix::AutoRelease {}.data()),
+        ("refund_after_timeout", ix::RefundAfterTimeout {}.data()),
+        (
+            "accept_escrow_credit",
+            ix::AcceptEscrowCredit {
+                amount: 10_000,
+                overrides,
+                terms_hash: [7; 32],
+            }
+            .data(),
+        ),
+        ("complete_task_credit", ix::CompleteTaskCredit {}.data()),
+        ("cancel_escrow_credit", ix::CancelEscrowCredit {}.data()),
+        ("withdraw_credits", ix::WithdrawCredits {}.data()),
+        ("dispute", ix::Dispute {}.data()),
+        ("commit_dispute_randomness", ix::CommitDisputeRandomness { commitment: [1; 32] }.data()),
+        ("reveal_dispute_randomness", ix::RevealDisputeRandomness { secret: [2; 32] }.data()),
+        ("finalize_dispute_randomness", ix::FinalizeDisputeRandomness {}.data()),
+        (
+            "submit_evidence",
+            ix::SubmitEvidence {
+                evidence_uri: "ipfs://evidence".to_string(),
+                evidence_hash: [23; 32],
+            }
+            .data(),
+        ),
+        ("resolve_dispute_release", ix::ResolveDisputeRelease { ruling_hash: [17; 32] }.data()),
+        ("resolve_dispute_refund", ix::ResolveDisputeRefund { ruling_hash: [17; 32] }.data()),
+        (
+            "resolve_dispute_split",
+            ix::ResolveDisputeSplit {
+                provider_bps: 6_000,
+                ruling_hash: [17; 32],
+            }
+            .data(),
+        ),
+        (
+            "add_milestone",
+            ix::AddMilestone {
+                description_hash: [21; 32],
+                amount: 10_000_000,
+            }
+            .data(),
+        ),
+        ("approve_milestone", ix::ApproveMilestone { index: 1 }.data()),
+        ("release_milestone", ix::ReleaseMilestone { index: 1 }.data()),
+        ("claim_streamed", ix::ClaimStreamed {}.data()),
+        ("offer_subscription", ix::OfferSubscription { offered: true }.data()),
+        (
+            "fund_subscription",
+            ix::FundSubscription {
+                periods: 4,
+                amount_per_period: 10_000_000,
+                terms_hash: [7; 32],
+            }
+            .data(),
+        ),
+        ("claim_period", ix::ClaimPeriod {}.data()),
+        ("cancel_subscription", ix::CancelSubscription {}.data()),
+        (
+            "set_terms_localization",
+            ix::SetTermsLocalization {
+                terms_hash: [7; 32],
+                language: "pt-BR".to_string(),
+                metadata_uri: Some("ipfs://terms-pt-br".to_string()),
+            }
+            .data(),
+        ),
+        ("wind_down", ix::WindDown {}.data()),
+        ("deprecate_listing", ix::DeprecateListing { sunset_at: 1_700_050_000 }.data()),
+        ("cancel_unfunded", ix::CancelUnfunded {}.data()),
+        ("create_listing", ix::CreateListing { listing_id: 4, terms: terms() }.data()),
+        (
+            "open_rental",
+            ix::OpenRental {
+                amount: 25_000_000,
+                overrides: FundOverrides::default(),
+                terms_hash: [7; 32],
+            }
+            .data(),
+        ),
+        ("close_listing", ix::CloseListing {}.data()),
+        ("create_demo_mint", ix::CreateDemoMint {}.data()),
+        ("airdrop_demo_tokens", ix::AirdropDemoTokens { amount: 50_000_000 }.data()),
+        ("expire_listing", ix::ExpireListing {}.data()),
+        ("fund_bond", ix::FundBond {}.data()),
+        ("release_bond", ix::ReleaseBond {}.data()),
+        (
+            "initialize_config",
+            ix::InitializeConfig {
+                fee_bps: 250,
+                fee_treasury: key(14),
+            }
+            .data(),
+        ),
+        ("update_fee", ix::UpdateFee { fee_bps: 300 }.data()),
+        ("update_treasury", ix::UpdateTreasury { fee_treasury: key(15) }.data()),
+        ("sync_config_features", ix::SyncConfigFeatures {}.data()),
+        (
+            "set_points_schedule",
+            ix::SetPointsSchedule {
+                schedule: vec![EmissionTier {
+                    starts_at: 1_700_000_000,
+                    points_bps: 100,
+                }],
+                redemption_enabled: true,
+            }
+            .data(),
+        ),
+        (
+            "set_screening",
+            ix::SetScreening {
+                screener: key(12),
+                threshold: 10_000_000_000,
+                delay_seconds: 86_400,
+            }
+            .data(),
+        ),
+        ("set_provider_verified", ix::SetProviderVerified { verified: true }.data()),
+        ("set_listing_throttle", ix::SetListingThrottle { max_per_epoch: 5 }.data()),
+        ("set_trial_limit", ix::SetTrialLimit { max_amount: 5_000_000 }.data()),
+        ("set_tax_bps", ix::SetTaxBps { tax_bps: 825 }.data()),
+        (
+            "set_risk_weights",
+            ix::SetRiskWeights {
+                weights: RiskWeights {
+                    disputes: 4,
+                    cancellations: 3,
+                    account_age: 2,
+                    bond_coverage: 1,
+                    mature_age_seconds: 15_552_000,
+                },
+            }
+            .data(),
+        ),
+        ("allow_mint", ix::AllowMint { mint: key(3) }.data()),
+        ("disallow_mint", ix::DisallowMint { mint: key(3) }.data()),
+        ("initialize_epoch_stats", ix::InitializeEpochStats {}.data()),
+        ("rollover_epoch", ix::RolloverEpoch {}.data()),
+        (
+            "queue_recovery",
+            ix::QueueRecovery {
+                kind: RecoveryKind::SettleWithProof {
+                    to_provider: 15_000_000,
+                    to_renter: 10_000_000,
+                },
+                proof_hash: [25; 32],
+            }
+            .data(),
+        ),
+        ("cancel_recovery", ix::CancelRecovery {}.data()),
+        ("force_expire", ix::ForceExpire {}.data()),
+        ("force_settle_with_proof", ix::ForceSettleWithProof {}.data()),
+        ("set_failure_streak_threshold", ix::SetFailureStreakThreshold { threshold: 3 }.data()),
+        (
+            "set_arbitration_window",
+            ix::SetArbitrationWindow {
+                arbitration_window_seconds: 259_200,
+                default_release: false,
+            }
+            .data(),
+        ),
+        ("claim_default_resolution", ix::ClaimDefaultResolution {}.data()),
+        ("declare_no_conflict", ix::DeclareNoConflict {}.data()),
+        ("report_conflict", ix::ReportConflict {}.data()),
+        (
+            "create_council",
+            ix::CreateCouncil {
+                council_id: 7,
+                members: vec![key(9), key(10), key(11)],
+                threshold: 2,
+            }
+            .data(),
+        ),
+        (
+            "propose_resolution",
+            ix::ProposeResolution {
+                outcome: ResolutionOutcome::Split(6_000),
+                ruling_hash: [24; 32],
+            }
+            .data(),
+        ),
+        (
+            "approve_resolution",
+            ix::ApproveResolution {
+                outcome: ResolutionOutcome::Split(6_000),
+                ruling_hash: [24; 32],
+            }
+            .data(),
+        ),
+        ("execute_resolution", ix::ExecuteResolution {}.data()),
+        ("issue_invoice", ix::IssueInvoice {}.data()),
+        ("approve_release", ix::ApproveRelease {}.data()),
+        ("open_points_account", ix::OpenPointsAccount {}.data()),
+        ("redeem_points", ix::RedeemPoints { amount: 10 }.data()),
+        ("check_timeout", ix::CheckTimeout {}.data()),
+        ("get_arbiter_stats", ix::GetArbiterStats {}.data()),
+        (
+            "preview_engagement",
+            ix::PreviewEngagement {
+                overrides: FundOverrides {
+                    review_window_seconds: Some(1_800),
+                    ..Default::default()
+                },
+            }
+            .data(),
+        ),
+        ("compute_risk", ix::ComputeRisk {}.data()),
+        ("snapshot_escrow", ix::SnapshotEscrow {}.data()),
+        ("migrate_escrow", ix::MigrateEscrow {}.data()),
+        (
+            "record_acceptance",
+            ix::RecordAcceptance {
+                expires_at: 1_700_086_400,
+                signature: [5; 64],
+            }
+            .data(),
+        ),
+        (
+            "validate_initialize",
+            ix::ValidateInitialize {
+                terms: terms(),
+                escrow_id: 7,
+            }
+            .data(),
+        ),
+        (
+            "validate_fund",
+            ix::ValidateFund {
+                amount: 25_000_000,
+                overrides: FundOverrides::default(),
+                terms_hash: [7; 32],
+            }
+            .data(),
+        ),
+        (
+            "validate_release",
+            ix::ValidateRelease {
+                key_ciphertext: Some(vec![1, 2, 3]),
+            }
+            .data(),
+        ),
+    ]
+}
+
+fn render(section: &str, vectors: &[(&str, Vec<u8>)], out: &mut String) {
+    writeln!(out, "[{section}]").unwrap();
+    for (name, bytes) in vectors {
+        let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        writeln!(out, "{name} = {hex}").unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+#[test]
+fn layouts_match_golden_vectors() {
+    let mut rendered = String::new();
+    render("accounts", &accounts(), &mut rendered);
+    render("instructions", &instructions(), &mut rendered);
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/layouts.txt");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &rendered).unwrap();
+        return;
+    }
+
+    let golden = std::fs::read_to_string(&path).expect("missing golden file; run with UPDATE_GOLDEN=1");
+    for (expected, actual) in golden.lines().zip(rendered.lines()) {
+        assert_eq!(actual, expected, "layout changed; see tests/golden_layouts.rs for how to regenerate");
+    }
+    assert_eq!(golden.lines().count(), rendered.lines().count(), "vector set changed");
+}
+
+#[test]
+fn fixed_size_accounts_fit_their_len() {
+    for (name, bytes) in accounts() {
+        let len = match name {
+            "EscrowAccount" => EscrowAccount::LEN,
+            "ProviderIndex" => ProviderIndex::LEN,
+            "ProviderStats" => ProviderStats::LEN,
+            "CreditBalance" => CreditBalance::LEN,
+            "Config" => Config::LEN,
+            "PointsAccount" => PointsAccount::LEN,
+            "DisputeRandomness" => DisputeRandomness::LEN,
+            "DisputeEvidence" => DisputeEvidence::LEN,
+            "SettlementChannel" => SettlementChannel::LEN,
+            "RentalRequest" => RentalRequest::LEN,
+            "Invoice" => Invoice::LEN,
+            "ArbiterStats" => ArbiterStats::LEN,
+            "Engagement" => Engagement::LEN,
+            "ArbiterDeclaration" => ArbiterDeclaration::LEN,
+            "ArbiterCouncil" => ArbiterCouncil::LEN,
+            "ResolutionProposal" => ResolutionProposal::LEN,
+            "RecoveryAction" => RecoveryAction::LEN,
+            "EpochStats" => EpochStats::LEN,
+            "EpochSnapshot" => EpochSnapshot::LEN,
+            "MilestonePlan" => MilestonePlan::LEN,
+            "SubscriptionPlan" => SubscriptionPlan::LEN,
+            "TermsAcceptance" => TermsAcceptance::LEN,
+            "TermsLocalization" => TermsLocalization::LEN,
+            "SkillListing" => SkillListing::LEN,
+            other => panic!("no LEN registered for {other}"),
+        };
+        assert!(bytes.len() <= len, "{name} serializes to {} bytes, LEN is {len}", bytes.len());
+    }
+}
@@ -0,0 +1,23 @@
+use escrow::{EscrowAccount, Invoice};
+
+#[test]
+fn tax_is_stated_on_the_provider_payout() {
+    assert_eq!(Invoice::tax_on(20_000_000, 825), 1_650_000);
+    assert_eq!(Invoice::tax_on(20_000_000, 0), 0);
+}
+
+#[test]
+fn settlement_records_each_partys_share() {
+    let mut escrow = EscrowAccount::default();
+    escrow.allocate_settlement(9_500, 500);
+    assert_eq!((escrow.provider_payout, escrow.renter_refund), (9_500, 500));
+}
+
+#[test]
+fn post_release_ruling_moves_the_refund_out_of_the_payout() {
+    let mut escrow = EscrowAccount::default();
+    escrow.allocate_settlement(10_000, 0);
+    escrow.vesting_halted_at = 1_700_002_000;
+    escrow.allocate_settlement(1_000, 3_000);
+    assert_eq!((escrow.provider_payout, escrow.renter_refund), (7_000, 3_000));
+}
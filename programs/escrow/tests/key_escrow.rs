@@ -0,0 +1,43 @@
+use anchor_lang::solana_program::hash::hash;
+use escrow::{EscrowAccount, EscrowError};
+
+fn committed_to(key_ciphertext: &[u8]) -> EscrowAccount {
+    EscrowAccount {
+        encrypted_key_hash: hash(key_ciphertext).to_bytes(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn release_reveals_the_committed_key() {
+    let escrow = committed_to(b"key sealed to the renter");
+    escrow.check_key_ciphertext(Some(b"key sealed to the renter")).unwrap();
+    assert_eq!(
+        escrow.check_key_ciphertext(Some(b"some other key")).unwrap_err(),
+        EscrowError::DecryptionKeyMismatch.into()
+    );
+    // The plain release paths cannot skip the reveal
+    assert_eq!(
+        escrow.check_key_ciphertext(None).unwrap_err(),
+        EscrowError::DecryptionKeyRequired.into()
+    );
+}
+
+#[test]
+fn deliverables_without_a_key_release_plainly() {
+    let escrow = EscrowAccount::default();
+    escrow.check_key_ciphertext(None).unwrap();
+    assert_eq!(
+        escrow.check_key_ciphertext(Some(b"unexpected")).unwrap_err(),
+        EscrowError::InvalidState.into()
+    );
+}
+
+#[test]
+fn oversized_ciphertext_is_refused_before_hashing() {
+    let oversized = vec![7u8; 4_096];
+    assert_eq!(
+        committed_to(&oversized).check_key_ciphertext(Some(&oversized)).unwrap_err(),
+        EscrowError::KeyCiphertextTooLong.into()
+    );
+}
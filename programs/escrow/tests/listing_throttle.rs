@@ -0,0 +1,38 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::ProviderStats;
+
+fn stats(verified: bool) -> ProviderStats {
+    ProviderStats {
+        provider: Pubkey::new_unique(),
+        lifetime_volume: 0,
+        lifetime_releases: 0,
+        lifetime_refunds: 0,
+        bump: 255,
+        last_init_epoch: 0,
+        epoch_initializations: 0,
+        verified,
+        failure_streak: 0,
+        lifetime_disputes: 0,
+    }
+}
+
+#[test]
+fn throttles_within_an_epoch_and_resets_on_the_next() {
+    let mut stats = stats(false);
+    assert!(stats.record_initialization(10, 2).is_ok());
+    assert!(stats.record_initialization(10, 2).is_ok());
+    assert!(stats.record_initialization(10, 2).is_err());
+
+    assert!(stats.record_initialization(11, 2).is_ok());
+    assert_eq!(stats.epoch_initializations, 1);
+}
+
+#[test]
+fn verified_providers_and_zero_limit_are_unthrottled() {
+    let mut verified = stats(true);
+    let mut unlimited = stats(false);
+    for _ in 0..10 {
+        assert!(verified.record_initialization(10, 1).is_ok());
+        assert!(unlimited.record_initialization(10, 0).is_ok());
+    }
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::{AnchorSerialize, Pubkey};
+use escrow::{EscrowError, MilestonePlan, MilestoneState, Reserved, MAX_MILESTONES};
+
+fn plan() -> MilestonePlan {
+    MilestonePlan {
+        escrow: Pubkey::new_unique(),
+        milestones: Vec::new(),
+        released: 0,
+        bump: 0,
+        _reserved: Reserved::default(),
+    }
+}
+
+#[test]
+fn milestones_move_from_pending_through_approval_to_release() {
+    let mut plan = plan();
+    plan.add([1; 32], 400, 1_000).unwrap();
+    plan.add([2; 32], 600, 1_000).unwrap();
+    assert_eq!(plan.scheduled(), 1_000);
+
+    assert_eq!(plan.release(0).unwrap_err(), EscrowError::MilestoneNotApproved.into());
+    assert_eq!(plan.approve(0).unwrap(), 400);
+    assert_eq!(plan.approve(0).unwrap_err(), EscrowError::InvalidMilestone.into());
+    assert_eq!(plan.release(0).unwrap(), 400);
+    assert_eq!(plan.milestones[0].state, MilestoneState::Released);
+    assert_eq!(plan.milestones[1].state, MilestoneState::Pending);
+    assert_eq!(plan.released, 400);
+
+    // Released milestones cannot be paid twice
+    assert_eq!(plan.release(0).unwrap_err(), EscrowError::MilestoneNotApproved.into());
+    assert_eq!(plan.approve(2).unwrap_err(), EscrowError::InvalidMilestone.into());
+}
+
+#[test]
+fn schedule_stays_within_the_cap() {
+    let mut plan = plan();
+    assert_eq!(plan.add([1; 32], 0, 1_000).unwrap_err(), EscrowError::InvalidMilestone.into());
+    plan.add([1; 32], 700, 1_000).unwrap();
+    assert_eq!(plan.add([2; 32], 301, 1_000).unwrap_err(), EscrowError::MilestonesExceedEscrow.into());
+    assert_eq!(plan.add([2; 32], u64::MAX, u64::MAX).unwrap_err(), EscrowError::MilestonesExceedEscrow.into());
+    plan.add([2; 32], 300, 1_000).unwrap();
+}
+
+#[test]
+fn full_plan_fits_its_len() {
+    let mut plan = plan();
+    for i in 0..MAX_MILESTONES {
+        plan.add([i as u8; 32], 1, u64::MAX).unwrap();
+    }
+    assert_eq!(plan.add([0; 32], 1, u64::MAX).unwrap_err(), EscrowError::TooManyMilestones.into());
+    assert_eq!(8 + plan.try_to_vec().unwrap().len(), MilestonePlan::LEN);
+}
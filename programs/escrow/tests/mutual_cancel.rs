@@ -0,0 +1,92 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{CancelProposer, EscrowAccount, EscrowError, EscrowState, CANCEL_PROPOSAL_SECONDS};
+
+fn funded_rental() -> EscrowAccount {
+    EscrowAccount {
+        state: EscrowState::Funded,
+        provider: Pubkey::new_unique(),
+        renter: Pubkey::new_unique(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn only_the_counterparty_accepts() {
+    let mut rental = funded_rental();
+    let (provider, renter) = (rental.provider, rental.renter);
+    rental.propose_cancel(&renter, 100).unwrap();
+    assert_eq!(rental.cancel_proposed_by, CancelProposer::Renter);
+
+    assert_eq!(rental.check_cancel_acceptance(&renter, 100).unwrap_err(), EscrowError::Unauthorized.into());
+    assert_eq!(
+        rental.check_cancel_acceptance(&Pubkey::new_unique(), 100).unwrap_err(),
+        EscrowError::Unauthorized.into()
+    );
+    rental.check_cancel_acceptance(&provider, 100).unwrap();
+
+    assert_eq!(
+        rental.propose_cancel(&Pubkey::new_unique(), 100).unwrap_err(),
+        EscrowError::Unauthorized.into()
+    );
+}
+
+#[test]
+fn proposals_expire() {
+    let mut rental = funded_rental();
+    let (provider, renter) = (rental.provider, rental.renter);
+    rental.propose_cancel(&provider, 100).unwrap();
+    let expires_at = 100 + CANCEL_PROPOSAL_SECONDS;
+    assert_eq!(rental.cancel_expires_at, expires_at);
+    rental.check_cancel_acceptance(&renter, expires_at).unwrap();
+    assert_eq!(
+        rental.check_cancel_acceptance(&renter, expires_at + 1).unwrap_err(),
+        EscrowError::CancelProposalExpired.into()
+    );
+}
+
+#[test]
+fn reproposing_replaces_the_pending_proposal() {
+    let mut rental = funded_rental();
+    let (provider, renter) = (rental.provider, rental.renter);
+    rental.propose_cancel(&provider, 100).unwrap();
+    rental.propose_cancel(&provider, 5_000).unwrap();
+    assert_eq!(rental.cancel_expires_at, 5_000 + CANCEL_PROPOSAL_SECONDS);
+
+    // The renter proposing back makes the provider the one who must accept
+    rental.propose_cancel(&renter, 6_000).unwrap();
+    assert_eq!(rental.cancel_proposed_by, CancelProposer::Renter);
+    assert_eq!(rental.check_cancel_acceptance(&renter, 6_000).unwrap_err(), EscrowError::Unauthorized.into());
+    rental.check_cancel_acceptance(&provider, 6_000).unwrap();
+}
+
+#[test]
+fn a_new_rental_starts_without_a_proposal() {
+    let mut rental = funded_rental();
+    let provider = rental.provider;
+    rental.propose_cancel(&provider, 100).unwrap();
+    let next_renter = Pubkey::new_unique();
+    rental.begin_rental(next_renter, 1_000, 200);
+    assert_eq!(rental.cancel_proposed_by, CancelProposer::None);
+    assert_eq!((rental.cancel_proposed_at, rental.cancel_expires_at), (0, 0));
+    assert_eq!(
+        rental.check_cancel_acceptance(&next_renter, 200).unwrap_err(),
+        EscrowError::NoCancelProposal.into()
+    );
+}
+
+#[test]
+fn only_funded_token_rentals_take_proposals() {
+    let mut listing = EscrowAccount {
+        state: EscrowState::Created,
+        ..funded_rental()
+    };
+    let provider = listing.provider;
+    assert_eq!(listing.propose_cancel(&provider, 100).unwrap_err(), EscrowError::InvalidState.into());
+
+    let mut credit = funded_rental();
+    credit.terms.settle_in_credits = true;
+    assert_eq!(
+        credit.propose_cancel(&provider, 100).unwrap_err(),
+        EscrowError::InvalidSettlementMode.into()
+    );
+}
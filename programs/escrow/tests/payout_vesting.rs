@@ -0,0 +1,38 @@
+use escrow::{EscrowAccount, EscrowTerms};
+
+fn vesting() -> EscrowAccount {
+    EscrowAccount {
+        terms: EscrowTerms {
+            vesting_seconds: 1_000,
+            ..Default::default()
+        },
+        amount: 10_000,
+        vesting_total: 9_000,
+        vesting_started_at: 5_000,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn payout_vests_linearly_until_the_end() {
+    let escrow = vesting();
+    assert_eq!(escrow.vested_at(4_000), 0);
+    assert_eq!(escrow.vested_at(5_250), 2_250);
+    assert_eq!(escrow.vested_at(6_000), 9_000);
+    assert_eq!(escrow.vested_at(9_000), 9_000);
+}
+
+#[test]
+fn post_release_dispute_freezes_vesting() {
+    let escrow = EscrowAccount {
+        vesting_halted_at: 5_400,
+        ..vesting()
+    };
+    assert_eq!(escrow.vested_at(5_900), 3_600);
+    assert_eq!(escrow.disputed_amount(), 5_400);
+}
+
+#[test]
+fn pre_release_disputes_cover_the_escrowed_amount() {
+    assert_eq!(vesting().disputed_amount(), 10_000);
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{Config, EscrowAccount, EscrowState, ResolutionOutcome, MAX_FEE_BPS};
+
+fn config(fee_bps: u16) -> Config {
+    Config {
+        admin: Pubkey::new_unique(),
+        points_schedule: vec![],
+        redemption_enabled: false,
+        bump: 0,
+        screener: Pubkey::default(),
+        screening_threshold: 0,
+        screening_delay_seconds: 0,
+        max_initializations_per_epoch: 0,
+        features: 0,
+        trial_max_amount: 0,
+        tax_bps: 0,
+        fee_bps,
+        fee_treasury: Pubkey::new_unique(),
+        failure_streak_threshold: 0,
+        arbitration_window_seconds: 0,
+        default_release: false,
+        allowed_mints: vec![],
+        risk_weights: Default::default(),
+    }
+}
+
+#[test]
+fn fee_is_a_rounded_down_share_of_the_payout() {
+    assert_eq!(config(250).fee_on(10_000_000), 250_000);
+    assert_eq!(config(250).fee_on(39), 0);
+    assert_eq!(config(0).fee_on(10_000_000), 0);
+    assert_eq!(config(MAX_FEE_BPS).fee_on(u64::MAX), u64::MAX / 10);
+}
+
+#[test]
+fn fee_is_capped_and_needs_a_treasury() {
+    let treasury = Pubkey::new_unique();
+    assert!(Config::valid_fee(MAX_FEE_BPS, &treasury));
+    assert!(!Config::valid_fee(MAX_FEE_BPS + 1, &treasury));
+    assert!(!Config::valid_fee(1, &Pubkey::default()));
+    // Fees can be switched off before the treasury is known
+    assert!(Config::valid_fee(0, &Pubkey::default()));
+}
+
+#[test]
+fn rulings_charge_the_fee_on_the_providers_share() {
+    let config = config(250);
+    let disputed = EscrowAccount {
+        state: EscrowState::Disputed,
+        amount: 10_000_000,
+        ..Default::default()
+    };
+    let split = ResolutionOutcome::Split(9_999);
+    // The treasury takes its cut of a near-total split just as of a release
+    assert_eq!(
+        disputed.ruling_shares(Some(&config), split).unwrap(),
+        (249_975, 9_749_025, 1_000)
+    );
+    assert_eq!(
+        disputed.ruling_shares(Some(&config), ResolutionOutcome::ReleasedToProvider).unwrap(),
+        (250_000, 9_750_000, 0)
+    );
+    assert_eq!(
+        disputed.ruling_shares(Some(&config), ResolutionOutcome::RefundedToRenter).unwrap(),
+        (0, 0, 10_000_000)
+    );
+
+    // A post-release dispute splits a payout the fee was already taken from
+    let post_release = EscrowAccount {
+        vesting_total: 9_750_000,
+        vesting_started_at: 100,
+        vesting_halted_at: 100,
+        ..disputed
+    };
+    let (fee, to_provider, to_renter) = post_release.ruling_shares(Some(&config), split).unwrap();
+    assert_eq!(fee, 0);
+    assert_eq!(to_provider + to_renter, post_release.disputed_amount());
+}
+
+#[test]
+fn empty_mint_allowlist_admits_any_mint() {
+    let usdc = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    assert!(config(0).allows_mint(&other));
+
+    let config = Config {
+        allowed_mints: vec![usdc],
+        risk_weights: Default::default(),
+        ..config(0)
+    };
+    assert!(config.allows_mint(&usdc));
+    assert!(!config.allows_mint(&other));
+}
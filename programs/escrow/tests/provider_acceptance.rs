@@ -0,0 +1,40 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{FundOverrides, RentalRequest, PROVIDER_ACCEPTANCE_WINDOW_SECONDS};
+
+fn request(renter: Pubkey) -> RentalRequest {
+    RentalRequest {
+        escrow: Pubkey::new_unique(),
+        renter,
+        amount: 25_000_000,
+        overrides: FundOverrides::default(),
+        terms_hash: [7; 32],
+        reputation_score: None,
+        requested_at: 1_700_000_000,
+        bump: 255,
+    }
+}
+
+#[test]
+fn acceptance_window_runs_from_the_request() {
+    let request = request(Pubkey::new_unique());
+    assert_eq!(request.expires_at(), 1_700_000_000 + PROVIDER_ACCEPTANCE_WINDOW_SECONDS);
+}
+
+#[test]
+fn renter_and_provider_may_withdraw_before_expiry() {
+    let renter = Pubkey::new_unique();
+    let provider = Pubkey::new_unique();
+    let request = request(renter);
+    let before = request.expires_at() - 1;
+
+    assert!(request.can_withdraw(&renter, &provider, before));
+    assert!(request.can_withdraw(&provider, &provider, before));
+    assert!(!request.can_withdraw(&Pubkey::new_unique(), &provider, before));
+}
+
+#[test]
+fn anyone_may_return_an_expired_deposit() {
+    let provider = Pubkey::new_unique();
+    let request = request(Pubkey::new_unique());
+    assert!(request.can_withdraw(&Pubkey::new_unique(), &provider, request.expires_at()));
+}
@@ -0,0 +1,39 @@
+use escrow::{EscrowAccount, EscrowState, ResolutionOutcome};
+
+const BOND: u64 = 5_000_000;
+
+fn settled(state: EscrowState, resolution: Option<ResolutionOutcome>) -> EscrowAccount {
+    EscrowAccount {
+        state,
+        resolution,
+        bond_held: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn held_until_the_escrow_settles() {
+    for state in [EscrowState::Created, EscrowState::Funded, EscrowState::Disputed, EscrowState::Vesting] {
+        assert_eq!(settled(state, None).bond_payouts(BOND), None);
+    }
+}
+
+#[test]
+fn returns_to_the_provider_without_a_ruling_against_them() {
+    assert_eq!(settled(EscrowState::Completed, None).bond_payouts(BOND), Some((BOND, 0)));
+    assert_eq!(settled(EscrowState::Cancelled, None).bond_payouts(BOND), Some((BOND, 0)));
+    assert_eq!(settled(EscrowState::Expired, None).bond_payouts(BOND), Some((BOND, 0)));
+    let released = settled(EscrowState::Completed, Some(ResolutionOutcome::ReleasedToProvider));
+    assert_eq!(released.bond_payouts(BOND), Some((BOND, 0)));
+}
+
+#[test]
+fn forfeits_to_the_renter_with_the_disputed_amount() {
+    let refunded = settled(EscrowState::Cancelled, Some(ResolutionOutcome::RefundedToRenter));
+    assert_eq!(refunded.bond_payouts(BOND), Some((0, BOND)));
+
+    // The provider keeps the share of the bond the arbiter awarded them, rounded down
+    let split = settled(EscrowState::Completed, Some(ResolutionOutcome::Split(3_333)));
+    assert_eq!(split.bond_payouts(BOND), Some((1_666_500, 3_333_500)));
+    assert_eq!(split.bond_payouts(1), Some((0, 1)));
+}
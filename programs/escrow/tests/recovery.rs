@@ -0,0 +1,47 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{RecoveryAction, RecoveryKind, RECOVERY_TIMELOCK_SECONDS};
+
+const QUEUED_AT: i64 = 1_700_000_000;
+
+fn queued(kind: RecoveryKind) -> RecoveryAction {
+    RecoveryAction {
+        escrow: Pubkey::new_unique(),
+        kind,
+        proof_hash: [7; 32],
+        queued_by: Pubkey::new_unique(),
+        queued_at: QUEUED_AT,
+        executable_at: QUEUED_AT + RECOVERY_TIMELOCK_SECONDS,
+        bump: 255,
+    }
+}
+
+#[test]
+fn recovery_waits_out_the_timelock() {
+    let recovery = queued(RecoveryKind::Expire);
+    assert!(!recovery.is_due(QUEUED_AT));
+    assert!(!recovery.is_due(QUEUED_AT + RECOVERY_TIMELOCK_SECONDS - 1));
+    assert!(recovery.is_due(QUEUED_AT + RECOVERY_TIMELOCK_SECONDS));
+}
+
+#[test]
+fn expiry_refunds_what_is_held_up_to_the_vault_balance() {
+    assert_eq!(RecoveryKind::Expire.payouts(25_000_000, 30_000_000), Some((0, 25_000_000)));
+    // A corrupt `amount` can claim more than the vault holds
+    assert_eq!(RecoveryKind::Expire.payouts(25_000_000, 4_000_000), Some((0, 4_000_000)));
+}
+
+#[test]
+fn proven_settlement_pays_exactly_what_was_queued() {
+    let kind = RecoveryKind::SettleWithProof {
+        to_provider: 15_000_000,
+        to_renter: 10_000_000,
+    };
+    assert_eq!(kind.payouts(0, 25_000_000), Some((15_000_000, 10_000_000)));
+    assert_eq!(kind.payouts(25_000_000, 24_999_999), None);
+
+    let overflowing = RecoveryKind::SettleWithProof {
+        to_provider: u64::MAX,
+        to_renter: 1,
+    };
+    assert_eq!(overflowing.payouts(0, u64::MAX), None);
+}
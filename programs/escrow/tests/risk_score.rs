@@ -0,0 +1,86 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowTerms, ProviderStats, RiskAssessment, RiskWeights};
+
+const MATURE: i64 = 180 * 86_400;
+
+fn weights() -> RiskWeights {
+    RiskWeights {
+        disputes: 4,
+        cancellations: 3,
+        account_age: 2,
+        bond_coverage: 1,
+        mature_age_seconds: MATURE,
+    }
+}
+
+fn stats(releases: u64, refunds: u64, disputes: u64) -> ProviderStats {
+    ProviderStats {
+        provider: Pubkey::new_unique(),
+        lifetime_volume: 0,
+        lifetime_releases: releases,
+        lifetime_refunds: refunds,
+        bump: 255,
+        last_init_epoch: 0,
+        epoch_initializations: 0,
+        verified: false,
+        failure_streak: 0,
+        lifetime_disputes: disputes,
+    }
+}
+
+fn listing(price: u64, bond: u64) -> EscrowAccount {
+    EscrowAccount {
+        provider: Pubkey::new_unique(),
+        terms: EscrowTerms {
+            price_usdc: price,
+            provider_bond: bond,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn assess(listing: &EscrowAccount, stats: &ProviderStats, age: i64) -> RiskAssessment {
+    RiskAssessment::new(Pubkey::new_unique(), listing, stats, age, &weights()).unwrap()
+}
+
+#[test]
+fn a_mature_bonded_provider_with_a_clean_record_scores_zero() {
+    let risk = assess(&listing(10_000_000, 10_000_000), &stats(20, 0, 0), MATURE * 2);
+    assert_eq!(risk.score, 0);
+    assert_eq!((risk.age_risk_bps, risk.bond_risk_bps), (0, 0));
+}
+
+#[test]
+fn a_brand_new_unbonded_provider_carries_only_age_and_bond_risk() {
+    let risk = assess(&listing(10_000_000, 0), &stats(0, 0, 0), 0);
+    assert_eq!((risk.dispute_risk_bps, risk.cancellation_risk_bps), (0, 0));
+    assert_eq!((risk.age_risk_bps, risk.bond_risk_bps), (10_000, 10_000));
+    // (2 + 1) of 10 weight at full risk
+    assert_eq!(risk.score, 30);
+}
+
+#[test]
+fn weighs_dispute_and_refund_rates() {
+    // 1 in 4 settled rentals disputed and refunded, half-aged, bond covering half the price
+    let risk = assess(&listing(10_000_000, 5_000_000), &stats(3, 1, 1), MATURE / 2);
+    assert_eq!((risk.dispute_risk_bps, risk.cancellation_risk_bps), (2_500, 2_500));
+    assert_eq!((risk.age_risk_bps, risk.bond_risk_bps), (5_000, 5_000));
+    // (4 * 2_500 + 3 * 2_500 + 2 * 5_000 + 1 * 5_000) / 10 = 3_250 bps
+    assert_eq!(risk.score, 32);
+
+    // Disputes still open count before their rental settles, capped at full risk
+    assert_eq!(assess(&listing(1, 0), &stats(0, 0, 2), 0).dispute_risk_bps, 10_000);
+}
+
+#[test]
+fn fails_until_the_admin_sets_weights() {
+    let result = RiskAssessment::new(
+        Pubkey::new_unique(),
+        &listing(10_000_000, 0),
+        &stats(1, 0, 0),
+        0,
+        &RiskWeights::default(),
+    );
+    assert!(result.is_err());
+}
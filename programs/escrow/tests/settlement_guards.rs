@@ -0,0 +1,137 @@
+//! Settlement hardening: reentrancy lock, unassigned renters, token-program substitution
+//! and transfer-fee mints.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token_2022::{
+    self,
+    spl_token_2022::extension::transfer_fee::{TransferFee, TransferFeeConfig},
+    spl_token_2022::extension::{BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut},
+    spl_token_2022::state::Mint,
+};
+use anchor_spl::token_interface::TokenInterface;
+use escrow::{received_at_epoch, EscrowAccount, EscrowError, UNASSIGNED_RENTER};
+
+#[test]
+fn settlement_lock_rejects_reentry() {
+    let mut escrow = EscrowAccount::default();
+
+    escrow.enter_settlement().unwrap();
+    let err = escrow.enter_settlement().unwrap_err();
+    assert_eq!(err, EscrowError::ReentrancyDetected.into());
+
+    escrow.exit_settlement();
+    escrow.enter_settlement().unwrap();
+}
+
+#[test]
+fn settlement_lock_survives_serialization() {
+    let mut escrow = EscrowAccount::default();
+    escrow.enter_settlement().unwrap();
+
+    // What a reentrant instruction would load after the pre-CPI `exit`
+    let mut data = Vec::new();
+    escrow.try_serialize(&mut data).unwrap();
+    let mut reloaded = EscrowAccount::try_deserialize(&mut data.as_slice()).unwrap();
+
+    assert!(reloaded.settlement_lock);
+    assert!(reloaded.enter_settlement().is_err());
+}
+
+#[test]
+fn unfunded_escrow_has_no_payable_renter() {
+    let mut escrow = EscrowAccount {
+        renter: UNASSIGNED_RENTER,
+        ..Default::default()
+    };
+    assert!(!escrow.has_renter());
+    assert_eq!(escrow.assigned_renter().unwrap_err(), EscrowError::RenterUnassigned.into());
+    assert!(!escrow.is_renter_or_watchtower(&UNASSIGNED_RENTER));
+
+    let renter = Pubkey::new_unique();
+    escrow.renter = renter;
+    assert_eq!(escrow.assigned_renter().unwrap(), renter);
+    assert!(escrow.is_renter_or_watchtower(&renter));
+}
+
+fn token_program(key: &Pubkey) -> Result<()> {
+    let loader = bpf_loader::id();
+    let mut lamports = 1;
+    let mut data = vec![];
+    let info = AccountInfo::new(key, false, false, &mut lamports, &mut data, &loader, true, 0);
+    Interface::<TokenInterface>::try_from(&info).map(|_| ())
+}
+
+#[test]
+fn malicious_token_program_is_rejected() {
+    assert_eq!(
+        token_program(&Pubkey::new_unique()).unwrap_err(),
+        ErrorCode::InvalidProgramId.into()
+    );
+}
+
+#[test]
+fn both_token_programs_are_accepted() {
+    token_program(&anchor_spl::token::ID).unwrap();
+    token_program(&anchor_spl::token_2022::ID).unwrap();
+}
+
+/// A Token-2022 mint charging `older_bps` until `newer_epoch`, then `newer_bps`, each
+/// capped at `maximum_fee`
+fn transfer_fee_mint(older_bps: u16, newer_epoch: u64, newer_bps: u16, maximum_fee: u64) -> Vec<u8> {
+    let len = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+    let mut data = vec![0; len];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+    config.older_transfer_fee = TransferFee {
+        epoch: 0.into(),
+        maximum_fee: maximum_fee.into(),
+        transfer_fee_basis_points: older_bps.into(),
+    };
+    config.newer_transfer_fee = TransferFee {
+        epoch: newer_epoch.into(),
+        maximum_fee: maximum_fee.into(),
+        transfer_fee_basis_points: newer_bps.into(),
+    };
+    state.base = Mint {
+        decimals: 6,
+        is_initialized: true,
+        ..Default::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    data
+}
+
+fn received(owner: &Pubkey, data: &mut [u8], amount: u64, epoch: u64) -> u64 {
+    let key = Pubkey::new_unique();
+    let mut lamports = 1;
+    let mint = AccountInfo::new(&key, false, false, &mut lamports, data, owner, false, 0);
+    received_at_epoch(&mint, amount, epoch).unwrap()
+}
+
+#[test]
+fn vaults_are_credited_net_of_the_transfer_fee_for_the_epoch() {
+    let mut mint = transfer_fee_mint(100, 10, 250, 5_000);
+    assert_eq!(received(&token_2022::ID, &mut mint, 100_000, 9), 99_000);
+    assert_eq!(received(&token_2022::ID, &mut mint, 100_000, 10), 97_500);
+    // The fee is capped at `maximum_fee`
+    assert_eq!(received(&token_2022::ID, &mut mint, 1_000_000, 10), 995_000);
+}
+
+#[test]
+fn mints_without_a_transfer_fee_are_credited_in_full() {
+    let mut mint = transfer_fee_mint(100, 0, 100, 5_000);
+    // Only Token-2022 mints carry the extension
+    assert_eq!(received(&anchor_spl::token::ID, &mut mint, 100_000, 0), 100_000);
+
+    let mut plain = vec![0; Mint::LEN];
+    Mint {
+        decimals: 6,
+        is_initialized: true,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut plain);
+    assert_eq!(received(&token_2022::ID, &mut plain, 100_000, 0), 100_000);
+}
@@ -0,0 +1,37 @@
+use anchor_lang::AnchorSerialize;
+use escrow::{
+    rental_escrow_id, EscrowTerms, FixedString, FundingRule, SlaTerms, MAX_METADATA_URI_LEN,
+    MAX_SKILL_NAME_LEN, RENTAL_ESCROW_ID_FLAG,
+};
+
+#[test]
+fn rentals_get_distinct_flagged_ids() {
+    let ids = [
+        rental_escrow_id(0, 0),
+        rental_escrow_id(0, 1),
+        rental_escrow_id(1, 0),
+        rental_escrow_id(u16::MAX, u32::MAX),
+    ];
+    for (i, id) in ids.iter().enumerate() {
+        assert_ne!(id & RENTAL_ESCROW_ID_FLAG, 0);
+        assert!(ids[i + 1..].iter().all(|other| other != id));
+    }
+}
+
+#[test]
+fn largest_terms_fit_their_len() {
+    let terms = EscrowTerms {
+        skill_name: FixedString::new(&"s".repeat(MAX_SKILL_NAME_LEN)).unwrap(),
+        category: "c".repeat(32),
+        metadata_uri: FixedString::new(&"u".repeat(MAX_METADATA_URI_LEN)).unwrap(),
+        sla: Some(SlaTerms {
+            max_latency_ms: Some(500),
+            min_uptime_bps: Some(9_900),
+            max_revisions: Some(2),
+            ..Default::default()
+        }),
+        funding_rule: Some(FundingRule::default()),
+        ..Default::default()
+    };
+    assert_eq!(terms.try_to_vec().unwrap().len(), EscrowTerms::LEN);
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowTerms, SlaMetric, SlaTerms, SLA_SCHEMA_VERSION};
+
+fn sla() -> SlaTerms {
+    SlaTerms {
+        max_latency_ms: Some(500),
+        min_uptime_bps: Some(9_900),
+        max_revisions: None,
+        verifier: Pubkey::new_unique(),
+        penalty_bps_per_breach: 1_000,
+        max_penalty_bps: 2_500,
+    }
+}
+
+#[test]
+fn canonical_bytes_round_trip_with_version_prefix() {
+    let terms = sla();
+    let bytes = terms.canonical_bytes().unwrap();
+    assert_eq!(bytes[0], SLA_SCHEMA_VERSION);
+    assert_eq!(SlaTerms::from_canonical_bytes(&bytes).unwrap(), terms);
+
+    let mut wrong_version = bytes.clone();
+    wrong_version[0] = SLA_SCHEMA_VERSION + 1;
+    assert!(SlaTerms::from_canonical_bytes(&wrong_version).is_err());
+}
+
+#[test]
+fn serialized_size_fits_len() {
+    let terms = SlaTerms {
+        max_revisions: Some(3),
+        ..sla()
+    };
+    assert_eq!(terms.canonical_bytes().unwrap().len(), 1 + SlaTerms::LEN);
+}
+
+#[test]
+fn breaches_only_count_for_tracked_metrics() {
+    let terms = sla();
+    assert_eq!(terms.breached(SlaMetric::LatencyMs, 501), Some(true));
+    assert_eq!(terms.breached(SlaMetric::LatencyMs, 500), Some(false));
+    assert_eq!(terms.breached(SlaMetric::UptimeBps, 9_899), Some(true));
+    assert_eq!(terms.breached(SlaMetric::Revisions, 10), None);
+}
+
+#[test]
+fn penalty_is_capped() {
+    let terms = sla();
+    assert_eq!(terms.penalty_bps(1), 1_000);
+    assert_eq!(terms.penalty_bps(5), 2_500);
+    assert!(terms.is_valid());
+    assert!(!SlaTerms { max_penalty_bps: 10_001, ..sla() }.is_valid());
+
+    let escrow = EscrowAccount {
+        amount: 25_000_000,
+        sla_penalty_bps: terms.penalty_bps(2),
+        terms: EscrowTerms {
+            sla: Some(terms),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(escrow.sla_penalty().unwrap(), 5_000_000);
+}
@@ -0,0 +1,67 @@
+use escrow::{EscrowAccount, EscrowState, EscrowTerms};
+
+const FUNDED_AT: i64 = 1_700_000_000;
+const DURATION: i64 = 4 * 3_600;
+const PRICE: u64 = 20_000_000;
+
+fn rental() -> EscrowAccount {
+    EscrowAccount {
+        state: EscrowState::Funded,
+        amount: PRICE,
+        funded_at: FUNDED_AT,
+        terms: EscrowTerms {
+            duration_seconds: DURATION,
+            price_usdc: PRICE,
+            stream_payment: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// What `claim_streamed` does to the escrow's balances
+fn claim(escrow: &mut EscrowAccount, now: i64) -> u64 {
+    let amount = escrow.streamable_at(now);
+    escrow.amount -= amount;
+    escrow.claimed_amount += amount;
+    amount
+}
+
+#[test]
+fn streams_pro_rata_to_elapsed_time() {
+    let escrow = rental();
+    assert_eq!(escrow.streamable_at(FUNDED_AT - 60), 0);
+    assert_eq!(escrow.streamable_at(FUNDED_AT), 0);
+    assert_eq!(escrow.streamable_at(FUNDED_AT + DURATION / 4), 5_000_000);
+    assert_eq!(escrow.streamable_at(FUNDED_AT + DURATION * 3), PRICE);
+}
+
+#[test]
+fn claims_subtract_what_was_already_streamed() {
+    let mut escrow = rental();
+    assert_eq!(claim(&mut escrow, FUNDED_AT + DURATION / 4), 5_000_000);
+    assert_eq!(escrow.streamable_at(FUNDED_AT + DURATION / 4), 0);
+    assert_eq!(claim(&mut escrow, FUNDED_AT + DURATION / 2), 5_000_000);
+    assert_eq!(claim(&mut escrow, FUNDED_AT + DURATION), 10_000_000);
+    assert_eq!((escrow.amount, escrow.claimed_amount), (0, PRICE));
+}
+
+#[test]
+fn extending_the_rental_slows_the_stream() {
+    let mut escrow = rental();
+    claim(&mut escrow, FUNDED_AT + DURATION / 2);
+    escrow.extend_duration(DURATION).unwrap();
+    // Half of the original period is a quarter of the extended one, already claimed
+    assert_eq!(escrow.streamable_at(FUNDED_AT + DURATION / 2), 0);
+    assert_eq!(escrow.streamable_at(FUNDED_AT + DURATION), 0);
+    assert_eq!(escrow.streamable_at(FUNDED_AT + DURATION * 2), 10_000_000);
+}
+
+#[test]
+fn settlement_keeps_streamed_payouts() {
+    let mut escrow = rental();
+    claim(&mut escrow, FUNDED_AT + DURATION / 2);
+    escrow.provider_payout = 10_000_000;
+    escrow.allocate_settlement(6_000_000, 4_000_000);
+    assert_eq!((escrow.provider_payout, escrow.renter_refund), (16_000_000, 4_000_000));
+}
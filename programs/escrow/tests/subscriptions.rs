@@ -0,0 +1,64 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{Reserved, SubscriptionPlan};
+
+const START: i64 = 1_700_000_000;
+const PERIOD: i64 = 3_600;
+
+fn plan(periods: u32, total: u64) -> SubscriptionPlan {
+    SubscriptionPlan {
+        escrow: Pubkey::new_unique(),
+        periods,
+        amount_per_period: total / periods as u64,
+        total,
+        period_seconds: PERIOD,
+        started_at: START,
+        claimed_periods: 0,
+        active_periods: periods,
+        cancelled_at: 0,
+        bump: 255,
+        _reserved: Reserved::default(),
+    }
+}
+
+#[test]
+fn provider_claims_each_period_once_it_elapses() {
+    let mut plan = plan(3, 30_000_000);
+    assert!(plan.claim(START + PERIOD - 1).is_err());
+    assert_eq!(plan.claim(START + PERIOD).unwrap(), 10_000_000);
+    assert!(plan.claim(START + PERIOD + 1).is_err());
+
+    // Periods left unclaimed accumulate
+    assert_eq!(plan.claim(START + 3 * PERIOD + 500).unwrap(), 20_000_000);
+    assert!(plan.is_finished());
+    assert!(plan.claim(START + 10 * PERIOD).is_err());
+}
+
+#[test]
+fn last_period_takes_the_rounding_remainder() {
+    // A transfer fee left the vault short of three equal periods
+    let mut plan = plan(3, 29_999_990);
+    let claimed: u64 = (1..=3).map(|period| plan.claim(START + period * PERIOD).unwrap()).sum();
+    assert_eq!(claimed, 29_999_990);
+}
+
+#[test]
+fn cancel_refunds_the_periods_not_yet_started() {
+    let mut plan = plan(4, 40_000_000);
+    assert_eq!(plan.claim(START + PERIOD).unwrap(), 10_000_000);
+
+    // The second period is under way and stays the provider's
+    assert_eq!(plan.cancel(START + PERIOD + 60).unwrap(), 20_000_000);
+    assert_eq!(plan.active_periods, 2);
+    assert!(plan.cancel(START + PERIOD + 120).is_err());
+    assert!(!plan.is_finished());
+    assert_eq!(plan.claim(START + 2 * PERIOD).unwrap(), 10_000_000);
+    assert!(plan.is_finished());
+}
+
+#[test]
+fn nothing_to_cancel_once_the_last_period_started() {
+    let mut plan = plan(2, 20_000_000);
+    assert!(plan.cancel(START + PERIOD).is_err());
+    assert_eq!(plan.started_periods(START - 1), 0);
+    assert_eq!(plan.started_periods(START), 1);
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{EscrowAccount, EscrowError, EscrowTerms};
+
+fn listing(terms: EscrowTerms) -> EscrowAccount {
+    let token_mint = Pubkey::new_unique();
+    EscrowAccount {
+        terms_hash: EscrowAccount::compute_terms_hash(&terms, &token_mint).unwrap(),
+        token_mint,
+        terms,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn hash_covers_the_terms_and_the_mint() {
+    let terms = EscrowTerms {
+        price_usdc: 25_000_000,
+        ..Default::default()
+    };
+    let mint = Pubkey::new_unique();
+    let hash = EscrowAccount::compute_terms_hash(&terms, &mint).unwrap();
+    assert_eq!(EscrowAccount::compute_terms_hash(&terms.clone(), &mint).unwrap(), hash);
+    assert_ne!(EscrowAccount::compute_terms_hash(&terms, &Pubkey::new_unique()).unwrap(), hash);
+}
+
+#[test]
+fn funding_must_echo_the_current_terms_hash() {
+    let mut listing = listing(EscrowTerms {
+        price_usdc: 25_000_000,
+        ..Default::default()
+    });
+    let reviewed = listing.terms_hash;
+    listing.check_fundable(&reviewed, false, 0).unwrap();
+
+    // The provider edits the listing after the renter reviewed it
+    listing.terms.duration_seconds = 60;
+    listing.terms_hash = EscrowAccount::compute_terms_hash(&listing.terms, &listing.token_mint).unwrap();
+    assert_eq!(
+        listing.check_fundable(&reviewed, false, 0).unwrap_err(),
+        EscrowError::TermsHashMismatch.into()
+    );
+    listing.check_fundable(&listing.terms_hash.clone(), false, 0).unwrap();
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::{AnchorSerialize, Pubkey};
+use escrow::{
+    EscrowError, LocalizedTerms, Reserved, TermsLocalization, MAX_LANGUAGE_TAG_LEN, MAX_LOCALIZATIONS,
+    MAX_LOCALIZED_URI_LEN,
+};
+
+fn localization() -> TermsLocalization {
+    TermsLocalization {
+        escrow: Pubkey::new_unique(),
+        terms_hash: [0; 32],
+        entries: Vec::new(),
+        bump: 0,
+        _reserved: Reserved::default(),
+    }
+}
+
+#[test]
+fn languages_are_set_replaced_and_withdrawn() {
+    let mut terms = localization();
+    terms.set([1; 32], "pt-BR", Some("ipfs://pt".to_string())).unwrap();
+    terms.set([1; 32], "de", Some("ipfs://de".to_string())).unwrap();
+    terms.set([1; 32], "pt-BR", Some("ipfs://pt-v2".to_string())).unwrap();
+    assert_eq!(terms.entries.len(), 2);
+    assert_eq!(terms.uri_for(&[1; 32], "pt-BR"), Some("ipfs://pt-v2"));
+
+    terms.set([1; 32], "de", None).unwrap();
+    assert_eq!(terms.uri_for(&[1; 32], "de"), None);
+    // Withdrawing an unlisted language is a no-op
+    terms.set([1; 32], "fr", None).unwrap();
+    assert_eq!(terms.entries.len(), 1);
+}
+
+#[test]
+fn new_terms_drop_translations_of_the_old_ones() {
+    let mut terms = localization();
+    terms.set([1; 32], "pt-BR", Some("ipfs://pt".to_string())).unwrap();
+    assert_eq!(terms.uri_for(&[2; 32], "pt-BR"), None);
+
+    terms.set([2; 32], "ja", Some("ipfs://ja".to_string())).unwrap();
+    assert_eq!(terms.terms_hash, [2; 32]);
+    assert_eq!(terms.uri_for(&[2; 32], "pt-BR"), None);
+    assert_eq!(terms.uri_for(&[2; 32], "ja"), Some("ipfs://ja"));
+}
+
+#[test]
+fn tags_and_uris_are_validated() {
+    assert!(LocalizedTerms::is_valid_language("zh-Hant-TW"));
+    assert!(!LocalizedTerms::is_valid_language("e"));
+    assert!(!LocalizedTerms::is_valid_language("-en"));
+    assert!(!LocalizedTerms::is_valid_language("en_US"));
+    assert!(!LocalizedTerms::is_valid_language(&"a".repeat(MAX_LANGUAGE_TAG_LEN + 1)));
+
+    let mut terms = localization();
+    assert_eq!(
+        terms.set([1; 32], "en US", Some("ipfs://en".to_string())).unwrap_err(),
+        EscrowError::InvalidLanguageTag.into()
+    );
+    assert_eq!(
+        terms.set([1; 32], "en", Some(String::new())).unwrap_err(),
+        EscrowError::InvalidLocalizedUri.into()
+    );
+    assert_eq!(
+        terms.set([1; 32], "en", Some("x".repeat(MAX_LOCALIZED_URI_LEN + 1))).unwrap_err(),
+        EscrowError::InvalidLocalizedUri.into()
+    );
+}
+
+#[test]
+fn full_localization_fits_its_len() {
+    let mut terms = localization();
+    for i in 0..MAX_LOCALIZATIONS {
+        let language = format!("{}{}", "a".repeat(MAX_LANGUAGE_TAG_LEN - 1), i);
+        terms.set([1; 32], &language, Some("x".repeat(MAX_LOCALIZED_URI_LEN))).unwrap();
+    }
+    assert_eq!(
+        terms.set([1; 32], "en", Some("ipfs://en".to_string())).unwrap_err(),
+        EscrowError::TooManyLocalizations.into()
+    );
+    assert_eq!(8 + terms.try_to_vec().unwrap().len(), TermsLocalization::LEN);
+}
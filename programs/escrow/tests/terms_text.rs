@@ -0,0 +1,34 @@
+use escrow::{EscrowTerms, FixedString, MAX_SKILL_NAME_LEN};
+
+#[test]
+fn text_fields_serialize_to_their_capacity() {
+    assert!(FixedString::<4>::new("abcd").is_some());
+    assert!(FixedString::<4>::new("abcde").is_none());
+    assert_eq!(FixedString::<{ MAX_SKILL_NAME_LEN }>::LEN, 2 + MAX_SKILL_NAME_LEN);
+}
+
+#[test]
+fn only_zero_padded_utf8_is_valid() {
+    let mut text = FixedString::<8>::new("abc").unwrap();
+    assert!(text.is_valid());
+
+    text.bytes[5] = b'x';
+    assert!(!text.is_valid(), "bytes past the text must be zero");
+
+    let mut text = FixedString::<8>::new("abc").unwrap();
+    text.bytes[1] = 0xff;
+    assert!(!text.is_valid());
+    assert_eq!(text.as_str(), "");
+
+    let text = FixedString::<8> {
+        len: 9,
+        ..Default::default()
+    };
+    assert!(!text.is_valid());
+
+    let terms = EscrowTerms {
+        skill_name: FixedString::new("translate").unwrap(),
+        ..Default::default()
+    };
+    assert!(terms.text_is_valid());
+}
@@ -0,0 +1,71 @@
+use escrow::{EscrowAccount, EscrowError, EscrowState, EscrowTerms};
+
+fn funded_escrow() -> EscrowAccount {
+    EscrowAccount {
+        state: EscrowState::Funded,
+        funded_at: 1_000,
+        dispute_window_seconds: 500,
+        terms: EscrowTerms {
+            duration_seconds: 100,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn refund_opens_when_the_rental_period_ends() {
+    let escrow = funded_escrow();
+    assert_eq!(escrow.rental_ends_at(), 1_100);
+    assert_eq!(
+        escrow.check_timeout_refund(1_099).unwrap_err(),
+        EscrowError::TimeoutNotElapsed.into()
+    );
+    // The dispute window does not hold the refund back
+    escrow.check_timeout_refund(1_100).unwrap();
+}
+
+#[test]
+fn any_delivery_blocks_the_refund() {
+    let mut escrow = funded_escrow();
+    escrow.first_delivered_at = 1_050;
+    // A revision request clears the latest delivery but not the first
+    escrow.delivered_at = 0;
+    assert_eq!(escrow.check_timeout_refund(5_000).unwrap_err(), EscrowError::InvalidState.into());
+}
+
+#[test]
+fn only_funded_rentals_are_refunded() {
+    for state in [EscrowState::Created, EscrowState::Disputed, EscrowState::Cancelled] {
+        let escrow = EscrowAccount {
+            state,
+            ..funded_escrow()
+        };
+        assert_eq!(escrow.check_timeout_refund(5_000).unwrap_err(), EscrowError::InvalidState.into());
+    }
+}
+
+#[test]
+fn rental_end_saturates() {
+    let escrow = EscrowAccount {
+        funded_at: i64::MAX - 10,
+        ..funded_escrow()
+    };
+    assert_eq!(escrow.rental_ends_at(), i64::MAX);
+    assert!(escrow.check_timeout_refund(i64::MAX - 1).is_err());
+}
+
+#[test]
+fn an_extension_moves_the_timeout_back() {
+    let mut escrow = funded_escrow();
+    escrow.extend_duration(3_600).unwrap();
+    assert_eq!(escrow.terms.duration_seconds, 3_700);
+    assert_eq!(escrow.rental_ends_at(), 4_700);
+    assert!(escrow.check_timeout_refund(4_699).is_err());
+    escrow.check_timeout_refund(4_700).unwrap();
+
+    assert!(escrow.extend_duration(0).is_err());
+    assert!(escrow.extend_duration(-60).is_err());
+    assert!(escrow.extend_duration(i64::MAX).is_err());
+    assert_eq!(escrow.terms.duration_seconds, 3_700);
+}
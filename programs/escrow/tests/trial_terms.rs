@@ -0,0 +1,38 @@
+use escrow::{EscrowTerms, FundingRule};
+
+fn trial(price_usdc: u64) -> EscrowTerms {
+    EscrowTerms {
+        price_usdc,
+        trial: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn trial_price_is_capped_by_config() {
+    assert!(trial(5_000_000).trial_is_valid(5_000_000));
+    assert!(!trial(5_000_001).trial_is_valid(5_000_000));
+}
+
+#[test]
+fn trials_are_disabled_without_a_cap() {
+    assert!(!trial(0).trial_is_valid(0));
+}
+
+#[test]
+fn trials_fund_in_full() {
+    let terms = EscrowTerms {
+        funding_rule: Some(FundingRule::default()),
+        ..trial(1_000_000)
+    };
+    assert!(!terms.trial_is_valid(5_000_000));
+}
+
+#[test]
+fn regular_listings_ignore_the_trial_cap() {
+    let terms = EscrowTerms {
+        price_usdc: 100_000_000,
+        ..Default::default()
+    };
+    assert!(terms.trial_is_valid(0));
+}
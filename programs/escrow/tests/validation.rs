@@ -0,0 +1,65 @@
+use anchor_lang::prelude::Pubkey;
+use escrow::{Config, EscrowAccount, EscrowError, EscrowState, Validation};
+
+fn config() -> Config {
+    Config {
+        admin: Pubkey::new_unique(),
+        points_schedule: vec![],
+        redemption_enabled: false,
+        bump: 0,
+        screener: Pubkey::new_unique(),
+        screening_threshold: 5_000_000,
+        screening_delay_seconds: 3_600,
+        max_initializations_per_epoch: 0,
+        features: 0,
+        trial_max_amount: 0,
+        tax_bps: 0,
+        fee_bps: 250,
+        fee_treasury: Pubkey::new_unique(),
+        failure_streak_threshold: 2,
+        arbitration_window_seconds: 86_400,
+        default_release: false,
+        allowed_mints: vec![],
+        risk_weights: Default::default(),
+    }
+}
+
+fn failure(result: anchor_lang::Result<()>) -> Option<String> {
+    Validation::of(result).failure.map(|failure| failure.name)
+}
+
+#[test]
+fn reports_the_first_listing_check_a_funding_fails() {
+    let mut listing = EscrowAccount {
+        terms_hash: [3; 32],
+        ..Default::default()
+    };
+    listing.terms.funding_deadline = 1_000;
+    assert_eq!(failure(listing.check_fundable(&[3; 32], false, 1_000)), None);
+    assert_eq!(failure(listing.check_fundable(&[4; 32], false, 1_000)).as_deref(), Some("TermsHashMismatch"));
+    assert_eq!(failure(listing.check_fundable(&[3; 32], false, 1_001)).as_deref(), Some("ListingExpired"));
+
+    listing.state = EscrowState::Funded;
+    let validation = Validation::of(listing.check_fundable(&[4; 32], false, 1_001));
+    let failure = validation.failure.unwrap();
+    assert_eq!(failure.code, 6000 + EscrowError::InvalidState as u64);
+    assert_eq!(failure.message, EscrowError::InvalidState.to_string());
+}
+
+#[test]
+fn release_waits_for_delivery_deferred_payment_and_screening() {
+    let config = config();
+    let mut rental = EscrowAccount {
+        state: EscrowState::Funded,
+        amount: 10_000_000,
+        funded_at: 100,
+        deferred_balance: 1_000,
+        deferred_due_at: 500,
+        ..Default::default()
+    };
+    assert_eq!(failure(rental.check_releasable(&config, false, 200)).as_deref(), Some("NotDelivered"));
+    rental.first_delivered_at = 150;
+    assert_eq!(failure(rental.check_releasable(&config, false, 200)).as_deref(), Some("DeferredBalanceOutstanding"));
+    assert_eq!(failure(rental.check_releasable(&config, false, 501)).as_deref(), Some("ScreeningPending"));
+    assert!(Validation::of(rental.check_releasable(&config, false, 3_700)).is_valid());
+}
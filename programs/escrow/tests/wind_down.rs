@@ -0,0 +1,19 @@
+use escrow::{EscrowAccount, EscrowError, EscrowState};
+
+#[test]
+fn only_quiet_unfunded_listings_are_closed() {
+    let mut listing = EscrowAccount::default();
+    listing.check_closable().unwrap();
+
+    listing.pending_requests = 1;
+    assert_eq!(listing.check_closable().unwrap_err(), EscrowError::RentalRequestsPending.into());
+    listing.pending_requests = 0;
+
+    listing.bond_held = true;
+    assert_eq!(listing.check_closable().unwrap_err(), EscrowError::BondAlreadyFunded.into());
+    listing.bond_held = false;
+
+    // Funded rentals are refunded instead, never closed as listings
+    listing.state = EscrowState::Funded;
+    assert_eq!(listing.check_closable().unwrap_err(), EscrowError::InvalidState.into());
+}
@@ -1,9 +1,41 @@
+use activity_log::program::ActivityLog;
+use activity_log::{ActivityKind, ACTIVITY_AUTHORITY_SEED};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::system_program;
 
 declare_id!("J9X4dDqyFL2pG3MZJn4WEEK3Mcku9nG8XJcEo8zB9z2");
 
 const REPUTATION_STATE_SEED: &[u8] = b"reputation_state";
 const AGENT_SEED: &[u8] = b"agent";
+const ATTESTOR_SEED: &[u8] = b"attestor";
+const EXTERNAL_REPUTATION_SEED: &[u8] = b"external_reputation";
+const REVIEW_PAGE_SEED: &[u8] = b"review_page";
+const DELEGATION_SEED: &[u8] = b"reviewer_delegation";
+const REVIEW_BOUNTY_SEED: &[u8] = b"review_bounty";
+const BOUNTY_CLAIM_SEED: &[u8] = b"bounty_claim";
+pub const REVIEW_SHARD_SEED: &[u8] = b"review_shard";
+
+/// Escrow program whose accounts verify that a reviewer actually rented from the agent
+pub const ESCROW_PROGRAM_ID: Pubkey = pubkey!("8uBMA8S33eGFMRA677Y1gPvmnBGUjFtdwxf2A8JufpA3");
+
+/// Reviews per `ReviewPage`; sized so a full page fits in return data
+pub const REVIEW_PAGE_SIZE: usize = 16;
+/// `ReviewShard`s per agent; a reviewer always lands in the same one
+pub const REVIEW_SHARDS: u8 = 8;
+/// Max delegate reviewer keys per organization
+pub const MAX_REVIEW_DELEGATES: usize = 8;
+
+/// Maximum share of the displayed score that imported reputation may contribute
+pub const EXTERNAL_WEIGHT_CAP_BPS: u16 = 2_500;
+
+/// Trailing window of the time-weighted average reputation
+pub const TWAR_WINDOW_SECONDS: i64 = 90 * 24 * 60 * 60;
+/// Fixed-point scale of `twar_score`: 87.5 is stored as 875_000
+pub const TWAR_SCALE: u64 = 10_000;
+/// Zeroed bytes at the end of `Agent`, from which future fields are carved without
+/// resizing accounts; carved fields must read zero as "unset"
+pub const ACCOUNT_RESERVE: usize = 64;
 
 #[program]
 pub mod reputation {
@@ -16,6 +48,7 @@ pub mod reputation {
         state.total_agents = 0;
         state.total_reviews = 0;
         state.reputation_sum = 0;
+        state.authority = ctx.accounts.authority.key();
         state.bump = ctx.bumps.state;
         Ok(())
     }
@@ -44,9 +77,20 @@ pub mod reputation {
         agent.created_at = Clock::get()?.unix_timestamp;
         agent.updated_at = Clock::get()?.unix_timestamp;
         agent.is_active = true;
+        agent.twar_score = 0;
+        agent.twar_updated_at = agent.created_at;
 
         state.total_agents += 1;
 
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.agent.authority,
+            ActivityKind::AgentRegistered,
+            ctx.accounts.agent.key(),
+            Pubkey::default(),
+            0,
+        )?;
+
         Ok(())
     }
 
@@ -62,28 +106,149 @@ pub mod reputation {
         let agent = &mut ctx.accounts.agent;
         let state = &mut ctx.accounts.state;
 
-        require!(rating >= 1 && rating <= 5, ErrorCode::InvalidRating);
+        require!((1..=5).contains(&rating), ErrorCode::InvalidRating);
         require!(comment.len() <= 500, ErrorCode::CommentTooLong);
         require!(skill_category.len() <= 32, ErrorCode::CategoryTooLong);
         require!(agent.is_active, ErrorCode::AgentNotActive);
 
+        // A delegate reviews on behalf of its organization; both are recorded
+        let signer_key = ctx.accounts.reviewer.key();
+        let (reviewer, delegate) = match &ctx.accounts.delegation {
+            Some(delegation) => {
+                let now = Clock::get()?.unix_timestamp;
+                require!(delegation.is_valid_delegate(&signer_key, now), ErrorCode::InvalidDelegate);
+                (delegation.organization, Some(signer_key))
+            }
+            None => (signer_key, None),
+        };
+
         review.agent = agent_key;
-        review.reviewer = ctx.accounts.reviewer.key();
+        review.reviewer = reviewer;
+        review.delegate = delegate;
         review.rating = rating;
+        review.comment_hash = hash(comment.as_bytes()).to_bytes();
         review.comment = comment;
         review.skill_category = skill_category;
         review.created_at = Clock::get()?.unix_timestamp;
 
+        // Append to the agent's paged review index
+        let page = &mut ctx.accounts.review_page;
+        if page.agent == Pubkey::default() {
+            page.agent = agent_key;
+            page.page = (agent.total_ratings / REVIEW_PAGE_SIZE as u64) as u32;
+            page.bump = ctx.bumps.review_page;
+        }
+        page.entries.push(ReviewSummary {
+            review: review.key(),
+            rating,
+            created_at: review.created_at,
+        });
+
         // Update agent stats
-        agent.total_ratings += 1;
-        agent.rating_sum += rating as u64;
-        agent.reputation_score = (agent.rating_sum / agent.total_ratings) as i64;
-        agent.updated_at = Clock::get()?.unix_timestamp;
+        agent.record_ratings(1, rating as u64, Clock::get()?.unix_timestamp);
 
         // Update global state
         state.total_reviews += 1;
         state.reputation_sum += rating as u64;
 
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.agent.authority,
+            ActivityKind::ReviewAdded,
+            ctx.accounts.review.key(),
+            ctx.accounts.review.reviewer,
+            rating as u64,
+        )?;
+
+        Ok(())
+    }
+
+    /// Review a heavily reviewed agent without write-locking it. The rating is parked in
+    /// the reviewer's `ReviewShard` and reaches the score at the next `update_score`.
+    /// Sharded reviews are not added to the agent's review pages.
+    pub fn add_sharded_review(
+        ctx: Context<AddShardedReview>,
+        rating: u8,
+        comment: String,
+        skill_category: String,
+    ) -> Result<()> {
+        require!((1..=5).contains(&rating), ErrorCode::InvalidRating);
+        require!(comment.len() <= 500, ErrorCode::CommentTooLong);
+        require!(skill_category.len() <= 32, ErrorCode::CategoryTooLong);
+        require!(ctx.accounts.agent.is_active, ErrorCode::AgentNotActive);
+
+        let signer_key = ctx.accounts.reviewer.key();
+        let (reviewer, delegate) = match &ctx.accounts.delegation {
+            Some(delegation) => {
+                let now = Clock::get()?.unix_timestamp;
+                require!(delegation.is_valid_delegate(&signer_key, now), ErrorCode::InvalidDelegate);
+                (delegation.organization, Some(signer_key))
+            }
+            None => (signer_key, None),
+        };
+
+        let review = &mut ctx.accounts.review;
+        review.agent = ctx.accounts.agent.key();
+        review.reviewer = reviewer;
+        review.delegate = delegate;
+        review.rating = rating;
+        review.comment_hash = hash(comment.as_bytes()).to_bytes();
+        review.comment = comment;
+        review.skill_category = skill_category;
+        review.created_at = Clock::get()?.unix_timestamp;
+
+        let shard = &mut ctx.accounts.review_shard;
+        if shard.agent == Pubkey::default() {
+            shard.agent = ctx.accounts.agent.key();
+            shard.shard = review_shard_index(&signer_key);
+            shard.bump = ctx.bumps.review_shard;
+        }
+        shard.pending_ratings = shard.pending_ratings.saturating_add(1);
+        shard.pending_rating_sum = shard.pending_rating_sum.saturating_add(rating as u64);
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.agent.authority,
+            ActivityKind::ReviewAdded,
+            ctx.accounts.review.key(),
+            ctx.accounts.review.reviewer,
+            rating as u64,
+        )?;
+
+        Ok(())
+    }
+
+    /// Permissionless: fold the pending ratings of the agent's `ReviewShard`s, passed in
+    /// `remaining_accounts`, into its score and empty them
+    pub fn update_score<'info>(ctx: Context<'_, '_, 'info, 'info, UpdateScore<'info>>) -> Result<()> {
+        let agent_key = ctx.accounts.agent.key();
+        let (mut ratings, mut rating_sum) = (0u64, 0u64);
+        for info in ctx.remaining_accounts {
+            let mut shard: Account<ReviewShard> = Account::try_from(info)?;
+            require_keys_eq!(shard.agent, agent_key, ErrorCode::InvalidReviewShard);
+            ratings = ratings.saturating_add(shard.pending_ratings);
+            rating_sum = rating_sum.saturating_add(shard.pending_rating_sum);
+            shard.pending_ratings = 0;
+            shard.pending_rating_sum = 0;
+            // Persist now, so a shard passed twice is only counted once
+            shard.exit(ctx.program_id)?;
+        }
+
+        let agent = &mut ctx.accounts.agent;
+        agent.record_ratings(ratings, rating_sum, Clock::get()?.unix_timestamp);
+        let state = &mut ctx.accounts.state;
+        state.total_reviews = state.total_reviews.saturating_add(ratings);
+        state.reputation_sum = state.reputation_sum.saturating_add(rating_sum);
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.agent.authority,
+            ActivityKind::ReputationUpdated,
+            agent_key,
+            Pubkey::default(),
+            ctx.accounts.agent.reputation_score as u64,
+        )?;
+
         Ok(())
     }
 
@@ -95,15 +260,25 @@ pub mod reputation {
         let agent = &mut ctx.accounts.agent;
         let state = &mut ctx.accounts.state;
 
-        require!(new_score >= 0 && new_score <= 100, ErrorCode::InvalidScore);
+        require!((0..=100).contains(&new_score), ErrorCode::InvalidScore);
 
         let old_score = agent.reputation_score;
+        agent.roll_twar(Clock::get()?.unix_timestamp);
         agent.reputation_score = new_score;
         agent.updated_at = Clock::get()?.unix_timestamp;
 
         // Adjust global sum
         state.reputation_sum = state.reputation_sum.saturating_sub(old_score as u64).saturating_add(new_score as u64);
 
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.agent.authority,
+            ActivityKind::ReputationUpdated,
+            ctx.accounts.agent.key(),
+            Pubkey::default(),
+            new_score as u64,
+        )?;
+
         Ok(())
     }
 
@@ -115,19 +290,222 @@ pub mod reputation {
         agent.is_active = false;
         agent.updated_at = Clock::get()?.unix_timestamp;
 
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.agent.authority,
+            ActivityKind::AgentDeactivated,
+            ctx.accounts.agent.key(),
+            Pubkey::default(),
+            0,
+        )?;
+
         Ok(())
     }
 
     /// Get agent's reputation data
     pub fn get_agent_reputation(_ctx: Context<GetAgentReputation>) -> Result<AgentData> {
+        let agent = &_ctx.accounts.agent;
+        let external_score = agent.external_score();
         Ok(AgentData {
-            reputation_score: _ctx.accounts.agent.reputation_score,
-            total_ratings: _ctx.accounts.agent.total_ratings,
-            rating_sum: _ctx.accounts.agent.rating_sum,
-            is_active: _ctx.accounts.agent.is_active,
-            updated_at: _ctx.accounts.agent.updated_at,
+            reputation_score: agent.reputation_score,
+            total_ratings: agent.total_ratings,
+            rating_sum: agent.rating_sum,
+            is_active: agent.is_active,
+            updated_at: agent.updated_at,
+            external_score,
+            external_weight_cap_bps: EXTERNAL_WEIGHT_CAP_BPS,
+            twar_score: agent.twar_at(Clock::get()?.unix_timestamp),
+        })
+    }
+
+    /// Break an agent's score down into the inputs that produced it (simulate to read)
+    pub fn explain_score(ctx: Context<GetAgentReputation>) -> Result<ScoreExplanation> {
+        Ok(ctx.accounts.agent.explain(Clock::get()?.unix_timestamp))
+    }
+
+    /// Return one page of an agent's review index via return data (simulate to read)
+    pub fn list_reviews(ctx: Context<ListReviews>, page: u32) -> Result<ReviewPageData> {
+        Ok(ReviewPageData {
+            page,
+            total_reviews: ctx.accounts.agent.total_ratings,
+            entries: ctx.accounts.review_page.entries.clone(),
         })
     }
+
+    /// Organization registers (or renews) a delegate key allowed to review on its behalf
+    pub fn add_reviewer_delegate(
+        ctx: Context<ManageReviewerDelegates>,
+        delegate: Pubkey,
+        expires_at: i64,
+    ) -> Result<()> {
+        require!(expires_at > Clock::get()?.unix_timestamp, ErrorCode::InvalidExpiry);
+        let delegation = &mut ctx.accounts.delegation;
+        if delegation.organization == Pubkey::default() {
+            delegation.organization = ctx.accounts.organization.key();
+            delegation.bump = ctx.bumps.delegation;
+        }
+
+        if let Some(existing) = delegation.delegates.iter_mut().find(|d| d.key == delegate) {
+            existing.expires_at = expires_at;
+        } else {
+            require!(delegation.delegates.len() < MAX_REVIEW_DELEGATES, ErrorCode::TooManyDelegates);
+            delegation.delegates.push(ReviewerDelegate { key: delegate, expires_at });
+        }
+
+        Ok(())
+    }
+
+    /// Organization removes a delegate key
+    pub fn remove_reviewer_delegate(ctx: Context<ManageReviewerDelegates>, delegate: Pubkey) -> Result<()> {
+        ctx.accounts.delegation.delegates.retain(|d| d.key != delegate);
+        Ok(())
+    }
+
+    /// Treasury posts a bounty paying the first `max_rewards` escrow-verified reviews
+    /// in `skill_category` an extra `reward_lamports` each (funded up front)
+    pub fn post_review_bounty(
+        ctx: Context<PostReviewBounty>,
+        skill_category: String,
+        reward_lamports: u64,
+        max_rewards: u16,
+    ) -> Result<()> {
+        require!(skill_category.len() <= 32, ErrorCode::CategoryTooLong);
+        require!(reward_lamports > 0 && max_rewards > 0, ErrorCode::InvalidBounty);
+
+        let bounty = &mut ctx.accounts.bounty;
+        bounty.treasury = ctx.accounts.authority.key();
+        bounty.skill_category = skill_category;
+        bounty.reward_lamports = reward_lamports;
+        bounty.max_rewards = max_rewards;
+        bounty.paid_rewards = 0;
+        bounty.bump = ctx.bumps.bounty;
+
+        let total = reward_lamports
+            .checked_mul(max_rewards as u64)
+            .ok_or(ErrorCode::InvalidBounty)?;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.bounty.to_account_info(),
+                },
+            ),
+            total,
+        )?;
+
+        Ok(())
+    }
+
+    /// Reviewer claims a bounty reward for a review backed by an escrow they funded
+    pub fn claim_review_bounty(ctx: Context<ClaimReviewBounty>) -> Result<()> {
+        let review = &ctx.accounts.review;
+        let bounty = &ctx.accounts.bounty;
+        require!(review.skill_category == bounty.skill_category, ErrorCode::CategoryMismatch);
+        require!(bounty.paid_rewards < bounty.max_rewards, ErrorCode::BountyExhausted);
+
+        let (provider, renter) = read_escrow_parties(&ctx.accounts.escrow)?;
+        require_keys_eq!(provider, ctx.accounts.agent.authority, ErrorCode::EscrowMismatch);
+        require_keys_eq!(renter, review.reviewer, ErrorCode::EscrowMismatch);
+
+        let claim = &mut ctx.accounts.claim;
+        claim.bounty = bounty.key();
+        claim.review = review.key();
+        claim.claimed_at = Clock::get()?.unix_timestamp;
+
+        let reward = bounty.reward_lamports;
+        ctx.accounts.bounty.paid_rewards += 1;
+        ctx.accounts.bounty.sub_lamports(reward)?;
+        ctx.accounts.reviewer.add_lamports(reward)?;
+
+        Ok(())
+    }
+
+    /// Point a review at translated versions of its comment (agent authority or moderator).
+    /// The caller must quote the canonical comment hash so translations always anchor to
+    /// the original text.
+    pub fn set_review_translations(
+        ctx: Context<SetReviewTranslations>,
+        comment_hash: [u8; 32],
+        translations_uri: Option<String>,
+    ) -> Result<()> {
+        let caller = ctx.accounts.authority.key();
+        require!(
+            caller == ctx.accounts.agent.authority || caller == ctx.accounts.state.authority,
+            ErrorCode::Unauthorized
+        );
+        if let Some(uri) = &translations_uri {
+            require!(uri.len() <= 200, ErrorCode::UriTooLong);
+        }
+        let review = &mut ctx.accounts.review;
+        require!(review.comment_hash == comment_hash, ErrorCode::CommentHashMismatch);
+        review.translations_uri = translations_uri;
+
+        Ok(())
+    }
+
+    /// Register (or re-activate) an attestor key allowed to import external reputation
+    pub fn register_attestor(ctx: Context<RegisterAttestor>, platform_id: String) -> Result<()> {
+        require!(platform_id.len() <= 32, ErrorCode::PlatformIdTooLong);
+
+        let attestor = &mut ctx.accounts.attestor;
+        attestor.key = ctx.accounts.attestor_key.key();
+        attestor.platform_id = platform_id;
+        attestor.is_active = true;
+        attestor.bump = ctx.bumps.attestor;
+
+        Ok(())
+    }
+
+    /// Revoke an attestor key
+    pub fn revoke_attestor(ctx: Context<RevokeAttestor>) -> Result<()> {
+        ctx.accounts.attestor.is_active = false;
+        Ok(())
+    }
+
+    /// Import an agent's reputation from an external platform (attestor only).
+    /// Stored separately from native reviews; re-importing replaces the previous score.
+    pub fn import_external_reputation(
+        ctx: Context<ImportExternalReputation>,
+        platform_id: String,
+        score: u8,
+        proof_hash: [u8; 32],
+    ) -> Result<()> {
+        let attestor = &ctx.accounts.attestor;
+        require!(attestor.is_active, ErrorCode::AttestorNotActive);
+        require!(attestor.platform_id == platform_id, ErrorCode::PlatformMismatch);
+        require!(score <= 100, ErrorCode::InvalidScore);
+
+        let agent_key = ctx.accounts.agent.key();
+        let imported = &mut ctx.accounts.external_reputation;
+        let agent = &mut ctx.accounts.agent;
+
+        if imported.agent == Pubkey::default() {
+            imported.agent = agent_key;
+            imported.platform_id = platform_id;
+            imported.bump = ctx.bumps.external_reputation;
+            agent.external_sources += 1;
+        } else {
+            agent.external_score_sum = agent.external_score_sum.saturating_sub(imported.score as u64);
+        }
+        agent.external_score_sum += score as u64;
+
+        imported.attestor = attestor.key;
+        imported.score = score;
+        imported.proof_hash = proof_hash;
+        imported.imported_at = Clock::get()?.unix_timestamp;
+
+        ctx.accounts.activity.log(
+            &ctx.bumps.activity,
+            ctx.accounts.agent.authority,
+            ActivityKind::ExternalReputationImported,
+            ctx.accounts.external_reputation.key(),
+            ctx.accounts.attestor.key,
+            score as u64,
+        )?;
+
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
@@ -164,6 +542,7 @@ pub struct RegisterAgent<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
+    pub activity: ActivityAccounts<'info>,
 }
 
 #[derive(Accounts)]
@@ -187,9 +566,102 @@ pub struct AddReview<'info> {
         space = Review::LEN
     )]
     pub review: Account<'info, Review>,
+    #[account(
+        init_if_needed,
+        payer = reviewer,
+        space = ReviewPage::LEN,
+        seeds = [
+            REVIEW_PAGE_SEED,
+            agent.key().as_ref(),
+            &((agent.total_ratings / REVIEW_PAGE_SIZE as u64) as u32).to_le_bytes()
+        ],
+        bump
+    )]
+    pub review_page: Account<'info, ReviewPage>,
+    /// Present when `reviewer` is a delegate reviewing on an organization's behalf
+    #[account(
+        seeds = [DELEGATION_SEED, delegation.organization.as_ref()],
+        bump = delegation.bump
+    )]
+    pub delegation: Option<Account<'info, ReviewerDelegation>>,
+    #[account(mut)]
+    pub reviewer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddShardedReview<'info> {
+    #[account(seeds = [AGENT_SEED, agent.authority.as_ref()], bump)]
+    pub agent: Account<'info, Agent>,
+    #[account(
+        init,
+        payer = reviewer,
+        space = Review::LEN
+    )]
+    pub review: Account<'info, Review>,
+    #[account(
+        init_if_needed,
+        payer = reviewer,
+        space = ReviewShard::LEN,
+        seeds = [REVIEW_SHARD_SEED, agent.key().as_ref(), &[review_shard_index(&reviewer.key())]],
+        bump
+    )]
+    pub review_shard: Account<'info, ReviewShard>,
+    /// Present when `reviewer` is a delegate reviewing on an organization's behalf
+    #[account(
+        seeds = [DELEGATION_SEED, delegation.organization.as_ref()],
+        bump = delegation.bump
+    )]
+    pub delegation: Option<Account<'info, ReviewerDelegation>>,
     #[account(mut)]
     pub reviewer: Signer<'info>,
     pub system_program: Program<'info, System>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateScore<'info> {
+    #[account(
+        mut,
+        seeds = [REPUTATION_STATE_SEED],
+        bump = state.bump
+    )]
+    pub state: Account<'info, ReputationState>,
+    #[account(
+        mut,
+        seeds = [AGENT_SEED, agent.authority.as_ref()],
+        bump,
+        has_one = state
+    )]
+    pub agent: Account<'info, Agent>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageReviewerDelegates<'info> {
+    #[account(
+        init_if_needed,
+        payer = organization,
+        space = ReviewerDelegation::LEN,
+        seeds = [DELEGATION_SEED, organization.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, ReviewerDelegation>,
+    #[account(mut)]
+    pub organization: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct ListReviews<'info> {
+    pub agent: Account<'info, Agent>,
+    #[account(
+        seeds = [REVIEW_PAGE_SEED, agent.key().as_ref(), &page.to_le_bytes()],
+        bump = review_page.bump
+    )]
+    pub review_page: Account<'info, ReviewPage>,
 }
 
 #[derive(Accounts)]
@@ -209,14 +681,21 @@ pub struct UpdateReputation<'info> {
     pub agent: Account<'info, Agent>,
     #[account(mut)]
     pub authority: Signer<'info>,
+    pub activity: ActivityAccounts<'info>,
 }
 
 #[derive(Accounts)]
 pub struct DeactivateAgent<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [AGENT_SEED, authority.key().as_ref()],
+        bump,
+        has_one = authority
+    )]
     pub agent: Account<'info, Agent>,
     #[account(mut)]
     pub authority: Signer<'info>,
+    pub activity: ActivityAccounts<'info>,
 }
 
 #[derive(Accounts)]
@@ -224,6 +703,196 @@ pub struct GetAgentReputation<'info> {
     pub agent: Account<'info, Agent>,
 }
 
+#[derive(Accounts)]
+#[instruction(skill_category: String)]
+pub struct PostReviewBounty<'info> {
+    #[account(
+        seeds = [REPUTATION_STATE_SEED],
+        bump = state.bump,
+        has_one = authority
+    )]
+    pub state: Account<'info, ReputationState>,
+    #[account(
+        init,
+        payer = authority,
+        space = ReviewBounty::LEN,
+        seeds = [REVIEW_BOUNTY_SEED, skill_category.as_bytes()],
+        bump
+    )]
+    pub bounty: Account<'info, ReviewBounty>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReviewBounty<'info> {
+    #[account(
+        mut,
+        seeds = [REVIEW_BOUNTY_SEED, bounty.skill_category.as_bytes()],
+        bump = bounty.bump
+    )]
+    pub bounty: Account<'info, ReviewBounty>,
+    #[account(
+        seeds = [AGENT_SEED, agent.authority.as_ref()],
+        bump
+    )]
+    pub agent: Account<'info, Agent>,
+    #[account(has_one = agent, has_one = reviewer)]
+    pub review: Account<'info, Review>,
+    /// CHECK: escrow program account; owner and layout verified in `read_escrow_parties`
+    pub escrow: UncheckedAccount<'info>,
+    /// One claim per escrow per bounty
+    #[account(
+        init,
+        payer = reviewer,
+        space = BountyClaim::LEN,
+        seeds = [BOUNTY_CLAIM_SEED, bounty.key().as_ref(), escrow.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, BountyClaim>,
+    #[account(mut)]
+    pub reviewer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReviewTranslations<'info> {
+    #[account(
+        seeds = [REPUTATION_STATE_SEED],
+        bump = state.bump
+    )]
+    pub state: Account<'info, ReputationState>,
+    #[account(
+        seeds = [AGENT_SEED, agent.authority.as_ref()],
+        bump
+    )]
+    pub agent: Account<'info, Agent>,
+    #[account(mut, has_one = agent)]
+    pub review: Account<'info, Review>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(platform_id: String)]
+pub struct RegisterAttestor<'info> {
+    #[account(
+        seeds = [REPUTATION_STATE_SEED],
+        bump = state.bump,
+        has_one = authority
+    )]
+    pub state: Account<'info, ReputationState>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Attestor::LEN,
+        seeds = [ATTESTOR_SEED, attestor_key.key().as_ref()],
+        bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+    /// CHECK: the key being authorized; only used as a PDA seed
+    pub attestor_key: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAttestor<'info> {
+    #[account(
+        seeds = [REPUTATION_STATE_SEED],
+        bump = state.bump,
+        has_one = authority
+    )]
+    pub state: Account<'info, ReputationState>,
+    #[account(
+        mut,
+        seeds = [ATTESTOR_SEED, attestor.key.as_ref()],
+        bump = attestor.bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(platform_id: String)]
+pub struct ImportExternalReputation<'info> {
+    #[account(
+        seeds = [ATTESTOR_SEED, signer.key().as_ref()],
+        bump = attestor.bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+    #[account(
+        mut,
+        seeds = [AGENT_SEED, agent.authority.as_ref()],
+        bump
+    )]
+    pub agent: Account<'info, Agent>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = ExternalReputation::LEN,
+        seeds = [EXTERNAL_REPUTATION_SEED, agent.key().as_ref(), platform_id.as_bytes()],
+        bump
+    )]
+    pub external_reputation: Account<'info, ExternalReputation>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub activity: ActivityAccounts<'info>,
+}
+
+/// Optional accounts mirroring a state change into the agent's activity feed;
+/// logging is skipped when they are omitted
+#[derive(Accounts)]
+pub struct ActivityAccounts<'info> {
+    pub activity_log_program: Option<Program<'info, ActivityLog>>,
+    /// CHECK: feed PDA; address checked in `log`, contents validated by the activity-log program
+    #[account(mut)]
+    pub activity_feed: Option<UncheckedAccount<'info>>,
+    /// CHECK: this program's signer PDA for activity-log CPIs
+    #[account(seeds = [ACTIVITY_AUTHORITY_SEED], bump)]
+    pub activity_authority: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> ActivityAccounts<'info> {
+    pub fn log(
+        &self,
+        bumps: &ActivityAccountsBumps,
+        agent: Pubkey,
+        kind: ActivityKind,
+        subject: Pubkey,
+        counterparty: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let (Some(program), Some(feed), Some(authority), Some(bump)) = (
+            &self.activity_log_program,
+            &self.activity_feed,
+            &self.activity_authority,
+            bumps.activity_authority,
+        ) else {
+            return Ok(());
+        };
+        let (expected_feed, _) =
+            Pubkey::find_program_address(&[activity_log::FEED_SEED, agent.as_ref()], &activity_log::ID);
+        require_keys_eq!(feed.key(), expected_feed, ErrorCode::InvalidActivityFeed);
+
+        let seeds = &[ACTIVITY_AUTHORITY_SEED, &[bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = activity_log::cpi::accounts::LogActivity {
+            feed: feed.to_account_info(),
+            authority: authority.to_account_info(),
+        };
+        activity_log::cpi::log_activity(
+            CpiContext::new_with_signer(program.to_account_info(), cpi_accounts, signer),
+            kind,
+            subject,
+            counterparty,
+            amount,
+        )
+    }
+}
+
 #[account]
 pub struct ReputationState {
     pub initialized: bool,
@@ -231,11 +900,43 @@ pub struct ReputationState {
     pub total_reviews: u64,
     pub reputation_sum: u64,
     pub bump: u8,
+    pub authority: Pubkey,
 }
 
 impl ReputationState {
-    /// 8 (discriminator) + 1 + 8 + 8 + 8 + 1
-    pub const LEN: usize = 8 + 1 + 8 + 8 + 8 + 1;
+    /// 8 (discriminator) + 1 + 8 + 8 + 8 + 1 + 32
+    pub const LEN: usize = 8 + 1 + 8 + 8 + 8 + 1 + 32;
+}
+
+#[account]
+pub struct Attestor {
+    pub key: Pubkey,
+    pub platform_id: String,
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+impl Attestor {
+    /// 8 + 32 + (4+32) + 1 + 1
+    pub const LEN: usize = 8 + 32 + 36 + 1 + 1;
+}
+
+/// Reputation imported from an external platform, kept apart from native reviews
+#[account]
+pub struct ExternalReputation {
+    pub agent: Pubkey,
+    pub attestor: Pubkey,
+    pub platform_id: String,
+    /// Normalized 0-100
+    pub score: u8,
+    pub proof_hash: [u8; 32],
+    pub imported_at: i64,
+    pub bump: u8,
+}
+
+impl ExternalReputation {
+    /// 8 + 32 + 32 + (4+32) + 1 + 32 + 8 + 1
+    pub const LEN: usize = 8 + 32 + 32 + 36 + 1 + 32 + 8 + 1;
 }
 
 #[account]
@@ -250,11 +951,97 @@ pub struct Agent {
     pub created_at: i64,
     pub updated_at: i64,
     pub is_active: bool,
+    pub external_score_sum: u64,
+    pub external_sources: u16,
+    /// Time-weighted average of `reputation_score` over the trailing
+    /// `TWAR_WINDOW_SECONDS`, scaled by `TWAR_SCALE`, as of `twar_updated_at`
+    pub twar_score: u64,
+    pub twar_updated_at: i64,
+    pub _reserved: [u8; ACCOUNT_RESERVE],
 }
 
 impl Agent {
-    /// 8 + 32 + 32 + (4+64) + (4+256) + 8 + 8 + 8 + 8 + 8 + 1
-    pub const LEN: usize = 8 + 32 + 32 + 68 + 260 + 8 + 8 + 8 + 8 + 8 + 1;
+    /// 8 + 32 + 32 + (4+64) + (4+256) + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 2 + 8 + 8 + reserve
+    pub const LEN: usize = 8 + 32 + 32 + 68 + 260 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 2 + 8 + 8 + ACCOUNT_RESERVE;
+
+    /// `twar_score` at `now`, assuming `reputation_score` has held since `twar_updated_at`.
+    ///
+    /// The part of the window that elapsed is replaced by the current score; the rest is
+    /// assumed to average the previous TWAR, so each update costs O(1) and a score spike
+    /// only moves the average in proportion to how long it lasts.
+    pub fn twar_at(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.twar_updated_at).clamp(0, TWAR_WINDOW_SECONDS) as i128;
+        let current = self.reputation_score.max(0) as i128 * TWAR_SCALE as i128;
+        let previous = self.twar_score as i128;
+        (previous + (current - previous) * elapsed / TWAR_WINDOW_SECONDS as i128) as u64
+    }
+
+    /// Average of imported external scores (0-100), 0 without imports
+    pub fn external_score(&self) -> i64 {
+        match self.external_sources {
+            0 => 0,
+            sources => (self.external_score_sum / sources as u64) as i64,
+        }
+    }
+
+    /// The inputs behind `reputation_score` at `now`
+    pub fn explain(&self, now: i64) -> ScoreExplanation {
+        let rating_term = match self.total_ratings {
+            0 => 0,
+            ratings => (self.rating_sum / ratings) as i64,
+        };
+        let elapsed = now.saturating_sub(self.twar_updated_at).clamp(0, TWAR_WINDOW_SECONDS);
+        ScoreExplanation {
+            score: self.reputation_score,
+            rating_term,
+            total_ratings: self.total_ratings,
+            manual_adjustment: self.reputation_score - rating_term,
+            external_score: self.external_score(),
+            external_sources: self.external_sources,
+            external_weight_cap_bps: EXTERNAL_WEIGHT_CAP_BPS,
+            twar_score: self.twar_at(now),
+            twar_current_weight_bps: (elapsed as i128 * 10_000 / TWAR_WINDOW_SECONDS as i128) as u16,
+        }
+    }
+
+    /// Count `count` new ratings summing to `sum` into the rating average at `now`
+    pub fn record_ratings(&mut self, count: u64, sum: u64, now: i64) {
+        if count == 0 {
+            return;
+        }
+        self.roll_twar(now);
+        self.total_ratings = self.total_ratings.saturating_add(count);
+        self.rating_sum = self.rating_sum.saturating_add(sum);
+        self.reputation_score = (self.rating_sum / self.total_ratings) as i64;
+        self.updated_at = now;
+    }
+
+    /// Fold the time since the last update into `twar_score`; call before changing the score
+    pub fn roll_twar(&mut self, now: i64) {
+        self.twar_score = self.twar_at(now);
+        self.twar_updated_at = now;
+    }
+}
+
+/// Ratings of an agent waiting to be folded in by `update_score`. Reviewers are spread
+/// over `REVIEW_SHARDS` of these so concurrent reviews do not contend for one account.
+#[account]
+pub struct ReviewShard {
+    pub agent: Pubkey,
+    pub shard: u8,
+    pub pending_ratings: u64,
+    pub pending_rating_sum: u64,
+    pub bump: u8,
+}
+
+impl ReviewShard {
+    /// 8 (discriminator) + 32 + 1 + 8 + 8 + 1
+    pub const LEN: usize = 8 + 32 + 1 + 8 + 8 + 1;
+}
+
+/// `ReviewShard` a reviewer's sharded reviews go to, by the hash of their key
+pub fn review_shard_index(reviewer: &Pubkey) -> u8 {
+    hash(reviewer.as_ref()).to_bytes()[0] % REVIEW_SHARDS
 }
 
 #[account]
@@ -265,11 +1052,114 @@ pub struct Review {
     pub comment: String,
     pub skill_category: String,
     pub created_at: i64,
+    /// SHA-256 of the original comment; the canonical text translations refer to
+    pub comment_hash: [u8; 32],
+    pub translations_uri: Option<String>,
+    /// Delegate key that signed on behalf of `reviewer`, if any
+    pub delegate: Option<Pubkey>,
 }
 
 impl Review {
-    /// 8 + 32 + 32 + 1 + (4+500) + (4+32) + 8
-    pub const LEN: usize = 8 + 32 + 32 + 1 + 504 + 36 + 8;
+    /// 8 + 32 + 32 + 1 + (4+500) + (4+32) + 8 + 32 + (1+4+200) + (1+32)
+    pub const LEN: usize = 8 + 32 + 32 + 1 + 504 + 36 + 8 + 32 + 205 + 33;
+}
+
+/// Bounty for reviews in an under-reviewed skill category
+#[account]
+pub struct ReviewBounty {
+    pub treasury: Pubkey,
+    pub skill_category: String,
+    pub reward_lamports: u64,
+    pub max_rewards: u16,
+    pub paid_rewards: u16,
+    pub bump: u8,
+}
+
+impl ReviewBounty {
+    /// 8 + 32 + (4+32) + 8 + 2 + 2 + 1
+    pub const LEN: usize = 8 + 32 + 36 + 8 + 2 + 2 + 1;
+}
+
+#[account]
+pub struct BountyClaim {
+    pub bounty: Pubkey,
+    pub review: Pubkey,
+    pub claimed_at: i64,
+}
+
+impl BountyClaim {
+    /// 8 + 32 + 32 + 8
+    pub const LEN: usize = 8 + 32 + 32 + 8;
+}
+
+/// Read `(provider, renter)` from an escrow program `EscrowAccount`, whose layout starts
+/// with those two keys. Fails unless the escrow has been funded.
+fn read_escrow_parties(escrow: &AccountInfo) -> Result<(Pubkey, Pubkey)> {
+    require_keys_eq!(*escrow.owner, ESCROW_PROGRAM_ID, ErrorCode::EscrowMismatch);
+    let data = escrow.try_borrow_data()?;
+    require!(
+        data.len() >= 72 && data[..8] == hash(b"account:EscrowAccount").to_bytes()[..8],
+        ErrorCode::EscrowMismatch
+    );
+    let provider = Pubkey::try_from(&data[8..40]).map_err(|_| ErrorCode::EscrowMismatch)?;
+    let renter = Pubkey::try_from(&data[40..72]).map_err(|_| ErrorCode::EscrowMismatch)?;
+    require_keys_neq!(renter, Pubkey::default(), ErrorCode::EscrowMismatch);
+    Ok((provider, renter))
+}
+
+/// Delegate reviewer keys registered by an organization wallet
+#[account]
+pub struct ReviewerDelegation {
+    pub organization: Pubkey,
+    pub delegates: Vec<ReviewerDelegate>,
+    pub bump: u8,
+}
+
+impl ReviewerDelegation {
+    /// 8 + 32 + (4 + 8 * 40) + 1
+    pub const LEN: usize = 8 + 32 + 4 + MAX_REVIEW_DELEGATES * 40 + 1;
+
+    pub fn is_valid_delegate(&self, key: &Pubkey, now: i64) -> bool {
+        self.delegates.iter().any(|d| d.key == *key && d.expires_at > now)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReviewerDelegate {
+    pub key: Pubkey,
+    pub expires_at: i64,
+}
+
+/// Fixed-size page of an agent's review index
+#[account]
+pub struct ReviewPage {
+    pub agent: Pubkey,
+    pub page: u32,
+    pub entries: Vec<ReviewSummary>,
+    pub bump: u8,
+}
+
+impl ReviewPage {
+    /// 8 + 32 + 4 + (4 + 16 * 41) + 1
+    pub const LEN: usize = 8 + 32 + 4 + 4 + REVIEW_PAGE_SIZE * ReviewSummary::LEN + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReviewSummary {
+    pub review: Pubkey,
+    pub rating: u8,
+    pub created_at: i64,
+}
+
+impl ReviewSummary {
+    pub const LEN: usize = 32 + 1 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReviewPageData {
+    pub page: u32,
+    pub total_reviews: u64,
+    pub entries: Vec<ReviewSummary>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -279,6 +1169,33 @@ pub struct AgentData {
     pub rating_sum: u64,
     pub is_active: bool,
     pub updated_at: i64,
+    /// Average of imported external scores (0-100)
+    pub external_score: i64,
+    /// Max share of a displayed score the external component may carry
+    pub external_weight_cap_bps: u16,
+    /// Trailing 90-day time-weighted average score, scaled by `TWAR_SCALE`
+    pub twar_score: u64,
+}
+
+/// Return data of `explain_score`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ScoreExplanation {
+    /// Stored `reputation_score`
+    pub score: i64,
+    /// Average native review rating, which each review resets the score to (0 without reviews)
+    pub rating_term: i64,
+    pub total_ratings: u64,
+    /// `score - rating_term`: what `update_reputation` changed since the last review
+    pub manual_adjustment: i64,
+    /// Average of imported external scores (0-100), shown beside the score, not in it
+    pub external_score: i64,
+    pub external_sources: u16,
+    /// Max share of a displayed score the external component may carry
+    pub external_weight_cap_bps: u16,
+    /// Trailing 90-day time-weighted average score, scaled by `TWAR_SCALE`
+    pub twar_score: u64,
+    /// Weight of the current score in `twar_score`; the rest is history decaying out of the window
+    pub twar_current_weight_bps: u16,
 }
 
 #[error_code]
@@ -301,4 +1218,34 @@ pub enum ErrorCode {
     AgentAlreadyInactive,
     #[msg("Reputation score must be 0-100")]
     InvalidScore,
+    #[msg("Platform id too long (max 32 chars)")]
+    PlatformIdTooLong,
+    #[msg("Attestor is not active")]
+    AttestorNotActive,
+    #[msg("Attestor is not registered for this platform")]
+    PlatformMismatch,
+    #[msg("Unauthorized caller")]
+    Unauthorized,
+    #[msg("URI too long (max 200 chars)")]
+    UriTooLong,
+    #[msg("Comment hash does not match the review")]
+    CommentHashMismatch,
+    #[msg("Signer is not an active delegate of the organization")]
+    InvalidDelegate,
+    #[msg("Too many delegates (max 8)")]
+    TooManyDelegates,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Bounty reward and count must be non-zero")]
+    InvalidBounty,
+    #[msg("Review category does not match the bounty")]
+    CategoryMismatch,
+    #[msg("Bounty has paid out all rewards")]
+    BountyExhausted,
+    #[msg("Escrow does not link this reviewer to this agent")]
+    EscrowMismatch,
+    #[msg("Activity feed does not belong to this agent")]
+    InvalidActivityFeed,
+    #[msg("Review shard belongs to another agent")]
+    InvalidReviewShard,
 }
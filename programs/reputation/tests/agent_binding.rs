@@ -0,0 +1,81 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use reputation::{Agent, DeactivateAgent, DeactivateAgentBumps, ACCOUNT_RESERVE};
+
+fn agent(authority: Pubkey) -> Vec<u8> {
+    let agent = Agent {
+        authority,
+        state: Pubkey::new_unique(),
+        name: String::new(),
+        bio: String::new(),
+        reputation_score: 0,
+        total_ratings: 0,
+        rating_sum: 0,
+        created_at: 0,
+        updated_at: 0,
+        is_active: true,
+        external_score_sum: 0,
+        external_sources: 0,
+        twar_score: 0,
+        twar_updated_at: 0,
+        _reserved: [0; ACCOUNT_RESERVE],
+    };
+    let mut data = Vec::new();
+    agent.try_serialize(&mut data).unwrap();
+    data.resize(Agent::LEN, 0);
+    data
+}
+
+/// Run the `DeactivateAgent` constraints for `signer` against an agent stored at `address`
+fn deactivate(address: Pubkey, owner: Pubkey, signer: Pubkey) -> Result<()> {
+    let program_id = reputation::ID;
+    let (mut agent_lamports, mut signer_lamports) = (1_000_000, 1_000_000);
+    let mut none_lamports = [0; 3];
+    let mut agent_data = agent(owner);
+    let mut signer_data = [];
+    let [a, b, c] = &mut none_lamports;
+    let (mut none_a, mut none_b, mut none_c) = ([], [], []);
+    let infos = [
+        AccountInfo::new(&address, false, true, &mut agent_lamports, &mut agent_data, &program_id, false, 0),
+        AccountInfo::new(&signer, true, true, &mut signer_lamports, &mut signer_data, &system_program::ID, false, 0),
+        // The program id stands in for each omitted activity account
+        AccountInfo::new(&program_id, false, false, a, &mut none_a, &program_id, true, 0),
+        AccountInfo::new(&program_id, false, false, b, &mut none_b, &program_id, true, 0),
+        AccountInfo::new(&program_id, false, false, c, &mut none_c, &program_id, true, 0),
+    ];
+    DeactivateAgent::try_accounts(
+        &program_id,
+        &mut &infos[..],
+        &[],
+        &mut DeactivateAgentBumps::default(),
+        &mut BTreeSet::new(),
+    )
+    .map(|_| ())
+}
+
+fn agent_address(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"agent", authority.as_ref()], &reputation::ID).0
+}
+
+#[test]
+fn authority_deactivates_its_own_agent() {
+    let authority = Pubkey::new_unique();
+    deactivate(agent_address(&authority), authority, authority).unwrap();
+}
+
+#[test]
+fn agent_must_sit_at_its_authority_seeded_address() {
+    let authority = Pubkey::new_unique();
+    let err = deactivate(Pubkey::new_unique(), authority, authority).unwrap_err();
+    assert_eq!(err, ErrorCode::ConstraintSeeds.into());
+}
+
+#[test]
+fn another_wallet_cannot_deactivate_the_agent() {
+    let (authority, intruder) = (Pubkey::new_unique(), Pubkey::new_unique());
+    // Even at the intruder's seed address, the stored authority must sign
+    let err = deactivate(agent_address(&intruder), authority, intruder).unwrap_err();
+    assert_eq!(err, ErrorCode::ConstraintHasOne.into());
+}
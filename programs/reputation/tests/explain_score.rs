@@ -0,0 +1,70 @@
+use anchor_lang::prelude::Pubkey;
+use reputation::{Agent, ScoreExplanation, ACCOUNT_RESERVE, EXTERNAL_WEIGHT_CAP_BPS, TWAR_SCALE, TWAR_WINDOW_SECONDS};
+
+fn agent() -> Agent {
+    Agent {
+        authority: Pubkey::new_unique(),
+        state: Pubkey::new_unique(),
+        name: String::new(),
+        bio: String::new(),
+        reputation_score: 4,
+        total_ratings: 3,
+        rating_sum: 13,
+        created_at: 0,
+        updated_at: 0,
+        is_active: true,
+        external_score_sum: 150,
+        external_sources: 2,
+        twar_score: 2 * TWAR_SCALE,
+        twar_updated_at: 0,
+        _reserved: [0; ACCOUNT_RESERVE],
+    }
+}
+
+#[test]
+fn review_average_explains_the_score() {
+    let agent = agent();
+    assert_eq!(
+        agent.explain(TWAR_WINDOW_SECONDS / 4),
+        ScoreExplanation {
+            score: 4,
+            rating_term: 4,
+            total_ratings: 3,
+            manual_adjustment: 0,
+            external_score: 75,
+            external_sources: 2,
+            external_weight_cap_bps: EXTERNAL_WEIGHT_CAP_BPS,
+            twar_score: 25_000,
+            twar_current_weight_bps: 2_500,
+        }
+    );
+}
+
+#[test]
+fn manual_updates_show_as_an_adjustment() {
+    let slashed = Agent {
+        reputation_score: 1,
+        ..agent()
+    };
+    assert_eq!(slashed.explain(0).manual_adjustment, -3);
+
+    let unrated = Agent {
+        reputation_score: 60,
+        total_ratings: 0,
+        rating_sum: 0,
+        external_sources: 0,
+        ..agent()
+    };
+    let explanation = unrated.explain(0);
+    assert_eq!(explanation.rating_term, 0);
+    assert_eq!(explanation.manual_adjustment, 60);
+    assert_eq!(explanation.external_score, 0);
+}
+
+#[test]
+fn current_score_weight_saturates_after_a_full_window() {
+    let agent = agent();
+    assert_eq!(agent.explain(0).twar_current_weight_bps, 0);
+    assert_eq!(agent.explain(10 * TWAR_WINDOW_SECONDS).twar_current_weight_bps, 10_000);
+    assert_eq!(agent.explain(-5).twar_current_weight_bps, 0);
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::Pubkey;
+use reputation::{review_shard_index, Agent, ACCOUNT_RESERVE, REVIEW_SHARDS};
+
+fn agent() -> Agent {
+    Agent {
+        authority: Pubkey::new_unique(),
+        state: Pubkey::new_unique(),
+        name: String::new(),
+        bio: String::new(),
+        reputation_score: 0,
+        total_ratings: 0,
+        rating_sum: 0,
+        created_at: 0,
+        updated_at: 0,
+        is_active: true,
+        external_score_sum: 0,
+        external_sources: 0,
+        twar_score: 0,
+        twar_updated_at: 0,
+        _reserved: [0; ACCOUNT_RESERVE],
+    }
+}
+
+#[test]
+fn reviewers_spread_over_every_shard() {
+    let mut seen = [0u32; REVIEW_SHARDS as usize];
+    for _ in 0..1_000 {
+        let reviewer = Pubkey::new_unique();
+        let shard = review_shard_index(&reviewer);
+        assert_eq!(shard, review_shard_index(&reviewer));
+        seen[shard as usize] += 1;
+    }
+    assert!(seen.iter().all(|&count| count > 50), "{seen:?}");
+}
+
+#[test]
+fn aggregated_shards_score_like_direct_reviews() {
+    let mut direct = agent();
+    for rating in [5, 4, 4, 1, 3] {
+        direct.record_ratings(1, rating, 100);
+    }
+
+    let mut sharded = agent();
+    sharded.record_ratings(2, 9, 100);
+    sharded.record_ratings(3, 8, 100);
+
+    assert_eq!(sharded.total_ratings, direct.total_ratings);
+    assert_eq!(sharded.rating_sum, direct.rating_sum);
+    assert_eq!(sharded.reputation_score, 3);
+    assert_eq!(sharded.reputation_score, direct.reputation_score);
+}
+
+#[test]
+fn empty_aggregation_leaves_the_agent_untouched() {
+    let mut agent = agent();
+    agent.record_ratings(0, 0, 500);
+    assert_eq!(agent.updated_at, 0);
+    assert_eq!(agent.twar_updated_at, 0);
+}
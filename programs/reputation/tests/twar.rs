@@ -0,0 +1,51 @@
+use anchor_lang::prelude::Pubkey;
+use reputation::{Agent, ACCOUNT_RESERVE, TWAR_SCALE, TWAR_WINDOW_SECONDS};
+
+fn agent(score: i64, twar: u64, at: i64) -> Agent {
+    Agent {
+        authority: Pubkey::new_unique(),
+        state: Pubkey::new_unique(),
+        name: String::new(),
+        bio: String::new(),
+        reputation_score: score,
+        total_ratings: 0,
+        rating_sum: 0,
+        created_at: 0,
+        updated_at: at,
+        is_active: true,
+        external_score_sum: 0,
+        external_sources: 0,
+        twar_score: twar,
+        twar_updated_at: at,
+        _reserved: [0; ACCOUNT_RESERVE],
+    }
+}
+
+#[test]
+fn twar_converges_to_a_held_score_over_the_window() {
+    let agent = agent(80, 40 * TWAR_SCALE, 0);
+    assert_eq!(agent.twar_at(0), 40 * TWAR_SCALE);
+    assert_eq!(agent.twar_at(TWAR_WINDOW_SECONDS / 2), 60 * TWAR_SCALE);
+    assert_eq!(agent.twar_at(TWAR_WINDOW_SECONDS), 80 * TWAR_SCALE);
+    assert_eq!(agent.twar_at(10 * TWAR_WINDOW_SECONDS), 80 * TWAR_SCALE);
+}
+
+#[test]
+fn short_spike_barely_moves_twar() {
+    let mut agent = agent(50, 50 * TWAR_SCALE, 0);
+    let day = 24 * 60 * 60;
+
+    agent.roll_twar(0);
+    agent.reputation_score = 100;
+    agent.roll_twar(day);
+    agent.reputation_score = 50;
+
+    let twar = agent.twar_at(day);
+    assert!(twar > 50 * TWAR_SCALE && twar < 51 * TWAR_SCALE, "twar {twar}");
+}
+
+#[test]
+fn clock_going_backwards_is_ignored() {
+    let agent = agent(90, 30 * TWAR_SCALE, 1_000);
+    assert_eq!(agent.twar_at(500), 30 * TWAR_SCALE);
+}
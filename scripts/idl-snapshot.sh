@@ -0,0 +1,76 @@
+#!/bin/bash
+# TrustyClaw IDL snapshot check
+# Builds each program's IDL with the `idl-build` feature (no Anchor CLI needed) and
+# compares it with the checked-in snapshot under idl/. Fails on any difference so
+# layout or interface changes that break external SDKs are caught in review.
+#
+#   scripts/idl-snapshot.sh           # check
+#   scripts/idl-snapshot.sh --update  # rewrite snapshots
+
+set -e
+
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+MODE="${1:-check}"
+STATUS=0
+
+for PROGRAM in activity-log escrow reputation; do
+    NAME="${PROGRAM//-/_}"
+    DIR="$ROOT/programs/$PROGRAM"
+    SNAPSHOT="$ROOT/idl/$NAME.json"
+    GENERATED="$(mktemp)"
+
+    (cd "$DIR" && ANCHOR_IDL_BUILD_PROGRAM_PATH="$DIR" ANCHOR_IDL_BUILD_SKIP_LINT=TRUE \
+        cargo test --features idl-build --lib __anchor_private_print_idl \
+        -- --show-output --quiet --test-threads=1 2>/dev/null) \
+        | python3 -c '
+import json, sys
+
+sections, current, buf = [], None, []
+for line in sys.stdin:
+    line = line.rstrip("\n")
+    if line.startswith("--- IDL begin "):
+        current, buf = line[len("--- IDL begin "):-len(" ---")], []
+    elif line.startswith("--- IDL end "):
+        sections.append((current, json.loads("\n".join(buf))))
+        current = None
+    elif current is not None:
+        buf.append(line)
+
+idl, events, errors, types, address = None, [], [], {}, ""
+for kind, value in sections:
+    if kind == "program":
+        idl = value
+    elif kind == "address":
+        address = json.loads(value)
+    elif kind == "errors":
+        errors = value
+    elif kind == "event":
+        events.append(value["event"])
+        for ty in value.get("types", []):
+            types[ty["name"]] = ty
+
+for ty in idl.get("types", []):
+    types[ty["name"]] = ty
+idl["address"] = address
+idl["events"] = sorted(events, key=lambda e: e["name"])
+idl["errors"] = errors
+idl["types"] = sorted(types.values(), key=lambda t: t["name"])
+json.dump(idl, sys.stdout, indent=2)
+print()
+' > "$GENERATED"
+
+    if [ "$MODE" = "--update" ]; then
+        mkdir -p "$ROOT/idl"
+        mv "$GENERATED" "$SNAPSHOT"
+        echo "updated idl/$NAME.json"
+    elif ! diff -u "$SNAPSHOT" "$GENERATED"; then
+        echo "❌ idl/$NAME.json is out of date; review the diff and run scripts/idl-snapshot.sh --update"
+        STATUS=1
+        rm -f "$GENERATED"
+    else
+        echo "✅ idl/$NAME.json"
+        rm -f "$GENERATED"
+    fi
+done
+
+exit $STATUS